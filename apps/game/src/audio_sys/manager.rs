@@ -5,7 +5,7 @@
 
 use crate::system::bus::{AudioCommand, SystemBus};
 use crossbeam_channel::Sender;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicU64, Ordering};
 
@@ -67,6 +67,20 @@ impl AudioManager {
         let _ = self.cmd_tx.send(AudioCommand::SetVolume { volume });
     }
 
+    /// Decodes the samples of the loaded map, so that hits only replay them.
+    pub fn load_samples(&self, paths: Vec<PathBuf>) {
+        let _ = self.cmd_tx.send(AudioCommand::LoadSamples { paths });
+    }
+
+    /// Plays a sample once on the effects channel, at the music speed.
+    /// Samples missing from [`Self::load_samples`] are not played.
+    pub fn play_sample(&self, path: &Path, volume: f32) {
+        let _ = self.cmd_tx.send(AudioCommand::PlaySample {
+            path: path.to_path_buf(),
+            volume,
+        });
+    }
+
     /// Seeks to a position in seconds.
     ///
     /// This operation is non-blocking; the audio thread handles the seek asynchronously.
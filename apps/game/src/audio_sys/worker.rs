@@ -3,7 +3,9 @@
 //! This prevents audio loading/seeking from blocking the game logic thread.
 
 use crate::system::bus::{AudioCommand, SystemBus};
+use rodio::source::Buffered;
 use rodio::{Decoder, OutputStream, OutputStreamBuilder, Sink, Source};
+use std::collections::HashMap;
 use std::fs::File;
 use std::io::BufReader;
 use std::path::{Path, PathBuf};
@@ -23,6 +25,8 @@ struct AudioWorker {
     position_counter: Arc<std::sync::atomic::AtomicU64>,
     /// True if audio is available, false for silent mode
     has_audio: bool,
+    /// Decoded samples (keysounds) of the current map.
+    samples: HashMap<PathBuf, Buffered<Decoder<BufReader<File>>>>,
 }
 
 impl AudioWorker {
@@ -40,6 +44,7 @@ impl AudioWorker {
                     channels: 2,
                     position_counter: bus.audio_position.clone(),
                     has_audio: true,
+                    samples: HashMap::new(),
                 }
             }
             Err(e) => {
@@ -57,6 +62,7 @@ impl AudioWorker {
                     channels: 2,
                     position_counter: bus.audio_position.clone(),
                     has_audio: false,
+                    samples: HashMap::new(),
                 }
             }
         }
@@ -98,10 +104,18 @@ impl AudioWorker {
                     sink.set_volume(volume);
                 }
             }
+            AudioCommand::LoadSamples { paths } => {
+                self.load_samples(paths);
+            }
+            AudioCommand::PlaySample { path, volume } => {
+                self.play_sample(&path, volume);
+            }
         }
     }
 
     fn load_music(&mut self, path: &Path, bus: &SystemBus) {
+        // Les samples appartiennent à la map précédente
+        self.samples.clear();
         self.current_path = Some(path.to_path_buf());
         self.load_from_position(0.0, bus);
     }
@@ -168,6 +182,45 @@ impl AudioWorker {
         log::info!("AUDIO: Loaded from {:.1}s", position_secs);
     }
 
+    /// Decodes the samples of the current map, so that a hit never waits
+    /// on the disk.
+    fn load_samples(&mut self, paths: Vec<PathBuf>) {
+        if !self.has_audio {
+            return;
+        }
+        for path in paths {
+            if self.samples.contains_key(&path) {
+                continue;
+            }
+            let Ok(file) = File::open(&path) else {
+                log::warn!("AUDIO: Cannot open sample {:?}", path);
+                continue;
+            };
+            let Ok(decoder) = Decoder::new(BufReader::new(file)) else {
+                log::warn!("AUDIO: Cannot decode sample {:?}", path);
+                continue;
+            };
+            // Bufferisé : chaque hit rejoue le buffer sans redécoder
+            self.samples.insert(path, decoder.buffered());
+        }
+        log::info!("AUDIO: {} samples loaded", self.samples.len());
+    }
+
+    /// Plays a sample on the mixer, next to the music sink, so it does not
+    /// follow the music volume (muted on keysounded maps).
+    fn play_sample(&self, path: &Path, volume: f32) {
+        let Some(stream) = &self.stream else {
+            return;
+        };
+        // Samples are decoded when the map loads, never on a hit
+        let Some(source) = self.samples.get(path) else {
+            return;
+        };
+        stream
+            .mixer()
+            .add(source.clone().speed(self.speed).amplify(volume));
+    }
+
     fn seek_to(&mut self, position_secs: f32, bus: &SystemBus) {
         let was_playing = self.sink.as_ref().map(|s| !s.is_paused()).unwrap_or(false);
 
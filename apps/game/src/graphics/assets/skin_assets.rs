@@ -142,8 +142,7 @@ impl SkinAssets {
             let receptor_pressed = Self::load_column_texture(
                 device,
                 cache,
-                skin.get_receptor_pressed_image(key_count, col)
-                    .or_else(|| skin.get_receptor_image(key_count, col)),
+                skin.get_receptor_image_for_state(key_count, col, true),
                 def_receptor,
                 &format!("{}K Receptor Pressed Col {}", key_count, col),
                 layout,
@@ -3,6 +3,8 @@
 use std::borrow::Cow;
 use wgpu::{Device, RenderPipeline, TextureFormat};
 
+use crate::graphics::primitives::InstanceRaw;
+use crate::render::utils::create_background_params_layout;
 use crate::shaders::constants::{
     BACKGROUND_SHADER_SRC, MAIN_SHADER_SRC, PROGRESS_SHADER_SRC, QUAD_SHADER_SRC,
 };
@@ -19,11 +21,14 @@ pub struct Pipelines {
     pub background: RenderPipeline,
     /// Shared bind group layout for textures
     pub texture_bind_group_layout: wgpu::BindGroupLayout,
+    /// Layout of the background params uniform (alpha, brightness, blur)
+    pub background_params_layout: wgpu::BindGroupLayout,
 }
 
 impl Pipelines {
     pub fn new(device: &Device, format: TextureFormat) -> Self {
         let texture_bind_group_layout = Self::create_texture_bind_group_layout(device);
+        let background_params_layout = create_background_params_layout(device);
 
         Self {
             sprite: Self::create_sprite_pipeline(device, format, &texture_bind_group_layout),
@@ -33,8 +38,10 @@ impl Pipelines {
                 device,
                 format,
                 &texture_bind_group_layout,
+                &background_params_layout,
             ),
             texture_bind_group_layout,
+            background_params_layout,
         }
     }
 
@@ -85,7 +92,7 @@ impl Pipelines {
                 module: &shader,
                 entry_point: Some("vs_main"),
                 buffers: &[wgpu::VertexBufferLayout {
-                    array_stride: 16,
+                    array_stride: std::mem::size_of::<InstanceRaw>() as wgpu::BufferAddress,
                     step_mode: wgpu::VertexStepMode::Instance,
                     attributes: &[
                         wgpu::VertexAttribute {
@@ -98,6 +105,11 @@ impl Pipelines {
                             shader_location: 6,
                             format: wgpu::VertexFormat::Float32x2,
                         },
+                        wgpu::VertexAttribute {
+                            offset: 16,
+                            shader_location: 7,
+                            format: wgpu::VertexFormat::Float32,
+                        },
                     ],
                 }],
                 compilation_options: Default::default(),
@@ -263,6 +275,7 @@ impl Pipelines {
         device: &Device,
         format: TextureFormat,
         bind_group_layout: &wgpu::BindGroupLayout,
+        params_layout: &wgpu::BindGroupLayout,
     ) -> RenderPipeline {
         let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: Some("Background Shader"),
@@ -271,7 +284,7 @@ impl Pipelines {
 
         let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
             label: Some("Background Pipeline Layout"),
-            bind_group_layouts: &[bind_group_layout],
+            bind_group_layouts: &[bind_group_layout, params_layout],
             push_constant_ranges: &[],
         });
 
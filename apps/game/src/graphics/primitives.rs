@@ -10,6 +10,8 @@ pub struct InstanceRaw {
     pub offset: [f32; 2],
     /// Size in normalized coordinates
     pub scale: [f32; 2],
+    /// Opacity multiplier (1.0 = fully visible)
+    pub alpha: f32,
 }
 
 /// Quad instance for colored rectangles.
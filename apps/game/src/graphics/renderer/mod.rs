@@ -16,8 +16,12 @@ mod result;
 mod song_select;
 
 use crate::input::events::GameAction;
+use crate::models::settings::FrameCap;
+use crate::render::capture::{capture_frame, save_screenshot};
 use crate::render::context::RenderContext;
+use crate::render::display::fullscreen;
 use crate::render::draw::draw_game;
+use crate::render::frame_stats::{FrameStats, fps_from_ms};
 use crate::render::resources::RenderResources;
 use crate::render::ui::UiOverlay;
 use crate::shared::snapshot::RenderState;
@@ -27,9 +31,14 @@ use crate::views::components::editor::SkinEditorLayout;
 use crate::views::components::menu::result_screen::ResultScreen;
 use std::sync::Arc;
 use winit::event::{ElementState, KeyEvent, WindowEvent};
-use winit::keyboard::PhysicalKey;
+use winit::keyboard::{KeyCode, PhysicalKey};
 use winit::window::Window;
 
+/// Toggles the frame-time overlay; handled here, not by the input thread.
+pub const FRAME_STATS_KEY: KeyCode = KeyCode::F9;
+/// Saves a screenshot of the next frame.
+pub const SCREENSHOT_KEY: KeyCode = KeyCode::F12;
+
 pub struct Renderer {
     pub ctx: RenderContext,
 
@@ -59,6 +68,11 @@ pub struct Renderer {
     frame_count: u32,
     last_fps_update: std::time::Instant,
     current_fps: f64,
+    frame_stats: FrameStats,
+    show_frame_stats: bool,
+
+    // Screenshot demandé (F12), pris à la fin de la prochaine frame
+    screenshot_requested: bool,
 
     // Key mode tracking
     current_key_count: usize,
@@ -66,7 +80,7 @@ pub struct Renderer {
 
 impl Renderer {
     pub async fn new(window: Arc<Window>) -> Self {
-        let ctx = RenderContext::new(window.clone()).await;
+        let mut ctx = RenderContext::new(window.clone()).await;
 
         // Instance UI pour la fenêtre principale
         let ui = UiOverlay::new(window.clone(), &ctx.device, ctx.config.format);
@@ -78,22 +92,18 @@ impl Renderer {
 
         // Positionnement initial des éléments
         resources.update_component_positions(ctx.config.width as f32, ctx.config.height as f32);
+        ctx.set_vsync(resources.settings.frame_cap.vsync());
 
         // Initialize main menu page with GPU resources
         let mut main_menu_page = MainMenuPage::new();
-        main_menu_page.init_gpu(
-            &ctx.device,
-            ctx.config.format,
-            ctx.config.width as f32,
-            ctx.config.height as f32,
-        );
+        main_menu_page.init_gpu(&ctx.device, ctx.config.format);
 
         Self {
             ctx,
             ui,
             offscreen_ui,
             resources,
-            current_state: RenderState::MainMenu,
+            current_state: RenderState::Empty,
 
             song_select_screen: SongSelectScreen::new(),
             result_screen: ResultScreen::new(),
@@ -109,11 +119,33 @@ impl Renderer {
             frame_count: 0,
             last_fps_update: std::time::Instant::now(),
             current_fps: 0.0,
+            frame_stats: FrameStats::new(),
+            show_frame_stats: false,
+            screenshot_requested: false,
 
             current_key_count: 4, // Default to 4K
         }
     }
 
+    /// Frame rate limit currently selected in the settings.
+    pub fn frame_cap(&self) -> FrameCap {
+        self.resources.settings.frame_cap
+    }
+
+    /// Switches the window to the display mode selected in the settings.
+    ///
+    /// The `Resized` event that follows reconfigures the surface and the
+    /// pixel system.
+    pub fn apply_display_mode(&self) {
+        let settings = &self.resources.settings;
+        let window = &self.ctx.window;
+        window.set_fullscreen(fullscreen(
+            settings.display_mode,
+            settings.monitor_index,
+            window.available_monitors(),
+        ));
+    }
+
     pub fn resize(&mut self, new_size: winit::dpi::PhysicalSize<u32>) {
         self.ctx.resize(new_size);
         self.resources
@@ -128,8 +160,6 @@ impl Renderer {
             self.ctx.config.width as f32,
             self.ctx.config.height as f32,
         );
-        self.main_menu_page
-            .resize(new_size.width as f32, new_size.height as f32);
     }
 
     pub fn handle_event(&mut self, window: &Window, event: &WindowEvent) -> bool {
@@ -140,14 +170,21 @@ impl Renderer {
                 KeyEvent {
                     state: ElementState::Pressed,
                     physical_key: PhysicalKey::Code(code),
+                    repeat,
                     ..
                 },
             ..
         } = event
-            && self.resources.settings.remapping_column.is_some()
         {
-            let label = format!("{:?}", code);
-            self.resources.settings.push_keybind_key(label);
+            if self.resources.settings.is_capturing_keybind() {
+                let label = format!("{:?}", code);
+                self.resources.settings.push_keybind_key(label);
+            } else if *code == FRAME_STATS_KEY && !repeat {
+                // Overlay de debug des frame times
+                self.show_frame_stats = !self.show_frame_stats;
+            } else if *code == SCREENSHOT_KEY && !repeat {
+                self.screenshot_requested = true;
+            }
         }
 
         handled
@@ -162,6 +199,18 @@ impl Renderer {
                 self.resources.set_key_mode(snapshot.key_count, &self.ctx);
                 log::info!("RENDERER: Switched to {}K mode", snapshot.key_count);
             }
+            // Taille des notes / largeur des colonnes changées en jeu
+            let settings = &mut self.resources.settings;
+            if snapshot.note_scale != settings.note_scale
+                || snapshot.column_width_scale != settings.column_width_scale
+            {
+                settings.note_scale = snapshot.note_scale;
+                settings.column_width_scale = snapshot.column_width_scale;
+                self.resources.update_component_positions(
+                    self.ctx.config.width as f32,
+                    self.ctx.config.height as f32,
+                );
+            }
         }
 
         if let RenderState::Menu(ref menu) = new_state
@@ -178,6 +227,9 @@ impl Renderer {
         // --- FPS Calculation ---
         self.frame_count += 1;
         let now = std::time::Instant::now();
+        let frame_dt = now.duration_since(self.last_frame_time).as_secs_f32();
+        self.last_frame_time = now;
+        self.frame_stats.push(frame_dt * 1000.0);
         let elapsed = now.duration_since(self.last_fps_update);
         if elapsed.as_secs_f64() >= 1.0 {
             self.current_fps = self.frame_count as f64 / elapsed.as_secs_f64();
@@ -208,7 +260,7 @@ impl Renderer {
 
         if is_editor {
             self.render_editor_offscreen(&mut encoder, window);
-        } else if matches!(self.current_state, RenderState::MainMenu) {
+        } else if let RenderState::MainMenu(particles) = &self.current_state {
             // --- MAIN MENU: Render 3D cube and particles ---
             {
                 let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
@@ -231,7 +283,7 @@ impl Renderer {
                 });
                 let aspect = self.ctx.config.width as f32 / self.ctx.config.height as f32;
                 self.main_menu_page
-                    .render_3d(&mut pass, &self.ctx.queue, aspect);
+                    .render_3d(&mut pass, &self.ctx.queue, aspect, particles);
             }
         } else {
             // --- MODE NORMAL : RENDU ONSCREEN ---
@@ -249,14 +301,17 @@ impl Renderer {
         // 2. UI LAYER PRINCIPALE (SWAPCHAIN)
         // =================================================================================
 
-        self.ui.begin_frame(window);
+        let ui_scale = self.resources.settings.ui_scale();
+        self.ui.begin_frame(window, ui_scale);
+        // egui::Context est un Arc : le clone est gratuit et laisse `self`
+        // libre d'être emprunté par les pages, sans sortir le contexte de l'UI
         let ctx_egui = self.ui.ctx.clone();
 
         // Clone state data before mutable operations to avoid borrow conflicts
         let current_state = self.current_state.clone();
 
         match &current_state {
-            RenderState::MainMenu => {
+            RenderState::MainMenu(_) => {
                 use crate::ui::page::main_menu::MainMenuAction;
                 let action = self.main_menu_page.render_ui(&ctx_egui);
                 match action {
@@ -286,16 +341,64 @@ impl Renderer {
             _ => {}
         }
 
+        if self.show_frame_stats {
+            render_frame_stats_overlay(&ctx_egui, &self.frame_stats, self.current_fps);
+        }
+
         self.ui
             .end_frame_and_draw(&self.ctx, &mut encoder, &swapchain_view);
+        // Seul submit de la frame : jeu puis UI, dans l'ordre d'enregistrement
         self.ctx.queue.submit(std::iter::once(encoder.finish()));
+        if std::mem::take(&mut self.screenshot_requested) {
+            // Dans l'éditeur on capture la prévisualisation, sinon l'écran complet
+            let texture = match &self.offscreen_texture {
+                Some(texture) if is_editor => texture,
+                _ => &output.texture,
+            };
+            self.take_screenshot(texture);
+        }
         output.present();
 
         Ok(actions_to_send)
     }
 
+    /// Reads back `texture` and saves it as a PNG on a background thread.
+    fn take_screenshot(&self, texture: &wgpu::Texture) {
+        if !texture.usage().contains(wgpu::TextureUsages::COPY_SRC) {
+            log::warn!("RENDER: Screenshots are not supported by this surface");
+            return;
+        }
+        let Some(image) = capture_frame(&self.ctx.device, &self.ctx.queue, texture) else {
+            return;
+        };
+        std::thread::spawn(move || match save_screenshot(&image) {
+            Ok(path) => log::info!("RENDER: Screenshot saved to {}", path.display()),
+            Err(e) => log::error!("RENDER: Failed to save screenshot: {e}"),
+        });
+    }
+
     /// Render editor preview to offscreen texture
     fn render_editor_offscreen(&mut self, encoder: &mut wgpu::CommandEncoder, window: &Window) {
         offscreen::render_editor_offscreen(self, encoder, window);
     }
 }
+
+/// Small corner overlay with the rolling frame-time statistics (toggled with F9).
+fn render_frame_stats_overlay(ctx: &egui::Context, stats: &FrameStats, fps: f64) {
+    let average_ms = stats.average_ms();
+    let low_ms = stats.one_percent_low_ms();
+    egui::Area::new(egui::Id::new("frame_stats_overlay"))
+        .anchor(egui::Align2::RIGHT_TOP, egui::vec2(-8.0, 8.0))
+        .interactable(false)
+        .show(ctx, |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                ui.monospace(format!("FPS     {fps:>7.0}"));
+                ui.monospace(format!("avg     {average_ms:>6.2}ms"));
+                ui.monospace(format!(
+                    "1% low  {low_ms:>6.2}ms ({:.0} FPS)",
+                    fps_from_ms(low_ms)
+                ));
+                ui.monospace(format!("frames  {:>7}", stats.len()));
+            });
+        });
+}
@@ -28,11 +28,40 @@ pub fn render(
             );
         }
 
+        let settings = &renderer.resources.settings;
+        if settings.playfield_alignment != snapshot.playfield_alignment
+            || settings.receptor_offset != snapshot.receptor_offset
+            || settings.note_scale != snapshot.note_scale
+            || settings.column_width_scale != snapshot.column_width_scale
+            || settings.palette != snapshot.palette
+            || settings.aspect_ratio_mode != snapshot.aspect_ratio_mode
+        {
+            renderer.resources.update_component_positions(
+                renderer.ctx.config.width as f32,
+                renderer.ctx.config.height as f32,
+            );
+        }
+
+        if renderer.resources.settings.frame_cap != snapshot.frame_cap {
+            let vsync = renderer.resources.settings.frame_cap.vsync();
+            renderer.ctx.set_vsync(vsync);
+        }
+
+        if renderer.resources.settings.display_mode != snapshot.display_mode
+            || renderer.resources.settings.monitor_index != snapshot.monitor_index
+        {
+            renderer.apply_display_mode();
+        }
+
         if let Some(volume) = result.volume_changed {
             actions.push(GameAction::UpdateVolume(volume));
         }
-        if let Some((mode, value)) = result.hit_window_changed {
-            actions.push(GameAction::UpdateHitWindow { mode, value });
+        if let Some((mode, value, custom_ms)) = result.hit_window_changed {
+            actions.push(GameAction::UpdateHitWindow {
+                mode,
+                value,
+                custom_ms,
+            });
         }
         if result.keybinds_updated {
             actions.push(GameAction::ReloadKeybinds);
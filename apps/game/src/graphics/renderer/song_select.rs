@@ -4,6 +4,7 @@ use super::Renderer;
 use crate::input::events::GameAction;
 use crate::state::MenuState;
 use crate::ui::page::song_select::UIPanelTextures;
+use crate::ui::song_select::difficulty_utils::rating_colors_with_palette;
 use crate::views::settings::{SettingsSnapshot, render_settings_window};
 
 pub fn render(
@@ -31,11 +32,40 @@ pub fn render(
             );
         }
 
+        let settings = &renderer.resources.settings;
+        if settings.playfield_alignment != snapshot.playfield_alignment
+            || settings.receptor_offset != snapshot.receptor_offset
+            || settings.note_scale != snapshot.note_scale
+            || settings.column_width_scale != snapshot.column_width_scale
+            || settings.palette != snapshot.palette
+            || settings.aspect_ratio_mode != snapshot.aspect_ratio_mode
+        {
+            renderer.resources.update_component_positions(
+                renderer.ctx.config.width as f32,
+                renderer.ctx.config.height as f32,
+            );
+        }
+
+        if renderer.resources.settings.frame_cap != snapshot.frame_cap {
+            let vsync = renderer.resources.settings.frame_cap.vsync();
+            renderer.ctx.set_vsync(vsync);
+        }
+
+        if renderer.resources.settings.display_mode != snapshot.display_mode
+            || renderer.resources.settings.monitor_index != snapshot.monitor_index
+        {
+            renderer.apply_display_mode();
+        }
+
         if let Some(volume) = result.volume_changed {
             actions.push(GameAction::UpdateVolume(volume));
         }
-        if let Some((mode, value)) = result.hit_window_changed {
-            actions.push(GameAction::UpdateHitWindow { mode, value });
+        if let Some((mode, value, custom_ms)) = result.hit_window_changed {
+            actions.push(GameAction::UpdateHitWindow {
+                mode,
+                value,
+                custom_ms,
+            });
         }
         if result.keybinds_updated {
             actions.push(GameAction::ReloadKeybinds);
@@ -54,16 +84,11 @@ pub fn render(
             (c[3] * 255.) as u8,
         )
     };
-    let hit_window = match renderer.resources.settings.hit_window_mode {
-        crate::models::settings::HitWindowMode::OsuOD => {
-            engine::hit_window::HitWindow::from_osu_od(renderer.resources.settings.hit_window_value)
-        }
-        crate::models::settings::HitWindowMode::EtternaJudge => {
-            engine::hit_window::HitWindow::from_etterna_judge(
-                renderer.resources.settings.hit_window_value as u8,
-            )
-        }
-    };
+    let hit_window = renderer.resources.settings.hit_window();
+    let rating_colors = rating_colors_with_palette(
+        &menus.song_select.rating_colors,
+        renderer.resources.settings.palette,
+    );
     let panel_textures = UIPanelTextures {
         beatmap_info_bg: renderer
             .resources
@@ -115,7 +140,7 @@ pub fn render(
             to_egui(menus.song_select.song_button.selected_border_color),
             to_egui(menus.song_select.difficulty_button.selected_text_color),
             &panel_textures,
-            Some(&menus.song_select.rating_colors),
+            Some(&rating_colors),
         );
 
     if let Some(calc_id) = calculator_changed {
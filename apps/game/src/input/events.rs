@@ -5,6 +5,7 @@
 
 use database::MenuSearchFilters;
 use std::collections::HashMap;
+use std::time::Instant;
 use winit::event::{ElementState, KeyEvent, WindowEvent};
 use winit::keyboard::{KeyCode, PhysicalKey};
 
@@ -78,15 +79,30 @@ impl std::fmt::Display for EditMode {
 pub enum GameAction {
     // Gameplay
     /// Key press on a column.
-    Hit { column: usize },
+    ///
+    /// `at` is when the input thread received the key event (`None` = when
+    /// the action is processed).
+    Hit { column: usize, at: Option<Instant> },
     /// Key release on a column.
-    Release { column: usize },
+    Release { column: usize, at: Option<Instant> },
     /// Restart the current map.
     Restart,
     /// Increase scroll speed by 10ms.
     ScrollSpeedUp,
     /// Decrease scroll speed by 10ms.
     ScrollSpeedDown,
+    /// Grow the notes without changing the column width.
+    NoteSizeUp,
+    /// Shrink the notes without changing the column width.
+    NoteSizeDown,
+    /// Widen the columns without changing the note size.
+    ColumnWidthUp,
+    /// Narrow the columns without changing the note size.
+    ColumnWidthDown,
+    /// Shift the offset of the current map later (saved per map).
+    BeatmapOffsetUp,
+    /// Shift the offset of the current map earlier (saved per map).
+    BeatmapOffsetDown,
 
     // Practice Mode (in-game)
     /// Place a checkpoint (max 1 every 15 seconds).
@@ -113,14 +129,22 @@ pub enum GameAction {
     SetSelection(usize),
     /// Set the selected difficulty by index.
     SetDifficulty(usize),
-
-    // Tabs / Settings
-    /// Switch to next tab.
-    TabNext,
-    /// Switch to previous tab.
-    TabPrev,
+    /// Filter the expanded beatmapset's difficulties by name.
+    FilterDifficulties(String),
+    /// Characters typed in the song wheel (jump to a matching title).
+    TypeAhead(String),
+    /// Select a random beatmapset among the filtered ones.
+    RandomSelect,
+
+    // Rate / Settings
+    /// Raise the song select rate by one step.
+    RateUp,
+    /// Lower the song select rate by one step.
+    RateDown,
     /// Toggle settings panel.
     ToggleSettings,
+    /// Show or hide the session recap.
+    ToggleSessionRecap,
     /// Update master volume.
     UpdateVolume(f32),
     /// Reload keybinds from disk.
@@ -149,6 +173,8 @@ pub enum GameAction {
     UpdateHitWindow {
         mode: crate::models::settings::HitWindowMode,
         value: f64,
+        /// Windows of the custom mode (in ms).
+        custom_ms: [f64; 6],
     },
 
     // Result screen
@@ -164,6 +190,39 @@ pub enum GameAction {
     // Mods
     /// Toggle a gameplay modifier.
     ToggleMod(crate::state::mods::GameMod),
+
+    // Leaderboard
+    /// Change which scores the leaderboard shows.
+    SetLeaderboardFilter(database::LeaderboardFilter),
+    /// Delete a stored replay (by hash) and refresh the leaderboard.
+    DeleteReplay(String),
+}
+
+impl GameAction {
+    /// Press on `column`, timed when processed.
+    pub fn hit(column: usize) -> Self {
+        GameAction::Hit { column, at: None }
+    }
+
+    /// Release on `column`, timed when processed.
+    pub fn release(column: usize) -> Self {
+        GameAction::Release { column, at: None }
+    }
+
+    /// Stamps a column press or release with the time its key event arrived.
+    pub fn stamped(self, at: Instant) -> Self {
+        match self {
+            GameAction::Hit { column, .. } => GameAction::Hit {
+                column,
+                at: Some(at),
+            },
+            GameAction::Release { column, .. } => GameAction::Release {
+                column,
+                at: Some(at),
+            },
+            action => action,
+        }
+    }
 }
 
 /// Commands sent to the input thread.
@@ -171,4 +230,11 @@ pub enum GameAction {
 pub enum InputCommand {
     /// Reload keybind configuration for the specified key count.
     ReloadKeybinds(HashMap<String, Vec<String>>, usize),
+    /// Reload the non-gameplay action keybinds.
+    ReloadActionKeybinds(HashMap<String, Vec<String>>),
+    /// The window lost focus: release every held key.
+    FocusLost,
+    /// Song select is open (or closed): typed letters go to the type-ahead
+    /// search instead of the letter bindings.
+    SetTextCapture(bool),
 }
@@ -1,28 +1,45 @@
 use super::events::{EditorTarget, GameAction, RawInputEvent};
 use super::keycode::parse_keycode;
-use crate::models::settings::SettingsState;
+use crate::models::settings::{BindableAction, SettingsState};
 use std::collections::{HashMap, HashSet};
 use winit::event::ElementState;
 use winit::keyboard::KeyCode;
 
 pub struct InputManager {
     bindings: HashMap<KeyCode, GameAction>,
+    /// User-configurable non-gameplay bindings, consulted before the
+    /// built-in shortcuts but after column keys.
+    action_bindings: HashMap<KeyCode, GameAction>,
     ctrl_left: bool,
     ctrl_right: bool,
     suppressed_keys: HashSet<KeyCode>,
+    /// Keys currently held down, used to drop OS auto-repeat presses.
+    pressed_keys: HashSet<KeyCode>,
+    /// Number of keys holding each column (several keys can share one).
+    column_holds: HashMap<usize, usize>,
+    /// Letters are typed into the song select type-ahead, not bound.
+    text_capture: bool,
 }
 
 impl InputManager {
     pub fn new() -> Self {
+        Self::from_settings(&SettingsState::load())
+    }
+
+    pub fn from_settings(settings: &SettingsState) -> Self {
         let mut manager = Self {
             bindings: HashMap::new(),
+            action_bindings: HashMap::new(),
             ctrl_left: false,
             ctrl_right: false,
             suppressed_keys: HashSet::new(),
+            pressed_keys: HashSet::new(),
+            column_holds: HashMap::new(),
+            text_capture: false,
         };
         manager.load_default_bindings();
-        let settings = SettingsState::load();
         manager.reload_keybinds(&settings.keybinds, 4); // Default to 4K
+        manager.reload_action_keybinds(&settings.action_keybinds);
         manager
     }
 
@@ -39,6 +56,16 @@ impl InputManager {
             _ => {}
         }
 
+        // Only down/up transitions count: a press of a held key is an
+        // auto-repeat, a release of a key never seen pressed is stale.
+        let is_transition = match event.state {
+            ElementState::Pressed => self.pressed_keys.insert(event.keycode),
+            ElementState::Released => self.pressed_keys.remove(&event.keycode),
+        };
+        if !is_transition {
+            return None;
+        }
+
         if self.suppressed_keys.contains(&event.keycode) {
             if event.state == ElementState::Released {
                 self.suppressed_keys.remove(&event.keycode);
@@ -54,22 +81,69 @@ impl InputManager {
             return Some(GameAction::ToggleSettings);
         }
 
-        if let Some(base_action) = self.bindings.get(&event.keycode) {
+        if self.text_capture && event.state == ElementState::Pressed && is_letter(event.keycode) {
+            // Its release is dropped too, even if the capture ends meanwhile
+            self.suppressed_keys.insert(event.keycode);
+            return None;
+        }
+
+        let base_action = match self.bindings.get(&event.keycode) {
+            Some(hit @ GameAction::Hit { .. }) => Some(hit),
+            builtin => self.action_bindings.get(&event.keycode).or(builtin),
+        };
+
+        if let Some(base_action) = base_action {
             match (event.state, base_action.clone()) {
-                (ElementState::Pressed, GameAction::Hit { column }) => {
-                    Some(GameAction::Hit { column })
-                }
-                (ElementState::Released, GameAction::Hit { column }) => {
-                    Some(GameAction::Release { column })
+                (ElementState::Pressed, hit @ GameAction::Hit { column, .. }) => {
+                    *self.column_holds.entry(column).or_default() += 1;
+                    Some(hit)
                 }
+                (ElementState::Released, GameAction::Hit { column, at }) => self
+                    .release_column(column)
+                    .then_some(GameAction::Release { column, at }),
                 (ElementState::Pressed, action) => Some(action),
                 _ => None,
             }
         } else {
-            match (event.state, event.keycode) {
-                (ElementState::Pressed, KeyCode::Escape) => Some(GameAction::Back),
-                (ElementState::Pressed, KeyCode::Enter) => Some(GameAction::Confirm),
-                _ => None,
+            None
+        }
+    }
+
+    /// Turns the song select text capture on or off (see
+    /// [`InputCommand::SetTextCapture`](super::events::InputCommand::SetTextCapture)).
+    pub fn set_text_capture(&mut self, capture: bool) {
+        self.text_capture = capture;
+    }
+
+    /// Forgets every held key, for when the window loses focus: their
+    /// releases would go to another window.
+    ///
+    /// Returns a release for each column that was held.
+    pub fn release_all(&mut self) -> Vec<GameAction> {
+        self.pressed_keys.clear();
+        self.suppressed_keys.clear();
+        self.ctrl_left = false;
+        self.ctrl_right = false;
+
+        let mut columns: Vec<usize> = self
+            .column_holds
+            .drain()
+            .map(|(column, _)| column)
+            .collect();
+        columns.sort_unstable();
+        columns.into_iter().map(GameAction::release).collect()
+    }
+
+    /// Counts a key of `column` going up; true once no key holds it anymore.
+    fn release_column(&mut self, column: usize) -> bool {
+        match self.column_holds.get_mut(&column) {
+            Some(count) if *count > 1 => {
+                *count -= 1;
+                false
+            }
+            _ => {
+                self.column_holds.remove(&column);
+                true
             }
         }
     }
@@ -82,12 +156,15 @@ impl InputManager {
         };
 
         let mut parsed = Vec::new();
-        for (idx, label) in entries.iter().enumerate() {
+        for (idx, entry) in entries.iter().enumerate() {
             if idx >= key_count {
                 break;
             }
-            if let Some(code) = parse_keycode(label) {
-                parsed.push((idx, code));
+            // A column can be bound to several keys
+            for label in settings::column_keys(entry) {
+                if let Some(code) = parse_keycode(label) {
+                    parsed.push((idx, code));
+                }
             }
         }
 
@@ -105,23 +182,40 @@ impl InputManager {
         }
 
         for (idx, code) in parsed {
-            self.bindings.insert(code, GameAction::Hit { column: idx });
+            self.bindings.insert(code, GameAction::hit(idx));
         }
 
         log::info!("INPUT: Loaded keybinds for {}K", key_count);
     }
 
+    /// Rebuilds the non-gameplay bindings (key = [`BindableAction::id`]).
+    ///
+    /// Actions missing from `keybinds` keep their default keys.
+    pub fn reload_action_keybinds(&mut self, keybinds: &HashMap<String, Vec<String>>) {
+        self.action_bindings.clear();
+        for &action in BindableAction::all() {
+            let labels: Vec<&str> = match keybinds.get(action.id()) {
+                Some(keys) => keys.iter().map(String::as_str).collect(),
+                None => action.default_keys().to_vec(),
+            };
+            for label in labels {
+                match parse_keycode(label) {
+                    Some(code) => {
+                        self.action_bindings.insert(code, game_action(action));
+                    }
+                    None => log::warn!("INPUT: Unknown key '{}' for {}", label, action.id()),
+                }
+            }
+        }
+        log::info!("INPUT: Loaded action keybinds");
+    }
+
     fn load_default_bindings(&mut self) {
         // Gameplay 4K
-        self.bindings
-            .insert(KeyCode::KeyD, GameAction::Hit { column: 0 });
-        self.bindings
-            .insert(KeyCode::KeyF, GameAction::Hit { column: 1 });
-        self.bindings
-            .insert(KeyCode::KeyJ, GameAction::Hit { column: 2 });
-        self.bindings
-            .insert(KeyCode::KeyK, GameAction::Hit { column: 3 });
-        self.bindings.insert(KeyCode::F5, GameAction::Restart);
+        self.bindings.insert(KeyCode::KeyD, GameAction::hit(0));
+        self.bindings.insert(KeyCode::KeyF, GameAction::hit(1));
+        self.bindings.insert(KeyCode::KeyJ, GameAction::hit(2));
+        self.bindings.insert(KeyCode::KeyK, GameAction::hit(3));
 
         // Practice Mode
         self.bindings
@@ -146,9 +240,7 @@ impl InputManager {
         self.bindings
             .insert(KeyCode::ArrowRight, GameAction::Navigation { x: 1, y: 0 });
 
-        // Tab / settings controls.
-        self.bindings.insert(KeyCode::PageUp, GameAction::TabPrev);
-        self.bindings.insert(KeyCode::PageDown, GameAction::TabNext);
+        // Settings controls.
         self.bindings
             .insert(KeyCode::KeyO, GameAction::ToggleSettings);
 
@@ -156,7 +248,7 @@ impl InputManager {
         self.bindings
             .insert(KeyCode::KeyE, GameAction::ToggleEditor); // F2 ou E
         self.bindings.insert(KeyCode::F2, GameAction::ToggleEditor);
-        self.bindings.insert(KeyCode::F8, GameAction::Rescan);
+        self.bindings.insert(KeyCode::F7, GameAction::RandomSelect);
 
         // Editor Selection Shortcuts
         self.bindings
@@ -190,3 +282,210 @@ impl InputManager {
             .insert(KeyCode::F10, GameAction::LaunchDebugMap);
     }
 }
+
+/// Letter keys, which song select reserves for the type-ahead search.
+fn is_letter(keycode: KeyCode) -> bool {
+    use KeyCode::*;
+    matches!(
+        keycode,
+        KeyA | KeyB
+            | KeyC
+            | KeyD
+            | KeyE
+            | KeyF
+            | KeyG
+            | KeyH
+            | KeyI
+            | KeyJ
+            | KeyK
+            | KeyL
+            | KeyM
+            | KeyN
+            | KeyO
+            | KeyP
+            | KeyQ
+            | KeyR
+            | KeyS
+            | KeyT
+            | KeyU
+            | KeyV
+            | KeyW
+            | KeyX
+            | KeyY
+            | KeyZ
+    )
+}
+
+/// Game action triggered by a bindable action.
+fn game_action(action: BindableAction) -> GameAction {
+    match action {
+        BindableAction::Back => GameAction::Back,
+        BindableAction::Confirm => GameAction::Confirm,
+        BindableAction::Rescan => GameAction::Rescan,
+        BindableAction::Restart => GameAction::Restart,
+        BindableAction::RateUp => GameAction::RateUp,
+        BindableAction::RateDown => GameAction::RateDown,
+        BindableAction::BeatmapOffsetUp => GameAction::BeatmapOffsetUp,
+        BindableAction::BeatmapOffsetDown => GameAction::BeatmapOffsetDown,
+        BindableAction::SessionRecap => GameAction::ToggleSessionRecap,
+        BindableAction::NoteSizeUp => GameAction::NoteSizeUp,
+        BindableAction::NoteSizeDown => GameAction::NoteSizeDown,
+        BindableAction::ColumnWidthUp => GameAction::ColumnWidthUp,
+        BindableAction::ColumnWidthDown => GameAction::ColumnWidthDown,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(manager: &mut InputManager, keycode: KeyCode) -> Option<GameAction> {
+        manager.process(RawInputEvent {
+            keycode,
+            state: ElementState::Pressed,
+        })
+    }
+
+    #[test]
+    fn test_remapped_confirm_key() {
+        let mut settings = SettingsState::new();
+        settings
+            .action_keybinds
+            .insert("confirm".to_string(), vec!["KeyQ".to_string()]);
+        let mut manager = InputManager::from_settings(&settings);
+
+        assert_eq!(
+            press(&mut manager, KeyCode::KeyQ),
+            Some(GameAction::Confirm)
+        );
+        assert_eq!(press(&mut manager, KeyCode::Enter), None);
+        // Other actions keep their defaults
+        assert_eq!(press(&mut manager, KeyCode::Escape), Some(GameAction::Back));
+        // Column keys are not overridden by actions
+        assert_eq!(press(&mut manager, KeyCode::KeyD), Some(GameAction::hit(0)));
+    }
+
+    #[test]
+    fn test_typed_letter_in_song_select_is_type_ahead() {
+        use crate::ui::song_select::song_list::type_ahead_action;
+
+        let mut manager = InputManager::from_settings(&SettingsState::new());
+        manager.set_text_capture(true);
+
+        // 'e' searches instead of opening the editor
+        assert_eq!(press(&mut manager, KeyCode::KeyE), None);
+        assert_eq!(
+            type_ahead_action(&[egui::Event::Text("e".to_string())]),
+            Some(GameAction::TypeAhead("e".to_string()))
+        );
+        // Non-letter shortcuts still work
+        assert_eq!(
+            press(&mut manager, KeyCode::F2),
+            Some(GameAction::ToggleEditor)
+        );
+
+        // Released during the capture, the key is bound again afterwards
+        manager.process(RawInputEvent {
+            keycode: KeyCode::KeyE,
+            state: ElementState::Released,
+        });
+        manager.set_text_capture(false);
+        assert_eq!(
+            press(&mut manager, KeyCode::KeyE),
+            Some(GameAction::ToggleEditor)
+        );
+    }
+
+    #[test]
+    fn test_default_action_keys_are_free() {
+        use crate::graphics::renderer::{FRAME_STATS_KEY, SCREENSHOT_KEY};
+
+        let manager = InputManager::from_settings(&SettingsState::new());
+        let mut taken: HashSet<KeyCode> = manager.bindings.keys().copied().collect();
+        taken.extend([FRAME_STATS_KEY, SCREENSHOT_KEY]);
+
+        for action in BindableAction::all() {
+            for label in action.default_keys() {
+                let code = parse_keycode(label).unwrap();
+                assert!(
+                    taken.insert(code),
+                    "{label} is bound twice ({})",
+                    action.id()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_rate_keys_change_rate() {
+        let mut manager = InputManager::from_settings(&SettingsState::new());
+        assert_eq!(
+            press(&mut manager, KeyCode::PageDown),
+            Some(GameAction::RateUp)
+        );
+        assert_eq!(
+            press(&mut manager, KeyCode::PageUp),
+            Some(GameAction::RateDown)
+        );
+    }
+
+    #[test]
+    fn test_key_repeat_yields_single_hit() {
+        let mut manager = InputManager::from_settings(&SettingsState::new());
+        let release = |manager: &mut InputManager| {
+            manager.process(RawInputEvent {
+                keycode: KeyCode::KeyD,
+                state: ElementState::Released,
+            })
+        };
+
+        let presses: Vec<_> = (0..5)
+            .filter_map(|_| press(&mut manager, KeyCode::KeyD))
+            .collect();
+        assert_eq!(presses, vec![GameAction::hit(0)]);
+        assert_eq!(release(&mut manager), Some(GameAction::release(0)));
+        assert_eq!(release(&mut manager), None);
+
+        // A new press after the release counts again
+        assert_eq!(press(&mut manager, KeyCode::KeyD), Some(GameAction::hit(0)));
+    }
+
+    #[test]
+    fn test_focus_loss_releases_held_columns() {
+        let mut manager = InputManager::from_settings(&SettingsState::new());
+        press(&mut manager, KeyCode::KeyD);
+        press(&mut manager, KeyCode::KeyJ);
+
+        assert_eq!(
+            manager.release_all(),
+            vec![GameAction::release(0), GameAction::release(2)]
+        );
+        assert!(manager.release_all().is_empty());
+        // The key counts as up: pressing it again is a new hit, not a repeat
+        assert_eq!(press(&mut manager, KeyCode::KeyD), Some(GameAction::hit(0)));
+    }
+
+    #[test]
+    fn test_two_keys_on_one_column() {
+        let mut settings = SettingsState::new();
+        settings.add_column_key(4, 0, "ArrowLeft".to_string());
+        let mut manager = InputManager::from_settings(&settings);
+
+        let release = |manager: &mut InputManager, keycode| {
+            manager.process(RawInputEvent {
+                keycode,
+                state: ElementState::Released,
+            })
+        };
+
+        for keycode in [KeyCode::KeyD, KeyCode::ArrowLeft] {
+            assert_eq!(press(&mut manager, keycode), Some(GameAction::hit(0)));
+        }
+        // The column stays held until its last key goes up
+        assert_eq!(release(&mut manager, KeyCode::ArrowLeft), None);
+        assert_eq!(
+            release(&mut manager, KeyCode::KeyD),
+            Some(GameAction::release(0))
+        );
+    }
+}
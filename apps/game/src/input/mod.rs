@@ -7,6 +7,7 @@ use crate::input::manager::InputManager;
 use crate::system::bus::SystemBus;
 use crossbeam_channel::select;
 use std::thread;
+use std::time::Instant;
 
 pub fn start_thread(bus: SystemBus, mut manager: InputManager) {
     thread::Builder::new()
@@ -21,8 +22,10 @@ pub fn start_thread(bus: SystemBus, mut manager: InputManager) {
                     recv(bus.raw_input_rx) -> raw => {
                         match raw {
                             Ok(raw_event) => {
+                                // Horodatage à la réception, avant tout traitement
+                                let received_at = Instant::now();
                                 if let Some(action) = manager.process(raw_event)
-                                    && let Err(e) = bus.action_tx.send(action) {
+                                    && let Err(e) = bus.action_tx.send(action.stamped(received_at)) {
                                         log::error!("INPUT: Failed to send action (Logic thread died?): {}", e);
                                         break;
                                     }
@@ -33,6 +36,14 @@ pub fn start_thread(bus: SystemBus, mut manager: InputManager) {
                     recv(bus.input_cmd_rx) -> cmd => {
                         match cmd {
                             Ok(InputCommand::ReloadKeybinds(map, key_count)) => manager.reload_keybinds(&map, key_count),
+                            Ok(InputCommand::ReloadActionKeybinds(map)) => manager.reload_action_keybinds(&map),
+                            Ok(InputCommand::FocusLost) => {
+                                let released_at = Instant::now();
+                                for action in manager.release_all() {
+                                    let _ = bus.action_tx.send(action.stamped(released_at));
+                                }
+                            }
+                            Ok(InputCommand::SetTextCapture(capture)) => manager.set_text_capture(capture),
                             Err(_) => break,
                         }
                     }
@@ -10,8 +10,44 @@ use database::DbManager;
 use std::thread;
 use std::time::{Duration, Instant};
 
-/// Target ticks per second for the logic thread.
-const TPS: u64 = 200;
+/// Most ticks run per loop iteration, so a long stall does not freeze the
+/// thread catching up.
+const MAX_TICKS_PER_ITERATION: u32 = 10;
+
+/// How often the count of snapshots replaced before rendering is logged.
+const DROPPED_SNAPSHOTS_LOG_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Fixed-timestep accumulator turning elapsed time into logic ticks.
+struct TickClock {
+    accumulator: Duration,
+    target_dt: Duration,
+}
+
+impl TickClock {
+    fn new(tick_rate: u32) -> Self {
+        Self {
+            accumulator: Duration::ZERO,
+            target_dt: Duration::from_secs(1) / tick_rate,
+        }
+    }
+
+    /// Duration of one tick.
+    fn target_dt(&self) -> Duration {
+        self.target_dt
+    }
+
+    /// Adds `delta` and returns how many ticks are due (at most
+    /// [`MAX_TICKS_PER_ITERATION`], the rest stays accumulated).
+    fn advance(&mut self, delta: Duration) -> u32 {
+        self.accumulator += delta;
+        let mut ticks = 0;
+        while self.accumulator >= self.target_dt && ticks < MAX_TICKS_PER_ITERATION {
+            self.accumulator -= self.target_dt;
+            ticks += 1;
+        }
+        ticks
+    }
+}
 
 /// Spawns the main logic thread that handles game state updates.
 ///
@@ -20,14 +56,16 @@ const TPS: u64 = 200;
 /// 2. Handles system events (resize, quit, etc.)
 /// 3. Updates game state at a fixed rate
 /// 4. Sends render snapshots to the render thread
-pub fn start_thread(bus: SystemBus, db_manager: DbManager) {
+///
+/// `tick_rate` is the number of updates per second.
+pub fn start_thread(bus: SystemBus, db_manager: DbManager, tick_rate: u32) {
     // Start the dedicated audio thread
     start_audio_thread(bus.clone());
 
     thread::Builder::new()
         .name("Logic Thread".to_string())
         .spawn(move || {
-            log::info!("LOGIC: Thread started");
+            log::info!("LOGIC: Thread started ({} TPS)", tick_rate);
 
             // Initialize and load database
             db_manager.init();
@@ -36,9 +74,10 @@ pub fn start_thread(bus: SystemBus, db_manager: DbManager) {
             let input_cmd_tx = bus.input_cmd_tx.clone();
             let mut state = GlobalState::new(db_manager, input_cmd_tx, bus.clone());
 
-            let mut accumulator = Duration::new(0, 0);
+            let mut clock = TickClock::new(tick_rate);
             let mut last_time = Instant::now();
-            let target_dt = Duration::from_secs_f64(1.0 / TPS as f64);
+            let target_dt = clock.target_dt();
+            let mut last_dropped_log = Instant::now();
 
             loop {
                 // 1. Process input actions
@@ -65,22 +104,27 @@ pub fn start_thread(bus: SystemBus, db_manager: DbManager) {
                 let current_time = Instant::now();
                 let delta = current_time - last_time;
                 last_time = current_time;
-                accumulator += delta;
 
-                let mut updated = false;
-                let mut loops = 0;
-                while accumulator >= target_dt && loops < 10 {
+                let loops = clock.advance(delta);
+                for _ in 0..loops {
                     state.update(target_dt.as_secs_f64());
-                    accumulator -= target_dt;
-                    loops += 1;
-                    updated = true;
                 }
+                let updated = loops > 0;
 
                 // 4. Send render snapshot only if we updated
                 // This avoids sending duplicate snapshots with the same audio time
                 if updated {
                     let snapshot = state.create_snapshot();
-                    let _ = bus.render_tx.try_send(snapshot);
+                    bus.render_tx.send(snapshot);
+                }
+                if current_time.duration_since(last_dropped_log) >= DROPPED_SNAPSHOTS_LOG_INTERVAL {
+                    last_dropped_log = current_time;
+                    let dropped = bus.render_tx.take_dropped();
+                    if dropped > 0 {
+                        // Normal when rendering slower than the tick rate; a spike means
+                        // the render thread stalled
+                        log::debug!("LOGIC: {} snapshots replaced before rendering", dropped);
+                    }
                 }
                 state.frame_end();
 
@@ -92,3 +136,34 @@ pub fn start_thread(bus: SystemBus, db_manager: DbManager) {
         })
         .expect("Failed to spawn Logic thread");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tick_clock_runs_configured_rate() {
+        let mut clock = TickClock::new(200);
+        assert_eq!(clock.target_dt(), Duration::from_millis(5));
+        // 12 ms at 200 TPS: two ticks, 2 ms carried over
+        assert_eq!(clock.advance(Duration::from_millis(12)), 2);
+        assert_eq!(clock.advance(Duration::from_millis(3)), 1);
+        assert_eq!(clock.advance(Duration::from_millis(4)), 0);
+
+        // One second always yields the configured rate
+        let mut clock = TickClock::new(500);
+        let ticks: u32 = (0..1000)
+            .map(|_| clock.advance(Duration::from_millis(1)))
+            .sum();
+        assert_eq!(ticks, 500);
+
+        // A stall is caught up over several iterations
+        let mut clock = TickClock::new(1000);
+        assert_eq!(
+            clock.advance(Duration::from_millis(25)),
+            MAX_TICKS_PER_ITERATION
+        );
+        assert_eq!(clock.advance(Duration::ZERO), MAX_TICKS_PER_ITERATION);
+        assert_eq!(clock.advance(Duration::ZERO), 5);
+    }
+}
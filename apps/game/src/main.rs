@@ -6,7 +6,7 @@
 //!
 //! The application uses a multi-threaded architecture:
 //! - **Main/Render thread**: Window events and GPU rendering (wgpu)
-//! - **Logic thread**: Game state at 200 TPS with fixed timestep
+//! - **Logic thread**: Game state at a fixed timestep (200 TPS by default)
 //! - **Input thread**: Keyboard input processing and keybind mapping
 //! - **Audio thread**: Dedicated audio playback with pitch shifting
 //!
@@ -26,8 +26,10 @@ mod state;
 mod ui;
 mod views;
 
+use crate::models::settings::SettingsState;
 use crate::system::bus::SystemBus;
 use database::DbManager;
+use settings::Profile;
 use std::path::PathBuf;
 
 /// Application entry point.
@@ -43,6 +45,15 @@ fn main() {
 
     log::info!("MAIN: Booting Prism...");
 
+    // Select the player profile before anything reads the settings
+    let profile = Profile::from_args(std::env::args().skip(1)).unwrap_or_default();
+    log::info!(
+        "MAIN: Profile '{}' (available: {})",
+        profile.name(),
+        Profile::list().join(", ")
+    );
+    Profile::set_active(profile);
+
     // Create the central communication hub
     let bus = SystemBus::new();
 
@@ -51,16 +62,25 @@ fn main() {
     let render_bus = bus.clone();
 
     // Initialize database manager
-    let db_path = PathBuf::from("main.db");
+    let settings = SettingsState::load();
+    ui::i18n::set_locale(ui::i18n::Locale::load(&settings.language));
+    let db_path = settings.db_path();
+    log::info!("MAIN: Database at {:?}", db_path);
+    database::replay_storage::set_replay_dir(settings.replay_dir());
     let songs_path = PathBuf::from("songs");
-    let db_manager = DbManager::new(db_path, songs_path);
+    let mut db_manager = DbManager::new(db_path, songs_path);
+    if settings.watch_songs
+        && let Err(e) = db_manager.watch_songs()
+    {
+        log::warn!("MAIN: Songs folder not watched: {}", e);
+    }
 
     // Initialize input manager
     let input_manager = input::manager::InputManager::new();
 
     // Spawn worker threads
     input::start_thread(input_bus, input_manager);
-    logic::start_thread(logic_bus, db_manager);
+    logic::start_thread(logic_bus, db_manager, settings.tick_rate());
 
     // Run the render loop (blocking)
     render::app::App::run(render_bus);
@@ -1,11 +1,33 @@
 //! User settings and configuration.
 //!
-//! This module handles loading/saving settings from `settings.toml`
-//! and provides the configuration UI state.
+//! This module handles loading/saving settings from the `settings.toml` of
+//! the active profile (see [`settings::Profile`]) and provides the
+//! configuration UI state.
 
+use chart::AnalysisLimits;
+use engine::{ComboBreak, HealthPreset, HitWindow, Palette, ScoringModel, US_PER_MS};
 use serde::{Deserialize, Serialize};
+pub use settings::BindableAction;
 use std::collections::HashMap;
 use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// Per-beatmap offset change of one nudge keypress (in ms).
+pub const BEATMAP_OFFSET_STEP_MS: f64 = 5.0;
+
+/// Note size / column width scale change of one keypress.
+pub const PLAYFIELD_SCALE_STEP: f32 = 0.05;
+
+/// Accepted note size and column width scales.
+pub const PLAYFIELD_SCALE_RANGE: std::ops::RangeInclusive<f32> = 0.5..=2.0;
+
+/// Moves a note size / column width scale by `step`, snapped to the step
+/// grid and bounded to [`PLAYFIELD_SCALE_RANGE`].
+pub fn step_playfield_scale(scale: f32, step: f32) -> f32 {
+    let snapped = ((scale + step) / PLAYFIELD_SCALE_STEP).round() * PLAYFIELD_SCALE_STEP;
+    snapped.clamp(*PLAYFIELD_SCALE_RANGE.start(), *PLAYFIELD_SCALE_RANGE.end())
+}
 
 /// Hit window calculation mode.
 #[derive(
@@ -25,6 +47,86 @@ pub enum HitWindowMode {
     OsuOD,
     /// Etterna/Quaver judge level based timing.
     EtternaJudge,
+    /// Windows given directly in milliseconds (Quaver, O2Jam...).
+    Custom,
+}
+
+impl HitWindowMode {
+    /// Builds the hit window of this mode.
+    ///
+    /// `value` is the OD or judge level; `custom_ms` is only read by `Custom`.
+    pub fn hit_window(self, value: f64, custom_ms: [f64; 6]) -> HitWindow {
+        match self {
+            HitWindowMode::OsuOD => HitWindow::from_osu_od(value),
+            HitWindowMode::EtternaJudge => HitWindow::from_etterna_judge(value as u8),
+            HitWindowMode::Custom => HitWindow::from_windows_ms(custom_ms),
+        }
+    }
+
+    /// Name of the mode in the settings.
+    pub fn name(self) -> &'static str {
+        match self {
+            HitWindowMode::OsuOD => "Osu! Overall Diff",
+            HitWindowMode::EtternaJudge => "Etterna Judge",
+            HitWindowMode::Custom => "Custom",
+        }
+    }
+
+    /// Mode and value as shown on the result screen (e.g. "OD 8.0", "Judge 4").
+    pub fn label(self, value: f64) -> String {
+        self.format_label(value, "Judge ")
+    }
+
+    /// Compact [`label`](Self::label) for song select (e.g. "J4").
+    pub fn short_label(self, value: f64) -> String {
+        self.format_label(value, "J")
+    }
+
+    fn format_label(self, value: f64, judge_prefix: &str) -> String {
+        match self {
+            HitWindowMode::OsuOD => format!("OD {value:.1}"),
+            HitWindowMode::EtternaJudge => format!("{judge_prefix}{value:.0}"),
+            HitWindowMode::Custom => self.name().to_string(),
+        }
+    }
+}
+
+/// Everything the hit window of a play is built from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HitWindowSettings {
+    /// Hit window calculation mode.
+    pub mode: HitWindowMode,
+    /// OD or judge level.
+    pub value: f64,
+    /// Windows of the custom mode in ms (`[marv, perfect, great, good, bad, miss]`).
+    pub custom_ms: [f64; 6],
+}
+
+impl HitWindowSettings {
+    /// Hit window the notes are judged with.
+    pub fn hit_window(&self) -> HitWindow {
+        self.mode.hit_window(self.value, self.custom_ms)
+    }
+}
+
+impl Default for HitWindowSettings {
+    fn default() -> Self {
+        Self {
+            mode: HitWindowMode::OsuOD,
+            value: 5.0,
+            custom_ms: settings::DEFAULT_CUSTOM_HIT_WINDOW_MS,
+        }
+    }
+}
+
+impl From<HitWindowMode> for engine::HitWindowMode {
+    fn from(mode: HitWindowMode) -> Self {
+        match mode {
+            HitWindowMode::OsuOD => engine::HitWindowMode::OsuOD,
+            HitWindowMode::EtternaJudge => engine::HitWindowMode::EtternaJudge,
+            HitWindowMode::Custom => engine::HitWindowMode::Custom,
+        }
+    }
 }
 
 /// Aspect ratio mode for the playfield.
@@ -38,6 +140,290 @@ pub enum AspectRatioMode {
     Ratio4_3,
 }
 
+impl AspectRatioMode {
+    /// Forced playfield ratio (width / height), `None` for the window's own.
+    pub fn ratio(&self) -> Option<f32> {
+        match self {
+            AspectRatioMode::Auto => None,
+            AspectRatioMode::Ratio16_9 => Some(16.0 / 9.0),
+            AspectRatioMode::Ratio4_3 => Some(4.0 / 3.0),
+        }
+    }
+}
+
+/// Horizontal placement of the playfield.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum PlayfieldAlignment {
+    /// Keep the position defined by the skin.
+    #[default]
+    Skin,
+    /// Flush against the left edge of the screen.
+    Left,
+    /// Centered on the screen.
+    Center,
+    /// Flush against the right edge of the screen.
+    Right,
+}
+
+impl PlayfieldAlignment {
+    /// Returns all alignments, in display order.
+    pub fn all() -> &'static [PlayfieldAlignment] {
+        &[
+            PlayfieldAlignment::Skin,
+            PlayfieldAlignment::Left,
+            PlayfieldAlignment::Center,
+            PlayfieldAlignment::Right,
+        ]
+    }
+
+    /// Returns a user-friendly display name.
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            PlayfieldAlignment::Skin => "Skin default",
+            PlayfieldAlignment::Left => "Left",
+            PlayfieldAlignment::Center => "Center",
+            PlayfieldAlignment::Right => "Right",
+        }
+    }
+
+    /// Horizontal offset (pixels from the screen center) of the playfield
+    /// center, or `None` to use the skin position.
+    pub fn playfield_x_offset(&self, screen_width: f32, playfield_width: f32) -> Option<f32> {
+        let edge = ((screen_width - playfield_width) / 2.0).max(0.0);
+        match self {
+            PlayfieldAlignment::Skin => None,
+            PlayfieldAlignment::Left => Some(-edge),
+            PlayfieldAlignment::Center => Some(0.0),
+            PlayfieldAlignment::Right => Some(edge),
+        }
+    }
+}
+
+/// Frame rate limit of the render loop.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum FrameCap {
+    /// Uncapped: redraw as fast as possible.
+    #[default]
+    Off,
+    Fps60,
+    Fps144,
+    Fps240,
+    /// Synchronized with the display refresh rate.
+    VSync,
+}
+
+impl FrameCap {
+    /// Returns all frame caps, in display order.
+    pub fn all() -> &'static [FrameCap] {
+        &[
+            FrameCap::Off,
+            FrameCap::Fps60,
+            FrameCap::Fps144,
+            FrameCap::Fps240,
+            FrameCap::VSync,
+        ]
+    }
+
+    /// Returns a user-friendly display name.
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            FrameCap::Off => "Unlimited",
+            FrameCap::Fps60 => "60 FPS",
+            FrameCap::Fps144 => "144 FPS",
+            FrameCap::Fps240 => "240 FPS",
+            FrameCap::VSync => "VSync",
+        }
+    }
+
+    /// Frame rate enforced by the event loop, if any.
+    pub fn target_fps(&self) -> Option<u32> {
+        match self {
+            FrameCap::Off | FrameCap::VSync => None,
+            FrameCap::Fps60 => Some(60),
+            FrameCap::Fps144 => Some(144),
+            FrameCap::Fps240 => Some(240),
+        }
+    }
+
+    /// Whether the surface should present with VSync.
+    pub fn vsync(&self) -> bool {
+        *self == FrameCap::VSync
+    }
+
+    /// When the next frame should start, or `None` to redraw immediately.
+    ///
+    /// Deadlines advance by a fixed interval from `previous` so the pace
+    /// doesn't drift; a frame that ran late restarts the schedule at `now`
+    /// instead of rushing to catch up.
+    pub fn next_deadline(&self, previous: Instant, now: Instant) -> Option<Instant> {
+        let fps = self.target_fps()?;
+        let next = previous + Duration::from_secs(1) / fps;
+        Some(next.max(now))
+    }
+}
+
+/// How the game window occupies the screen.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum DisplayMode {
+    /// Regular resizable window.
+    #[default]
+    Windowed,
+    /// Borderless window covering the whole monitor.
+    Borderless,
+    /// Exclusive fullscreen using the monitor's best video mode.
+    Exclusive,
+}
+
+impl DisplayMode {
+    /// Returns all display modes, in display order.
+    pub fn all() -> &'static [DisplayMode] {
+        &[
+            DisplayMode::Windowed,
+            DisplayMode::Borderless,
+            DisplayMode::Exclusive,
+        ]
+    }
+
+    /// Returns a user-friendly display name.
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            DisplayMode::Windowed => "Windowed",
+            DisplayMode::Borderless => "Borderless",
+            DisplayMode::Exclusive => "Fullscreen (exclusive)",
+        }
+    }
+}
+
+/// Fade band used by the note visibility mods.
+///
+/// Positions are expressed as scroll progress: `0.0` is the receptor line and
+/// `1.0` is the top of the visible area. Notes are fully transparent at
+/// `transparent_at`, fully opaque at `opaque_at`, and linearly faded in between.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct VisibilityBand {
+    pub transparent_at: f32,
+    pub opaque_at: f32,
+}
+
+impl VisibilityBand {
+    /// Default band for the Hidden mod (notes vanish close to the receptors).
+    pub fn hidden() -> Self {
+        Self {
+            transparent_at: 0.3,
+            opaque_at: 0.55,
+        }
+    }
+
+    /// Default band for the FadeIn mod (notes appear only once close enough).
+    pub fn fade_in() -> Self {
+        Self {
+            transparent_at: 0.85,
+            opaque_at: 0.6,
+        }
+    }
+}
+
+/// Combo counts that trigger a milestone popup during gameplay.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ComboMilestoneConfig {
+    /// Whether milestone popups are shown at all.
+    pub enabled: bool,
+    /// Combo counts to celebrate (e.g. 100, 200).
+    pub thresholds: Vec<u32>,
+    /// Whether finishing a map without breaking combo is celebrated.
+    pub full_combo: bool,
+}
+
+impl Default for ComboMilestoneConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            thresholds: vec![100, 200, 500, 1000],
+            full_combo: true,
+        }
+    }
+}
+
+/// Darkening of the gameplay background behind the playfield.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BackgroundDim {
+    /// Opacity of the black overlay (0 = untouched, 1 = black).
+    pub dim: f32,
+    /// Whether the overlay fades out as the combo grows.
+    pub combo_reactive: bool,
+    /// Overlay opacity once the combo reaches `full_combo_at`.
+    pub min_dim: f32,
+    /// Combo at which the reactive dim bottoms out at `min_dim`.
+    pub full_combo_at: u32,
+}
+
+impl BackgroundDim {
+    /// Overlay opacity for the current combo.
+    ///
+    /// Static unless `combo_reactive` is set, in which case it goes linearly
+    /// from `dim` at combo 0 to `min_dim` at `full_combo_at`.
+    pub fn overlay_alpha(&self, combo: u32) -> f32 {
+        let dim = self.dim.clamp(0.0, 1.0);
+        if !self.combo_reactive || self.full_combo_at == 0 {
+            return dim;
+        }
+        let min_dim = self.min_dim.clamp(0.0, dim);
+        let progress = (combo as f32 / self.full_combo_at as f32).min(1.0);
+        dim + (min_dim - dim) * progress
+    }
+}
+
+impl Default for BackgroundDim {
+    fn default() -> Self {
+        Self {
+            dim: 1.0,
+            combo_reactive: false,
+            min_dim: 0.5,
+            full_combo_at: 500,
+        }
+    }
+}
+
+/// Song select background: the selected beatmap's image.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MenuBackground {
+    /// Darkening of the image (0 = untouched, 1 = black).
+    pub dim: f32,
+    /// Blur radius in texture pixels (0 = sharp).
+    pub blur: f32,
+    /// Duration of the crossfade when the selection changes (in ms).
+    pub crossfade_ms: f32,
+}
+
+impl MenuBackground {
+    /// Color multiplier applied to the image.
+    pub fn brightness(&self) -> f32 {
+        1.0 - self.dim.clamp(0.0, 1.0)
+    }
+
+    /// Opacity of the new background `elapsed` after the selection changed.
+    ///
+    /// Eases from 0 to 1 over `crossfade_ms` (smoothstep); a zero duration
+    /// switches instantly.
+    pub fn crossfade_alpha(&self, elapsed: Duration) -> f32 {
+        if self.crossfade_ms <= 0.0 {
+            return 1.0;
+        }
+        let t = (elapsed.as_secs_f32() * 1000.0 / self.crossfade_ms).clamp(0.0, 1.0);
+        t * t * (3.0 - 2.0 * t)
+    }
+}
+
+impl Default for MenuBackground {
+    fn default() -> Self {
+        Self {
+            dim: 0.6,
+            blur: 0.0,
+            crossfade_ms: 300.0,
+        }
+    }
+}
+
 /// Persistent user settings.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SettingsState {
@@ -50,17 +436,118 @@ pub struct SettingsState {
     /// Negative values = notes appear earlier (audio is early/notes late).
     #[serde(default)]
     pub global_audio_offset_ms: f64,
+    /// Per-beatmap offsets in milliseconds (key = beatmap hash), added to
+    /// the global offset.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub beatmap_offsets: HashMap<String, f64>,
+    /// Play the per-note samples of keysounded maps on hit.
+    #[serde(default = "SettingsState::default_keysounds")]
+    pub keysounds: bool,
+    /// Mute the music track of keysounded maps (the keysounds carry the song).
+    #[serde(default)]
+    pub mute_music_on_keysounds: bool,
     /// Hit window calculation mode.
     pub hit_window_mode: HitWindowMode,
     /// Hit window value (OD or judge level).
     pub hit_window_value: f64,
+    /// Windows of the custom mode in ms (`[marv, perfect, great, good, bad, miss]`).
+    #[serde(default = "SettingsState::default_custom_hit_window_ms")]
+    pub custom_hit_window_ms: [f64; 6],
+    /// Scoring model (raw point sum or normalized to 1,000,000).
+    #[serde(default)]
+    pub scoring_model: ScoringModel,
+    /// Lowest judgement that breaks combo (default: only misses).
+    #[serde(default)]
+    pub combo_break_rule: ComboBreak,
+    /// Life bar drain preset (off by default).
+    #[serde(default)]
+    pub health_preset: HealthPreset,
+    /// Fail the run once the life bar is empty.
+    #[serde(default)]
+    pub fail_on_empty_health: bool,
+    /// Save runs quit before the end as incomplete replays.
+    #[serde(default)]
+    pub save_abandoned_runs: bool,
     /// Aspect ratio mode.
     pub aspect_ratio_mode: AspectRatioMode,
+    /// Playfield alignment (overrides the skin position unless `Skin`).
+    #[serde(default)]
+    pub playfield_alignment: PlayfieldAlignment,
+    /// Raises the receptor line above its default height, in pixels.
+    #[serde(default)]
+    pub receptor_offset: f32,
+    /// Scale of the skin's note size, independent of the column width.
+    #[serde(default = "SettingsState::default_playfield_scale")]
+    pub note_scale: f32,
+    /// Scale of the skin's column widths, independent of the note size.
+    #[serde(default = "SettingsState::default_playfield_scale")]
+    pub column_width_scale: f32,
+    /// Frame rate limit.
+    #[serde(default)]
+    pub frame_cap: FrameCap,
+    /// Interpolate note positions between logic ticks (extrapolate if off).
+    #[serde(default = "SettingsState::default_snapshot_interpolation")]
+    pub snapshot_interpolation: bool,
+    /// Flash receptors in the judgement color on hit.
+    #[serde(default = "SettingsState::default_receptor_glow")]
+    pub receptor_glow: bool,
+    /// Colorblind-friendly judgement and rating colors (skin colors by default).
+    #[serde(default)]
+    pub palette: Palette,
+    /// Windowed or fullscreen display.
+    #[serde(default)]
+    pub display_mode: DisplayMode,
+    /// Monitor used in fullscreen (index in the system's monitor list).
+    #[serde(default)]
+    pub monitor_index: usize,
     /// Current skin name.
     pub current_skin: String,
+    /// Language of the UI strings (a file of `assets/locales`). Read at launch.
+    #[serde(default = "SettingsState::default_language")]
+    pub language: String,
+    /// Fade band applied when the Hidden mod is active.
+    #[serde(default = "VisibilityBand::hidden")]
+    pub hidden_band: VisibilityBand,
+    /// Fade band applied when the FadeIn mod is active.
+    #[serde(default = "VisibilityBand::fade_in")]
+    pub fade_in_band: VisibilityBand,
+    /// Combo milestone popups.
+    #[serde(default)]
+    pub combo_milestones: ComboMilestoneConfig,
+    /// Gameplay background dim.
+    #[serde(default)]
+    pub background_dim: BackgroundDim,
+    /// Song select background dim, blur and crossfade.
+    #[serde(default)]
+    pub menu_background: MenuBackground,
+    /// Charts with more notes than this are not rated (keeps the menu responsive).
+    #[serde(default = "SettingsState::default_max_analyzed_notes")]
+    pub max_analyzed_notes: usize,
+    /// Rates rated in song select.
+    #[serde(default)]
+    pub rate_range: settings::RateRange,
+    /// Directory of the score database (`None` = the profile directory).
+    /// Read at launch.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data_dir: Option<PathBuf>,
+    /// Rescan beatmapsets added or removed while the game runs. Read at launch.
+    #[serde(default = "SettingsState::default_watch_songs")]
+    pub watch_songs: bool,
+    /// Unlock lifetime achievements with the saved runs.
+    #[serde(default = "SettingsState::default_track_achievements")]
+    pub track_achievements: bool,
+    /// Logic ticks per second (see [`SettingsState::tick_rate`]). Read at launch.
+    #[serde(default = "SettingsState::default_tick_rate")]
+    pub tick_rate: u32,
+    /// Size of menus and panels (see [`SettingsState::ui_scale`]).
+    #[serde(default = "SettingsState::default_ui_scale")]
+    pub ui_scale: f32,
 
     /// Keybinds per key count (key = "4", "5", etc.).
     pub keybinds: HashMap<String, Vec<String>>,
+    /// Keybinds of non-gameplay actions (key = [`BindableAction::id`]).
+    #[serde(default = "settings::default_action_keybinds")]
+    pub action_keybinds: HashMap<String, Vec<String>>,
 
     /// Whether settings panel is open (UI state, not persisted).
     #[serde(skip)]
@@ -74,6 +561,12 @@ pub struct SettingsState {
     /// Buffer for keys being captured during remapping.
     #[serde(skip)]
     pub remapping_buffer: Vec<String>,
+    /// Action being remapped (if any).
+    #[serde(skip)]
+    pub remapping_action: Option<BindableAction>,
+    /// Column (key count, column) waiting for an additional key.
+    #[serde(skip)]
+    pub adding_column_key: Option<(usize, usize)>,
 }
 
 impl SettingsState {
@@ -83,69 +576,197 @@ impl SettingsState {
             master_volume: 0.5,
             scroll_speed: 500.0,
             global_audio_offset_ms: 0.0,
+            beatmap_offsets: HashMap::new(),
+            keysounds: Self::default_keysounds(),
+            mute_music_on_keysounds: false,
             hit_window_mode: HitWindowMode::OsuOD,
             hit_window_value: 5.0,
+            custom_hit_window_ms: Self::default_custom_hit_window_ms(),
+            scoring_model: ScoringModel::default(),
+            combo_break_rule: ComboBreak::default(),
+            health_preset: HealthPreset::default(),
+            fail_on_empty_health: false,
+            save_abandoned_runs: false,
             aspect_ratio_mode: AspectRatioMode::Auto,
+            playfield_alignment: PlayfieldAlignment::default(),
+            receptor_offset: 0.0,
+            note_scale: Self::default_playfield_scale(),
+            column_width_scale: Self::default_playfield_scale(),
+            frame_cap: FrameCap::default(),
+            snapshot_interpolation: Self::default_snapshot_interpolation(),
+            receptor_glow: Self::default_receptor_glow(),
+            palette: Palette::default(),
+            display_mode: DisplayMode::default(),
+            monitor_index: 0,
             current_skin: "default".to_string(),
+            language: Self::default_language(),
+            hidden_band: VisibilityBand::hidden(),
+            fade_in_band: VisibilityBand::fade_in(),
+            combo_milestones: ComboMilestoneConfig::default(),
+            background_dim: BackgroundDim::default(),
+            menu_background: MenuBackground::default(),
+            max_analyzed_notes: Self::default_max_analyzed_notes(),
+            rate_range: settings::RateRange::default(),
+            data_dir: None,
+            watch_songs: Self::default_watch_songs(),
+            track_achievements: Self::default_track_achievements(),
+            tick_rate: Self::default_tick_rate(),
+            ui_scale: Self::default_ui_scale(),
             keybinds: Self::default_keybinds(),
+            action_keybinds: settings::default_action_keybinds(),
 
             is_open: false,
             show_keybindings: false,
             remapping_column: None,
             remapping_buffer: Vec::new(),
+            remapping_action: None,
+            adding_column_key: None,
         }
     }
 
-    /// Loads settings from `settings.toml`, or returns defaults if not found.
+    /// Loads the profile settings, or returns defaults if not found.
+    ///
+    /// A file that fails to parse is backed up before the defaults are used.
     pub fn load() -> Self {
-        if let Ok(content) = fs::read_to_string("settings.toml") {
+        let path = settings::Profile::active().settings_path();
+        if let Ok(content) = fs::read_to_string(&path) {
             if let Ok(mut settings) = toml::from_str::<SettingsState>(&content) {
                 settings.is_open = false;
                 settings.show_keybindings = false;
                 settings.remapping_column = None;
                 settings.remapping_buffer = Vec::new();
+                settings.remapping_action = None;
+                settings.adding_column_key = None;
 
                 if settings.keybinds.is_empty() {
                     settings.keybinds = Self::default_keybinds();
                 }
                 return settings;
             }
-            eprintln!("Failed to parse settings.toml, using defaults.");
+            match settings::backup_invalid_file(&path) {
+                Ok(backup) => eprintln!(
+                    "Failed to parse settings.toml, using defaults (backed up to {}).",
+                    backup.display()
+                ),
+                Err(e) => eprintln!("Failed to parse settings.toml, using defaults ({e})."),
+            }
         }
         Self::new()
     }
 
-    /// Saves settings to `settings.toml`.
+    /// Saves settings to the profile's `settings.toml`, atomically.
     pub fn save(&self) {
+        let path = settings::Profile::active().settings_path();
         match toml::to_string_pretty(self) {
             Ok(content) => {
-                if let Err(e) = fs::write("settings.toml", content) {
-                    eprintln!("Failed to write settings.toml: {e}");
+                if let Err(e) = settings::write_atomic(&path, content) {
+                    eprintln!("Failed to write {}: {e}", path.display());
                 }
             }
             Err(e) => eprintln!("Failed to serialize settings: {e}"),
         }
     }
 
+    /// Score database of the active profile.
+    pub fn db_path(&self) -> PathBuf {
+        settings::Profile::active().db_path(self.data_dir.as_deref())
+    }
+
+    /// Replay directory of the active profile.
+    pub fn replay_dir(&self) -> PathBuf {
+        settings::Profile::active().replay_dir(self.data_dir.as_deref())
+    }
+
     /// Resets keybinds to defaults.
     pub fn reset_keybinds(&mut self) {
         self.keybinds = Self::default_keybinds();
+        self.action_keybinds = settings::default_action_keybinds();
     }
 
     /// Begins capturing keybinds for a specific column count.
     pub fn begin_keybind_capture(&mut self, columns: usize) {
         self.remapping_column = Some(columns);
+        self.remapping_action = None;
+        self.adding_column_key = None;
         self.remapping_buffer.clear();
     }
 
+    /// Begins capturing an additional key for one column.
+    pub fn begin_column_key_capture(&mut self, key_count: usize, column: usize) {
+        self.cancel_keybind_capture();
+        self.adding_column_key = Some((key_count, column));
+    }
+
+    /// Begins capturing the key of a non-gameplay action.
+    pub fn begin_action_capture(&mut self, action: BindableAction) {
+        self.cancel_keybind_capture();
+        self.remapping_action = Some(action);
+    }
+
     /// Cancels the current keybind capture.
     pub fn cancel_keybind_capture(&mut self) {
         self.remapping_column = None;
+        self.remapping_action = None;
+        self.adding_column_key = None;
         self.remapping_buffer.clear();
     }
 
+    /// Whether a column or action remapping is waiting for keys.
+    pub fn is_capturing_keybind(&self) -> bool {
+        self.remapping_column.is_some()
+            || self.remapping_action.is_some()
+            || self.adding_column_key.is_some()
+    }
+
+    /// Keys bound to each column of a key count.
+    pub fn column_keys(&self, key_count: usize) -> Vec<Vec<String>> {
+        let entries = self.keybinds.get(&key_count.to_string());
+        (0..key_count)
+            .map(|column| {
+                entries
+                    .and_then(|entries| entries.get(column))
+                    .map(|entry| settings::column_keys(entry).map(str::to_string).collect())
+                    .unwrap_or_default()
+            })
+            .collect()
+    }
+
+    /// Binds an additional key to a column, keeping its current keys.
+    pub fn add_column_key(&mut self, key_count: usize, column: usize, key_label: String) {
+        settings::add_column_key(&mut self.keybinds, key_count, column, &key_label);
+    }
+
+    /// Unbinds one key of a column.
+    pub fn remove_column_key(&mut self, key_count: usize, column: usize, key_label: &str) {
+        settings::remove_column_key(&mut self.keybinds, key_count, column, key_label);
+    }
+
+    /// Keys bound to an action, falling back to its defaults.
+    pub fn action_keys(&self, action: BindableAction) -> Vec<String> {
+        match self.action_keybinds.get(action.id()) {
+            Some(keys) => keys.clone(),
+            None => action
+                .default_keys()
+                .iter()
+                .map(|k| k.to_string())
+                .collect(),
+        }
+    }
+
     /// Adds a key to the capture buffer during remapping.
+    ///
+    /// An action is bound to the single key pressed.
     pub fn push_keybind_key(&mut self, key_label: String) {
+        if let Some(action) = self.remapping_action.take() {
+            self.action_keybinds
+                .insert(action.id().to_string(), vec![key_label]);
+            return;
+        }
+        if let Some((key_count, column)) = self.adding_column_key.take() {
+            self.add_column_key(key_count, column, key_label);
+            return;
+        }
+
         let Some(target_columns) = self.remapping_column else {
             return;
         };
@@ -163,6 +784,118 @@ impl SettingsState {
         }
     }
 
+    fn default_custom_hit_window_ms() -> [f64; 6] {
+        settings::DEFAULT_CUSTOM_HIT_WINDOW_MS
+    }
+
+    /// Hit window settings of the selected judgement mode.
+    pub fn hit_window_settings(&self) -> HitWindowSettings {
+        HitWindowSettings {
+            mode: self.hit_window_mode,
+            value: self.hit_window_value,
+            custom_ms: self.custom_hit_window_ms,
+        }
+    }
+
+    /// Hit window of the selected judgement mode.
+    pub fn hit_window(&self) -> HitWindow {
+        self.hit_window_settings().hit_window()
+    }
+
+    /// Audio offset of a beatmap in milliseconds: the global offset plus
+    /// the beatmap's own override.
+    pub fn effective_offset_ms(&self, beatmap_hash: Option<&str>) -> f64 {
+        let beatmap_offset = beatmap_hash
+            .and_then(|hash| self.beatmap_offsets.get(hash))
+            .copied()
+            .unwrap_or(0.0);
+        self.global_audio_offset_ms + beatmap_offset
+    }
+
+    /// [`Self::effective_offset_ms`] in µs, as the engine uses it.
+    pub fn audio_offset_us(&self, beatmap_hash: Option<&str>) -> i64 {
+        (self.effective_offset_ms(beatmap_hash) * US_PER_MS as f64) as i64
+    }
+
+    /// Shifts the offset of a beatmap by `delta_ms`, returning the new
+    /// override. An override back at zero is removed.
+    pub fn nudge_beatmap_offset(&mut self, beatmap_hash: &str, delta_ms: f64) -> f64 {
+        let offset = self
+            .beatmap_offsets
+            .get(beatmap_hash)
+            .copied()
+            .unwrap_or(0.0)
+            + delta_ms;
+        if offset.abs() < f64::EPSILON {
+            self.beatmap_offsets.remove(beatmap_hash);
+        } else {
+            self.beatmap_offsets
+                .insert(beatmap_hash.to_string(), offset);
+        }
+        offset
+    }
+
+    fn default_max_analyzed_notes() -> usize {
+        AnalysisLimits::DEFAULT_MAX_NOTES
+    }
+
+    fn default_watch_songs() -> bool {
+        true
+    }
+
+    fn default_track_achievements() -> bool {
+        true
+    }
+
+    fn default_playfield_scale() -> f32 {
+        1.0
+    }
+
+    fn default_snapshot_interpolation() -> bool {
+        true
+    }
+
+    fn default_receptor_glow() -> bool {
+        true
+    }
+
+    fn default_keysounds() -> bool {
+        true
+    }
+
+    fn default_tick_rate() -> u32 {
+        settings::DEFAULT_TICK_RATE
+    }
+
+    /// Logic ticks per second, bounded to [`settings::TICK_RATE_RANGE`].
+    pub fn tick_rate(&self) -> u32 {
+        let range = settings::TICK_RATE_RANGE;
+        self.tick_rate.clamp(*range.start(), *range.end())
+    }
+
+    fn default_language() -> String {
+        crate::ui::i18n::DEFAULT_LANGUAGE.to_string()
+    }
+
+    fn default_ui_scale() -> f32 {
+        settings::DEFAULT_UI_SCALE
+    }
+
+    /// Multiplier of the window scale factor for egui, bounded to
+    /// [`settings::UI_SCALE_RANGE`].
+    pub fn ui_scale(&self) -> f32 {
+        let range = settings::UI_SCALE_RANGE;
+        self.ui_scale.clamp(*range.start(), *range.end())
+    }
+
+    /// Bounds applied to difficulty calculations.
+    pub fn analysis_limits(&self) -> AnalysisLimits {
+        AnalysisLimits {
+            max_notes: self.max_analyzed_notes,
+            ..AnalysisLimits::default()
+        }
+    }
+
     /// Returns the default keybinds for 4K, 5K, 6K, and 7K.
     fn default_keybinds() -> HashMap<String, Vec<String>> {
         let mut map = HashMap::new();
@@ -217,3 +950,129 @@ impl Default for SettingsState {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hit_window_labels() {
+        assert_eq!(HitWindowMode::OsuOD.label(8.0), "OD 8.0");
+        assert_eq!(HitWindowMode::EtternaJudge.label(4.0), "Judge 4");
+        assert_eq!(HitWindowMode::EtternaJudge.short_label(4.0), "J4");
+        assert_eq!(HitWindowMode::Custom.short_label(4.0), "Custom");
+    }
+
+    #[test]
+    fn test_engine_window_follows_settings_mode() {
+        let mut settings = SettingsState::new();
+        settings.hit_window_mode = HitWindowMode::EtternaJudge;
+        settings.hit_window_value = 4.0;
+        let window = settings.hit_window_settings().hit_window();
+        assert_eq!(window.great_us, HitWindow::from_etterna_judge(4).great_us);
+    }
+
+    #[test]
+    fn test_effective_offset_adds_beatmap_override() {
+        let mut settings = SettingsState::new();
+        settings.global_audio_offset_ms = 12.0;
+        settings.nudge_beatmap_offset("map", BEATMAP_OFFSET_STEP_MS);
+        settings.nudge_beatmap_offset("map", BEATMAP_OFFSET_STEP_MS);
+
+        assert_eq!(
+            settings.effective_offset_ms(Some("map")),
+            12.0 + 2.0 * BEATMAP_OFFSET_STEP_MS
+        );
+        assert_eq!(
+            settings.audio_offset_us(Some("map")),
+            ((12.0 + 2.0 * BEATMAP_OFFSET_STEP_MS) * 1000.0) as i64
+        );
+        // Other maps only get the global offset
+        assert_eq!(settings.effective_offset_ms(Some("other")), 12.0);
+        assert_eq!(settings.effective_offset_ms(None), 12.0);
+
+        // Nudging back to zero drops the override
+        settings.nudge_beatmap_offset("map", -2.0 * BEATMAP_OFFSET_STEP_MS);
+        assert!(settings.beatmap_offsets.is_empty());
+    }
+
+    #[test]
+    fn test_playfield_alignment_offsets() {
+        // 400px wide playfield on a 1920px wide screen
+        let offset = |alignment: PlayfieldAlignment| alignment.playfield_x_offset(1920.0, 400.0);
+        assert_eq!(offset(PlayfieldAlignment::Left), Some(-760.0));
+        assert_eq!(offset(PlayfieldAlignment::Center), Some(0.0));
+        assert_eq!(offset(PlayfieldAlignment::Right), Some(760.0));
+        assert_eq!(offset(PlayfieldAlignment::Skin), None);
+
+        // The left edge of a left-aligned playfield touches the screen edge
+        let left_x = 1920.0 / 2.0 + offset(PlayfieldAlignment::Left).unwrap() - 400.0 / 2.0;
+        assert_eq!(left_x, 0.0);
+    }
+
+    #[test]
+    fn test_frame_cap_deadline() {
+        let start = Instant::now();
+        let interval = Duration::from_secs(1) / 144;
+
+        // On schedule: the next frame is one interval after the previous one
+        let deadline = FrameCap::Fps144.next_deadline(start, start + Duration::from_millis(2));
+        assert_eq!(deadline, Some(start + interval));
+
+        // Running late: render right away
+        let late = start + Duration::from_millis(20);
+        assert_eq!(FrameCap::Fps144.next_deadline(start, late), Some(late));
+
+        assert_eq!(
+            FrameCap::Fps60.next_deadline(start, start),
+            Some(start + Duration::from_nanos(16_666_666))
+        );
+        assert_eq!(FrameCap::Off.next_deadline(start, start), None);
+        assert_eq!(FrameCap::VSync.next_deadline(start, start), None);
+    }
+
+    #[test]
+    fn test_background_dim_alpha() {
+        let mut dim = BackgroundDim {
+            dim: 0.8,
+            combo_reactive: false,
+            min_dim: 0.2,
+            full_combo_at: 400,
+        };
+        // Static by default: the combo is ignored
+        assert_eq!(dim.overlay_alpha(0), 0.8);
+        assert_eq!(dim.overlay_alpha(1000), 0.8);
+
+        dim.combo_reactive = true;
+        assert_eq!(dim.overlay_alpha(0), 0.8);
+        assert!((dim.overlay_alpha(200) - 0.5).abs() < 1e-6);
+        assert!((dim.overlay_alpha(400) - 0.2).abs() < 1e-6);
+        // Stays at the floor past the target combo
+        assert!((dim.overlay_alpha(5000) - 0.2).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_menu_background_crossfade_alpha() {
+        let mut style = MenuBackground {
+            crossfade_ms: 400.0,
+            ..MenuBackground::default()
+        };
+        assert_eq!(style.crossfade_alpha(Duration::ZERO), 0.0);
+        assert_eq!(style.crossfade_alpha(Duration::from_millis(200)), 0.5);
+        assert_eq!(style.crossfade_alpha(Duration::from_millis(400)), 1.0);
+        assert_eq!(style.crossfade_alpha(Duration::MAX), 1.0);
+
+        // Eased: slow start and end, monotonic in between
+        let early = style.crossfade_alpha(Duration::from_millis(40));
+        assert!(early > 0.0 && early < 0.1);
+        let mut last = 0.0;
+        for ms in (0..=400).step_by(20) {
+            let alpha = style.crossfade_alpha(Duration::from_millis(ms));
+            assert!(alpha >= last);
+            last = alpha;
+        }
+
+        style.crossfade_ms = 0.0;
+        assert_eq!(style.crossfade_alpha(Duration::ZERO), 1.0);
+    }
+}
@@ -4,14 +4,17 @@
 //! game's internal event system.
 
 use std::sync::Arc;
+use std::time::Instant;
 use winit::application::ApplicationHandler;
-use winit::event::WindowEvent;
-use winit::event_loop::ActiveEventLoop;
+use winit::event::{StartCause, WindowEvent};
+use winit::event_loop::{ActiveEventLoop, ControlFlow};
 use winit::keyboard::PhysicalKey;
 use winit::window::{Window, WindowId};
 
 use crate::graphics::renderer::Renderer;
-use crate::input::events::RawInputEvent;
+use crate::input::events::{InputCommand, RawInputEvent};
+use crate::models::settings::SettingsState;
+use crate::render::display::fullscreen;
 use crate::system::bus::{SystemBus, SystemEvent};
 
 /// Main application struct handling window events.
@@ -19,6 +22,8 @@ pub struct App {
     bus: SystemBus,
     window: Option<Arc<Window>>,
     renderer: Option<Renderer>,
+    /// Start of the next frame when the frame rate is capped.
+    next_frame: Instant,
 }
 
 impl App {
@@ -28,20 +33,51 @@ impl App {
             bus,
             window: None,
             renderer: None,
+            next_frame: Instant::now(),
         }
     }
 
     /// Runs the application event loop (blocking).
     pub fn run(bus: SystemBus) {
         let event_loop = winit::event_loop::EventLoop::new().unwrap();
-        event_loop.set_control_flow(winit::event_loop::ControlFlow::Poll);
+        event_loop.set_control_flow(ControlFlow::Poll);
 
         let mut app = App::new(bus);
         let _ = event_loop.run_app(&mut app);
     }
 }
 
+impl App {
+    /// Requests the next frame right away, or schedules it when capped.
+    fn schedule_redraw(&mut self, event_loop: &ActiveEventLoop, window: &Window) {
+        let frame_cap = self
+            .renderer
+            .as_ref()
+            .map(Renderer::frame_cap)
+            .unwrap_or_default();
+        match frame_cap.next_deadline(self.next_frame, Instant::now()) {
+            Some(deadline) => {
+                self.next_frame = deadline;
+                event_loop.set_control_flow(ControlFlow::WaitUntil(deadline));
+            }
+            None => {
+                event_loop.set_control_flow(ControlFlow::Poll);
+                window.request_redraw();
+            }
+        }
+    }
+}
+
 impl ApplicationHandler for App {
+    fn new_events(&mut self, _event_loop: &ActiveEventLoop, cause: StartCause) {
+        // Capped frame rate: the deadline set in `schedule_redraw` was reached
+        if let StartCause::ResumeTimeReached { .. } = cause
+            && let Some(window) = self.window.as_ref()
+        {
+            window.request_redraw();
+        }
+    }
+
     fn resumed(&mut self, event_loop: &ActiveEventLoop) {
         if self.window.is_none() {
             log::info!("RENDER: Creating window...");
@@ -50,6 +86,13 @@ impl ApplicationHandler for App {
                 .with_title("Prism")
                 .with_inner_size(winit::dpi::LogicalSize::new(1280.0, 720.0));
 
+            let settings = SettingsState::load();
+            win_attr = win_attr.with_fullscreen(fullscreen(
+                settings.display_mode,
+                settings.monitor_index,
+                event_loop.available_monitors(),
+            ));
+
             // Attempt to load window icon
             if let Ok(image) = image::open("assets/logo.png") {
                 let image = image.into_rgba8();
@@ -69,6 +112,13 @@ impl ApplicationHandler for App {
             let renderer = pollster::block_on(Renderer::new(window.clone()));
             self.renderer = Some(renderer);
 
+            // The logic thread lays out the menu particles at this size
+            let size = window.inner_size();
+            let _ = self.bus.sys_tx.send(SystemEvent::Resize {
+                width: size.width,
+                height: size.height,
+            });
+
             window.request_redraw();
         }
     }
@@ -100,6 +150,10 @@ impl ApplicationHandler for App {
                     let _ = self.bus.raw_input_tx.send(raw_event);
                 }
             }
+            WindowEvent::Focused(false) => {
+                // Keys released in another window never reach us
+                let _ = self.bus.input_cmd_tx.send(InputCommand::FocusLost);
+            }
             WindowEvent::CloseRequested => {
                 log::info!("RENDER: Close requested");
                 let _ = self.bus.sys_tx.send(SystemEvent::Quit);
@@ -115,9 +169,9 @@ impl ApplicationHandler for App {
                 });
             }
             WindowEvent::RedrawRequested => {
-                if let Some(window) = self.window.as_ref() {
+                if let Some(window) = self.window.clone() {
                     // Update state from logic thread
-                    if let Some(snapshot) = self.bus.render_rx.try_iter().last()
+                    if let Ok(snapshot) = self.bus.render_rx.try_recv()
                         && let Some(renderer) = self.renderer.as_mut()
                     {
                         renderer.update_state(snapshot);
@@ -125,7 +179,7 @@ impl ApplicationHandler for App {
 
                     // Render and send UI actions (mouse) to logic
                     if let Some(renderer) = self.renderer.as_mut() {
-                        match renderer.render(window) {
+                        match renderer.render(&window) {
                             Ok(actions) => {
                                 for action in actions {
                                     let _ = self.bus.action_tx.send(action);
@@ -147,7 +201,7 @@ impl ApplicationHandler for App {
                             Err(e) => log::error!("Render error: {e:?}"),
                         }
                     }
-                    window.request_redraw();
+                    self.schedule_redraw(event_loop, &window);
                 }
             }
             _ => {}
@@ -0,0 +1,166 @@
+//! Frame capture for screenshots.
+//!
+//! Textures are read back through a staging buffer. wgpu requires each row
+//! of a texture-to-buffer copy to be padded to
+//! [`wgpu::COPY_BYTES_PER_ROW_ALIGNMENT`] (256 bytes), so the padding is
+//! stripped before building the image.
+
+use image::RgbaImage;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Directory where screenshots are written.
+pub const SCREENSHOT_DIR: &str = "screenshots";
+
+const BYTES_PER_PIXEL: u32 = 4;
+
+/// Row size of a `width` pixels wide copy, padded to the wgpu alignment.
+pub fn padded_bytes_per_row(width: u32) -> u32 {
+    let unpadded = width * BYTES_PER_PIXEL;
+    unpadded.div_ceil(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT) * wgpu::COPY_BYTES_PER_ROW_ALIGNMENT
+}
+
+/// Converts a buffer with padded rows into tightly packed rows.
+pub fn unpad_rows(padded: &[u8], width: u32, height: u32, padded_bytes_per_row: u32) -> Vec<u8> {
+    let row_len = (width * BYTES_PER_PIXEL) as usize;
+    let mut pixels = Vec::with_capacity(row_len * height as usize);
+    for row in padded
+        .chunks(padded_bytes_per_row as usize)
+        .take(height as usize)
+    {
+        pixels.extend_from_slice(&row[..row_len]);
+    }
+    pixels
+}
+
+/// Reads back a rendered texture (swapchain or offscreen) into an image.
+///
+/// The texture must have been created with `COPY_SRC` usage and use an
+/// 8-bit RGBA or BGRA format. Blocks until the GPU has finished the copy.
+pub fn capture_frame(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    texture: &wgpu::Texture,
+) -> Option<RgbaImage> {
+    let swap_red_blue = match texture.format() {
+        wgpu::TextureFormat::Rgba8Unorm | wgpu::TextureFormat::Rgba8UnormSrgb => false,
+        wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb => true,
+        format => {
+            log::warn!("CAPTURE: Unsupported texture format {format:?}");
+            return None;
+        }
+    };
+
+    let width = texture.width();
+    let height = texture.height();
+    let bytes_per_row = padded_bytes_per_row(width);
+
+    let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Capture Buffer"),
+        size: (bytes_per_row * height) as u64,
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("Capture Encoder"),
+    });
+    encoder.copy_texture_to_buffer(
+        wgpu::TexelCopyTextureInfo {
+            texture,
+            mip_level: 0,
+            origin: wgpu::Origin3d::ZERO,
+            aspect: wgpu::TextureAspect::All,
+        },
+        wgpu::TexelCopyBufferInfo {
+            buffer: &buffer,
+            layout: wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(bytes_per_row),
+                rows_per_image: Some(height),
+            },
+        },
+        wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+    );
+    queue.submit(std::iter::once(encoder.finish()));
+
+    let slice = buffer.slice(..);
+    let (sender, receiver) = std::sync::mpsc::channel();
+    slice.map_async(wgpu::MapMode::Read, move |result| {
+        let _ = sender.send(result);
+    });
+    if let Err(e) = device.poll(wgpu::PollType::wait_indefinitely()) {
+        log::error!("CAPTURE: Failed to wait for the GPU: {e}");
+        return None;
+    }
+    match receiver.recv() {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => {
+            log::error!("CAPTURE: Failed to map the capture buffer: {e}");
+            return None;
+        }
+        Err(_) => return None,
+    }
+
+    let mut pixels = unpad_rows(&slice.get_mapped_range(), width, height, bytes_per_row);
+    buffer.unmap();
+
+    if swap_red_blue {
+        for pixel in pixels.chunks_exact_mut(BYTES_PER_PIXEL as usize) {
+            pixel.swap(0, 2);
+        }
+    }
+    RgbaImage::from_raw(width, height, pixels)
+}
+
+/// Timestamped PNG path inside `dir`.
+pub fn screenshot_path(dir: &Path) -> PathBuf {
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    dir.join(format!("screenshot_{millis}.png"))
+}
+
+/// Writes `image` as a timestamped PNG in [`SCREENSHOT_DIR`].
+pub fn save_screenshot(image: &RgbaImage) -> Result<PathBuf, String> {
+    let dir = Path::new(SCREENSHOT_DIR);
+    std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    let path = screenshot_path(dir);
+    image.save(&path).map_err(|e| e.to_string())?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unpad_rows() {
+        // 3x2 image: 12 bytes per row, padded to 256
+        let width = 3;
+        let height = 2;
+        let stride = padded_bytes_per_row(width);
+        assert_eq!(stride, 256);
+        assert_eq!(padded_bytes_per_row(64), 256);
+        assert_eq!(padded_bytes_per_row(65), 512);
+
+        let mut padded = vec![0xAA; (stride * height) as usize];
+        for y in 0..height {
+            for x in 0..width * BYTES_PER_PIXEL {
+                padded[(y * stride + x) as usize] = (y * 100 + x) as u8;
+            }
+        }
+
+        let pixels = unpad_rows(&padded, width, height, stride);
+        let expected: Vec<u8> = (0..height)
+            .flat_map(|y| (0..width * BYTES_PER_PIXEL).map(move |x| (y * 100 + x) as u8))
+            .collect();
+        assert_eq!(pixels, expected);
+        assert!(RgbaImage::from_raw(width, height, pixels).is_some());
+    }
+}
@@ -8,6 +8,8 @@ pub struct RenderContext {
     pub config: wgpu::SurfaceConfiguration,
     pub size: winit::dpi::PhysicalSize<u32>,
     pub window: Arc<Window>,
+    /// Present mode used when VSync is off (Immediate or Mailbox if supported).
+    uncapped_present_mode: wgpu::PresentMode,
 }
 
 impl RenderContext {
@@ -64,8 +66,12 @@ impl RenderContext {
 
         log::info!("RENDER: Selected Present Mode: {:?}", present_mode);
 
+        // COPY_SRC permet de relire la frame pour les screenshots
+        let usage = wgpu::TextureUsages::RENDER_ATTACHMENT
+            | (surface_caps.usages & wgpu::TextureUsages::COPY_SRC);
+
         let config = wgpu::SurfaceConfiguration {
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            usage,
             format: texture_format,
             width: size.width,
             height: size.height,
@@ -84,6 +90,21 @@ impl RenderContext {
             config,
             size,
             window,
+            uncapped_present_mode: present_mode,
+        }
+    }
+
+    /// Switches the surface between VSync (Fifo) and the uncapped present mode.
+    pub fn set_vsync(&mut self, vsync: bool) {
+        let present_mode = if vsync {
+            wgpu::PresentMode::Fifo
+        } else {
+            self.uncapped_present_mode
+        };
+        if self.config.present_mode != present_mode {
+            log::info!("RENDER: Switching Present Mode to {:?}", present_mode);
+            self.config.present_mode = present_mode;
+            self.surface.configure(&self.device, &self.config);
         }
     }
 
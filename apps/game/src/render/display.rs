@@ -0,0 +1,113 @@
+//! Fullscreen / monitor selection for the game window.
+//!
+//! The choice of monitor and video mode is kept generic over the monitor
+//! and video mode types so it can be tested without a display server;
+//! [`fullscreen`] plugs in winit's handles.
+
+use crate::models::settings::DisplayMode;
+use winit::monitor::{MonitorHandle, VideoModeHandle};
+use winit::window::Fullscreen;
+
+/// Fullscreen state to request for a display mode.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FullscreenChoice<M, V> {
+    Windowed,
+    /// `None` lets winit use the monitor the window is on.
+    Borderless(Option<M>),
+    Exclusive(V),
+}
+
+/// Picks the fullscreen state for `mode` on the `monitor_index`-th monitor.
+///
+/// An out-of-range index falls back to the first monitor. Exclusive mode
+/// falls back to borderless when the monitor reports no video mode.
+pub fn choose_fullscreen<M: Clone, V>(
+    mode: DisplayMode,
+    monitor_index: usize,
+    monitors: &[M],
+    best_video_mode: impl FnOnce(&M) -> Option<V>,
+) -> FullscreenChoice<M, V> {
+    let monitor = monitors.get(monitor_index).or_else(|| monitors.first());
+    match mode {
+        DisplayMode::Windowed => FullscreenChoice::Windowed,
+        DisplayMode::Borderless => FullscreenChoice::Borderless(monitor.cloned()),
+        DisplayMode::Exclusive => match monitor.and_then(best_video_mode) {
+            Some(video_mode) => FullscreenChoice::Exclusive(video_mode),
+            None => FullscreenChoice::Borderless(monitor.cloned()),
+        },
+    }
+}
+
+/// Highest resolution video mode of a monitor, preferring the highest refresh rate.
+fn best_video_mode(monitor: &MonitorHandle) -> Option<VideoModeHandle> {
+    monitor.video_modes().max_by_key(|mode| {
+        let size = mode.size();
+        (
+            size.width as u64 * size.height as u64,
+            mode.refresh_rate_millihertz(),
+        )
+    })
+}
+
+/// winit fullscreen value for `mode`, `None` meaning windowed.
+pub fn fullscreen(
+    mode: DisplayMode,
+    monitor_index: usize,
+    monitors: impl IntoIterator<Item = MonitorHandle>,
+) -> Option<Fullscreen> {
+    let monitors: Vec<MonitorHandle> = monitors.into_iter().collect();
+    match choose_fullscreen(mode, monitor_index, &monitors, best_video_mode) {
+        FullscreenChoice::Windowed => None,
+        FullscreenChoice::Borderless(monitor) => Some(Fullscreen::Borderless(monitor)),
+        FullscreenChoice::Exclusive(video_mode) => Some(Fullscreen::Exclusive(video_mode)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Mock monitor: a name and its video modes as (width, height).
+    type Monitor = (&'static str, Vec<(u32, u32)>);
+
+    fn largest(monitor: &Monitor) -> Option<(u32, u32)> {
+        monitor.1.iter().copied().max_by_key(|(w, h)| w * h)
+    }
+
+    #[test]
+    fn test_choose_fullscreen_for_monitors() {
+        let monitors: Vec<Monitor> = vec![
+            ("left", vec![(1920, 1080), (1280, 720)]),
+            ("right", vec![(2560, 1440)]),
+            ("virtual", vec![]),
+        ];
+
+        assert_eq!(
+            choose_fullscreen(DisplayMode::Windowed, 1, &monitors, largest),
+            FullscreenChoice::Windowed
+        );
+        assert_eq!(
+            choose_fullscreen(DisplayMode::Borderless, 1, &monitors, largest),
+            FullscreenChoice::Borderless(Some(monitors[1].clone()))
+        );
+        assert_eq!(
+            choose_fullscreen(DisplayMode::Exclusive, 0, &monitors, largest),
+            FullscreenChoice::Exclusive((1920, 1080))
+        );
+        // Unplugged monitor: use the first one
+        assert_eq!(
+            choose_fullscreen(DisplayMode::Exclusive, 5, &monitors, largest),
+            FullscreenChoice::Exclusive((1920, 1080))
+        );
+        // No video mode: borderless on the same monitor
+        assert_eq!(
+            choose_fullscreen(DisplayMode::Exclusive, 2, &monitors, largest),
+            FullscreenChoice::Borderless(Some(monitors[2].clone()))
+        );
+        // No monitor reported: borderless on the current one
+        assert_eq!(
+            choose_fullscreen(DisplayMode::Exclusive, 0, &[], largest),
+            FullscreenChoice::<Monitor, (u32, u32)>::Borderless(None)
+        );
+    }
+}
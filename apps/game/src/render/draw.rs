@@ -1,13 +1,21 @@
 //! Main draw dispatcher - routes to appropriate draw functions.
 //!
-//! This file has been refactored to use the new graphics/draw/ architecture.
+//! Draw functions only record passes into the frame's shared
+//! [`CommandEncoder`]; none of them submits. The renderer submits the encoder
+//! once, after the egui layer, so the game is always drawn under the UI.
 
 use crate::render::context::RenderContext;
-use crate::render::resources::RenderResources;
+use crate::render::resources::{BackgroundParams, RenderResources};
 use crate::shared::snapshot::{GameplaySnapshot, RenderState};
-use crate::views::context::GameplayRenderContext;
+use crate::views::components::common::primitives::QuadInstance;
+use crate::views::context::{GameplayRenderContext, set_viewport};
+use engine::Viewport;
+use std::time::Duration;
 use wgpu::{Color, CommandEncoder, LoadOp, Operations, RenderPassDescriptor, TextureView};
 
+/// Brightness of the background under the gameplay dim overlay.
+const GAMEPLAY_BACKGROUND_BRIGHTNESS: f32 = 0.4;
+
 /// Main entry point for all rendering based on game state.
 pub fn draw_game(
     ctx: &RenderContext,
@@ -19,7 +27,7 @@ pub fn draw_game(
 ) {
     match state {
         RenderState::InGame(snapshot) => {
-            clear_screen(encoder, view, "Gameplay Clear");
+            draw_gameplay_background(ctx, res, encoder, view, snapshot.combo);
             draw_gameplay_v2(ctx, res, encoder, view, snapshot, fps);
         }
         RenderState::Editor(snapshot) => {
@@ -27,13 +35,13 @@ pub fn draw_game(
             draw_gameplay_v2(ctx, res, encoder, view, &snapshot.game, fps);
         }
         RenderState::Menu(_) => {
-            draw_background_pass(ctx, res, encoder, view);
+            draw_background_pass(ctx, res, encoder, view, true);
         }
         RenderState::Result(_) => {
-            draw_background_pass(ctx, res, encoder, view);
+            draw_background_pass(ctx, res, encoder, view, true);
         }
-        RenderState::MainMenu => {
-            draw_background_pass(ctx, res, encoder, view);
+        RenderState::MainMenu(_) => {
+            draw_background_pass(ctx, res, encoder, view, true);
         }
         RenderState::Empty => {
             clear_screen(encoder, view, "Empty Clear");
@@ -60,35 +68,124 @@ fn clear_screen(encoder: &mut CommandEncoder, view: &TextureView, label: &str) {
     });
 }
 
-/// Draw background with the new architecture.
+/// Draw the current background, crossfading from the previous one.
+///
+/// Menus use the song select dim/blur settings; gameplay keeps the sharp
+/// image, darkens it with its own overlay and stays inside its viewport.
 fn draw_background_pass(
-    _ctx: &RenderContext,
+    ctx: &RenderContext,
     res: &RenderResources,
     encoder: &mut CommandEncoder,
     view: &TextureView,
+    menu: bool,
 ) {
-    if let Some(bg_group) = &res.background_bind_group {
-        let mut pass = encoder.begin_render_pass(&RenderPassDescriptor {
-            label: Some("Background Pass"),
-            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                view,
-                resolve_target: None,
-                ops: Operations {
-                    load: LoadOp::Clear(Color::BLACK),
-                    store: wgpu::StoreOp::Store,
-                },
-                depth_slice: None,
-            })],
-            depth_stencil_attachment: None,
-            timestamp_writes: None,
-            occlusion_query_set: None,
-        });
-        pass.set_pipeline(&res.background_pipeline);
-        pass.set_bind_group(0, bg_group, &[]);
-        pass.draw(0..6, 0..1);
-    } else {
+    let Some(bg_group) = &res.background_bind_group else {
         clear_screen(encoder, view, "Clear (No BG)");
+        return;
+    };
+
+    let (alpha, brightness, blur_px) = if menu {
+        let style = &res.settings.menu_background;
+        let elapsed = res
+            .background_loaded_at
+            .map_or(Duration::MAX, |loaded_at| loaded_at.elapsed());
+        (
+            style.crossfade_alpha(elapsed),
+            style.brightness(),
+            style.blur,
+        )
+    } else {
+        (1.0, GAMEPLAY_BACKGROUND_BRIGHTNESS, 0.0)
+    };
+    let layers = [
+        BackgroundParams::new(1.0, brightness, blur_px),
+        BackgroundParams::new(alpha, brightness, blur_px),
+    ];
+    for (buffer, params) in res.background_params_buffers.iter().zip(&layers) {
+        ctx.queue
+            .write_buffer(buffer, 0, bytemuck::bytes_of(params));
+    }
+
+    let mut pass = encoder.begin_render_pass(&RenderPassDescriptor {
+        label: Some("Background Pass"),
+        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+            view,
+            resolve_target: None,
+            ops: Operations {
+                load: LoadOp::Clear(Color::BLACK),
+                store: wgpu::StoreOp::Store,
+            },
+            depth_slice: None,
+        })],
+        depth_stencil_attachment: None,
+        timestamp_writes: None,
+        occlusion_query_set: None,
+    });
+    if !menu {
+        set_viewport(&mut pass, res.gameplay_viewport);
     }
+    pass.set_pipeline(&res.background_pipeline);
+    if alpha < 1.0
+        && let Some(previous) = &res.previous_background_bind_group
+    {
+        pass.set_bind_group(0, previous, &[]);
+        pass.set_bind_group(1, &res.background_params_bind_groups[0], &[]);
+        pass.draw(0..6, 0..1);
+    }
+    pass.set_bind_group(0, bg_group, &[]);
+    pass.set_bind_group(1, &res.background_params_bind_groups[1], &[]);
+    pass.draw(0..6, 0..1);
+}
+
+/// Draw the song background under the playfield, dimmed by a black overlay.
+///
+/// The overlay opacity comes from the background dim setting and may follow
+/// the combo; a fully opaque dim skips the background entirely.
+fn draw_gameplay_background(
+    ctx: &RenderContext,
+    res: &RenderResources,
+    encoder: &mut CommandEncoder,
+    view: &TextureView,
+    combo: u32,
+) {
+    let alpha = res.settings.background_dim.overlay_alpha(combo);
+    if alpha >= 1.0 || res.background_bind_group.is_none() {
+        clear_screen(encoder, view, "Gameplay Clear");
+        return;
+    }
+
+    draw_background_pass(ctx, res, encoder, view, false);
+    if alpha <= 0.0 {
+        return;
+    }
+
+    let overlay = QuadInstance {
+        center: [0.0, 0.0],
+        size: [2.0, 2.0],
+        color: [0.0, 0.0, 0.0, alpha],
+    };
+    ctx.queue
+        .write_buffer(&res.dim_buffer, 0, bytemuck::bytes_of(&overlay));
+
+    let mut pass = encoder.begin_render_pass(&RenderPassDescriptor {
+        label: Some("Background Dim Pass"),
+        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+            view,
+            resolve_target: None,
+            ops: Operations {
+                load: LoadOp::Load,
+                store: wgpu::StoreOp::Store,
+            },
+            depth_slice: None,
+        })],
+        depth_stencil_attachment: None,
+        timestamp_writes: None,
+        occlusion_query_set: None,
+    });
+    set_viewport(&mut pass, res.gameplay_viewport);
+    pass.set_pipeline(&res.quad_pipeline);
+    pass.set_vertex_buffer(0, res.dim_buffer.slice(..));
+    pass.draw(0..4, 0..1);
 }
 
 /// Draw gameplay using the new v2 architecture (hybrid mode).
@@ -168,6 +265,7 @@ fn draw_hud_legacy(
     snapshot: &GameplaySnapshot,
     fps: f64,
 ) {
+    let viewport = res.gameplay_viewport;
     let mut view_ctx = GameplayRenderContext {
         device: &ctx.device,
         queue: &ctx.queue,
@@ -186,9 +284,14 @@ fn draw_hud_legacy(
         burst_body_bind_group: res.burst_body_bind_group.as_ref(),
         burst_end_bind_group: res.burst_end_bind_group.as_ref(),
         view,
-        pixel_system: &res.pixel_system,
-        screen_width: ctx.config.width as f32,
-        screen_height: ctx.config.height as f32,
+        pixel_system: &res.gameplay_pixel_system,
+        viewport,
+        target: Viewport::full(
+            res.pixel_system.window_width,
+            res.pixel_system.window_height,
+        ),
+        screen_width: viewport.width,
+        screen_height: viewport.height,
         fps,
         master_volume: 1.0,
     };
@@ -203,10 +306,16 @@ fn draw_hud_legacy(
         bad: judgement.bad.color,
         miss: judgement.miss.color,
         ghost_tap: judgement.ghost_tap.color,
-    };
+    }
+    .with_palette(res.settings.palette);
 
     let labels = res.skin.get_judgement_labels();
 
+    res.gameplay_view
+        .set_snapshot_interpolation(res.settings.snapshot_interpolation);
+    res.gameplay_view
+        .set_receptor_glow(res.settings.receptor_glow);
+
     // Use legacy gameplay_view for full rendering (including notes for now)
     let _ = res.gameplay_view.render(
         &mut view_ctx,
@@ -0,0 +1,136 @@
+//! Rolling frame-time statistics for the performance overlay.
+//!
+//! Frame times are kept in a fixed-size ring buffer so recording a frame
+//! never allocates; percentiles are only computed when the overlay is shown.
+
+/// Number of frames kept for the rolling statistics.
+pub const FRAME_STATS_CAPACITY: usize = 1000;
+
+/// Ring buffer of the most recent frame times, in milliseconds.
+#[derive(Clone, Debug)]
+pub struct FrameStats {
+    samples: Vec<f32>,
+    /// Frames kept; `Vec::capacity` may round up, so it is stored apart.
+    capacity: usize,
+    next: usize,
+}
+
+impl FrameStats {
+    pub fn new() -> Self {
+        Self::with_capacity(FRAME_STATS_CAPACITY)
+    }
+
+    /// Creates a buffer keeping the last `capacity` frames.
+    pub fn with_capacity(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            samples: Vec::with_capacity(capacity),
+            capacity,
+            next: 0,
+        }
+    }
+
+    /// Records the duration of a frame, replacing the oldest one when full.
+    pub fn push(&mut self, frame_time_ms: f32) {
+        if self.samples.len() < self.capacity {
+            self.samples.push(frame_time_ms);
+        } else {
+            self.samples[self.next] = frame_time_ms;
+        }
+        self.next = (self.next + 1) % self.capacity;
+    }
+
+    /// Number of recorded frames.
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Checks if no frame was recorded yet.
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Average frame time in ms.
+    pub fn average_ms(&self) -> f32 {
+        if self.samples.is_empty() {
+            return 0.0;
+        }
+        self.samples.iter().sum::<f32>() / self.samples.len() as f32
+    }
+
+    /// Frame time below which `percent`% of the frames fall (nearest rank).
+    pub fn percentile_ms(&self, percent: f32) -> f32 {
+        if self.samples.is_empty() {
+            return 0.0;
+        }
+        let mut sorted = self.samples.clone();
+        sorted.sort_by(f32::total_cmp);
+        let rank = (percent.clamp(0.0, 100.0) / 100.0 * sorted.len() as f32).ceil() as usize;
+        sorted[rank.saturating_sub(1)]
+    }
+
+    /// Frame time of the slowest 1% of frames ("1% low"), in ms.
+    pub fn one_percent_low_ms(&self) -> f32 {
+        self.percentile_ms(99.0)
+    }
+}
+
+impl Default for FrameStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Converts a frame time in ms to frames per second.
+pub fn fps_from_ms(frame_time_ms: f32) -> f32 {
+    if frame_time_ms > 0.0 {
+        1000.0 / frame_time_ms
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentiles_over_known_series() {
+        let mut stats = FrameStats::with_capacity(100);
+        // 1, 2, ..., 100 ms in shuffled order
+        for i in 0..100 {
+            stats.push(((i * 37) % 100 + 1) as f32);
+        }
+        assert_eq!(stats.len(), 100);
+        assert_eq!(stats.average_ms(), 50.5);
+        assert_eq!(stats.percentile_ms(50.0), 50.0);
+        assert_eq!(stats.one_percent_low_ms(), 99.0);
+        assert_eq!(stats.percentile_ms(100.0), 100.0);
+
+        // The ring buffer drops the oldest frames once full
+        for _ in 0..100 {
+            stats.push(4.0);
+        }
+        assert_eq!(stats.len(), 100);
+        assert_eq!(stats.one_percent_low_ms(), 4.0);
+        assert_eq!(fps_from_ms(stats.average_ms()), 250.0);
+    }
+
+    #[test]
+    fn test_ring_keeps_exactly_its_capacity() {
+        let mut stats = FrameStats::with_capacity(3);
+        for frame in [1.0, 2.0, 3.0, 4.0, 5.0] {
+            stats.push(frame);
+        }
+        assert_eq!(stats.len(), 3);
+        // Frames 1 and 2 were replaced
+        assert_eq!(stats.average_ms(), 4.0);
+
+        // A zero capacity still keeps the last frame
+        let mut stats = FrameStats::with_capacity(0);
+        stats.push(1.0);
+        stats.push(2.0);
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats.average_ms(), 2.0);
+    }
+}
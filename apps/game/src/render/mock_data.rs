@@ -1,9 +1,10 @@
-use crate::shared::snapshot::{GameplaySnapshot, RenderState};
+use crate::shared::snapshot::{GameplaySnapshot, HitEvent, RenderState};
 use crate::state::{GameResultData, MenuState};
 use crate::views::components::editor::layout::EditorScene;
 use database::models::{Beatmap, BeatmapRating, BeatmapWithRatings, Beatmapset};
 use engine::NoteData;
 use engine::{HitStats, Judgement};
+use std::sync::Arc;
 use std::time::Instant;
 
 /// Génère un état de rendu factice basé sur la scène sélectionnée dans l'éditeur.
@@ -55,29 +56,36 @@ fn create_mock_gameplay(key_count: usize) -> RenderState {
         key_count,
         audio_time: (time_base_us + 500 * US_PER_MS) as f64 / US_PER_MS as f64, // Keep as ms for now
         timestamp: Instant::now(),
+        previous_tick: None,
         rate: 1.0,
         scroll_speed: 650.0,
-        visible_notes: notes,
+        note_scale: 1.0,
+        column_width_scale: 1.0,
+        visible_notes: Arc::new(notes),
         keys_held: vec![false; key_count], // Aucune touche pressée
         score: 125000,
         accuracy: 98.45,
         combo: 124,
-        hit_stats: HitStats {
-            marv: 100,
-            perfect: 20,
-            great: 4,
-            good: 0,
-            bad: 0,
-            miss: 0,
-            ghost_tap: 0,
-        },
+        hit_stats: mock_hit_stats([100, 20, 4, 0, 0, 0, 0]),
         remaining_notes: 50,
         last_hit_judgement: Some(Judgement::Marv), // Affiche un jugement pour tester la position
+        last_hit_column: None,
         last_hit_timing: Some(-4.5),
+        receptor_hits: vec![None; key_count], // Pas de lueur figée dans l'éditeur
         nps: 12.5,
+        smoothed_nps: 12.5,
         practice_mode: false,
         checkpoints: vec![],
         map_duration: 120000.0,
+        visibility: Default::default(),
+        recent_hits: vec![HitEvent {
+            id: 1,
+            timing: -4.5,
+            judgement: Judgement::Marv,
+        }],
+        combo_break_id: 0,
+        combo_milestone: (0, None),
+        health: Some(0.8),
     })
 }
 
@@ -101,6 +109,7 @@ fn create_mock_menu() -> RenderState {
         note_count: 1540,
         duration_ms: 180000,
         nps: 15.4,
+        peak_nps: 24.0,
         bpm: 180.0,
         key_count: 4,
     };
@@ -128,15 +137,7 @@ fn create_mock_menu() -> RenderState {
 
 fn create_mock_result() -> RenderState {
     RenderState::Result(GameResultData {
-        hit_stats: HitStats {
-            marv: 850,
-            perfect: 120,
-            great: 15,
-            good: 2,
-            bad: 0,
-            miss: 1,
-            ghost_tap: 5,
-        },
+        hit_stats: mock_hit_stats([850, 120, 15, 2, 0, 1, 5]),
         replay_data: replay::ReplayData::default(),
         replay_result: replay::ReplayResult::new(), // Vide pour l'instant (graphes vides)
         score: 985420,
@@ -146,5 +147,21 @@ fn create_mock_result() -> RenderState {
         rate: 1.1,
         judge_text: String::from("OD 8.5"),
         show_settings: false,
+        failed: false,
     })
 }
+
+/// Stats fictives : `[marv, perfect, great, good, bad, miss, ghost_tap]`.
+fn mock_hit_stats(counts: [u32; 7]) -> HitStats {
+    let mut stats = HitStats::new();
+    [
+        stats.marv,
+        stats.perfect,
+        stats.great,
+        stats.good,
+        stats.bad,
+        stats.miss,
+        stats.ghost_tap,
+    ] = counts;
+    stats
+}
@@ -1,6 +1,9 @@
 pub mod app;
+pub mod capture;
 pub mod context;
+pub mod display;
 pub mod draw;
+pub mod frame_stats;
 pub mod mock_data;
 pub mod resources;
 pub mod ui;
@@ -4,22 +4,76 @@ use crate::models::settings::SettingsState;
 use crate::render::context::RenderContext;
 use crate::render::utils::*;
 use crate::shaders::constants::{BACKGROUND_SHADER_SRC, PROGRESS_SHADER_SRC, QUAD_SHADER_SRC};
-use crate::views::components::common::primitives::ProgressInstance;
+use crate::ui::theme::apply_skin_theme;
+use crate::views::components::common::primitives::{ProgressInstance, QuadInstance};
 use crate::views::components::{
     AccuracyDisplay, ComboDisplay, HitBarDisplay, JudgementFlash, JudgementPanel,
     NotesRemainingDisplay, NpsDisplay, PlayfieldDisplay, ScoreDisplay, ScrollSpeedDisplay,
     TimeLeftDisplay,
 };
 use crate::views::gameplay::GameplayView;
-use engine::{InstanceRaw, NUM_COLUMNS, PixelSystem, PlayfieldConfig};
+use engine::{InstanceRaw, NUM_COLUMNS, PixelSystem, PlayfieldConfig, Viewport};
 use skin::Skin;
 use std::path::PathBuf;
+use std::time::Instant;
 
 // New graphics architecture imports
 
 use crate::graphics::assets::SkinAssets;
 use crate::ui::gameplay::playfield::{Playfield, PlayfieldConfig as NewPlayfieldConfig};
 
+/// Uniform du shader de fond : opacité, luminosité et flou d'une couche.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct BackgroundParams {
+    pub alpha: f32,
+    pub brightness: f32,
+    pub blur_px: f32,
+    pub _pad: f32,
+}
+
+impl BackgroundParams {
+    pub fn new(alpha: f32, brightness: f32, blur_px: f32) -> Self {
+        Self {
+            alpha,
+            brightness,
+            blur_px,
+            _pad: 0.0,
+        }
+    }
+}
+
+/// Colors of the judgement panel (separate from the flash colors).
+fn judgement_panel_colors(skin: &Skin) -> engine::JudgementColors {
+    let panel = &skin.hud.judgement_panel;
+    engine::JudgementColors {
+        marv: panel.marv_color,
+        perfect: panel.perfect_color,
+        great: panel.great_color,
+        good: panel.good_color,
+        bad: panel.bad_color,
+        miss: panel.miss_color,
+        ghost_tap: panel.ghost_tap_color,
+    }
+}
+
+/// Playfield sizes from the skin, with the note size and column width scaled
+/// independently.
+///
+/// Offsets are left to the caller: they depend on the screen.
+fn scaled_playfield_config(skin: &Skin, note_scale: f32, column_scale: f32) -> PlayfieldConfig {
+    let mut config = skin.gameplay.playfield.layout();
+    config.note_width_pixels *= note_scale;
+    config.note_height_pixels *= note_scale;
+    config.receptor_width_pixels *= note_scale;
+    config.receptor_height_pixels *= note_scale;
+    config.column_width_pixels *= column_scale;
+    for width in &mut config.column_widths {
+        *width *= column_scale;
+    }
+    config
+}
+
 pub struct RenderResources {
     pub render_pipeline: wgpu::RenderPipeline,
     pub bind_group_layout: wgpu::BindGroupLayout,
@@ -31,6 +85,8 @@ pub struct RenderResources {
     pub receptor_buffer: wgpu::Buffer,
     pub quad_buffer: wgpu::Buffer,
     pub progress_buffer: wgpu::Buffer,
+    /// Single fullscreen quad used to dim the gameplay background.
+    pub dim_buffer: wgpu::Buffer,
 
     // Legacy bind groups (will be replaced by SkinAssets)
     pub note_bind_groups: Vec<wgpu::BindGroup>,
@@ -45,6 +101,13 @@ pub struct RenderResources {
     pub burst_end_bind_group: Option<wgpu::BindGroup>,
 
     pub background_bind_group: Option<wgpu::BindGroup>,
+    /// Background fading out after the selection changed.
+    pub previous_background_bind_group: Option<wgpu::BindGroup>,
+    /// When the current background was loaded (start of the crossfade).
+    pub background_loaded_at: Option<Instant>,
+    /// Shader params of the previous (0) and current (1) background layers.
+    pub background_params_buffers: [wgpu::Buffer; 2],
+    pub background_params_bind_groups: [wgpu::BindGroup; 2],
     pub background_sampler: wgpu::Sampler,
     pub current_background_path: Option<String>,
 
@@ -60,6 +123,10 @@ pub struct RenderResources {
 
     pub text_brush: wgpu_text::TextBrush,
     pub pixel_system: PixelSystem,
+    /// Area of the target gameplay is drawn in (letterboxed for fixed ratios).
+    pub gameplay_viewport: Viewport,
+    /// Pixel system mapped to `gameplay_viewport`.
+    pub gameplay_pixel_system: PixelSystem,
 
     pub skin: Skin,
     pub settings: SettingsState,
@@ -160,10 +227,7 @@ impl RenderResources {
             }));
 
             // Pressed receptor texture
-            let path_p = self
-                .skin
-                .get_receptor_pressed_image(key_count, col)
-                .or(path.clone());
+            let path_p = self.skin.get_receptor_image_for_state(key_count, col, true);
             let tex_p = path_p
                 .as_ref()
                 .and_then(|p| load_texture_from_path(device, queue, p).map(|(t, _, _)| t))
@@ -286,6 +350,9 @@ impl RenderResources {
     }
 
     fn reload_menu_assets(&mut self, egui_ctx: &egui::Context, skin: &Skin) {
+        // Thème egui de base aux couleurs du skin
+        apply_skin_theme(egui_ctx, &skin.menus.panels);
+
         let load_egui_tex = |path: Option<PathBuf>, name: &str| -> Option<egui::TextureHandle> {
             let p = path?;
             if !p.exists() {
@@ -412,9 +479,7 @@ impl RenderResources {
                 }));
 
             // Pressed
-            let path_p = skin
-                .get_receptor_pressed_image(NUM_COLUMNS, col)
-                .or(path.clone());
+            let path_p = skin.get_receptor_image_for_state(NUM_COLUMNS, col, true);
             let tex_p = path_p
                 .as_ref()
                 .and_then(|p| load_texture_from_path(device, queue, p).map(|(t, _, _)| t))
@@ -513,6 +578,26 @@ impl RenderResources {
 
         let bg_sampler = create_sampler(device);
         let bg_layout = create_bind_group_layout(device);
+        let bg_params_layout = create_background_params_layout(device);
+        // Une couche pour le fond précédent (fondu sortant), une pour le courant
+        let bg_params_buffers = [(); 2].map(|_| {
+            device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("Background Params Buffer"),
+                size: std::mem::size_of::<BackgroundParams>() as u64,
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            })
+        });
+        let bg_params_bind_groups = bg_params_buffers.each_ref().map(|buffer| {
+            device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("Background Params BG"),
+                layout: &bg_params_layout,
+                entries: &[wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: buffer.as_entire_binding(),
+                }],
+            })
+        });
         let bg_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: Some("BG Shader"),
             source: wgpu::ShaderSource::Wgsl(std::borrow::Cow::Borrowed(BACKGROUND_SHADER_SRC)),
@@ -522,7 +607,7 @@ impl RenderResources {
             layout: Some(
                 &device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
                     label: Some("BG Layout"),
-                    bind_group_layouts: &[&bg_layout],
+                    bind_group_layouts: &[&bg_layout, &bg_params_layout],
                     push_constant_ranges: &[],
                 }),
             ),
@@ -626,6 +711,12 @@ impl RenderResources {
             usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
             mapped_at_creation: false,
         });
+        let dim_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Background Dim Buffer"),
+            size: std::mem::size_of::<QuadInstance>() as u64,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
 
         // PROGRESS PIPELINE
         let progress_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
@@ -852,19 +943,10 @@ impl RenderResources {
         );
         let pixel_system = PixelSystem::new(config.width, config.height);
 
-        let mut pf_config = PlayfieldConfig::new();
-        pf_config.column_width_pixels = skin.gameplay.playfield.column_width;
+        let pf_config = skin.gameplay.playfield.layout();
 
         // Get judgement PANEL colors from judgement_panel config (SEPARATE from flash)
-        let colors = engine::JudgementColors {
-            marv: skin.hud.judgement_panel.marv_color,
-            perfect: skin.hud.judgement_panel.perfect_color,
-            great: skin.hud.judgement_panel.great_color,
-            good: skin.hud.judgement_panel.good_color,
-            bad: skin.hud.judgement_panel.bad_color,
-            miss: skin.hud.judgement_panel.miss_color,
-            ghost_tap: skin.hud.judgement_panel.ghost_tap_color,
-        };
+        let colors = judgement_panel_colors(&skin);
 
         let mut res = Self {
             render_pipeline,
@@ -876,10 +958,15 @@ impl RenderResources {
             receptor_buffer,
             quad_buffer,
             progress_buffer, // NEW
+            dim_buffer,
             note_bind_groups: Vec::new(),
             receptor_bind_groups: Vec::new(),
             receptor_pressed_bind_groups: Vec::new(),
             background_bind_group: None,
+            previous_background_bind_group: None,
+            background_loaded_at: None,
+            background_params_buffers: bg_params_buffers,
+            background_params_bind_groups: bg_params_bind_groups,
             background_sampler: bg_sampler,
             current_background_path: None,
 
@@ -901,6 +988,8 @@ impl RenderResources {
 
             text_brush,
             pixel_system,
+            gameplay_viewport: Viewport::full(config.width, config.height),
+            gameplay_pixel_system: PixelSystem::new(config.width, config.height),
             skin,
             settings,
 
@@ -949,20 +1038,39 @@ impl RenderResources {
         // 1. Mise à jour Playfield
         let pf = self.gameplay_view.playfield_component_mut();
 
-        pf.config.note_width_pixels = gameplay.playfield.note_size.x;
-        pf.config.note_height_pixels = gameplay.playfield.note_size.y;
-        pf.config.receptor_width_pixels = gameplay.playfield.receptor_size.x;
-        pf.config.receptor_height_pixels = gameplay.playfield.receptor_size.y;
-        pf.config.receptor_spacing_pixels = gameplay.playfield.receptor_spacing;
-        pf.config.column_width_pixels = gameplay.playfield.column_width;
+        // Taille des notes (et récepteurs) et largeur des colonnes réglées
+        // séparément.
+        pf.config = scaled_playfield_config(
+            &self.skin,
+            self.settings.note_scale,
+            self.settings.column_width_scale,
+        );
 
         let playfield_width_px = pf.get_total_width_pixels();
-        // Centrage: x = 640 est le centre de 1280.
-        let x_offset = gameplay.playfield.position.x - (screen_width / 2.0);
+        // Le gameplay est dessiné dans le viewport (letterboxing éventuel),
+        // recalculé ici seulement: au redimensionnement ou changement de réglage.
+        let viewport = Viewport::letterbox(
+            screen_width as u32,
+            screen_height as u32,
+            self.settings.aspect_ratio_mode.ratio(),
+        );
+        let field_width = viewport.width;
+        // Centrage: x = 640 est le centre de 1280. L'alignement des settings
+        // remplace la position du skin.
+        let x_offset = self
+            .settings
+            .playfield_alignment
+            .playfield_x_offset(field_width, playfield_width_px)
+            .unwrap_or(gameplay.playfield.position.x - (field_width / 2.0));
         let y_offset = gameplay.playfield.position.y;
 
         pf.config.x_offset_pixels = x_offset;
         pf.config.y_offset_pixels = y_offset;
+        pf.config.hit_line_offset_pixels = self.settings.receptor_offset;
+
+        self.gameplay_viewport = viewport;
+        self.gameplay_pixel_system
+            .update_size(viewport.width as u32, viewport.height as u32, None);
 
         // 2. Mise à jour HUD
         self.score_display
@@ -972,6 +1080,7 @@ impl RenderResources {
         self.combo_display
             .set_position(hud.combo.position.x, hud.combo.position.y);
         self.combo_display.set_size(hud.combo.scale);
+        self.gameplay_view.set_combo_break_shake(hud.combo.break_shake);
 
         self.accuracy_panel
             .set_position(hud.accuracy.position.x, hud.accuracy.position.y);
@@ -984,10 +1093,13 @@ impl RenderResources {
         );
         self.judgements_panel
             .set_size(hud.judgement_panel.text_scale);
+        self.judgements_panel
+            .set_colors(judgement_panel_colors(&self.skin).with_palette(self.settings.palette));
 
         self.nps_display
             .set_position(hud.nps.position.x, hud.nps.position.y);
         self.nps_display.set_size(hud.nps.scale);
+        self.nps_display.smoothed = hud.nps.smoothed;
 
         let hitbar_width = playfield_width_px * 0.8;
         self.hit_bar.set_geometry(
@@ -997,12 +1109,13 @@ impl RenderResources {
             hud.hit_bar.scale,
         );
 
-        // Judgement Flash - uses the marv position as central flash position
+        // Judgement Flash - each judgement has its own position
         self.judgement_flash
-            .set_position(hud.judgement.marv.position.x, hud.judgement.marv.position.y);
+            .set_positions(hud.judgement.positions());
 
         // Set timing indicator option from skin config
         self.judgement_flash.show_timing = hud.judgement.show_timing;
+        self.judgement_flash.per_column = hud.judgement.per_column;
 
         // NEW: Notes Remaining display (separate from judgement panel)
         self.notes_remaining_display.set_position(
@@ -1086,9 +1199,42 @@ impl RenderResources {
                 ],
             });
 
-            self.background_bind_group = Some(bind_group);
+            // Previous image fades out under the new one
+            self.previous_background_bind_group = self.background_bind_group.replace(bind_group);
+            self.background_loaded_at = Some(Instant::now());
             self.current_background_path = Some(path_str.to_string());
             log::info!("RENDER: Background loaded: {:?}", path);
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_note_scale_does_not_change_column_width() {
+        let mut skin = Skin::default();
+        skin.gameplay.playfield.column_width = 100.0;
+        skin.gameplay.playfield.column_widths = vec![120.0];
+        skin.gameplay.playfield.note_size.x = 90.0;
+        skin.gameplay.playfield.note_size.y = 60.0;
+
+        let base = scaled_playfield_config(&skin, 1.0, 1.0);
+        let small_notes = scaled_playfield_config(&skin, 0.5, 1.0);
+        assert_eq!(small_notes.note_width_pixels, 45.0);
+        assert_eq!(small_notes.note_height_pixels, 30.0);
+        assert_eq!(small_notes.column_width_pixels, base.column_width_pixels);
+        assert_eq!(small_notes.total_width(4), base.total_width(4));
+
+        // And the other way around
+        let wide_columns = scaled_playfield_config(&skin, 1.0, 1.5);
+        assert_eq!(wide_columns.column_width_pixels, 150.0);
+        assert_eq!(wide_columns.column_widths, vec![180.0]);
+        assert_eq!(wide_columns.note_width_pixels, base.note_width_pixels);
+        assert_eq!(
+            wide_columns.receptor_width_pixels,
+            base.receptor_width_pixels
+        );
+    }
+}
@@ -46,7 +46,9 @@ impl UiOverlay {
         response.consumed
     }
 
-    pub fn begin_frame(&mut self, window: &Window) {
+    /// `ui_scale` multiplies the window scale factor (egui zoom).
+    pub fn begin_frame(&mut self, window: &Window, ui_scale: f32) {
+        self.ctx.set_zoom_factor(ui_scale);
         let raw_input = self.state.take_egui_input(window);
         self.ctx.begin_pass(raw_input);
     }
@@ -90,9 +92,9 @@ impl UiOverlay {
         self.state
             .handle_platform_output(&ctx.window, full_output.platform_output);
 
-        let tris = self
-            .ctx
-            .tessellate(full_output.shapes, ctx.window.scale_factor() as f32);
+        // Facteur de la fenêtre multiplié par le zoom (échelle de l'UI)
+        let pixels_per_point = full_output.pixels_per_point;
+        let tris = self.ctx.tessellate(full_output.shapes, pixels_per_point);
 
         for (id, image) in &full_output.textures_delta.set {
             self.renderer
@@ -101,13 +103,35 @@ impl UiOverlay {
 
         let screen_descriptor = egui_wgpu::ScreenDescriptor {
             size_in_pixels: [ctx.config.width, ctx.config.height],
-            pixels_per_point: ctx.window.scale_factor() as f32,
+            pixels_per_point,
         };
 
         self.renderer
             .update_buffers(&ctx.device, &ctx.queue, encoder, &tris, &screen_descriptor);
 
-        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        paint(&self.renderer, encoder, view, &tris, &screen_descriptor);
+
+        for id in &full_output.textures_delta.free {
+            self.renderer.free_texture(id);
+        }
+    }
+}
+
+/// Dessine l'UI tessellée par-dessus `view`.
+///
+/// egui-wgpu demande une `RenderPass<'static>` : `forget_lifetime` détache la
+/// passe de l'emprunt de l'encoder (wgpu vérifie alors à l'exécution qu'elle
+/// est terminée avant de réutiliser l'encoder, ce que garantit la fin de
+/// cette fonction).
+fn paint(
+    renderer: &EguiRenderer,
+    encoder: &mut wgpu::CommandEncoder,
+    view: &wgpu::TextureView,
+    tris: &[egui::ClippedPrimitive],
+    screen_descriptor: &egui_wgpu::ScreenDescriptor,
+) {
+    let mut render_pass = encoder
+        .begin_render_pass(&wgpu::RenderPassDescriptor {
             label: Some("Egui Main Pass"),
             color_attachments: &[Some(wgpu::RenderPassColorAttachment {
                 view,
@@ -121,19 +145,117 @@ impl UiOverlay {
             depth_stencil_attachment: None,
             timestamp_writes: None,
             occlusion_query_set: None,
-        });
+        })
+        .forget_lifetime();
 
-        let rpass_static = unsafe {
-            std::mem::transmute::<&mut wgpu::RenderPass<'_>, &mut wgpu::RenderPass<'static>>(
-                &mut render_pass,
-            )
+    renderer.render(&mut render_pass, tris, screen_descriptor);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::settings::SettingsState;
+
+    #[test]
+    fn test_egui_frame_renders_headless() {
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::default());
+        let Ok(adapter) =
+            pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions::default()))
+        else {
+            eprintln!("Pas d'adaptateur GPU, test ignoré");
+            return;
         };
+        let (device, queue) =
+            pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor::default()))
+                .expect("Failed to create device");
 
-        self.renderer
-            .render(rpass_static, &tris, &screen_descriptor);
+        let format = TextureFormat::Rgba8UnormSrgb;
+        let size = 64;
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Egui Test Target"),
+            size: wgpu::Extent3d {
+                width: size,
+                height: size,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let mut renderer = EguiRenderer::new(&device, format, RendererOptions::default());
 
-        for id in &full_output.textures_delta.free {
-            self.renderer.free_texture(id);
+        let ctx = egui::Context::default();
+        ctx.begin_pass(egui::RawInput::default());
+        egui::CentralPanel::default().show(&ctx, |ui| ui.label("Prism"));
+        let output = ctx.end_pass();
+        let tris = ctx.tessellate(output.shapes, output.pixels_per_point);
+        for (id, image) in &output.textures_delta.set {
+            renderer.update_texture(&device, &queue, *id, image);
         }
+
+        let screen_descriptor = egui_wgpu::ScreenDescriptor {
+            size_in_pixels: [size, size],
+            pixels_per_point: 1.0,
+        };
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        renderer.update_buffers(&device, &queue, &mut encoder, &tris, &screen_descriptor);
+        paint(&renderer, &mut encoder, &view, &tris, &screen_descriptor);
+        // L'encoder est de nouveau utilisable une fois la passe terminée
+        queue.submit([encoder.finish()]);
+    }
+
+    /// Pixels par point d'une frame sur une fenêtre d'échelle `window_scale`.
+    fn frame_pixels_per_point(window_scale: f32, ui_scale: f32) -> f32 {
+        let ctx = egui::Context::default();
+        ctx.set_zoom_factor(ui_scale);
+        let mut input = egui::RawInput::default();
+        input
+            .viewports
+            .entry(egui::ViewportId::ROOT)
+            .or_default()
+            .native_pixels_per_point = Some(window_scale);
+        ctx.run(input, |_| {}).pixels_per_point
+    }
+
+    #[test]
+    fn test_cloned_context_stays_valid_across_frames() {
+        // Même déroulé que le renderer : passe sur le contexte de l'UI,
+        // pages dessinées avec un clone
+        let overlay_ctx = egui::Context::default();
+        let counter = egui::Id::new("frames");
+        let frame = || {
+            overlay_ctx.begin_pass(egui::RawInput::default());
+            let ctx_egui = overlay_ctx.clone();
+            egui::CentralPanel::default().show(&ctx_egui, |ui| ui.label("Prism"));
+            ctx_egui.data_mut(|data| *data.get_temp_mut_or_default::<u32>(counter) += 1);
+            overlay_ctx.end_pass()
+        };
+
+        let first = frame();
+        let second = frame();
+        assert!(!first.shapes.is_empty());
+        assert!(!second.shapes.is_empty());
+        // Le clone partage l'état du contexte d'une frame à l'autre
+        assert_eq!(
+            overlay_ctx.data(|data| data.get_temp::<u32>(counter)),
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn test_pixels_per_point_is_window_scale_times_ui_scale() {
+        let mut settings = SettingsState::new();
+        assert_eq!(frame_pixels_per_point(1.5, settings.ui_scale()), 1.5);
+
+        settings.ui_scale = 1.5;
+        assert_eq!(frame_pixels_per_point(2.0, settings.ui_scale()), 3.0);
+
+        // Échelle hors limites bornée
+        settings.ui_scale = 10.0;
+        assert_eq!(frame_pixels_per_point(1.0, settings.ui_scale()), 2.0);
     }
 }
@@ -128,6 +128,23 @@ pub fn create_bind_group_layout(device: &Device) -> BindGroupLayout {
     })
 }
 
+/// Layout du uniform `BackgroundParams` (group 1 du shader de fond).
+pub fn create_background_params_layout(device: &Device) -> BindGroupLayout {
+    device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        label: Some("Background Params Layout"),
+        entries: &[wgpu::BindGroupLayoutEntry {
+            binding: 0,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        }],
+    })
+}
+
 pub fn create_sampler(device: &Device) -> Sampler {
     device.create_sampler(&wgpu::SamplerDescriptor {
         address_mode_u: wgpu::AddressMode::ClampToEdge,
@@ -164,6 +181,11 @@ pub fn create_render_pipeline(
                 shader_location: 6,
                 format: wgpu::VertexFormat::Float32x2,
             }, // Scale
+            wgpu::VertexAttribute {
+                offset: 16,
+                shader_location: 7,
+                format: wgpu::VertexFormat::Float32,
+            }, // Alpha
         ],
     };
 
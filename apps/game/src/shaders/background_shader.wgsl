@@ -24,9 +24,37 @@ fn vs_main(@builtin(vertex_index) in_vertex_index: u32) -> VertexOutput {
 @group(0) @binding(0) var t_background: texture_2d<f32>;
 @group(0) @binding(1) var s_background: sampler;
 
+struct BackgroundParams {
+    // Opacity of the layer (crossfade)
+    alpha: f32,
+    // Color multiplier (1 = untouched, 0 = black)
+    brightness: f32,
+    // Blur radius in texture pixels (0 = sharp)
+    blur_px: f32,
+    _pad: f32,
+};
+
+@group(1) @binding(0) var<uniform> params: BackgroundParams;
+
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
-    let color = textureSample(t_background, s_background, in.tex_coords);
-    return color * vec4<f32>(0.4, 0.4, 0.4, 1.0);
-}
+    var color = textureSampleLevel(t_background, s_background, in.tex_coords, 0.0);
+
+    if (params.blur_px > 0.0) {
+        // 5x5 gaussian taps spread over the blur radius
+        let step = params.blur_px * 0.5 / vec2<f32>(textureDimensions(t_background));
+        var sum = vec4<f32>(0.0);
+        var weight_sum = 0.0;
+        for (var x = -2; x <= 2; x++) {
+            for (var y = -2; y <= 2; y++) {
+                let weight = exp(-f32(x * x + y * y) / 4.0);
+                let offset = vec2<f32>(f32(x), f32(y)) * step;
+                sum += textureSampleLevel(t_background, s_background, in.tex_coords + offset, 0.0) * weight;
+                weight_sum += weight;
+            }
+        }
+        color = sum / weight_sum;
+    }
 
+    return vec4<f32>(color.rgb * params.brightness, color.a * params.alpha);
+}
@@ -4,7 +4,9 @@
 struct Uniforms {
     time: f32,
     aspect: f32,
-    _padding: vec2<f32>,
+    line_thickness: f32,
+    _padding: f32,
+    color: vec4<f32>,
 };
 
 @group(0) @binding(0)
@@ -15,16 +17,11 @@ struct VertexOutput {
     @location(0) edge_factor: f32,
 };
 
-// Cube size (half-extent)
-const CUBE_SIZE: f32 = 0.3;
-
 // Rotation speed
 const ROTATION_SPEED: f32 = 0.05;
 
-@vertex
-fn vs_main(@location(0) position: vec3<f32>, @location(1) edge_factor: f32) -> VertexOutput {
-    var out: VertexOutput;
-    
+// Rotates a corner and projects it to clip space
+fn project(position: vec3<f32>) -> vec4<f32> {
     // Calculate rotation angles based on time
     let angle_x = uniforms.time * ROTATION_SPEED;
     let angle_y = uniforms.time * ROTATION_SPEED * 1.3;
@@ -48,19 +45,49 @@ fn vs_main(@location(0) position: vec3<f32>, @location(1) edge_factor: f32) -> V
     );
     
     // Apply rotations
-    var rotated = rot_y * rot_x * position;
+    let rotated = rot_y * rot_x * position;
     
     // Simple perspective projection
     let z_offset = 2.0; // Camera distance
     let perspective_scale = 1.0 / (rotated.z + z_offset);
     
     // Apply aspect ratio correction and perspective
-    out.position = vec4<f32>(
+    return vec4<f32>(
         rotated.x * perspective_scale / uniforms.aspect,
         rotated.y * perspective_scale,
         (rotated.z + z_offset) / 4.0, // Normalize depth
         1.0
     );
+}
+
+@vertex
+fn vs_main(
+    @location(0) position: vec3<f32>,
+    @location(1) edge_factor: f32,
+    @location(2) other: vec3<f32>,
+    @location(3) side: f32,
+) -> VertexOutput {
+    var out: VertexOutput;
+    
+    let start = project(position);
+    let end = project(other);
+    
+    // Push the corner sideways, perpendicular to the edge on screen
+    // (computed without the aspect ratio so the thickness is even)
+    let dir = (end.xy - start.xy) * vec2<f32>(uniforms.aspect, 1.0);
+    var normal = vec2<f32>(0.0, 0.0);
+    if (length(dir) > 0.0) {
+        normal = normalize(vec2<f32>(-dir.y, dir.x));
+    }
+    // Clip space spans 2 units of screen height
+    let offset = normal * side * uniforms.line_thickness;
+    
+    out.position = vec4<f32>(
+        start.x + offset.x / uniforms.aspect,
+        start.y + offset.y,
+        start.z,
+        1.0
+    );
     
     out.edge_factor = edge_factor;
     
@@ -69,9 +96,8 @@ fn vs_main(@location(0) position: vec3<f32>, @location(1) edge_factor: f32) -> V
 
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
-    // Prism primary color with glow based on edge factor
-    let base_color = vec3<f32>(1.0, 0.0, 0.235); // #ff003c
+    // Configured color with glow based on edge factor
     let glow_intensity = 0.3 + in.edge_factor * 0.7;
     
-    return vec4<f32>(base_color * glow_intensity, 0.9);
+    return vec4<f32>(uniforms.color.rgb * glow_intensity, uniforms.color.a);
 }
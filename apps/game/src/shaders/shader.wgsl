@@ -1,11 +1,13 @@
 struct VertexOutput {
     @builtin(position) clip_position: vec4<f32>,
     @location(0) tex_coords: vec2<f32>,
+    @location(1) alpha: f32,
 };
 
 struct InstanceInput {
     @location(5) offset: vec2<f32>,
     @location(6) scale: vec2<f32>,
+    @location(7) alpha: f32,
 };
 
 @vertex
@@ -28,6 +30,7 @@ fn vs_main(
     var out: VertexOutput;
     out.clip_position = vec4<f32>(world_pos, 0.0, 1.0);
     out.tex_coords = uvs[in_vertex_index];
+    out.alpha = instance.alpha;
     return out;
 }
 
@@ -36,6 +39,7 @@ fn vs_main(
 
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
-    return textureSample(t_diffuse, s_diffuse, in.tex_coords);
+    let color = textureSample(t_diffuse, s_diffuse, in.tex_coords);
+    return vec4<f32>(color.rgb, color.a * in.alpha);
 }
 
@@ -4,9 +4,13 @@
 //! to the render thread. This decouples game logic from rendering.
 
 use crate::input::events::{EditMode, EditorTarget};
+use crate::state::game::ComboMilestone;
+use crate::state::mods::NoteVisibility;
 use crate::state::{GameResultData, MenuState};
+use crate::ui::common::ParticleField;
 use engine::NoteData;
 use engine::{HitStats, Judgement};
+use std::sync::Arc;
 use std::time::Instant;
 
 /// High-level render state representing the current game mode.
@@ -14,8 +18,8 @@ use std::time::Instant;
 pub enum RenderState {
     /// Initial empty state.
     Empty,
-    /// Main menu (Play/Quit).
-    MainMenu,
+    /// Main menu (Play/Quit), with its background particles.
+    MainMenu(ParticleField),
     /// Song select menu.
     Menu(MenuState),
     /// Active gameplay.
@@ -43,6 +47,24 @@ pub struct EditorSnapshot {
     pub save_requested: bool,
 }
 
+/// Hit on a column, flashed on its receptor.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ReceptorHit {
+    /// Audio time of the hit in milliseconds.
+    pub time: f64,
+    pub judgement: Judgement,
+}
+
+/// Timed hit, with an id so the renderer can tell which ones it already showed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HitEvent {
+    /// Event id (see [`GameplaySnapshot::combo_break_id`]).
+    pub id: u64,
+    /// Hit timing offset in ms.
+    pub timing: f64,
+    pub judgement: Judgement,
+}
+
 /// Snapshot of gameplay state for rendering.
 #[derive(Clone, Debug)]
 pub struct GameplaySnapshot {
@@ -52,13 +74,22 @@ pub struct GameplaySnapshot {
     pub audio_time: f64,
     /// Wall-clock time when snapshot was created (for interpolation).
     pub timestamp: Instant,
+    /// (instant, audio time in ms) of the engine tick before this one.
+    ///
+    /// Snapshots are latest-wins, so the renderer can't rebuild the tick
+    /// interval from the snapshots it happens to receive.
+    pub previous_tick: Option<(Instant, f64)>,
     /// Playback rate multiplier.
     pub rate: f64,
     /// Scroll speed in milliseconds.
     pub scroll_speed: f64,
+    /// Scale of the skin's note size.
+    pub note_scale: f32,
+    /// Scale of the skin's column widths.
+    pub column_width_scale: f32,
 
-    /// Notes currently visible on screen.
-    pub visible_notes: Vec<NoteData>,
+    /// Notes currently visible on screen (buffer shared with the engine).
+    pub visible_notes: Arc<Vec<NoteData>>,
     /// Per-column key held state.
     pub keys_held: Vec<bool>,
 
@@ -75,11 +106,17 @@ pub struct GameplaySnapshot {
 
     /// Last hit judgement (for flash display).
     pub last_hit_judgement: Option<Judgement>,
+    /// Column of the last hit (for per-column flashes).
+    pub last_hit_column: Option<usize>,
     /// Last hit timing offset in ms.
     pub last_hit_timing: Option<f64>,
+    /// Last hit on each column (for the receptor glow).
+    pub receptor_hits: Vec<Option<ReceptorHit>>,
 
-    /// Current notes per second.
+    /// Current notes per second (raw 1-second window).
     pub nps: f64,
+    /// Exponentially smoothed notes per second.
+    pub smoothed_nps: f64,
 
     /// Whether practice mode is enabled.
     pub practice_mode: bool,
@@ -87,4 +124,18 @@ pub struct GameplaySnapshot {
     pub checkpoints: Vec<f64>,
     /// Total map duration (for progress graph).
     pub map_duration: f64,
+    /// Note visibility rules from the active mods.
+    pub visibility: NoteVisibility,
+    /// Latest timed hits, oldest first (for the hit bar).
+    pub recent_hits: Vec<HitEvent>,
+    /// Id of the last combo break (0 before the first one).
+    ///
+    /// The renderer shakes when it grows, even if it skipped the snapshot
+    /// that first carried it.
+    pub combo_break_id: u64,
+    /// Id of the last combo milestone (0 before the first one), and the
+    /// milestone itself.
+    pub combo_milestone: (u64, Option<ComboMilestone>),
+    /// Life bar health in [0, 1] (None when the life bar is off).
+    pub health: Option<f32>,
 }
@@ -4,8 +4,7 @@ use std::time::{SystemTime, UNIX_EPOCH};
 
 use super::GameEngine;
 use database::SaveReplayCommand;
-use replay::simulate;
-use crate::models::settings::HitWindowMode;
+use replay::{simulate, validate_result};
 use crate::shared::snapshot::GameplaySnapshot;
 use crate::state::GameResultData;
 use crate::state::traits::{Snapshot, Transition, Update, UpdateContext};
@@ -32,23 +31,44 @@ impl Update for GameEngine {
         }
 
         // Game finished - build results and save replay
-        let chart = self.get_chart();
-        let replay_result = simulate(&self.replay_data, &chart, &self.hit_window);
-        let accuracy = replay_result.accuracy;
+        self.replay_data.set_hit_window(&self.hit_window);
+        let chart = self.chart();
+        let replay_result = simulate(&self.replay_data, chart, &self.hit_window);
+        // The notes after a fail were never played: keep the live stats
+        let (hit_stats, accuracy) = if self.failed {
+            (self.hit_stats.clone(), self.hit_stats.calculate_accuracy())
+        } else {
+            (replay_result.hit_stats.clone(), replay_result.accuracy)
+        };
 
-        // Save replay to database
-        if let Some(payload) = build_replay_payload(self, accuracy) {
+        // Save replay to database, unless it desynced from the chart
+        if !self.is_submittable() {
+            log::info!("REPLAY: Not saving replay of an autoplay run");
+        } else if self.failed {
+            if let Some(payload) = self.incomplete_replay_payload() {
+                ctx.db_manager.save_replay(payload);
+            }
+        } else if let Err(e) =
+            validate_result(&replay_result, &self.replay_data, chart, &self.hit_window)
+        {
+            log::warn!("REPLAY: Not saving replay that failed validation: {}", e);
+        } else if let Some(payload) =
+            build_replay_payload(self, accuracy, ctx.settings.track_achievements)
+        {
             ctx.db_manager.save_replay(payload);
         }
 
         // Format judge text from settings
-        let judge_text =
-            format_hit_window_text(ctx.settings.hit_window_mode, ctx.settings.hit_window_value);
+        let judge_text = ctx
+            .settings
+            .hit_window_mode
+            .label(ctx.settings.hit_window_value);
 
-        // Build result data
+        // Build result data. The engine is replaced by the result screen,
+        // so its replay is moved rather than copied.
         let result = GameResultData {
-            hit_stats: replay_result.hit_stats.clone(),
-            replay_data: self.replay_data.clone(),
+            hit_stats,
+            replay_data: std::mem::take(&mut self.replay_data),
             replay_result,
             score: self.score,
             accuracy,
@@ -57,22 +77,44 @@ impl Update for GameEngine {
             rate: self.rate,
             judge_text,
             show_settings: false,
+            failed: self.failed,
         };
 
         Some(Transition::ToResult(result))
     }
 }
 
-/// Formats the hit window mode and value as a display string.
-fn format_hit_window_text(mode: HitWindowMode, value: f64) -> String {
-    match mode {
-        HitWindowMode::OsuOD => format!("OD {:.1}", value),
-        HitWindowMode::EtternaJudge => format!("Judge {:.0}", value),
+impl GameEngine {
+    /// Replay of a run quit before the end, flagged incomplete.
+    ///
+    /// Returns `None` for runs that may not be saved or where nothing was
+    /// played yet.
+    pub fn abandoned_replay_payload(&self) -> Option<SaveReplayCommand> {
+        if self.is_finished() {
+            return None;
+        }
+        self.incomplete_replay_payload()
+    }
+
+    /// Replay of the run so far, flagged incomplete (abandoned or failed).
+    fn incomplete_replay_payload(&self) -> Option<SaveReplayCommand> {
+        if !self.is_submittable() || self.replay_data.inputs.is_empty() {
+            return None;
+        }
+        // Incomplete runs never unlock achievements
+        let mut payload = build_replay_payload(self, self.hit_stats.calculate_accuracy(), false)?;
+        payload.data.set_hit_window(&self.hit_window);
+        payload.data.incomplete = true;
+        Some(payload)
     }
 }
 
 /// Converts gameplay stats into a DB command for replay persistence.
-fn build_replay_payload(engine: &GameEngine, accuracy: f64) -> Option<SaveReplayCommand> {
+fn build_replay_payload(
+    engine: &GameEngine,
+    accuracy: f64,
+    track_achievements: bool,
+) -> Option<SaveReplayCommand> {
     let hash = match engine.beatmap_hash.clone() {
         Some(h) => h,
         None => {
@@ -93,6 +135,10 @@ fn build_replay_payload(engine: &GameEngine, accuracy: f64) -> Option<SaveReplay
         accuracy,
         max_combo: engine.max_combo.min(i32::MAX as u32) as i32,
         rate: engine.rate,
+        hit_window_mode: engine.hit_window_mode.into(),
+        hit_window_value: engine.hit_window_value,
+        full_combo: engine.is_full_combo(),
+        track_achievements,
         data: engine.replay_data.clone(),
     })
 }
@@ -0,0 +1,99 @@
+//! Autoplay - the engine plays back a generated perfect replay.
+//!
+//! All times are in microseconds (i64).
+
+use super::GameEngine;
+use engine::GameMod;
+use replay::generate_autoplay;
+
+impl GameEngine {
+    /// Replaces the recorded inputs with a perfect run of the chart.
+    ///
+    /// Called by [`GameEngine::apply_mods`] when Autoplay is active, after
+    /// the chart-altering mods have been applied.
+    pub(crate) fn enable_autoplay(&mut self) {
+        let generated = generate_autoplay(&self.chart, self.rate, self.key_count);
+        self.replay_data.inputs = generated.inputs;
+        self.autoplay_cursor = Some(0);
+        log::info!(
+            "AUTOPLAY: Enabled ({} inputs)",
+            self.replay_data.inputs.len()
+        );
+    }
+
+    /// Whether the engine plays the chart by itself.
+    pub fn is_autoplay(&self) -> bool {
+        self.autoplay_cursor.is_some()
+    }
+
+    /// Whether this run may be saved as a score.
+    pub fn is_submittable(&self) -> bool {
+        self.replay_data.mods.iter().all(GameMod::is_submittable)
+    }
+
+    /// Feeds the generated inputs up to `current_time_us` (chart time).
+    ///
+    /// Each input is judged at its own timestamp rather than the frame
+    /// time, so the live score matches the replay simulation.
+    pub(crate) fn step_autoplay(&mut self, current_time_us: i64) {
+        let Some(mut cursor) = self.autoplay_cursor else {
+            return;
+        };
+
+        while let Some(input) = self.replay_data.inputs.get(cursor) {
+            if input.time_us > current_time_us {
+                break;
+            }
+            let (column, is_press) = input.unpack();
+            let time_us = input.time_us;
+            cursor += 1;
+
+            if column < self.keys_held.len() {
+                self.keys_held[column] = is_press;
+            }
+            if is_press {
+                // Bursts need several taps: tap until complete
+                while let Some(idx) = self.process_hit_at(column, time_us)
+                    && self.chart[idx].is_burst()
+                    && !self.chart[idx].state.hit
+                {}
+            } else {
+                self.process_release_at(column, time_us);
+            }
+        }
+
+        self.autoplay_cursor = Some(cursor);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::events::GameAction;
+    use crate::state::game::test_engine;
+    use engine::NoteData;
+
+    #[test]
+    fn test_autoplay_run_is_perfect_and_not_submittable() {
+        let chart = vec![
+            NoteData::tap(1_000_000, 0),
+            NoteData::hold(1_200_000, 1, 400_000),
+            NoteData::tap(1_500_000, 2),
+        ];
+        let mut engine = test_engine(chart);
+        assert!(engine.is_submittable());
+        engine.apply_mods(&[GameMod::Autoplay]);
+        assert!(engine.is_autoplay());
+        assert!(!engine.is_submittable());
+
+        // Player inputs are ignored
+        engine.handle_input(GameAction::hit(3));
+        while !engine.is_finished() {
+            engine.update(1.0 / 60.0);
+        }
+
+        assert_eq!(engine.hit_stats.marv, 3);
+        assert_eq!(engine.hit_stats.ghost_tap, 0);
+        assert_eq!(engine.hit_stats.miss, 0);
+    }
+}
@@ -0,0 +1,71 @@
+//! Fail conditions for GameEngine.
+//!
+//! A failed run ends at once and goes to the result screen marked as
+//! failed. Its replay is saved as incomplete, never as a score.
+
+use super::GameEngine;
+use engine::{GameMod, HealthPreset, LifeBar};
+
+impl GameEngine {
+    /// Selects the life bar preset, and whether emptying it fails the run.
+    ///
+    /// Must be called before the play starts.
+    pub fn set_life_bar(&mut self, preset: HealthPreset, fail_on_empty: bool) {
+        self.life_bar = LifeBar::from_preset(preset);
+        self.fail_on_empty_health = fail_on_empty;
+    }
+
+    /// Whether a fail condition ended the run.
+    pub fn is_failed(&self) -> bool {
+        self.failed
+    }
+
+    /// Fails the run on a miss under Sudden Death, or once the life bar is
+    /// empty if that fails the run.
+    ///
+    /// Practice runs never fail: checkpoints are there to retry sections.
+    pub(crate) fn check_fail(&mut self) {
+        if self.failed || self.practice_mode {
+            return;
+        }
+        if self.replay_data.mods.contains(&GameMod::SuddenDeath) && self.hit_stats.miss > 0 {
+            log::info!("GAME: Sudden Death failed at {} µs", self.audio_clock_us);
+            self.failed = true;
+        }
+        if self.fail_on_empty_health && self.life_bar.is_some_and(|life| life.is_empty()) {
+            log::info!("GAME: Life bar emptied at {} µs", self.audio_clock_us);
+            self.failed = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::game::test_engine;
+    use engine::NoteData;
+    use std::time::Instant;
+
+    /// Lets the first of two notes pass unhit.
+    fn miss_first_note(mods: &[GameMod]) -> GameEngine {
+        let chart = vec![NoteData::tap(1_000_000, 0), NoteData::tap(5_000_000, 1)];
+        let mut engine = test_engine(chart);
+        engine.apply_mods(mods);
+        engine.audio_clock_us = 2_000_000;
+        engine.update_at(0.0, Instant::now());
+        assert_eq!(engine.hit_stats.miss, 1);
+        engine
+    }
+
+    #[test]
+    fn test_sudden_death_fails_on_first_miss() {
+        let engine = miss_first_note(&[GameMod::SuddenDeath]);
+        assert!(engine.is_failed());
+        // The run ends without waiting for the last note
+        assert!(engine.is_finished());
+
+        let engine = miss_first_note(&[]);
+        assert!(!engine.is_failed());
+        assert!(!engine.is_finished());
+    }
+}
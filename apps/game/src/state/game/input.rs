@@ -1,8 +1,9 @@
-//! Input handling for GameEngine - process_hit, process_release, handle_input
+//! Input handling for GameEngine - handle_input, process_hit_at, process_release_at
 //!
 //! All times are in microseconds (i64).
 
 use super::GameEngine;
+use super::snapshot::{RECENT_HITS, next_event_id};
 use crate::input::events::GameAction;
 
 use engine::Judgement;
@@ -11,28 +12,32 @@ impl GameEngine {
     /// Handles a gameplay input action.
     pub fn handle_input(&mut self, action: GameAction) {
         match action {
-            GameAction::Hit { column } => {
+            // Autoplay plays the notes itself
+            GameAction::Hit { .. } | GameAction::Release { .. } if self.is_autoplay() => {}
+            GameAction::Hit { column, at } => {
                 if column < self.keys_held.len() {
                     self.keys_held[column] = true;
                 }
 
                 // Record the raw PRESS input in the replay (in µs)
-                self.replay_data.add_press(self.audio_clock_us, column);
+                let time_us = self.audio_time_at(at);
+                self.replay_data.add_press(time_us, column);
 
-                // Record input timestamp for NPS calculation
-                self.input_timestamps.push_back(self.audio_clock_us);
-                self.process_hit(column);
+                // Apply global audio offset to compensate for audio latency
+                // Positive offset = notes appear later (audio late), Negative = notes appear earlier (audio early)
+                self.process_hit_at(column, time_us + self.audio_offset_us);
             }
-            GameAction::Release { column } => {
+            GameAction::Release { column, at } => {
                 if column < self.keys_held.len() {
                     self.keys_held[column] = false;
                 }
 
                 // Record the raw RELEASE input in the replay
-                self.replay_data.add_release(self.audio_clock_us, column);
+                let time_us = self.audio_time_at(at);
+                self.replay_data.add_release(time_us, column);
 
-                // Check if releasing a hold note
-                self.process_release(column);
+                // Check if releasing a hold note (same offset as presses)
+                self.process_release_at(column, time_us + self.audio_offset_us);
             }
             GameAction::TogglePause => { /* TODO */ }
             GameAction::PracticeCheckpoint => {
@@ -49,14 +54,13 @@ impl GameEngine {
         }
     }
 
-    /// Processes a hit input on the given column.
+    /// Processes a hit at an exact chart time (offset already applied).
     ///
     /// Finds the closest unhit note within the hit window and applies
     /// the appropriate judgement based on note type.
-    pub(crate) fn process_hit(&mut self, column: usize) {
-        // Apply global audio offset to compensate for audio latency
-        // Positive offset = notes appear later (audio late), Negative = notes appear earlier (audio early)
-        let current_time_us = self.audio_clock_us + self.audio_offset_us;
+    ///
+    /// Returns the index of the note that received the hit, if any.
+    pub(crate) fn process_hit_at(&mut self, column: usize, current_time_us: i64) -> Option<usize> {
         let miss_us = self.hit_window.miss_us;
         let mut best_note_idx = None;
         let mut min_diff: i64 = i64::MAX;
@@ -67,7 +71,8 @@ impl GameEngine {
             if note.time_us() > search_limit {
                 break;
             }
-            if note.column() == column && !note.state.hit {
+            // Mines are never hit by a press; they trigger in update_notes
+            if note.column() == column && !note.state.hit && !note.is_mine() {
                 let diff = (note.time_us() - current_time_us).abs();
                 if diff <= miss_us && diff < min_diff {
                     min_diff = diff;
@@ -78,6 +83,7 @@ impl GameEngine {
 
         // Apply judgement based on note type
         if let Some(idx) = best_note_idx {
+            self.play_keysound(idx);
             let diff_us = self.chart[idx].time_us() - current_time_us;
 
             if self.chart[idx].is_tap() {
@@ -85,6 +91,9 @@ impl GameEngine {
                 self.chart[idx].state.hit = true;
                 self.last_hit_timing_us = Some(diff_us);
                 self.last_hit_judgement = Some(judgement);
+                self.last_hit_column = Some(column);
+                self.record_column_hit(column, current_time_us, judgement);
+                self.record_timed_hit(diff_us, judgement);
                 self.apply_judgement(judgement);
             } else if self.chart[idx].is_hold() {
                 // Start holding - judgement comes when hold is complete
@@ -93,13 +102,10 @@ impl GameEngine {
                 self.chart[idx].state.hold.is_held = true;
                 self.last_hit_timing_us = Some(diff_us);
                 self.last_hit_judgement = Some(judgement);
+                self.last_hit_column = Some(column);
+                self.record_column_hit(column, current_time_us, judgement);
+                self.record_timed_hit(diff_us, judgement);
                 // Don't mark as hit yet - wait for release/completion
-            } else if self.chart[idx].is_mine() {
-                // Hit a mine = bad!
-                self.chart[idx].state.hit = true;
-                self.last_hit_timing_us = Some(diff_us);
-                self.last_hit_judgement = Some(Judgement::Miss);
-                self.apply_judgement(Judgement::Miss);
             } else if self.chart[idx].is_burst() {
                 // Increment hit count
                 self.chart[idx].state.burst.current_hits += 1;
@@ -111,21 +117,39 @@ impl GameEngine {
                     let (judgement, _) = self.hit_window.judge(diff_us);
                     self.last_hit_timing_us = Some(diff_us);
                     self.last_hit_judgement = Some(judgement);
+                    self.last_hit_column = Some(column);
+                    self.record_column_hit(column, current_time_us, judgement);
+                    self.record_timed_hit(diff_us, judgement);
                     self.apply_judgement(judgement);
                 }
             }
         } else {
             self.last_hit_timing_us = None;
             self.last_hit_judgement = Some(Judgement::GhostTap);
+            self.last_hit_column = Some(column);
             self.apply_judgement(Judgement::GhostTap);
         }
+        best_note_idx
+    }
+
+    /// Remembers a hit on `column`, for the receptor glow.
+    fn record_column_hit(&mut self, column: usize, time_us: i64, judgement: Judgement) {
+        if let Some(hit) = self.last_column_hits.get_mut(column) {
+            *hit = Some((time_us, judgement));
+        }
     }
 
-    /// Processes a release input on the given column (for hold notes).
-    pub(crate) fn process_release(&mut self, column: usize) {
-        // Apply global audio offset for consistency with process_hit
-        let current_time_us = self.audio_clock_us + self.audio_offset_us;
+    /// Remembers a timed hit, for the hit bar.
+    fn record_timed_hit(&mut self, diff_us: i64, judgement: Judgement) {
+        if self.recent_hits.len() == RECENT_HITS {
+            self.recent_hits.pop_front();
+        }
+        self.recent_hits
+            .push_back((next_event_id(), diff_us, judgement));
+    }
 
+    /// Processes a release at an exact chart time (offset already applied).
+    pub(crate) fn process_release_at(&mut self, column: usize, current_time_us: i64) {
         // Find active hold in this column
         for note in self.chart.iter_mut().skip(self.head_index) {
             if note.column() != column || note.state.hit {
@@ -161,6 +185,7 @@ impl GameEngine {
                 };
 
                 self.last_hit_judgement = Some(judgement);
+                self.last_hit_column = Some(column);
                 self.apply_judgement(judgement);
                 break;
             }
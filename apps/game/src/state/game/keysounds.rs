@@ -0,0 +1,161 @@
+//! Keysounds - per-note samples of keysounded charts, played on hit.
+
+use super::GameEngine;
+use std::collections::BTreeSet;
+use std::path::{Component, Path, PathBuf};
+
+/// Keysound playback settings of an engine.
+#[derive(Clone, Debug)]
+pub(crate) struct Keysounds {
+    /// Folder the note samples are relative to (None without audio).
+    pub sample_dir: Option<PathBuf>,
+    /// Whether samples are played on hit.
+    pub enabled: bool,
+    /// Whether the music is muted on keysounded charts.
+    pub mute_music: bool,
+    /// Master volume, shared by the samples and the music.
+    pub volume: f32,
+}
+
+impl Keysounds {
+    pub fn new(sample_dir: Option<PathBuf>) -> Self {
+        Self {
+            sample_dir,
+            enabled: false,
+            mute_music: false,
+            volume: 1.0,
+        }
+    }
+
+    /// Path of a note sample, or `None` if its name leaves the sample
+    /// folder (absolute path or `..` component).
+    pub fn sample_path(&self, sample: &str) -> Option<PathBuf> {
+        let dir = self.sample_dir.as_ref()?;
+        let inside = Path::new(sample)
+            .components()
+            .all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+        inside.then(|| dir.join(sample))
+    }
+}
+
+impl GameEngine {
+    /// Returns true if at least one note has a keysound.
+    pub fn is_keysounded(&self) -> bool {
+        self.chart.iter().any(|note| note.sample.is_some())
+    }
+
+    /// Enables keysounds. With `mute_music`, the music track of keysounded
+    /// charts is silenced and only the samples are heard.
+    ///
+    /// Enabling them has the audio thread decode every sample of the chart.
+    pub fn set_keysounds(&mut self, enabled: bool, mute_music: bool) {
+        self.keysounds.enabled = enabled;
+        self.keysounds.mute_music = mute_music;
+        self.set_volume(self.keysounds.volume);
+        if enabled && self.is_keysounded() {
+            self.preload_keysounds();
+        }
+    }
+
+    /// Sends the distinct samples of the chart to the audio thread.
+    fn preload_keysounds(&self) {
+        if self.keysounds.sample_dir.is_none() {
+            return;
+        }
+        let samples: BTreeSet<&str> = self
+            .chart
+            .iter()
+            .filter_map(|note| note.sample.as_deref())
+            .collect();
+        let paths = samples
+            .into_iter()
+            .filter_map(|sample| {
+                let path = self.keysounds.sample_path(sample);
+                if path.is_none() {
+                    log::warn!(
+                        "KEYSOUNDS: Ignoring sample outside the map folder: {}",
+                        sample
+                    );
+                }
+                path
+            })
+            .collect();
+        self.audio_manager.load_samples(paths);
+    }
+
+    /// Sets the master volume of the music and the keysounds.
+    pub fn set_volume(&mut self, volume: f32) {
+        self.keysounds.volume = volume;
+        let muted = self.keysounds.enabled && self.keysounds.mute_music && self.is_keysounded();
+        self.audio_manager
+            .set_volume(if muted { 0.0 } else { volume });
+    }
+
+    /// Plays the keysound of a note that has just been hit.
+    pub(crate) fn play_keysound(&self, note_idx: usize) {
+        if !self.keysounds.enabled {
+            return;
+        }
+        let Some(path) = self.chart[note_idx]
+            .sample
+            .as_deref()
+            .and_then(|sample| self.keysounds.sample_path(sample))
+        else {
+            return;
+        };
+        self.audio_manager.play_sample(&path, self.keysounds.volume);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::settings::HitWindowSettings;
+    use crate::system::bus::{AudioCommand, SystemBus};
+    use engine::NoteData;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_sample_path_stays_in_map_folder() {
+        let keysounds = Keysounds::new(Some(PathBuf::from("songs/map")));
+        assert_eq!(
+            keysounds.sample_path("drums/kick.wav"),
+            Some(PathBuf::from("songs/map/drums/kick.wav"))
+        );
+        assert_eq!(keysounds.sample_path("../other/kick.wav"), None);
+        assert_eq!(keysounds.sample_path("drums/../../kick.wav"), None);
+        assert_eq!(keysounds.sample_path("/etc/kick.wav"), None);
+        assert_eq!(Keysounds::new(None).sample_path("kick.wav"), None);
+    }
+
+    #[test]
+    fn test_enabling_keysounds_preloads_samples() {
+        let bus = SystemBus::new();
+        let sample = |name: &str| Some(Arc::from(name));
+        let chart = vec![
+            NoteData::tap(1_000_000, 0).with_sample(sample("kick.wav")),
+            NoteData::tap(1_100_000, 1).with_sample(sample("snare.wav")),
+            NoteData::tap(1_200_000, 0).with_sample(sample("kick.wav")),
+            NoteData::tap(1_300_000, 2).with_sample(sample("../escape.wav")),
+        ];
+        let mut engine = GameEngine::from_debug_chart(&bus, chart, HitWindowSettings::default(), 4);
+        engine.keysounds.sample_dir = Some(PathBuf::from("songs/map"));
+        engine.set_keysounds(true, false);
+
+        let loaded: Vec<Vec<PathBuf>> = bus
+            .audio_cmd_rx
+            .try_iter()
+            .filter_map(|cmd| match cmd {
+                AudioCommand::LoadSamples { paths } => Some(paths),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            loaded,
+            vec![vec![
+                PathBuf::from("songs/map/kick.wav"),
+                PathBuf::from("songs/map/snare.wav"),
+            ]]
+        );
+    }
+}
@@ -0,0 +1,148 @@
+//! Combo milestone detection for GameEngine.
+//!
+//! Milestones are events like combo breaks: snapshots carry the id of the
+//! last one, and the renderer flashes "100!" or "FULL COMBO!" when the id
+//! grows, even if it skipped the snapshot that first carried it.
+
+use super::GameEngine;
+use super::snapshot::next_event_id;
+use crate::models::settings::ComboMilestoneConfig;
+
+/// A combo milestone reached during play.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ComboMilestone {
+    /// The combo reached one of the configured thresholds.
+    Combo(u32),
+    /// The last note was judged without breaking combo.
+    FullCombo,
+}
+
+impl ComboMilestone {
+    /// Text shown when the milestone is reached.
+    pub fn label(&self) -> String {
+        match self {
+            ComboMilestone::Combo(combo) => format!("{combo}!"),
+            ComboMilestone::FullCombo => "FULL COMBO!".to_string(),
+        }
+    }
+}
+
+impl GameEngine {
+    /// Selects which combo counts trigger a milestone event.
+    pub fn set_combo_milestones(&mut self, config: &ComboMilestoneConfig) {
+        self.combo_milestones = config.clone();
+        self.combo_milestones.thresholds.sort_unstable();
+        self.combo_milestones.thresholds.dedup();
+    }
+
+    /// Whether every note was judged without breaking combo.
+    pub fn is_full_combo(&self) -> bool {
+        self.notes_passed as usize == self.judgeable_notes && self.combo == self.notes_passed
+    }
+
+    /// Emits a milestone event if the combo just crossed a threshold.
+    ///
+    /// Each threshold fires once per combo streak; a combo break re-arms them.
+    pub(crate) fn check_combo_milestone(&mut self) {
+        if !self.combo_milestones.enabled {
+            return;
+        }
+        if self.combo < self.milestone_reached {
+            // The combo was broken since the last milestone
+            self.milestone_reached = 0;
+        }
+
+        if self.is_full_combo() && self.combo_milestones.full_combo {
+            self.emit_milestone(ComboMilestone::FullCombo);
+            return;
+        }
+
+        if let Some(&threshold) = self
+            .combo_milestones
+            .thresholds
+            .iter()
+            .rev()
+            .find(|&&t| t <= self.combo)
+            && threshold > self.milestone_reached
+        {
+            self.milestone_reached = threshold;
+            self.emit_milestone(ComboMilestone::Combo(threshold));
+        }
+    }
+
+    fn emit_milestone(&mut self, milestone: ComboMilestone) {
+        self.combo_milestone = (next_event_id(), Some(milestone));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::game::test_engine;
+    use engine::{Judgement, NoteData};
+
+    fn engine_with_notes(count: i64) -> GameEngine {
+        let chart = (0..count)
+            .map(|i| NoteData::tap(1_000_000 + i * 100_000, (i % 4) as u8))
+            .collect();
+        let mut engine = test_engine(chart);
+        engine.set_combo_milestones(&ComboMilestoneConfig {
+            enabled: true,
+            thresholds: vec![200, 100],
+            full_combo: true,
+        });
+        engine
+    }
+
+    /// Applies `count` hits and returns the milestones emitted along the way.
+    fn hit(engine: &mut GameEngine, count: usize) -> Vec<ComboMilestone> {
+        let mut fired = Vec::new();
+        for _ in 0..count {
+            let (seen, _) = engine.get_snapshot().combo_milestone;
+            engine.apply_judgement(Judgement::Marv);
+            let (id, milestone) = engine.get_snapshot().combo_milestone;
+            if id > seen {
+                fired.extend(milestone);
+            }
+        }
+        fired
+    }
+
+    #[test]
+    fn test_milestone_fires_once() {
+        let mut engine = engine_with_notes(500);
+        assert!(hit(&mut engine, 99).is_empty());
+        assert_eq!(hit(&mut engine, 1), vec![ComboMilestone::Combo(100)]);
+        // Keeps rising past 100 without re-firing
+        assert!(hit(&mut engine, 50).is_empty());
+        assert_eq!(hit(&mut engine, 50), vec![ComboMilestone::Combo(200)]);
+
+        // A new streak re-arms the thresholds
+        engine.apply_judgement(Judgement::Miss);
+        assert_eq!(hit(&mut engine, 100), vec![ComboMilestone::Combo(100)]);
+    }
+
+    #[test]
+    fn test_full_combo_on_last_note() {
+        let mut engine = engine_with_notes(150);
+        let fired = hit(&mut engine, 150);
+        assert_eq!(
+            fired,
+            vec![ComboMilestone::Combo(100), ComboMilestone::FullCombo]
+        );
+    }
+
+    #[test]
+    fn test_next_run_milestone_is_newer() {
+        let mut first = engine_with_notes(500);
+        hit(&mut first, 100);
+        let (first_id, _) = first.get_snapshot().combo_milestone;
+
+        // Same milestone, same count in the next run: still a new event
+        let mut next = engine_with_notes(500);
+        hit(&mut next, 100);
+        let (next_id, milestone) = next.get_snapshot().combo_milestone;
+        assert!(next_id > first_id);
+        assert_eq!(milestone, Some(ComboMilestone::Combo(100)));
+    }
+}
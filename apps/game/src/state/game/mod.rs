@@ -8,21 +8,34 @@
 //!
 //! All times are in **microseconds (i64)** for precision.
 
+mod autoplay;
+mod fail;
 mod input;
+mod keysounds;
+mod milestones;
 mod notes;
 mod practice;
 mod snapshot;
 
 pub mod actions;
 
+use keysounds::Keysounds;
+pub use milestones::ComboMilestone;
+pub use snapshot::MAX_SNAPSHOT_NOTES;
+use snapshot::{RECENT_HITS, SNAPSHOT_BUFFERS, snapshot_buffers};
+
 use crate::audio_sys::AudioManager;
-use crate::models::settings::HitWindowMode;
+use crate::models::settings::{ComboMilestoneConfig, HitWindowMode, HitWindowSettings};
+use crate::state::global::create_debug_chart;
+use crate::state::mods::NoteVisibility;
 use crate::system::bus::SystemBus;
-use engine::{HitStats, Judgement};
-use engine::{HitWindow, NoteData, US_PER_MS, load_map};
+use engine::{ComboBreak, GameMod, HitStats, Judgement, ScoringModel, apply_mods};
+use engine::{HitWindow, LifeBar, NoteData, US_PER_MS, US_PER_SECOND, load_map, seconds_to_us};
 use replay::ReplayData;
 use std::collections::VecDeque;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 /// Saved state at a checkpoint for restoration.
 #[derive(Clone)]
@@ -34,6 +47,7 @@ pub(crate) struct CheckpointState {
     pub max_combo: u32,
     pub hit_stats: HitStats,
     pub notes_passed: u32,
+    pub life_bar: Option<LifeBar>,
     /// Hit state of each note at checkpoint time.
     pub note_hit_states: Vec<bool>,
 }
@@ -47,6 +61,12 @@ pub struct GameEngine {
 
     /// Current score.
     pub score: u32,
+    /// Scoring model used for the live score and the saved replay.
+    pub scoring_model: ScoringModel,
+    /// Lowest judgement that breaks combo.
+    pub combo_break_rule: ComboBreak,
+    /// Number of judgeable notes (mines excluded), used by the normalized model.
+    pub(crate) judgeable_notes: usize,
     /// Current combo count.
     pub combo: u32,
     /// Maximum combo achieved.
@@ -64,18 +84,34 @@ pub struct GameEngine {
     pub last_hit_timing_us: Option<i64>,
     /// Judgement of the last hit.
     pub last_hit_judgement: Option<Judgement>,
+    /// Column of the last hit.
+    pub last_hit_column: Option<usize>,
+    /// Chart time (µs) and judgement of the last hit on each column.
+    pub last_column_hits: Vec<Option<(i64, Judgement)>>,
+    /// Latest timed hits as (event id, offset µs, judgement).
+    pub(crate) recent_hits: VecDeque<(u64, i64, Judgement)>,
 
     /// Audio manager for music playback.
     pub audio_manager: AudioManager,
     /// Smoothed audio clock in microseconds.
     pub audio_clock_us: i64,
+    /// Wall-clock instant `audio_clock_us` was last updated at.
+    pub(crate) audio_clock_at: Option<Instant>,
+    /// Instant and audio clock of the tick before the last one.
+    pub(crate) previous_tick: Option<(Instant, i64)>,
     /// Whether audio is loaded (false for debug mode).
     pub(crate) has_audio: bool,
+    /// Keysound playback (keysounded charts only).
+    pub(crate) keysounds: Keysounds,
 
     /// Playback rate multiplier.
     pub rate: f64,
     /// Scroll speed in milliseconds (time visible on screen).
     pub scroll_speed_ms: f64,
+    /// Scale of the skin's note size.
+    pub note_scale: f32,
+    /// Scale of the skin's column widths.
+    pub column_width_scale: f32,
     /// Hit window configuration.
     pub hit_window: HitWindow,
     /// Hit window mode (osu! OD or Etterna judge).
@@ -90,10 +126,12 @@ pub struct GameEngine {
     /// Whether audio has started playing.
     pub(crate) started_audio: bool,
 
-    /// Timestamps of recent inputs for NPS calculation (in µs).
+    /// Timestamps of recently judged notes for NPS calculation (in µs).
     pub(crate) input_timestamps: VecDeque<i64>,
-    /// Current notes per second.
+    /// Current notes per second (raw 1-second window).
     pub(crate) current_nps: f64,
+    /// Exponentially smoothed notes per second.
+    pub(crate) smoothed_nps: f64,
 
     /// Whether practice mode is enabled.
     pub practice_mode: bool,
@@ -104,11 +142,40 @@ pub struct GameEngine {
     /// Global audio offset in microseconds.
     /// Applied to note timing calculations to compensate for audio latency.
     pub audio_offset_us: i64,
+    /// Note visibility rules from the active mods (rendering only).
+    pub visibility: NoteVisibility,
+    /// Id of the last combo break (see [`snapshot::next_event_id`]).
+    pub(crate) combo_break_id: u64,
+    /// Combo counts that trigger a milestone event.
+    pub(crate) combo_milestones: ComboMilestoneConfig,
+    /// Highest threshold reached in the current combo streak.
+    pub(crate) milestone_reached: u32,
+    /// Id of the last milestone (see [`snapshot::next_event_id`]), and the
+    /// milestone itself.
+    pub(crate) combo_milestone: (u64, Option<ComboMilestone>),
+    /// Seed used to generate the debug chart (None for real maps).
+    pub debug_seed: Option<u64>,
+    /// Set once a fail condition is met (ends the run).
+    pub(crate) failed: bool,
+    /// Health of the run (None when the life bar is off).
+    pub(crate) life_bar: Option<LifeBar>,
+    /// Fail the run once the life bar is empty.
+    pub(crate) fail_on_empty_health: bool,
+    /// Next autoplay input to play (None when the player is playing).
+    pub(crate) autoplay_cursor: Option<usize>,
+    /// Note buffers shared with the snapshots, refilled once the renderer
+    /// has dropped them.
+    pub(crate) snapshot_buffers: Mutex<[Arc<Vec<NoteData>>; SNAPSHOT_BUFFERS]>,
 }
 
 impl GameEngine {
     /// Pre-roll time before the first note (in µs).
     const PRE_ROLL_US: i64 = 3_000_000; // 3 seconds
+    /// Time constant of the NPS exponential smoothing (in seconds).
+    const NPS_SMOOTHING_SECONDS: f64 = 0.5;
+    /// Largest gap between an input and the clock it is placed against (in µs).
+    /// Older inputs (e.g. across a checkpoint seek) use the clock as is.
+    const MAX_INPUT_AGE_US: i64 = 100_000;
 
     /// Creates a new `GameEngine` by loading the map from a file.
     /// Returns `None` if the map cannot be loaded.
@@ -117,8 +184,7 @@ impl GameEngine {
         map_path: PathBuf,
         rate: f64,
         beatmap_hash: Option<String>,
-        hit_window_mode: HitWindowMode,
-        hit_window_value: f64,
+        hit_window: HitWindowSettings,
     ) -> Option<Self> {
         match load_map(map_path.clone()) {
             Ok((audio_path, chart, key_count)) => Some(Self::from_cached(
@@ -127,8 +193,7 @@ impl GameEngine {
                 audio_path,
                 rate,
                 beatmap_hash,
-                hit_window_mode,
-                hit_window_value,
+                hit_window,
                 key_count,
             )),
             Err(e) => {
@@ -147,23 +212,25 @@ impl GameEngine {
         audio_path: PathBuf,
         rate: f64,
         beatmap_hash: Option<String>,
-        hit_window_mode: HitWindowMode,
-        hit_window_value: f64,
+        hit_window: HitWindowSettings,
         key_count: usize,
     ) -> Self {
         let mut audio_manager = AudioManager::new(bus);
         audio_manager.load_music(&audio_path);
         audio_manager.set_speed(rate as f32);
 
-        let hit_window = match hit_window_mode {
-            HitWindowMode::OsuOD => HitWindow::from_osu_od(hit_window_value),
-            HitWindowMode::EtternaJudge => HitWindow::from_etterna_judge(hit_window_value as u8),
-        };
+        let judgeable_notes = chart.iter().filter(|n| !n.is_mine()).count();
+        let mut replay_data = ReplayData::new(rate);
+        replay_data.key_count = key_count;
+        replay_data.judged_notes = judgeable_notes;
 
         Self {
             chart,
             head_index: 0,
             score: 0,
+            scoring_model: ScoringModel::default(),
+            combo_break_rule: ComboBreak::default(),
+            judgeable_notes,
             combo: 0,
             max_combo: 0,
             hit_stats: HitStats::new(),
@@ -172,24 +239,44 @@ impl GameEngine {
             keys_held: vec![false; key_count],
             last_hit_timing_us: None,
             last_hit_judgement: None,
+            last_hit_column: None,
+            last_column_hits: vec![None; key_count],
+            recent_hits: VecDeque::with_capacity(RECENT_HITS),
             audio_manager,
             audio_clock_us: -Self::PRE_ROLL_US,
+            audio_clock_at: None,
+            previous_tick: None,
             has_audio: true,
-            replay_data: ReplayData::new(rate),
+            keysounds: Keysounds::new(audio_path.parent().map(Path::to_path_buf)),
+            replay_data,
             beatmap_hash,
             started_audio: false,
             rate,
             scroll_speed_ms: 500.0,
-            hit_window,
-            hit_window_mode,
-            hit_window_value,
+            note_scale: 1.0,
+            column_width_scale: 1.0,
+            hit_window: hit_window.hit_window(),
+            hit_window_mode: hit_window.mode,
+            hit_window_value: hit_window.value,
             input_timestamps: VecDeque::new(),
             current_nps: 0.0,
+            smoothed_nps: 0.0,
             // Practice Mode
             practice_mode: false,
             checkpoint_state: None,
             last_checkpoint_time_us: i64::MIN,
             audio_offset_us: 0,
+            visibility: NoteVisibility::default(),
+            combo_break_id: 0,
+            combo_milestones: ComboMilestoneConfig::default(),
+            milestone_reached: 0,
+            combo_milestone: (0, None),
+            debug_seed: None,
+            failed: false,
+            life_bar: None,
+            fail_on_empty_health: false,
+            autoplay_cursor: None,
+            snapshot_buffers: Mutex::new(snapshot_buffers()),
         }
     }
 
@@ -197,22 +284,24 @@ impl GameEngine {
     pub fn from_debug_chart(
         bus: &SystemBus,
         chart: Vec<NoteData>,
-        hit_window_mode: HitWindowMode,
-        hit_window_value: f64,
+        hit_window: HitWindowSettings,
         key_count: usize,
     ) -> Self {
         let audio_manager = AudioManager::new(bus);
         // No audio loaded - we'll run in silent mode
 
-        let hit_window = match hit_window_mode {
-            HitWindowMode::OsuOD => HitWindow::from_osu_od(hit_window_value),
-            HitWindowMode::EtternaJudge => HitWindow::from_etterna_judge(hit_window_value as u8),
-        };
+        let judgeable_notes = chart.iter().filter(|n| !n.is_mine()).count();
+        let mut replay_data = ReplayData::new(1.0);
+        replay_data.key_count = key_count;
+        replay_data.judged_notes = judgeable_notes;
 
         Self {
             chart,
             head_index: 0,
             score: 0,
+            scoring_model: ScoringModel::default(),
+            combo_break_rule: ComboBreak::default(),
+            judgeable_notes,
             combo: 0,
             max_combo: 0,
             hit_stats: HitStats::new(),
@@ -221,27 +310,56 @@ impl GameEngine {
             keys_held: vec![false; key_count],
             last_hit_timing_us: None,
             last_hit_judgement: None,
+            last_hit_column: None,
+            last_column_hits: vec![None; key_count],
+            recent_hits: VecDeque::with_capacity(RECENT_HITS),
             audio_manager,
             audio_clock_us: -Self::PRE_ROLL_US,
+            audio_clock_at: None,
+            previous_tick: None,
             has_audio: false, // Debug mode - no audio
-            replay_data: ReplayData::new(1.0),
+            keysounds: Keysounds::new(None),
+            replay_data,
             beatmap_hash: Some("debug_map".to_string()),
             started_audio: true, // No audio, but consider it "started" for gameplay
             rate: 1.0,
             scroll_speed_ms: 500.0,
-            hit_window,
-            hit_window_mode,
-            hit_window_value,
+            note_scale: 1.0,
+            column_width_scale: 1.0,
+            hit_window: hit_window.hit_window(),
+            hit_window_mode: hit_window.mode,
+            hit_window_value: hit_window.value,
             input_timestamps: VecDeque::new(),
             current_nps: 0.0,
+            smoothed_nps: 0.0,
             // Practice Mode
             practice_mode: false,
             checkpoint_state: None,
             last_checkpoint_time_us: i64::MIN,
             audio_offset_us: 0,
+            visibility: NoteVisibility::default(),
+            combo_break_id: 0,
+            combo_milestones: ComboMilestoneConfig::default(),
+            milestone_reached: 0,
+            combo_milestone: (0, None),
+            debug_seed: None,
+            failed: false,
+            life_bar: None,
+            fail_on_empty_health: false,
+            autoplay_cursor: None,
+            snapshot_buffers: Mutex::new(snapshot_buffers()),
         }
     }
 
+    /// Creates a `GameEngine` on the seeded debug chart (no audio).
+    /// The same seed always produces the same chart, for reproducible bug reports.
+    pub fn new_debug(bus: &SystemBus, seed: u64, hit_window: HitWindowSettings) -> Self {
+        let (chart, key_count) = create_debug_chart(seed);
+        let mut engine = Self::from_debug_chart(bus, chart, hit_window, key_count);
+        engine.debug_seed = Some(seed);
+        engine
+    }
+
     /// Updates the game state for one tick.
     ///
     /// This method:
@@ -250,8 +368,16 @@ impl GameEngine {
     /// 3. Processes missed notes
     /// 4. Updates NPS tracking
     pub fn update(&mut self, dt_seconds: f64) {
+        self.update_at(dt_seconds, Instant::now());
+    }
+
+    /// [`Self::update`] for a tick happening at `now`.
+    pub(crate) fn update_at(&mut self, dt_seconds: f64, now: Instant) {
+        self.previous_tick = self.audio_clock_at.map(|at| (at, self.audio_clock_us));
+        self.audio_clock_at = Some(now);
+
         // 1. Advance the smoothed clock (dt in seconds -> µs)
-        let dt_us = (dt_seconds * 1_000_000.0 * self.rate) as i64;
+        let dt_us = seconds_to_us(dt_seconds * self.rate);
         self.audio_clock_us += dt_us;
 
         if !self.started_audio {
@@ -264,7 +390,7 @@ impl GameEngine {
                     let audio_pos = self.audio_manager.get_position_seconds();
                     if audio_pos > 0.001 {
                         // Audio has started! Sync our clock to it
-                        self.audio_clock_us = (audio_pos * 1_000_000.0) as i64;
+                        self.audio_clock_us = seconds_to_us(audio_pos);
                         self.started_audio = true;
                     } else {
                         // Audio not started yet, keep clock at 0 and wait
@@ -284,8 +410,7 @@ impl GameEngine {
         // 2. Re-synchronize with the audio device if drifted
         // Skip sync if audio is seeking (loading in background) or no audio (debug mode)
         if self.has_audio && !self.audio_manager.is_seeking() {
-            let raw_audio_time_us =
-                (self.audio_manager.get_position_seconds() * 1_000_000.0) as i64;
+            let raw_audio_time_us = seconds_to_us(self.audio_manager.get_position_seconds());
             let drift_us = raw_audio_time_us - self.audio_clock_us;
 
             if drift_us.abs() > 80_000 {
@@ -304,14 +429,35 @@ impl GameEngine {
         // 3. Note state updates and miss handling
         // Apply audio offset for note timing calculations
         let offset_time_us = current_time_us + self.audio_offset_us;
+        self.step_autoplay(offset_time_us);
         self.update_notes(offset_time_us);
+        self.check_fail();
 
         // 4. Update NPS tracking
-        self.update_nps();
+        self.update_nps(dt_seconds);
+    }
+
+    /// Audio clock (in µs) at the instant an input arrived.
+    ///
+    /// Places the input against the clock of the last tick, so the time spent
+    /// waiting for the logic thread doesn't shift it.
+    pub(crate) fn audio_time_at(&self, at: Option<Instant>) -> i64 {
+        let (Some(at), Some(clock_at)) = (at, self.audio_clock_at) else {
+            return self.audio_clock_us;
+        };
+        let elapsed_us = if at >= clock_at {
+            seconds_to_us(at.duration_since(clock_at).as_secs_f64() * self.rate)
+        } else {
+            -seconds_to_us(clock_at.duration_since(at).as_secs_f64() * self.rate)
+        };
+        if elapsed_us.abs() > Self::MAX_INPUT_AGE_US {
+            return self.audio_clock_us;
+        }
+        self.audio_clock_us + elapsed_us
     }
 
     /// Updates the notes-per-second tracking.
-    fn update_nps(&mut self) {
+    fn update_nps(&mut self, dt_seconds: f64) {
         let current_time_us = self.audio_clock_us;
         let window_start_us = current_time_us - 1_000_000; // 1 second window
 
@@ -324,8 +470,15 @@ impl GameEngine {
             }
         }
 
-        // NPS = number of inputs in the last second
+        // NPS = number of judged notes in the last second
         self.current_nps = self.input_timestamps.len() as f64;
+        self.smoothed_nps = Self::smooth_nps(self.smoothed_nps, self.current_nps, dt_seconds);
+    }
+
+    /// Moves `previous` towards `raw` with an exponential moving average.
+    pub(crate) fn smooth_nps(previous: f64, raw: f64, dt_seconds: f64) -> f64 {
+        let factor = 1.0 - (-dt_seconds / Self::NPS_SMOOTHING_SECONDS).exp();
+        previous + (raw - previous) * factor
     }
 
     /// Returns the current audio clock time in microseconds.
@@ -338,26 +491,139 @@ impl GameEngine {
         self.audio_clock_us as f64 / US_PER_MS as f64
     }
 
-    /// Returns `true` if the map has finished (2 seconds after last note).
+    /// Time spent playing so far (song time at the played rate).
+    pub fn played_time(&self) -> Duration {
+        Duration::from_secs_f64(
+            self.audio_clock_us.max(0) as f64 / US_PER_SECOND as f64 / self.rate,
+        )
+    }
+
+    /// Returns `true` if the map has finished (2 seconds after last note)
+    /// or the run was failed.
     pub fn is_finished(&self) -> bool {
         let buffer_us = 2_000_000; // 2 seconds
-        self.chart
-            .last()
-            .is_none_or(|n| self.audio_clock_us > n.time_us() + buffer_us)
+        self.failed
+            || self
+                .chart
+                .last()
+                .is_none_or(|n| self.audio_clock_us > n.time_us() + buffer_us)
     }
 
     /// Updates the hit window configuration.
-    pub fn update_hit_window(&mut self, mode: HitWindowMode, value: f64) {
-        self.hit_window = match mode {
-            HitWindowMode::OsuOD => HitWindow::from_osu_od(value),
-            HitWindowMode::EtternaJudge => HitWindow::from_etterna_judge(value as u8),
-        };
-        self.hit_window_mode = mode;
-        self.hit_window_value = value;
+    pub fn update_hit_window(&mut self, settings: HitWindowSettings) {
+        self.hit_window = settings.hit_window();
+        self.hit_window_mode = settings.mode;
+        self.hit_window_value = settings.value;
+    }
+
+    /// Selects the scoring model for the live score and the recorded replay.
+    pub fn set_scoring_model(&mut self, model: ScoringModel) {
+        self.scoring_model = model;
+        self.replay_data.scoring_model = model;
+    }
+
+    /// Selects which judgements break combo, live and in the recorded replay.
+    pub fn set_combo_break_rule(&mut self, rule: ComboBreak) {
+        self.combo_break_rule = rule;
+        self.replay_data.combo_break = rule;
+    }
+
+    /// Applies the chart-altering mods and records them in the replay.
+    ///
+    /// Must be called before the play starts.
+    pub fn apply_mods(&mut self, mods: &[GameMod]) {
+        self.chart = apply_mods(&self.chart, mods);
+        self.judgeable_notes = self.chart.iter().filter(|n| !n.is_mine()).count();
+        self.replay_data.mods = mods.to_vec();
+        if mods.contains(&GameMod::Autoplay) {
+            self.enable_autoplay();
+        }
     }
 
-    /// Returns a copy of the chart (for replay simulation).
+    /// The chart being played (e.g. for replay simulation).
+    pub fn chart(&self) -> &[NoteData] {
+        &self.chart
+    }
+
+    /// Returns an owned copy of the chart, when ownership is needed.
     pub fn get_chart(&self) -> Vec<NoteData> {
         self.chart.clone()
     }
 }
+
+/// Silent 4-key engine on `chart`, judged with the default hit window.
+#[cfg(test)]
+pub(crate) fn test_engine(chart: Vec<NoteData>) -> GameEngine {
+    GameEngine::from_debug_chart(&SystemBus::new(), chart, HitWindowSettings::default(), 4)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_smoothed_nps_converges_to_steady_rate() {
+        let dt = 1.0 / 200.0;
+        let mut smoothed = 0.0;
+        // 5 seconds of a steady 12 NPS stream
+        for _ in 0..1000 {
+            smoothed = GameEngine::smooth_nps(smoothed, 12.0, dt);
+        }
+        assert!((smoothed - 12.0).abs() < 0.01);
+
+        // Never overshoots while converging
+        let mut smoothed = 0.0;
+        for _ in 0..100 {
+            smoothed = GameEngine::smooth_nps(smoothed, 12.0, dt);
+            assert!(smoothed <= 12.0);
+        }
+    }
+
+    #[test]
+    fn test_borrowed_chart_simulates_like_a_copy() {
+        let chart = vec![
+            NoteData::tap(1_000_000, 0),
+            NoteData::hold(1_200_000, 1, 300_000),
+            NoteData::tap(1_400_000, 2),
+        ];
+        let mut engine = GameEngine::from_debug_chart(&SystemBus::new(), chart);
+        engine.replay_data.add_press(1_005_000, 0);
+        engine.replay_data.add_release(1_050_000, 0);
+        engine.replay_data.add_press(1_190_000, 1);
+        engine.replay_data.add_release(1_500_000, 1);
+
+        let borrowed = replay::simulate(&engine.replay_data, engine.chart(), &engine.hit_window);
+        let copied = replay::simulate(&engine.replay_data, &engine.get_chart(), &engine.hit_window);
+        assert_eq!(borrowed, copied);
+        assert!(borrowed.hit_stats.miss > 0);
+    }
+
+    #[test]
+    fn test_processing_delay_keeps_hit_offset() {
+        use crate::input::events::GameAction;
+        use std::time::Duration;
+
+        let start = Instant::now();
+        let pressed_at = start + Duration::from_millis(3);
+        let hit_offset = |extra_ticks: u32| {
+            let chart = vec![NoteData::tap(1_000_000, 0)];
+            let mut engine = test_engine(chart);
+            engine.audio_clock_us = 990_000;
+            // The key event arrives 3 ms after this tick...
+            engine.update_at(0.005, start);
+            // ...but the logic thread may only get to it some ticks later
+            for tick in 1..=extra_ticks {
+                engine.update_at(0.005, start + Duration::from_millis(5 * tick as u64));
+            }
+            engine.handle_input(GameAction::Hit {
+                column: 0,
+                at: Some(pressed_at),
+            });
+            engine.last_hit_timing_us
+        };
+
+        assert_eq!(hit_offset(0), Some(2_000));
+        assert_eq!(hit_offset(1), Some(2_000));
+        assert_eq!(hit_offset(2), Some(2_000));
+    }
+}
@@ -3,6 +3,7 @@
 //! All times are in microseconds (i64).
 
 use super::GameEngine;
+use super::snapshot::next_event_id;
 use engine::Judgement;
 
 impl GameEngine {
@@ -14,6 +15,20 @@ impl GameEngine {
         // Collect judgements to apply (to avoid borrow conflicts)
         let mut judgements: Vec<Judgement> = Vec::new();
 
+        // Mines trigger as they cross the receptor, independently of the head note
+        let mut mine_hits = 0;
+        for note in self.chart.iter_mut().skip(self.head_index) {
+            if note.time_us() > current_time_us {
+                break;
+            }
+            if note.is_mine() && !note.state.hit {
+                note.state.hit = true;
+                if self.keys_held.get(note.column()).copied().unwrap_or(false) {
+                    mine_hits += 1;
+                }
+            }
+        }
+
         while new_head < self.chart.len() {
             let note = &mut self.chart[new_head];
 
@@ -57,13 +72,8 @@ impl GameEngine {
                     break;
                 }
             } else if note.is_mine() {
-                if current_time_us > note_time_us + miss_us {
-                    note.state.hit = true;
-                    // No judgement - mines that pass are good!
-                    new_head += 1;
-                } else {
-                    break;
-                }
+                // Not reached yet (reached mines were resolved above)
+                break;
             } else if note.is_burst() {
                 let duration_us = note.duration_us();
                 if current_time_us > note_time_us + duration_us {
@@ -96,18 +106,40 @@ impl GameEngine {
         for j in judgements {
             self.apply_judgement(j);
         }
+        for _ in 0..mine_hits {
+            self.apply_mine_hit();
+        }
+    }
+
+    /// Penalizes a mine crossing the receptor while its key is held.
+    ///
+    /// Breaks combo without counting as a judged note.
+    pub(crate) fn apply_mine_hit(&mut self) {
+        self.hit_stats.mine_hits += 1;
+        if self.combo > 0 {
+            self.combo_break_id = next_event_id();
+        }
+        self.combo = 0;
     }
 
     /// Applies a judgement to the game state (score, combo, stats).
     pub(crate) fn apply_judgement(&mut self, j: Judgement) {
+        if let Some(life_bar) = &mut self.life_bar {
+            life_bar.apply(j);
+        }
         match j {
             Judgement::Miss => {
                 self.hit_stats.miss += 1;
+                if self.combo > 0 {
+                    self.combo_break_id = next_event_id();
+                }
                 self.combo = 0;
                 self.notes_passed += 1;
             }
             Judgement::GhostTap => {
                 self.hit_stats.ghost_tap += 1;
+                // Ghost taps are not notes, so they don't count towards NPS
+                return;
             }
             _ => {
                 match j {
@@ -118,17 +150,99 @@ impl GameEngine {
                     Judgement::Bad => self.hit_stats.bad += 1,
                     _ => {}
                 }
-                self.combo += 1;
-                self.max_combo = self.max_combo.max(self.combo);
+                if self.combo_break_rule.breaks_combo(j) {
+                    if self.combo > 0 {
+                        self.combo_break_id = next_event_id();
+                    }
+                    self.combo = 0;
+                } else {
+                    self.combo += 1;
+                    self.max_combo = self.max_combo.max(self.combo);
+                    self.check_combo_milestone();
+                }
                 self.notes_passed += 1;
-                self.score += match j {
-                    Judgement::Marv | Judgement::Perfect => 300,
-                    Judgement::Great => 200,
-                    Judgement::Good => 100,
-                    Judgement::Bad => 50,
-                    _ => 0,
-                };
+                self.score = self.scoring_model.compute(
+                    &self.hit_stats,
+                    self.max_combo,
+                    self.judgeable_notes,
+                );
             }
         }
+
+        // Record judged note timestamp for NPS calculation
+        self.input_timestamps.push_back(self.audio_clock_us);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::events::GameAction;
+    use crate::state::game::test_engine;
+    use engine::{ComboBreak, NoteData};
+
+    #[test]
+    fn test_miss_after_combo_sets_break_once() {
+        let mut engine = test_engine(Vec::new());
+        engine.apply_judgement(Judgement::Marv);
+        engine.apply_judgement(Judgement::Great);
+        assert_eq!(engine.combo_break_id, 0);
+
+        engine.apply_judgement(Judgement::Miss);
+        let first_break = engine.combo_break_id;
+        assert_ne!(first_break, 0);
+
+        // Combo is already 0, so a second miss is not a new break
+        engine.apply_judgement(Judgement::Miss);
+        assert_eq!(engine.combo_break_id, first_break);
+    }
+
+    #[test]
+    fn test_break_on_bad_keeps_combo_on_good() {
+        let mut engine = test_engine(Vec::new());
+        engine.set_combo_break_rule(ComboBreak::Bad);
+        engine.apply_judgement(Judgement::Marv);
+        engine.apply_judgement(Judgement::Good);
+        assert_eq!(engine.combo, 2);
+
+        engine.apply_judgement(Judgement::Bad);
+        assert_eq!(engine.combo, 0);
+        assert_ne!(engine.combo_break_id, 0);
+        assert_eq!(engine.hit_stats.bad, 1);
+        assert_eq!(engine.max_combo, 2);
+        assert_eq!(engine.replay_data.combo_break, ComboBreak::Bad);
+    }
+
+    #[test]
+    fn test_holding_on_mine_breaks_combo() {
+        let chart = vec![NoteData::tap(1_000_000, 0), NoteData::mine(1_500_000, 1)];
+        let mut engine = test_engine(chart);
+
+        engine.audio_clock_us = 1_000_000;
+        engine.handle_input(GameAction::hit(0));
+        assert_eq!(engine.combo, 1);
+
+        // Pressing on the mine doesn't judge it directly
+        engine.audio_clock_us = 1_500_000;
+        engine.handle_input(GameAction::hit(1));
+        assert_eq!(engine.combo, 1);
+
+        // It triggers as it crosses the receptor with the key held
+        engine.update_notes(1_500_000);
+        assert_eq!(engine.combo, 0);
+        assert_ne!(engine.combo_break_id, 0);
+        assert_eq!(engine.hit_stats.mine_hits, 1);
+        assert_eq!(engine.hit_stats.miss, 0);
+        assert_eq!(engine.notes_passed, 1);
+    }
+
+    #[test]
+    fn test_mine_passes_without_held_key() {
+        let chart = vec![NoteData::mine(1_000_000, 0)];
+        let mut engine = test_engine(chart);
+
+        engine.update_notes(1_000_000);
+        assert_eq!(engine.hit_stats.mine_hits, 0);
+        assert_eq!(engine.head_index, 1);
     }
 }
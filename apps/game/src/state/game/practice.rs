@@ -47,6 +47,7 @@ impl GameEngine {
             max_combo: self.max_combo,
             hit_stats: self.hit_stats.clone(),
             notes_passed: self.notes_passed,
+            life_bar: self.life_bar,
             note_hit_states,
         });
 
@@ -81,6 +82,7 @@ impl GameEngine {
         self.combo = state.combo;
         self.hit_stats = state.hit_stats;
         self.notes_passed = state.notes_passed;
+        self.life_bar = state.life_bar;
 
         log::info!(
             "PRACTICE: Restoring {} notes state",
@@ -114,8 +116,17 @@ impl GameEngine {
 
         log::info!("PRACTICE: Notes restored, truncating replay");
 
-        // Truncate replay inputs after the checkpoint
-        self.replay_data.truncate_inputs_after(state.time_us);
+        if self.autoplay_cursor.is_some() {
+            // Autoplay keeps its inputs and resumes from the checkpoint
+            let resume = self
+                .replay_data
+                .inputs
+                .partition_point(|input| input.time_us < state.time_us);
+            self.autoplay_cursor = Some(resume);
+        } else {
+            // Truncate replay inputs after the checkpoint
+            self.replay_data.truncate_inputs_after(state.time_us);
+        }
 
         log::info!(
             "PRACTICE: Seeking audio to {:.1}s",
@@ -133,6 +144,7 @@ impl GameEngine {
         self.keys_held.fill(false);
         self.input_timestamps.clear();
         self.current_nps = 0.0;
+        self.smoothed_nps = 0.0;
 
         log::info!(
             "PRACTICE: Returned to checkpoint at {:.1}s (retry from {:.1}s)",
@@ -147,8 +159,12 @@ impl GameEngine {
     //     &self.replay_data.checkpoints
     // }
 
-    /// Returns the total duration of the map in µs (last note timestamp).
+    /// Returns the total duration of the map in µs (end of the last note, hold tails included).
     pub fn get_map_duration_us(&self) -> i64 {
-        self.chart.last().map_or(0, |n| n.time_us())
+        self.chart
+            .iter()
+            .map(|n| n.end_time_us())
+            .max()
+            .unwrap_or(0)
     }
 }
@@ -5,10 +5,45 @@
 use super::GameEngine;
 use engine::NoteData;
 use engine::US_PER_MS;
-use crate::shared::snapshot::GameplaySnapshot;
+use crate::shared::snapshot::{GameplaySnapshot, HitEvent, ReceptorHit};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, PoisonError};
+
+/// Most notes a snapshot carries. The nearest ones are kept, which covers
+/// the screen even on the densest charts.
+pub const MAX_SNAPSHOT_NOTES: usize = 1024;
+
+/// Timed hits a snapshot carries, enough for every hit between two frames
+/// even when the renderer falls behind.
+pub(crate) const RECENT_HITS: usize = 16;
+
+/// Last id handed out by [`next_event_id`].
+static LAST_EVENT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Id for a gameplay event carried by snapshots (never 0).
+///
+/// Snapshots can be skipped, so events are carried as the id of the latest
+/// one. Ids keep growing across runs, so a new run never repeats an id the
+/// renderer has already seen.
+pub(crate) fn next_event_id() -> u64 {
+    LAST_EVENT_ID.fetch_add(1, Ordering::Relaxed) + 1
+}
+
+/// Note buffers in rotation: one being filled, one waiting in the render
+/// channel and one on screen.
+pub(crate) const SNAPSHOT_BUFFERS: usize = 3;
+
+/// Empty note buffers, preallocated to the snapshot cap.
+pub(crate) fn snapshot_buffers() -> [Arc<Vec<NoteData>>; SNAPSHOT_BUFFERS] {
+    std::array::from_fn(|_| Arc::new(Vec::with_capacity(MAX_SNAPSHOT_NOTES)))
+}
 
 impl GameEngine {
     /// Creates a snapshot of the current game state for rendering.
+    ///
+    /// Visible notes are gathered in a buffer no snapshot holds anymore and
+    /// shared with the new one, so nothing is allocated once the renderer
+    /// keeps up.
     pub fn get_snapshot(&self) -> GameplaySnapshot {
         // Apply audio offset for visual synchronization
         let offset_clock_us = self.audio_clock_us + self.audio_offset_us;
@@ -19,25 +54,42 @@ impl GameEngine {
 
         // For notes with duration (Hold/Burst), we need to keep them visible
         // until their end time has passed, not just their start time
-        let visible_notes: Vec<NoteData> = self
-            .chart
-            .iter()
-            .skip(self.head_index)
-            .take_while(|n| n.time_us() <= max_visible_time_us + buffer_us)
-            .filter(|n| {
-                if n.state.hit {
-                    return false;
-                }
-                // For notes with duration, keep visible until end time passes
-                if n.has_duration() {
-                    // Keep visible if end hasn't passed yet
-                    n.end_time_us() > offset_clock_us - 100_000 // 100ms
-                } else {
-                    true
-                }
-            })
-            .cloned()
-            .collect();
+        let mut buffers = self
+            .snapshot_buffers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let slot = match buffers.iter_mut().position(|b| Arc::get_mut(b).is_some()) {
+            Some(slot) => slot,
+            None => {
+                // The renderer holds every buffer: replace one, it frees the old
+                buffers[0] = Arc::new(Vec::with_capacity(MAX_SNAPSHOT_NOTES));
+                0
+            }
+        };
+        let buffer = Arc::get_mut(&mut buffers[slot]).expect("buffer is not shared");
+        buffer.clear();
+        buffer.extend(
+            self.chart
+                .iter()
+                .skip(self.head_index)
+                .take_while(|n| n.time_us() <= max_visible_time_us + buffer_us)
+                .filter(|n| {
+                    if n.state.hit {
+                        return false;
+                    }
+                    // For notes with duration, keep visible until end time passes
+                    if n.has_duration() {
+                        // Keep visible if end hasn't passed yet
+                        n.end_time_us() > offset_clock_us - 100_000 // 100ms
+                    } else {
+                        true
+                    }
+                })
+                .take(MAX_SNAPSHOT_NOTES)
+                .cloned(),
+        );
+        let visible_notes = Arc::clone(&buffers[slot]);
+        drop(buffers);
 
         // Convert checkpoints from i64 µs to f64 ms for compatibility
         let checkpoints_ms: Vec<f64> = self
@@ -51,23 +103,200 @@ impl GameEngine {
             key_count: self.key_count,
             audio_time: offset_clock_us as f64 / US_PER_MS as f64,
             timestamp: std::time::Instant::now(),
+            previous_tick: self.previous_tick.map(|(at, clock_us)| {
+                (
+                    at,
+                    (clock_us + self.audio_offset_us) as f64 / US_PER_MS as f64,
+                )
+            }),
             rate: self.rate,
             scroll_speed: self.scroll_speed_ms,
+            note_scale: self.note_scale,
+            column_width_scale: self.column_width_scale,
             visible_notes,
             keys_held: self.keys_held.clone(),
             score: self.score,
             accuracy: self.hit_stats.calculate_accuracy(),
             combo: self.combo,
             hit_stats: self.hit_stats.clone(),
-            remaining_notes: self.chart.len().saturating_sub(self.notes_passed as usize),
+            remaining_notes: self.remaining_notes(),
             last_hit_judgement: self.last_hit_judgement,
+            last_hit_column: self.last_hit_column,
             last_hit_timing: self
                 .last_hit_timing_us
                 .map(|us| us as f64 / US_PER_MS as f64),
+            receptor_hits: self
+                .last_column_hits
+                .iter()
+                .map(|hit| {
+                    hit.map(|(time_us, judgement)| ReceptorHit {
+                        time: time_us as f64 / US_PER_MS as f64,
+                        judgement,
+                    })
+                })
+                .collect(),
             nps: self.current_nps,
+            smoothed_nps: self.smoothed_nps,
             practice_mode: self.practice_mode,
             checkpoints: checkpoints_ms,
             map_duration: self.get_map_duration_us() as f64 / US_PER_MS as f64,
+            visibility: self.visibility,
+            recent_hits: self
+                .recent_hits
+                .iter()
+                .map(|&(id, timing_us, judgement)| HitEvent {
+                    id,
+                    timing: timing_us as f64 / US_PER_MS as f64,
+                    judgement,
+                })
+                .collect(),
+            combo_break_id: self.combo_break_id,
+            combo_milestone: self.combo_milestone,
+            health: self.life_bar.map(|life_bar| life_bar.health()),
+        }
+    }
+
+    /// Number of notes not judged yet (mines excluded).
+    ///
+    /// Every note is judged once, through `apply_judgement`, so this is the
+    /// judgements still to come. Active holds still count as remaining.
+    pub(crate) fn remaining_notes(&self) -> usize {
+        self.judgeable_notes
+            .saturating_sub(self.notes_passed as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::events::GameAction;
+    use crate::state::game::test_engine;
+    use engine::Judgement;
+
+    #[test]
+    fn test_combo_breaks_survive_skipped_snapshots_and_runs() {
+        let new_run = || test_engine(Vec::new());
+        let mut engine = new_run();
+        let before = engine.get_snapshot();
+
+        // Break combo, then keep playing without taking a snapshot
+        engine.apply_judgement(Judgement::Marv);
+        engine.apply_judgement(Judgement::Miss);
+        engine.apply_judgement(Judgement::Marv);
+        let after = engine.get_snapshot();
+        assert!(after.combo_break_id > before.combo_break_id);
+
+        // The first break of the next run is still newer
+        let mut next = new_run();
+        next.apply_judgement(Judgement::Marv);
+        next.apply_judgement(Judgement::Miss);
+        assert!(next.get_snapshot().combo_break_id > after.combo_break_id);
+    }
+
+    #[test]
+    fn test_hits_survive_skipped_snapshots() {
+        let chart = vec![NoteData::tap(1_000_000, 0), NoteData::tap(1_100_000, 1)];
+        let mut engine = test_engine(chart);
+
+        // Two hits land between rendered snapshots
+        engine.audio_clock_us = 1_000_000;
+        engine.handle_input(GameAction::hit(0));
+        engine.audio_clock_us = 1_100_000;
+        engine.handle_input(GameAction::hit(1));
+
+        let hits = engine.get_snapshot().recent_hits;
+        assert_eq!(hits.len(), 2);
+        assert!(hits[0].id < hits[1].id);
+        assert!(hits.iter().all(|hit| hit.judgement == Judgement::Marv));
+    }
+
+    #[test]
+    fn test_snapshot_carries_previous_tick() {
+        let mut engine = test_engine(vec![NoteData::tap(1_000_000, 0)]);
+        let start = std::time::Instant::now();
+        engine.update_at(0.005, start);
+        let first = engine.get_snapshot();
+
+        // Only the second snapshot reaches the renderer: it still knows the
+        // tick interval
+        engine.update_at(0.005, start + std::time::Duration::from_millis(5));
+        let second = engine.get_snapshot();
+        assert_eq!(second.previous_tick, Some((start, first.audio_time)));
+        assert!(second.audio_time > first.audio_time);
+    }
+
+    #[test]
+    fn test_remaining_plus_judged_equals_total() {
+        let chart = vec![
+            NoteData::tap(1_000_000, 0),
+            NoteData::tap(1_200_000, 1),
+            NoteData::hold(1_400_000, 2, 500_000),
+            NoteData::mine(1_500_000, 3),
+            NoteData::tap(2_000_000, 0),
+            NoteData::tap(2_200_000, 1),
+        ];
+        let total = chart.iter().filter(|n| !n.is_mine()).count();
+        let mut engine = test_engine(chart);
+        assert_eq!(engine.get_snapshot().remaining_notes, total);
+
+        // Hit the first tap, let the second one pass, then start holding
+        engine.audio_clock_us = 1_000_000;
+        engine.handle_input(GameAction::hit(0));
+        engine.update_notes(1_380_000);
+        engine.audio_clock_us = 1_400_000;
+        engine.handle_input(GameAction::hit(2));
+        engine.update_notes(1_400_000);
+
+        let stats = &engine.hit_stats;
+        let judged =
+            (stats.marv + stats.perfect + stats.great + stats.good + stats.bad + stats.miss)
+                as usize;
+        let remaining = engine.get_snapshot().remaining_notes;
+        assert_eq!(judged, 2);
+        assert_eq!(remaining, 3);
+        assert_eq!(remaining + judged, total);
+    }
+
+    #[test]
+    fn test_snapshot_is_capped_to_nearest_notes() {
+        let chart: Vec<NoteData> = (0..3 * MAX_SNAPSHOT_NOTES as i64)
+            .map(|i| NoteData::tap(10_000 + i * 100, (i % 4) as usize))
+            .collect();
+        let mut engine = test_engine(chart.clone());
+        engine.audio_clock_us = 0;
+
+        for _ in 0..2 {
+            let visible = engine.get_snapshot().visible_notes;
+            assert_eq!(visible.len(), MAX_SNAPSHOT_NOTES);
+            assert_eq!(visible[0].time_us(), chart[0].time_us());
+            assert_eq!(
+                visible[MAX_SNAPSHOT_NOTES - 1].time_us(),
+                chart[MAX_SNAPSHOT_NOTES - 1].time_us()
+            );
         }
     }
+
+    #[test]
+    fn test_snapshot_buffers_are_recycled() {
+        let chart: Vec<NoteData> = (0..64)
+            .map(|i| NoteData::tap(10_000 + i * 100, (i % 4) as usize))
+            .collect();
+        let mut engine = test_engine(chart);
+        engine.audio_clock_us = 0;
+
+        // A dropped snapshot gives its buffer back
+        let first = engine.get_snapshot().visible_notes;
+        let ptr = Arc::as_ptr(&first);
+        drop(first);
+        let second = engine.get_snapshot().visible_notes;
+        assert_eq!(Arc::as_ptr(&second), ptr);
+
+        // Buffers still on screen are never overwritten
+        let held: Vec<_> = (0..SNAPSHOT_BUFFERS + 1)
+            .map(|_| engine.get_snapshot().visible_notes)
+            .collect();
+        assert!(held.iter().all(|notes| notes.len() == 64));
+        assert!(!held.iter().any(|notes| Arc::ptr_eq(notes, &second)));
+        assert_eq!(second.len(), 64);
+    }
 }
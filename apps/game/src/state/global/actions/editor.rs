@@ -65,16 +65,8 @@ pub fn apply(
             state.persist_settings();
             None
         }
-        GameAction::Hit { column } => {
-            editor
-                .engine
-                .handle_input(GameAction::Hit { column: *column });
-            None
-        }
-        GameAction::Release { column } => {
-            editor
-                .engine
-                .handle_input(GameAction::Release { column: *column });
+        GameAction::Hit { .. } | GameAction::Release { .. } => {
+            editor.engine.handle_input(action.clone());
             None
         }
         _ => None,
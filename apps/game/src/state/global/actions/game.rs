@@ -1,9 +1,8 @@
 use crate::input::events::GameAction;
-use crate::models::settings::HitWindowMode;
+use crate::models::settings::{BEATMAP_OFFSET_STEP_MS, PLAYFIELD_SCALE_STEP, step_playfield_scale};
 use crate::state::GameEngine;
 use crate::state::global::GlobalState;
 use crate::state::global::app_state::AppState;
-use engine::hit_window::HitWindow;
 
 pub fn apply(
     state: &mut GlobalState,
@@ -13,6 +12,12 @@ pub fn apply(
     match action {
         GameAction::Back => {
             engine.audio_manager.stop();
+            if state.settings.save_abandoned_runs
+                && let Some(payload) = engine.abandoned_replay_payload()
+            {
+                log::info!("REPLAY: Saving abandoned run as incomplete");
+                state.db_manager.save_replay(payload);
+            }
             state.requested_leaderboard_hash = None;
             let menu = state.saved_menu_state.clone();
             let request_hash = menu.get_selected_beatmap_hash();
@@ -21,28 +26,41 @@ pub fn apply(
         }
         GameAction::UpdateVolume(value) => {
             state.settings.master_volume = *value;
-            engine.audio_manager.set_volume(*value);
+            engine.set_volume(*value);
             state.persist_settings();
             None
         }
         GameAction::ReloadKeybinds => None,
-        GameAction::UpdateHitWindow { mode, value } => {
+        GameAction::UpdateHitWindow {
+            mode,
+            value,
+            custom_ms,
+        } => {
             state.settings.hit_window_mode = *mode;
             state.settings.hit_window_value = *value;
+            state.settings.custom_hit_window_ms = *custom_ms;
             state.persist_settings();
 
-            let hw = match mode {
-                HitWindowMode::OsuOD => HitWindow::from_osu_od(*value),
-                HitWindowMode::EtternaJudge => HitWindow::from_etterna_judge(*value as u8),
-            };
-
-            engine.hit_window = hw;
             // Note: hit_window_mode/value now stored on engine, not replay_data
-            engine.hit_window_mode = *mode;
-            engine.hit_window_value = *value;
+            engine.update_hit_window(state.settings.hit_window_settings());
 
             None
         }
+        GameAction::BeatmapOffsetUp | GameAction::BeatmapOffsetDown => {
+            let Some(hash) = engine.beatmap_hash.clone() else {
+                return None;
+            };
+            let step = if matches!(action, GameAction::BeatmapOffsetUp) {
+                BEATMAP_OFFSET_STEP_MS
+            } else {
+                -BEATMAP_OFFSET_STEP_MS
+            };
+            let offset = state.settings.nudge_beatmap_offset(&hash, step);
+            engine.audio_offset_us = state.settings.audio_offset_us(Some(&hash));
+            state.persist_settings();
+            log::info!("GAME: Beatmap offset set to {:+.0}ms", offset);
+            None
+        }
         GameAction::ScrollSpeedUp => {
             engine.scroll_speed_ms = (engine.scroll_speed_ms + 10.0).min(1500.0);
             state.settings.scroll_speed = engine.scroll_speed_ms;
@@ -55,6 +73,28 @@ pub fn apply(
             state.persist_settings();
             None
         }
+        GameAction::NoteSizeUp | GameAction::NoteSizeDown => {
+            let step = if matches!(action, GameAction::NoteSizeUp) {
+                PLAYFIELD_SCALE_STEP
+            } else {
+                -PLAYFIELD_SCALE_STEP
+            };
+            engine.note_scale = step_playfield_scale(engine.note_scale, step);
+            state.settings.note_scale = engine.note_scale;
+            state.persist_settings();
+            None
+        }
+        GameAction::ColumnWidthUp | GameAction::ColumnWidthDown => {
+            let step = if matches!(action, GameAction::ColumnWidthUp) {
+                PLAYFIELD_SCALE_STEP
+            } else {
+                -PLAYFIELD_SCALE_STEP
+            };
+            engine.column_width_scale = step_playfield_scale(engine.column_width_scale, step);
+            state.settings.column_width_scale = engine.column_width_scale;
+            state.persist_settings();
+            None
+        }
         _ => {
             engine.handle_input(action.clone());
             None
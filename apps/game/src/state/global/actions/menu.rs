@@ -1,27 +1,31 @@
 use crate::input::events::GameAction;
 use crate::state::global::GlobalState;
 use crate::state::global::app_state::AppState;
-use crate::state::global::helpers::create_debug_chart;
+use crate::state::mods::NoteVisibility;
 use crate::state::{GameEngine, MenuState};
-use engine::US_PER_MS;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 
 pub fn apply(
     state: &mut GlobalState,
     menu: &mut MenuState,
     action: &GameAction,
 ) -> Option<AppState> {
-    match action {
+    let selected_set = menu.selected_set_id();
+    let next = match action {
         GameAction::Navigation { x, y } => handle_navigation(state, menu, *x, *y),
         GameAction::SetSelection(idx) => handle_set_selection(state, menu, *idx),
         GameAction::SetDifficulty(idx) => handle_set_difficulty(state, menu, *idx),
+        GameAction::FilterDifficulties(query) => handle_filter_difficulties(state, menu, query),
+        GameAction::TypeAhead(text) => handle_type_ahead(state, menu, text),
+        GameAction::RandomSelect => handle_random_select(state, menu),
         GameAction::Confirm => handle_confirm(state, menu),
         GameAction::LaunchPractice => handle_launch_practice(state, menu),
         GameAction::ToggleEditor => handle_toggle_editor(state, menu),
-        GameAction::TabNext => {
+        GameAction::RateUp => {
             menu.increase_rate();
             None
         }
-        GameAction::TabPrev => {
+        GameAction::RateDown => {
             menu.decrease_rate();
             None
         }
@@ -32,6 +36,13 @@ pub fn apply(
             }
             None
         }
+        GameAction::ToggleSessionRecap => {
+            menu.session_recap = match menu.session_recap {
+                Some(_) => None,
+                None => Some(state.session.clone()),
+            };
+            None
+        }
         GameAction::UpdateVolume(value) => {
             state.settings.master_volume = *value;
             state.persist_settings();
@@ -55,9 +66,14 @@ pub fn apply(
             menu.ensure_difficulty_calculated();
             None
         }
-        GameAction::UpdateHitWindow { mode, value } => {
+        GameAction::UpdateHitWindow {
+            mode,
+            value,
+            custom_ms,
+        } => {
             state.settings.hit_window_mode = *mode;
             state.settings.hit_window_value = *value;
+            state.settings.custom_hit_window_ms = *custom_ms;
             state.persist_settings();
             None
         }
@@ -91,8 +107,19 @@ pub fn apply(
             log::info!("MODS: Toggled {:?}", game_mod);
             None
         }
+        GameAction::SetLeaderboardFilter(filter) => {
+            menu.leaderboard_filter = filter.clone();
+            state.db_manager.set_leaderboard_filter(filter.clone());
+            None
+        }
+        GameAction::DeleteReplay(hash) => {
+            state.db_manager.delete_replay(hash);
+            None
+        }
         _ => None,
-    }
+    };
+    menu.reset_difficulty_filter_on_set_change(selected_set);
+    next
 }
 
 fn handle_navigation(
@@ -101,6 +128,7 @@ fn handle_navigation(
     x: i32,
     y: i32,
 ) -> Option<AppState> {
+    menu.type_ahead.clear();
     if y < 0 {
         menu.move_up();
     }
@@ -159,6 +187,55 @@ fn handle_set_difficulty(
     None
 }
 
+fn handle_filter_difficulties(
+    state: &mut GlobalState,
+    menu: &mut MenuState,
+    query: &str,
+) -> Option<AppState> {
+    let previous = menu.selected_difficulty_index;
+    menu.set_difficulty_filter(query);
+    if menu.selected_difficulty_index != previous {
+        if menu.show_settings {
+            menu.ensure_chart_cache();
+        }
+        let request_hash = menu.get_selected_beatmap_hash();
+        state.request_leaderboard_for_hash(request_hash);
+    }
+    None
+}
+
+fn handle_type_ahead(
+    state: &mut GlobalState,
+    menu: &mut MenuState,
+    text: &str,
+) -> Option<AppState> {
+    let now = Instant::now();
+    let mut moved = false;
+    for c in text.chars() {
+        moved |= menu.type_ahead(c, now);
+    }
+    if moved {
+        if menu.show_settings {
+            menu.ensure_chart_cache();
+        }
+        let request_hash = menu.get_selected_beatmap_hash();
+        state.request_leaderboard_for_hash(request_hash);
+    }
+    None
+}
+
+fn handle_random_select(state: &mut GlobalState, menu: &mut MenuState) -> Option<AppState> {
+    if menu.random_select(&mut rand::rng()) {
+        menu.type_ahead.clear();
+        if menu.show_settings {
+            menu.ensure_chart_cache();
+        }
+        let request_hash = menu.get_selected_beatmap_hash();
+        state.request_leaderboard_for_hash(request_hash);
+    }
+    None
+}
+
 fn handle_confirm(state: &mut GlobalState, menu: &mut MenuState) -> Option<AppState> {
     state.reload_settings();
     menu.ensure_chart_cache();
@@ -178,8 +255,7 @@ fn handle_confirm(state: &mut GlobalState, menu: &mut MenuState) -> Option<AppSt
             cache.audio_path.clone(),
             menu.rate,
             beatmap_hash,
-            state.settings.hit_window_mode,
-            state.settings.hit_window_value,
+            state.settings.hit_window_settings(),
             cache.key_count,
         )
     } else if let Some(path) = menu.get_selected_beatmap_path() {
@@ -193,8 +269,7 @@ fn handle_confirm(state: &mut GlobalState, menu: &mut MenuState) -> Option<AppSt
             path,
             menu.rate,
             beatmap_hash,
-            state.settings.hit_window_mode,
-            state.settings.hit_window_value,
+            state.settings.hit_window_settings(),
         ) {
             e
         } else {
@@ -206,10 +281,25 @@ fn handle_confirm(state: &mut GlobalState, menu: &mut MenuState) -> Option<AppSt
 
     let mut engine = engine;
     engine.scroll_speed_ms = state.settings.scroll_speed;
-    engine.audio_offset_us = (state.settings.global_audio_offset_ms * US_PER_MS as f64) as i64;
-    engine
-        .audio_manager
-        .set_volume(state.settings.master_volume);
+    engine.note_scale = state.settings.note_scale;
+    engine.column_width_scale = state.settings.column_width_scale;
+    engine.audio_offset_us = state
+        .settings
+        .audio_offset_us(engine.beatmap_hash.as_deref());
+    engine.set_scoring_model(state.settings.scoring_model);
+    engine.set_combo_break_rule(state.settings.combo_break_rule);
+    engine.set_combo_milestones(&state.settings.combo_milestones);
+    engine.set_life_bar(
+        state.settings.health_preset,
+        state.settings.fail_on_empty_health,
+    );
+    engine.set_keysounds(
+        state.settings.keysounds,
+        state.settings.mute_music_on_keysounds,
+    );
+    engine.set_volume(state.settings.master_volume);
+    engine.visibility = NoteVisibility::from_mods(&menu.active_mods, &state.settings);
+    engine.apply_mods(&menu.active_mods.to_vec());
 
     // Switch keybinds to match the map's key count
     state.set_key_count(engine.key_count);
@@ -236,8 +326,7 @@ fn handle_launch_practice(state: &mut GlobalState, menu: &mut MenuState) -> Opti
             cache.audio_path.clone(),
             menu.rate,
             beatmap_hash,
-            state.settings.hit_window_mode,
-            state.settings.hit_window_value,
+            state.settings.hit_window_settings(),
             cache.key_count,
         )
     } else if let Some(path) = menu.get_selected_beatmap_path() {
@@ -251,8 +340,7 @@ fn handle_launch_practice(state: &mut GlobalState, menu: &mut MenuState) -> Opti
             path,
             menu.rate,
             beatmap_hash,
-            state.settings.hit_window_mode,
-            state.settings.hit_window_value,
+            state.settings.hit_window_settings(),
         ) {
             e
         } else {
@@ -264,10 +352,25 @@ fn handle_launch_practice(state: &mut GlobalState, menu: &mut MenuState) -> Opti
 
     let mut engine = engine;
     engine.scroll_speed_ms = state.settings.scroll_speed;
-    engine.audio_offset_us = (state.settings.global_audio_offset_ms * US_PER_MS as f64) as i64;
-    engine
-        .audio_manager
-        .set_volume(state.settings.master_volume);
+    engine.note_scale = state.settings.note_scale;
+    engine.column_width_scale = state.settings.column_width_scale;
+    engine.audio_offset_us = state
+        .settings
+        .audio_offset_us(engine.beatmap_hash.as_deref());
+    engine.set_scoring_model(state.settings.scoring_model);
+    engine.set_combo_break_rule(state.settings.combo_break_rule);
+    engine.set_combo_milestones(&state.settings.combo_milestones);
+    engine.set_life_bar(
+        state.settings.health_preset,
+        state.settings.fail_on_empty_health,
+    );
+    engine.set_keysounds(
+        state.settings.keysounds,
+        state.settings.mute_music_on_keysounds,
+    );
+    engine.set_volume(state.settings.master_volume);
+    engine.visibility = NoteVisibility::from_mods(&menu.active_mods, &state.settings);
+    engine.apply_mods(&menu.active_mods.to_vec());
     engine.enable_practice_mode();
 
     // Switch keybinds to match the map's key count
@@ -290,8 +393,7 @@ fn handle_toggle_editor(state: &mut GlobalState, menu: &mut MenuState) -> Option
             cache.audio_path.clone(),
             1.0,
             None,
-            state.settings.hit_window_mode,
-            state.settings.hit_window_value,
+            state.settings.hit_window_settings(),
             cache.key_count,
         )
     } else if let Some(path) = menu.get_selected_beatmap_path() {
@@ -300,8 +402,7 @@ fn handle_toggle_editor(state: &mut GlobalState, menu: &mut MenuState) -> Option
             path,
             1.0,
             None,
-            state.settings.hit_window_mode,
-            state.settings.hit_window_value,
+            state.settings.hit_window_settings(),
         ) {
             e
         } else {
@@ -313,7 +414,13 @@ fn handle_toggle_editor(state: &mut GlobalState, menu: &mut MenuState) -> Option
 
     let mut engine = engine;
     engine.scroll_speed_ms = state.settings.scroll_speed;
-    engine.audio_offset_us = (state.settings.global_audio_offset_ms * US_PER_MS as f64) as i64;
+    engine.note_scale = state.settings.note_scale;
+    engine.column_width_scale = state.settings.column_width_scale;
+    engine.audio_offset_us = state
+        .settings
+        .audio_offset_us(engine.beatmap_hash.as_deref());
+    engine.set_scoring_model(state.settings.scoring_model);
+    engine.set_combo_break_rule(state.settings.combo_break_rule);
     engine
         .audio_manager
         .set_volume(state.settings.master_volume);
@@ -326,17 +433,25 @@ fn handle_toggle_editor(state: &mut GlobalState, menu: &mut MenuState) -> Option
 
 fn handle_launch_debug_map(state: &mut GlobalState) -> Option<AppState> {
     state.reload_settings();
-    let (chart, key_count) = create_debug_chart();
-    let engine = GameEngine::from_debug_chart(
-        &state.bus,
-        chart,
-        state.settings.hit_window_mode,
-        state.settings.hit_window_value,
-        key_count,
-    );
-    let mut engine = engine;
+    let seed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or_default();
+    log::info!("GAME: Launching debug map with seed {}", seed);
+    let mut engine = GameEngine::new_debug(&state.bus, seed, state.settings.hit_window_settings());
     engine.scroll_speed_ms = state.settings.scroll_speed;
-    engine.audio_offset_us = (state.settings.global_audio_offset_ms * US_PER_MS as f64) as i64;
+    engine.note_scale = state.settings.note_scale;
+    engine.column_width_scale = state.settings.column_width_scale;
+    engine.audio_offset_us = state
+        .settings
+        .audio_offset_us(engine.beatmap_hash.as_deref());
+    engine.set_scoring_model(state.settings.scoring_model);
+    engine.set_combo_break_rule(state.settings.combo_break_rule);
+    engine.set_combo_milestones(&state.settings.combo_milestones);
+    engine.set_life_bar(
+        state.settings.health_preset,
+        state.settings.fail_on_empty_health,
+    );
 
     // Switch keybinds to match the map's key count
     state.set_key_count(engine.key_count);
@@ -1,9 +1,8 @@
 use crate::input::events::GameAction;
-use crate::models::settings::HitWindowMode;
 use crate::state::GameResultData;
 use crate::state::global::GlobalState;
 use crate::state::global::app_state::AppState;
-use replay::simulate;
+use replay::rejudge;
 
 pub fn apply(
     state: &mut GlobalState,
@@ -22,18 +21,21 @@ pub fn apply(
             result.show_settings = !result.show_settings;
             None
         }
-        GameAction::UpdateHitWindow { mode, value } => {
+        GameAction::UpdateHitWindow {
+            mode,
+            value,
+            custom_ms,
+        } => {
             state.settings.hit_window_mode = *mode;
             state.settings.hit_window_value = *value;
+            state.settings.custom_hit_window_ms = *custom_ms;
             state.persist_settings();
 
             // Update judge text from settings
-            result.judge_text = match state.settings.hit_window_mode {
-                HitWindowMode::OsuOD => format!("OD {:.1}", state.settings.hit_window_value),
-                HitWindowMode::EtternaJudge => {
-                    format!("Judge {:.0}", state.settings.hit_window_value)
-                }
-            };
+            result.judge_text = state
+                .settings
+                .hit_window_mode
+                .label(state.settings.hit_window_value);
 
             let chart_opt = state
                 .saved_menu_state
@@ -48,13 +50,8 @@ pub fn apply(
                     *value
                 );
                 // Build hit window from settings, not replay_data
-                let hit_window = match mode {
-                    HitWindowMode::OsuOD => engine::HitWindow::from_osu_od(*value),
-                    HitWindowMode::EtternaJudge => {
-                        engine::HitWindow::from_etterna_judge(*value as u8)
-                    }
-                };
-                let sim_res = simulate(&result.replay_data, &chart, &hit_window);
+                let hit_window = state.settings.hit_window();
+                let sim_res = rejudge(&result.replay_data, &chart, &hit_window);
 
                 log::info!(
                     "RESULT: New Accuracy: {:.2}% (Marv: {}, Perf: {}, Miss: {})",
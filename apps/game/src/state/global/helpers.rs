@@ -1,10 +1,16 @@
 //! Debug helpers for testing and development.
 
 use engine::{NoteData, US_PER_MS};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
 
 /// Creates a debug chart with all note types for testing rendering.
+/// Columns of each note group are shuffled with an RNG seeded by `seed`,
+/// so the same seed always produces the same chart.
 /// Returns (notes, key_count).
-pub(super) fn create_debug_chart() -> (Vec<NoteData>, usize) {
+pub(crate) fn create_debug_chart(seed: u64) -> (Vec<NoteData>, usize) {
+    let mut rng = StdRng::seed_from_u64(seed);
     let mut notes = Vec::new();
     let time_us: i64 = 1_000_000; // Start at 1 second (in µs)
     let spacing_us: i64 = 500 * US_PER_MS; // 500ms between note groups
@@ -13,6 +19,7 @@ pub(super) fn create_debug_chart() -> (Vec<NoteData>, usize) {
     // Loop to create multiple sets of all note types
     for iteration in 0..10 {
         let base_time_us = time_us + (iteration as i64 * spacing_us * 8);
+        let group_start = notes.len();
 
         // Tap notes (one per column)
         for col in 0..4u8 {
@@ -66,6 +73,13 @@ pub(super) fn create_debug_chart() -> (Vec<NoteData>, usize) {
             2,
             300 * US_PER_MS,
         ));
+
+        // Shuffle the columns of this group
+        let mut columns: Vec<u8> = (0..key_count as u8).collect();
+        columns.shuffle(&mut rng);
+        for note in &mut notes[group_start..] {
+            *note = remap_column(note, columns[note.column()]);
+        }
     }
 
     // Sort by timestamp
@@ -73,3 +87,100 @@ pub(super) fn create_debug_chart() -> (Vec<NoteData>, usize) {
 
     (notes, key_count)
 }
+
+/// Rebuilds a note on another column, keeping its type and timing.
+fn remap_column(note: &NoteData, column: u8) -> NoteData {
+    if note.is_hold() {
+        NoteData::hold(note.time_us(), column, note.duration_us())
+    } else if note.is_burst() {
+        NoteData::burst(note.time_us(), column, note.duration_us())
+    } else if note.is_mine() {
+        NoteData::mine(note.time_us(), column)
+    } else {
+        NoteData::tap(note.time_us(), column)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fingerprint(notes: &[NoteData]) -> Vec<(i64, usize, i64, bool, bool, bool)> {
+        notes
+            .iter()
+            .map(|n| {
+                (
+                    n.time_us(),
+                    n.column(),
+                    n.duration_us(),
+                    n.is_hold(),
+                    n.is_burst(),
+                    n.is_mine(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_same_seed_same_chart() {
+        let (a, keys_a) = create_debug_chart(42);
+        let (b, keys_b) = create_debug_chart(42);
+        assert_eq!(keys_a, keys_b);
+        assert_eq!(fingerprint(&a), fingerprint(&b));
+    }
+
+    #[test]
+    fn test_different_seed_different_chart() {
+        let (a, _) = create_debug_chart(1);
+        let (b, _) = create_debug_chart(2);
+        assert_eq!(a.len(), b.len());
+        assert_ne!(fingerprint(&a), fingerprint(&b));
+    }
+
+    #[test]
+    fn test_same_seed_same_run() {
+        use crate::input::events::GameAction;
+        use crate::models::settings::HitWindowSettings;
+        use crate::state::GameEngine;
+        use crate::system::bus::SystemBus;
+        use engine::HitStats;
+        use std::time::{Duration, Instant};
+
+        let start = Instant::now();
+        let play = || {
+            let mut engine =
+                GameEngine::new_debug(&SystemBus::new(), 42, HitWindowSettings::default());
+            // 12 s at 200 TPS: the 3 s pre-roll, then the first note groups
+            for tick in 0..2_400u64 {
+                let now = start + Duration::from_millis(5 * tick);
+                engine.update_at(0.005, now);
+                // Scripted mashing: a press every 100 ms, held for 50 ms
+                let column = (tick / 20 % 4) as usize;
+                match tick % 20 {
+                    0 => engine.handle_input(GameAction::Hit {
+                        column,
+                        at: Some(now),
+                    }),
+                    10 => engine.handle_input(GameAction::Release {
+                        column,
+                        at: Some(now),
+                    }),
+                    _ => {}
+                }
+            }
+            engine
+        };
+
+        let a = play();
+        let b = play();
+        assert_ne!(a.hit_stats, HitStats::new(), "the run judged notes");
+        assert_eq!(a.hit_stats, b.hit_stats);
+        assert_eq!(
+            (a.score, a.combo, a.max_combo),
+            (b.score, b.combo, b.max_combo)
+        );
+        let hits =
+            |engine: &GameEngine| -> Vec<bool> { engine.chart().iter().map(|n| n.hit()).collect() };
+        assert_eq!(hits(&a), hits(&b));
+    }
+}
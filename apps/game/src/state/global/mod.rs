@@ -3,12 +3,15 @@
 mod actions;
 mod app_state;
 mod helpers;
+mod session;
 
 use actions::editor::apply as apply_to_editor;
 use actions::game::apply as apply_to_game;
 use actions::menu::apply as apply_to_menu;
 use actions::result::apply as apply_to_result;
 use app_state::AppState;
+pub(crate) use helpers::create_debug_chart;
+pub use session::SessionStats;
 
 use crate::input::events::{GameAction, InputCommand};
 use crate::models::settings::SettingsState;
@@ -16,6 +19,7 @@ use crate::shared::snapshot::{EditorSnapshot, RenderState};
 use crate::state::MenuState;
 use crate::state::traits::{Snapshot, Transition, Update, UpdateContext};
 use crate::system::bus::SystemBus;
+use crate::ui::common::{ParticleConfig, ParticleField};
 use crossbeam_channel::Sender;
 use database::{DbManager, DbStatus};
 use std::sync::Arc;
@@ -33,6 +37,12 @@ pub struct GlobalState {
     pub(super) bus: SystemBus,
     /// Current key mode for keybind loading.
     pub(super) current_key_count: usize,
+    /// Stats of the plays since launch.
+    pub(super) session: SessionStats,
+    /// Main menu background, animated here so rendering only draws it.
+    pub(super) menu_particles: ParticleField,
+    /// Whether the input thread reserves letters for the type-ahead search.
+    pub(super) text_capture: bool,
 }
 
 impl GlobalState {
@@ -53,11 +63,19 @@ impl GlobalState {
             input_cmd_tx,
             bus,
             current_key_count: 4, // Default to 4K
+            session: SessionStats::new(),
+            // Window size until the renderer reports the real one
+            menu_particles: ParticleField::new(ParticleConfig::default(), 1280.0, 720.0),
+            text_capture: false,
         }
     }
 
-    pub fn resize(&mut self, _w: u32, _h: u32) {}
-    pub fn shutdown(&mut self) {}
+    pub fn resize(&mut self, w: u32, h: u32) {
+        self.menu_particles.resize(w as f32, h as f32);
+    }
+    pub fn shutdown(&mut self) {
+        log::info!("SESSION: {}", self.session.summary());
+    }
 
     /// Ticks the active state and processes end-of-run transitions.
     pub fn update(&mut self, dt: f64) {
@@ -72,7 +90,10 @@ impl GlobalState {
 
         // Call update on the current state and collect any transition
         let transition = match &mut self.current_state {
-            AppState::MainMenu => None, // No update needed for main menu
+            AppState::MainMenu => {
+                self.menu_particles.tick(dt as f32);
+                None
+            }
             AppState::Menu(menu) => Update::update(menu, dt, &mut ctx),
             AppState::Game(engine) => Update::update(engine, dt, &mut ctx),
             AppState::Result(result) => Update::update(result, dt, &mut ctx),
@@ -85,8 +106,34 @@ impl GlobalState {
 
         // Apply any transition
         if let Some(Transition::ToResult(result)) = transition {
+            if let AppState::Game(engine) = &self.current_state
+                && engine.is_submittable()
+            {
+                self.session
+                    .record(&result.hit_stats, result.accuracy, engine.played_time());
+            }
             self.current_state = AppState::Result(result);
         }
+        self.sync_text_capture();
+    }
+
+    /// Tells the input thread whether song select is open, so typed letters
+    /// reach the type-ahead search instead of the letter bindings.
+    fn sync_text_capture(&mut self) {
+        let capture = matches!(self.current_state, AppState::Menu(_));
+        if capture == self.text_capture {
+            return;
+        }
+        self.text_capture = capture;
+        if let Err(e) = self
+            .input_cmd_tx
+            .send(InputCommand::SetTextCapture(capture))
+        {
+            log::error!(
+                "LOGIC: Failed to send the text capture to input thread: {}",
+                e
+            );
+        }
     }
 
     /// Mirrors database snapshots into the menu whenever new data is available.
@@ -103,12 +150,14 @@ impl GlobalState {
                 let mut request_hash = None;
                 let mut cache = None;
                 if let AppState::Menu(menu) = &mut self.current_state {
+                    let selected_set = menu.selected_set_id();
                     menu.beatmapsets = Arc::new(guard.beatmapsets.clone());
                     menu.update_filtered_indices(); // CRITICAL: Update indices after new data
                     menu.start_index = 0;
                     menu.end_index = menu.visible_count.min(menu.filtered_indices.len()); // Use filtered len
                     menu.selected_index = menu.filtered_indices.first().copied().unwrap_or(0);
                     menu.selected_difficulty_index = 0;
+                    menu.reset_difficulty_filter_on_set_change(selected_set);
                     request_hash = menu.get_selected_beatmap_hash();
                     cache = Some(menu.clone());
                 }
@@ -173,6 +222,16 @@ impl GlobalState {
         )) {
             log::error!("LOGIC: Failed to forward keybinds to input thread: {}", e);
         }
+
+        self.settings.action_keybinds = disk_settings.action_keybinds;
+        if let Err(e) = self.input_cmd_tx.send(InputCommand::ReloadActionKeybinds(
+            self.settings.action_keybinds.clone(),
+        )) {
+            log::error!(
+                "LOGIC: Failed to forward action keybinds to input thread: {}",
+                e
+            );
+        }
     }
 
     /// Sets the current key count and reloads keybinds for that mode.
@@ -218,6 +277,10 @@ impl GlobalState {
             }
             AppState::Menu(menu) => {
                 let next = apply_to_menu(self, menu, &action);
+                if next.is_some() {
+                    // The next screen takes over the audio
+                    menu.preview.reset();
+                }
                 self.cache_menu_state(menu.clone());
                 next
             }
@@ -227,6 +290,7 @@ impl GlobalState {
         };
 
         self.current_state = transition.unwrap_or(current_state);
+        self.sync_text_capture();
     }
 
     /// Cleans up transient editor buffers so next frame starts fresh.
@@ -241,7 +305,7 @@ impl GlobalState {
     /// Produces a render-ready snapshot for the renderer thread.
     pub fn create_snapshot(&mut self) -> RenderState {
         match &mut self.current_state {
-            AppState::MainMenu => RenderState::MainMenu,
+            AppState::MainMenu => RenderState::MainMenu(self.menu_particles.clone()),
             AppState::Menu(menu) => RenderState::Menu(Snapshot::create_snapshot(menu)),
             AppState::Game(engine) => RenderState::InGame(Snapshot::create_snapshot(engine)),
             AppState::Editor(editor) => {
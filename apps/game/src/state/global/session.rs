@@ -0,0 +1,90 @@
+//! Session recap - stats aggregated over the plays since launch.
+//!
+//! Kept in memory only: the recap starts empty on every launch.
+
+use engine::HitStats;
+use std::time::Duration;
+
+/// Totals of the plays finished this session.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SessionStats {
+    /// Plays that reached the result screen.
+    pub plays: u32,
+    /// Notes hit (every judgement but misses).
+    pub notes_hit: u64,
+    /// Sum of the play accuracies, for the average.
+    accuracy_sum: f64,
+    /// Time spent playing (rate applied, pauses excluded).
+    pub time_played: Duration,
+}
+
+impl SessionStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a finished play.
+    pub fn record(&mut self, hit_stats: &HitStats, accuracy: f64, played: Duration) {
+        self.plays += 1;
+        self.notes_hit += u64::from(
+            hit_stats.marv + hit_stats.perfect + hit_stats.great + hit_stats.good + hit_stats.bad,
+        );
+        self.accuracy_sum += accuracy;
+        self.time_played += played;
+    }
+
+    /// Mean accuracy of the plays, `None` before the first one.
+    pub fn average_accuracy(&self) -> Option<f64> {
+        (self.plays > 0).then(|| self.accuracy_sum / self.plays as f64)
+    }
+
+    /// Time played as `m:ss`.
+    pub fn time_played_label(&self) -> String {
+        let seconds = self.time_played.as_secs();
+        format!("{}:{:02}", seconds / 60, seconds % 60)
+    }
+
+    /// One-line recap, e.g. for the log on exit.
+    pub fn summary(&self) -> String {
+        format!(
+            "{} plays, {} notes hit, {:.2}% average accuracy, {} played",
+            self.plays,
+            self.notes_hit,
+            self.average_accuracy().unwrap_or(0.0),
+            self.time_played_label()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(marv: u32, great: u32, miss: u32) -> HitStats {
+        // `HitStats` is non-exhaustive: no struct literal outside the engine
+        let mut stats = HitStats::new();
+        stats.marv = marv;
+        stats.great = great;
+        stats.miss = miss;
+        stats
+    }
+
+    #[test]
+    fn test_two_plays_aggregate() {
+        let mut session = SessionStats::new();
+        assert_eq!(session.average_accuracy(), None);
+
+        session.record(&stats(100, 10, 5), 95.0, Duration::from_secs(90));
+        session.record(&stats(200, 0, 0), 100.0, Duration::from_secs(150));
+
+        assert_eq!(session.plays, 2);
+        // Misses are not hits
+        assert_eq!(session.notes_hit, 310);
+        assert_eq!(session.average_accuracy(), Some(97.5));
+        assert_eq!(session.time_played, Duration::from_secs(240));
+        assert_eq!(
+            session.summary(),
+            "2 plays, 310 notes hit, 97.50% average accuracy, 4:00 played"
+        );
+    }
+}
@@ -1,7 +1,9 @@
 //! Trait implementations for MenuState.
 
-use super::MenuState;
+use super::{DIFFICULTY_JOBS_IN_FLIGHT, MenuState};
+use crate::audio_sys::AudioManager;
 use crate::state::traits::{Snapshot, Transition, Update, UpdateContext};
+use std::time::Instant;
 
 // MenuState implements Snapshot by cloning itself.
 // It's already Arc-wrapped for cheap clones.
@@ -15,10 +17,25 @@ impl Snapshot for MenuState {
 
 // MenuState performs cache updates during update().
 impl Update for MenuState {
-    fn update(&mut self, _dt: f64, _ctx: &mut UpdateContext) -> Option<Transition> {
+    fn update(&mut self, _dt: f64, ctx: &mut UpdateContext) -> Option<Transition> {
+        self.set_analysis_limits(ctx.settings.analysis_limits());
+        self.set_rate_range(ctx.settings.rate_range);
+
         // Ensure caches are up-to-date
+        for rating in self.poll_difficulty_results() {
+            ctx.db_manager.save_rating(rating);
+        }
+        self.expire_difficulty_timeout(self.running_difficulty());
+        self.refresh_difficulty_queue();
         self.ensure_selected_rate_cache();
         self.ensure_chart_cache();
+        self.process_difficulty_queue(DIFFICULTY_JOBS_IN_FLIGHT);
+
+        // Preview the selected song once the wheel stops moving
+        let track = self.selected_preview_track();
+        if let Some(command) = self.preview.update(track, Instant::now()) {
+            command.send(&mut AudioManager::new(ctx.bus), ctx.settings.master_volume);
+        }
         None
     }
 }
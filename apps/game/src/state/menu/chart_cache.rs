@@ -1,7 +1,12 @@
 //! Cache de chart pour le menu.
 
-use engine::NoteData;
+use engine::{ChartMetadata, NoteData};
+use std::collections::VecDeque;
 use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Nombre de charts gardées en mémoire par défaut.
+pub const DEFAULT_CHART_CACHE_CAPACITY: usize = 8;
 
 /// Cache de la chart actuellement sélectionnée.
 /// Permet de pré-charger la map et de l'utiliser pour:
@@ -19,4 +24,117 @@ pub struct ChartCache {
     pub map_path: PathBuf,
     /// Number of columns (key count).
     pub key_count: usize,
+    /// Titre, artiste, BPM... lus en même temps que les notes.
+    pub metadata: ChartMetadata,
+}
+
+/// Cache LRU des charts récemment sélectionnées, indexé par hash.
+/// Évite de re-parser une map quand on navigue entre quelques maps.
+#[derive(Clone, Debug)]
+pub struct ChartLruCache {
+    /// Entrées de la plus récente à la plus ancienne.
+    entries: VecDeque<Arc<ChartCache>>,
+    /// Nombre maximum de charts gardées.
+    capacity: usize,
+}
+
+impl ChartLruCache {
+    pub fn new() -> Self {
+        Self::with_capacity(DEFAULT_CHART_CACHE_CAPACITY)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::with_capacity(capacity),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Gets a chart by hash and marks it as most recently used.
+    pub fn get(&mut self, beatmap_hash: &str) -> Option<Arc<ChartCache>> {
+        let pos = self
+            .entries
+            .iter()
+            .position(|c| c.beatmap_hash == beatmap_hash)?;
+        let entry = self.entries.remove(pos)?;
+        self.entries.push_front(Arc::clone(&entry));
+        Some(entry)
+    }
+
+    /// Inserts a chart as most recently used, evicting the oldest when full.
+    pub fn insert(&mut self, chart: Arc<ChartCache>) {
+        self.entries
+            .retain(|c| c.beatmap_hash != chart.beatmap_hash);
+        while self.entries.len() >= self.capacity {
+            self.entries.pop_back();
+        }
+        self.entries.push_front(chart);
+    }
+
+    /// Checks if a chart is cached, without touching its recency.
+    pub fn contains(&self, beatmap_hash: &str) -> bool {
+        self.entries.iter().any(|c| c.beatmap_hash == beatmap_hash)
+    }
+
+    /// Clears all cached charts.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Returns the number of cached charts.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Checks if the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl Default for ChartLruCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chart(hash: &str) -> Arc<ChartCache> {
+        Arc::new(ChartCache {
+            beatmap_hash: hash.to_string(),
+            chart: Vec::new(),
+            audio_path: PathBuf::new(),
+            map_path: PathBuf::new(),
+            key_count: 4,
+            metadata: ChartMetadata::default(),
+        })
+    }
+
+    #[test]
+    fn test_reselect_is_hit_and_oldest_is_evicted() {
+        let mut cache = ChartLruCache::with_capacity(2);
+        cache.insert(chart("a"));
+        cache.insert(chart("b"));
+
+        // Going back to "a" is a hit and makes "b" the oldest
+        assert!(cache.get("a").is_some());
+
+        cache.insert(chart("c"));
+        assert_eq!(cache.len(), 2);
+        assert!(cache.contains("a"));
+        assert!(cache.contains("c"));
+        assert!(!cache.contains("b"));
+        assert!(cache.get("b").is_none());
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut cache = ChartLruCache::new();
+        cache.insert(chart("a"));
+        cache.clear();
+        assert!(cache.is_empty());
+    }
 }
@@ -1,12 +1,12 @@
 //! In-memory difficulty cache for on-demand calculation.
 //!
-//! This cache stores difficulty ratings calculated during the session.
-//! Ratings are NOT persisted to the database - they are calculated on-the-fly
-//! when a beatmap is selected.
+//! This cache stores difficulty ratings calculated during the session, at
+//! every rate and calculator. Only the 1.0x ratings are persisted to the
+//! database (see `MenuState::apply_difficulty_result`).
 
-use chart::BeatmapSsr;
+use chart::{BeatmapSsr, CalcError};
 use ordered_float::OrderedFloat;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 /// Key for the difficulty cache: (beatmap_hash, calculator_id, rate)
 pub type DifficultyKey = (String, String, OrderedFloat<f64>);
@@ -18,6 +18,10 @@ pub struct DifficultyCache {
     cache: HashMap<DifficultyKey, BeatmapSsr>,
     /// Maximum cache size (to prevent unbounded growth)
     max_size: usize,
+    /// Beatmaps skipped for being over the analysis note limit
+    too_large: HashSet<String>,
+    /// Last calculation error per beatmap hash
+    errors: HashMap<String, String>,
 }
 
 impl DifficultyCache {
@@ -25,6 +29,8 @@ impl DifficultyCache {
         Self {
             cache: HashMap::new(),
             max_size: 1000, // Cache up to 1000 entries
+            too_large: HashSet::new(),
+            errors: HashMap::new(),
         }
     }
 
@@ -32,6 +38,8 @@ impl DifficultyCache {
         Self {
             cache: HashMap::new(),
             max_size,
+            too_large: HashSet::new(),
+            errors: HashMap::new(),
         }
     }
 
@@ -74,9 +82,42 @@ impl DifficultyCache {
         self.cache.contains_key(&key)
     }
 
+    /// Flags a beatmap as too large to rate.
+    pub fn mark_too_large(&mut self, beatmap_hash: &str) {
+        self.too_large.insert(beatmap_hash.to_string());
+    }
+
+    /// Whether the beatmap was skipped for being over the note limit.
+    pub fn is_too_large(&self, beatmap_hash: &str) -> bool {
+        self.too_large.contains(beatmap_hash)
+    }
+
+    /// Forgets the too-large flags (e.g. after the limit changed).
+    pub fn clear_too_large(&mut self) {
+        self.too_large.clear();
+    }
+
+    /// Remembers why the calculation failed for a beatmap.
+    pub fn record_error(&mut self, beatmap_hash: &str, error: &CalcError) {
+        self.errors
+            .insert(beatmap_hash.to_string(), error.to_string());
+    }
+
+    /// Last calculation error of a beatmap, if any.
+    pub fn error(&self, beatmap_hash: &str) -> Option<&str> {
+        self.errors.get(beatmap_hash).map(String::as_str)
+    }
+
+    /// Forgets the error of a beatmap (after a successful calculation).
+    pub fn clear_error(&mut self, beatmap_hash: &str) {
+        self.errors.remove(beatmap_hash);
+    }
+
     /// Clears all cached ratings.
     pub fn clear(&mut self) {
         self.cache.clear();
+        self.too_large.clear();
+        self.errors.clear();
     }
 
     /// Returns the number of cached entries.
@@ -0,0 +1,165 @@
+//! Prioritized queue of on-demand difficulty calculations.
+//!
+//! Beatmaps visible in the song wheel are queued nearest to the current
+//! selection first, so ratings fill in around the cursor as you scroll.
+//! Only a few jobs are handed to the worker thread at a time, so a change of
+//! selection doesn't wait behind calculations that are no longer wanted.
+
+use std::collections::{HashSet, VecDeque};
+use std::ops::Range;
+
+/// Maximum number of difficulty calculations pending on the worker at once.
+pub const DIFFICULTY_JOBS_IN_FLIGHT: usize = 2;
+
+/// A pending difficulty calculation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DifficultyJob {
+    pub beatmap_hash: String,
+    pub beatmap_path: String,
+}
+
+/// Window state the queue was last built for.
+#[derive(Clone, Debug, PartialEq)]
+struct QueueKey {
+    window: Range<usize>,
+    selected: usize,
+    calculator: String,
+    rate: f64,
+}
+
+/// Prioritized queue of difficulty jobs for the visible song wheel.
+#[derive(Clone, Debug, Default)]
+pub struct DifficultyQueue {
+    jobs: VecDeque<DifficultyJob>,
+    key: Option<QueueKey>,
+    /// Hashes that failed to load or calculate (not retried this session).
+    failed: HashSet<String>,
+}
+
+impl DifficultyQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether the queue was already built for this window/selection/calculator/rate.
+    pub fn is_current(
+        &self,
+        window: &Range<usize>,
+        selected: usize,
+        calculator: &str,
+        rate: f64,
+    ) -> bool {
+        self.key.as_ref().is_some_and(|key| {
+            key.window == *window
+                && key.selected == selected
+                && key.calculator == calculator
+                && key.rate == rate
+        })
+    }
+
+    /// Rebuilds the queue for the visible window.
+    ///
+    /// `jobs_for` returns the jobs still needed for a beatmapset index.
+    /// Sets are visited closest to `selected` first.
+    pub fn enqueue_window(
+        &mut self,
+        window: Range<usize>,
+        selected: usize,
+        calculator: &str,
+        rate: f64,
+        mut jobs_for: impl FnMut(usize) -> Vec<DifficultyJob>,
+    ) {
+        self.jobs.clear();
+        for index in proximity_order(window.clone(), selected) {
+            for job in jobs_for(index) {
+                if !self.failed.contains(&job.beatmap_hash) && !self.jobs.contains(&job) {
+                    self.jobs.push_back(job);
+                }
+            }
+        }
+        self.key = Some(QueueKey {
+            window,
+            selected,
+            calculator: calculator.to_string(),
+            rate,
+        });
+    }
+
+    /// Takes the next job to run.
+    pub fn pop(&mut self) -> Option<DifficultyJob> {
+        self.jobs.pop_front()
+    }
+
+    /// Marks a beatmap as failed so it isn't queued again.
+    pub fn mark_failed(&mut self, beatmap_hash: &str) {
+        self.failed.insert(beatmap_hash.to_string());
+    }
+
+    /// Drops pending jobs and forces a rebuild on the next refresh.
+    pub fn clear(&mut self) {
+        self.jobs.clear();
+        self.key = None;
+        self.failed.clear();
+    }
+
+    /// Returns the number of pending jobs.
+    pub fn len(&self) -> usize {
+        self.jobs.len()
+    }
+
+    /// Checks if there are no pending jobs.
+    pub fn is_empty(&self) -> bool {
+        self.jobs.is_empty()
+    }
+}
+
+/// Orders the indices of `window` by distance to `selected`.
+///
+/// Ties go to the entry above the selection first.
+pub fn proximity_order(window: Range<usize>, selected: usize) -> Vec<usize> {
+    let mut indices: Vec<usize> = window.collect();
+    indices.sort_by_key(|&i| (i.abs_diff(selected), i > selected));
+    indices
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(index: usize) -> DifficultyJob {
+        DifficultyJob {
+            beatmap_hash: format!("hash{index}"),
+            beatmap_path: format!("map{index}.osu"),
+        }
+    }
+
+    #[test]
+    fn test_proximity_order() {
+        assert_eq!(proximity_order(10..16, 12), vec![12, 11, 13, 10, 14, 15]);
+        // Selection outside the window still orders by distance
+        assert_eq!(proximity_order(0..3, 5), vec![2, 1, 0]);
+    }
+
+    #[test]
+    fn test_enqueue_window_orders_by_proximity() {
+        let mut queue = DifficultyQueue::new();
+        queue.enqueue_window(20..25, 22, "etterna", 1.0, |i| {
+            // Set 24 is already cached
+            if i == 24 { Vec::new() } else { vec![job(i)] }
+        });
+        assert!(queue.is_current(&(20..25), 22, "etterna", 1.0));
+        assert!(!queue.is_current(&(20..25), 23, "etterna", 1.0));
+
+        let order: Vec<DifficultyJob> = std::iter::from_fn(|| queue.pop()).collect();
+        assert_eq!(order, vec![job(22), job(21), job(23), job(20)]);
+    }
+
+    #[test]
+    fn test_failed_jobs_are_not_requeued() {
+        let mut queue = DifficultyQueue::new();
+        queue.mark_failed("hash1");
+        queue.enqueue_window(0..3, 0, "etterna", 1.0, |i| vec![job(i)]);
+        let order: Vec<DifficultyJob> = std::iter::from_fn(|| queue.pop()).collect();
+        assert_eq!(order, vec![job(0), job(2)]);
+    }
+}
@@ -0,0 +1,313 @@
+//! Background thread running the menu's difficulty calculations.
+//!
+//! The logic thread submits tasks and polls their results every tick, so a
+//! slow chart never stalls it. A single persistent thread serves every
+//! request: MinaCalc sits behind one lock anyway, so calculations would run
+//! one at a time regardless.
+//!
+//! Tasks that are no longer wanted can be cancelled: the worker skips them
+//! if they haven't started, and the caller drops their result otherwise.
+
+use super::difficulty_cache::DifficultyKey;
+use chart::{AnalysisLimits, BeatmapSsr, CalcError, RateDifficultyCache};
+use ordered_float::OrderedFloat;
+use settings::RateRange;
+use std::collections::HashSet;
+use std::panic::AssertUnwindSafe;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// What to calculate for a chart.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DifficultyRequest {
+    /// One calculator at one rate (selection and song wheel).
+    Rating { calculator: String, rate: f64 },
+    /// Every rate of `range`, for the rate list of the selected chart.
+    AllRates { range: RateRange },
+}
+
+/// Identifies a task, so the same calculation is never queued twice.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DifficultyTaskKey {
+    Rating(DifficultyKey),
+    AllRates(String),
+}
+
+impl DifficultyTaskKey {
+    pub fn beatmap_hash(&self) -> &str {
+        match self {
+            DifficultyTaskKey::Rating((hash, _, _)) => hash,
+            DifficultyTaskKey::AllRates(hash) => hash,
+        }
+    }
+}
+
+/// A submitted task the caller is waiting on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PendingDifficulty {
+    /// Id returned by [`DifficultyWorker::submit`].
+    pub id: u64,
+    /// Queued for the song wheel: cancelled when the wheel moves.
+    pub background: bool,
+}
+
+/// A calculation handed to the worker.
+#[derive(Clone, Debug)]
+pub struct DifficultyTask {
+    pub beatmap_hash: String,
+    pub beatmap_path: String,
+    pub limits: AnalysisLimits,
+    pub request: DifficultyRequest,
+}
+
+/// Outcome of a [`DifficultyTask`].
+#[derive(Debug)]
+pub enum DifficultyOutput {
+    Rating(Result<BeatmapSsr, CalcError>),
+    AllRates(Result<RateDifficultyCache, CalcError>),
+}
+
+/// A finished task, posted back to the logic thread.
+#[derive(Debug)]
+pub struct DifficultyResult {
+    /// Id returned by [`DifficultyWorker::submit`].
+    pub id: u64,
+    pub task: DifficultyTask,
+    pub output: DifficultyOutput,
+}
+
+impl DifficultyTask {
+    pub fn key(&self) -> DifficultyTaskKey {
+        match &self.request {
+            DifficultyRequest::Rating { calculator, rate } => DifficultyTaskKey::Rating((
+                self.beatmap_hash.clone(),
+                calculator.clone(),
+                OrderedFloat(*rate),
+            )),
+            DifficultyRequest::AllRates { .. } => {
+                DifficultyTaskKey::AllRates(self.beatmap_hash.clone())
+            }
+        }
+    }
+
+    /// Loads the chart and runs the calculation on the calling thread.
+    pub fn run(&self) -> DifficultyOutput {
+        let map = chart::load_as_rosu_beatmap(Path::new(&self.beatmap_path))
+            .map_err(CalcError::InvalidBeatmap);
+        match &self.request {
+            DifficultyRequest::Rating { calculator, rate } => {
+                DifficultyOutput::Rating(map.and_then(|map| {
+                    chart::calculate_on_demand_bounded(&map, calculator, *rate, &self.limits)
+                }))
+            }
+            DifficultyRequest::AllRates { range } => {
+                let range = *range;
+                DifficultyOutput::AllRates(map.and_then(|map| {
+                    chart::analyze_all_rates_bounded(&map, &self.limits, |rate| {
+                        range.contains(rate)
+                    })
+                }))
+            }
+        }
+    }
+
+    /// Output reported when the calculation panicked.
+    fn failed(&self, message: &str) -> DifficultyOutput {
+        let error = CalcError::CalculationFailed(message.to_string());
+        match self.request {
+            DifficultyRequest::Rating { .. } => DifficultyOutput::Rating(Err(error)),
+            DifficultyRequest::AllRates { .. } => DifficultyOutput::AllRates(Err(error)),
+        }
+    }
+}
+
+/// State shared with the calculation thread.
+#[derive(Default)]
+struct Shared {
+    queue: Mutex<Queue>,
+    /// Task being calculated, and since when.
+    running: Mutex<Option<(u64, Instant)>>,
+}
+
+/// Where the calculation thread is in the task queue.
+///
+/// Tasks are taken in id order, so only ids from `next` on can still be
+/// skipped: cancelling any other id is a no-op, and every id in `cancelled`
+/// is removed once its task is taken.
+#[derive(Default)]
+struct Queue {
+    /// Id of the next task to be taken.
+    next: u64,
+    /// Ids of queued tasks to skip.
+    cancelled: HashSet<u64>,
+}
+
+impl Queue {
+    /// Takes task `id` off the queue, returning whether to skip it.
+    fn take(&mut self, id: u64) -> bool {
+        self.next = id + 1;
+        self.cancelled.remove(&id)
+    }
+}
+
+/// Handle to the calculation thread.
+///
+/// The thread stops once the handle is dropped.
+pub struct DifficultyWorker {
+    tasks: Sender<(u64, DifficultyTask)>,
+    results: Mutex<Receiver<DifficultyResult>>,
+    shared: Arc<Shared>,
+    next_id: AtomicU64,
+}
+
+impl DifficultyWorker {
+    /// Starts the calculation thread.
+    pub fn spawn() -> Self {
+        let (task_tx, task_rx) = mpsc::channel::<(u64, DifficultyTask)>();
+        let (result_tx, result_rx) = mpsc::channel();
+        let shared = Arc::new(Shared::default());
+
+        let thread_shared = Arc::clone(&shared);
+        let spawned = std::thread::Builder::new()
+            .name("difficulty".to_string())
+            .spawn(move || {
+                while let Ok((id, task)) = task_rx.recv() {
+                    if lock(&thread_shared.queue).take(id) {
+                        continue;
+                    }
+                    *lock(&thread_shared.running) = Some((id, Instant::now()));
+                    // A panicking calculator must not take the worker down
+                    let output = std::panic::catch_unwind(AssertUnwindSafe(|| task.run()))
+                        .unwrap_or_else(|_| task.failed("calculation panicked"));
+                    *lock(&thread_shared.running) = None;
+                    if result_tx
+                        .send(DifficultyResult { id, task, output })
+                        .is_err()
+                    {
+                        break;
+                    }
+                }
+            });
+        if let Err(e) = spawned {
+            log::error!("MENU: Failed to start the difficulty worker: {}", e);
+        }
+
+        Self {
+            tasks: task_tx,
+            results: Mutex::new(result_rx),
+            shared,
+            next_id: AtomicU64::new(0),
+        }
+    }
+
+    /// Queues a task, returning its id (`None` if the worker is gone).
+    pub fn submit(&self, task: DifficultyTask) -> Option<u64> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.tasks.send((id, task)).ok().map(|_| id)
+    }
+
+    /// Skips a task if it hasn't started yet.
+    ///
+    /// A task already running still posts its result; callers drop it.
+    pub fn cancel(&self, id: u64) {
+        let mut queue = lock(&self.shared.queue);
+        if id >= queue.next && id < self.next_id.load(Ordering::Relaxed) {
+            queue.cancelled.insert(id);
+        }
+    }
+
+    /// Task being calculated, and for how long.
+    pub fn running(&self) -> Option<(u64, Duration)> {
+        let running = *lock(&self.shared.running);
+        running.map(|(id, started)| (id, started.elapsed()))
+    }
+
+    /// Results finished since the last call, without waiting.
+    pub fn poll(&self) -> Vec<DifficultyResult> {
+        lock(&self.results).try_iter().collect()
+    }
+}
+
+/// Locks a mutex of the worker, whose data stays valid after a panic.
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+impl std::fmt::Debug for DifficultyWorker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DifficultyWorker").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Task on a chart that doesn't exist: fails fast.
+    fn missing_task() -> DifficultyTask {
+        DifficultyTask {
+            beatmap_hash: "hash-missing".to_string(),
+            beatmap_path: "missing/chart.osu".to_string(),
+            limits: AnalysisLimits::default(),
+            request: DifficultyRequest::Rating {
+                calculator: "etterna".to_string(),
+                rate: 1.0,
+            },
+        }
+    }
+
+    /// Polls the worker until it answers or 5 s have passed.
+    fn wait_for_results(worker: &DifficultyWorker) -> Vec<DifficultyResult> {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        loop {
+            let results = worker.poll();
+            if !results.is_empty() || Instant::now() > deadline {
+                return results;
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    #[test]
+    fn test_results_are_posted_back() {
+        let worker = DifficultyWorker::spawn();
+        let id = worker.submit(missing_task()).unwrap();
+
+        // The caller never blocks: poll until the worker answers
+        let results = wait_for_results(&worker);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, id);
+        assert_eq!(results[0].task.beatmap_hash, "hash-missing");
+        assert!(matches!(
+            results[0].output,
+            DifficultyOutput::Rating(Err(CalcError::InvalidBeatmap(_)))
+        ));
+    }
+
+    #[test]
+    fn test_late_cancels_are_not_kept() {
+        let worker = DifficultyWorker::spawn();
+        let id = worker.submit(missing_task()).unwrap();
+        assert_eq!(wait_for_results(&worker).len(), 1);
+
+        // Finished and never submitted tasks have nothing left to skip
+        worker.cancel(id);
+        worker.cancel(id + 10);
+        assert!(lock(&worker.shared.queue).cancelled.is_empty());
+    }
+
+    #[test]
+    fn test_cancelled_ids_are_dropped_when_taken() {
+        let mut queue = Queue::default();
+        queue.cancelled.insert(1);
+        assert!(!queue.take(0));
+        assert!(queue.take(1));
+        assert_eq!(queue.next, 2);
+        assert!(queue.cancelled.is_empty());
+    }
+}
@@ -6,29 +6,48 @@
 //! ## Architecture
 //!
 //! - Beatmaps are loaded via pagination (50 items at a time)
-//! - Difficulty ratings are calculated ON-DEMAND when a map is selected
-//! - Ratings are cached in memory (not DB) for the session
+//! - Difficulty ratings are calculated ON-DEMAND when a map is selected,
+//!   and in the background for the visible song wheel (nearest first)
+//! - Calculations run on a worker thread; results are picked up each tick
+//! - Ratings are cached in memory for the session; the 1.0x ones are also
+//!   saved to the database, which the filters, sorting and song cards read
 
 pub mod actions;
 mod chart_cache;
 mod difficulty_cache;
+mod difficulty_queue;
+mod difficulty_worker;
+mod preview;
 mod rate_cache;
+mod type_ahead;
 
 // Re-exports
-pub use chart_cache::ChartCache;
+pub use chart_cache::{ChartCache, ChartLruCache};
 pub use difficulty_cache::DifficultyCache;
+pub use difficulty_queue::{DIFFICULTY_JOBS_IN_FLIGHT, DifficultyJob, DifficultyQueue};
+pub use difficulty_worker::{
+    DifficultyOutput, DifficultyRequest, DifficultyResult, DifficultyTask, DifficultyTaskKey,
+    DifficultyWorker, PendingDifficulty,
+};
+pub use preview::{PREVIEW_SETTLE_DELAY, PreviewCommand, PreviewTrack, SongPreview};
 pub use rate_cache::RateCacheEntry;
+pub use type_ahead::{TYPE_AHEAD_TIMEOUT, TypeAhead};
 
+use crate::state::global::SessionStats;
 use crate::state::mods::ActiveMods;
 use crate::state::result::GameResultData;
-use crate::ui::song_select::CalculatorOption;
-use chart::{self, BeatmapSsr};
-use database::MenuSearchFilters;
+use crate::ui::song_select::{CalculatorOption, RatingScale, default_calculators};
+use chart::{AnalysisLimits, BeatmapSsr, CalcError};
 use database::models::Replay;
-use database::{BeatmapRating, BeatmapWithRatings, Beatmapset, Database};
+use database::{BeatmapRating, BeatmapWithRatings, Beatmapset, Database, SaveRatingCommand};
+use database::{LeaderboardFilter, MenuSearchFilters};
+use rand::Rng;
+use settings::RateRange;
+use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
 
 /// Modes available in the song selection screen
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
@@ -78,12 +97,18 @@ pub struct MenuState {
     pub selected_index: usize,
     pub selected_difficulty_index: usize,
     pub visible_count: usize,
+    // Difficulty name filter for the expanded beatmapset
+    pub difficulty_filter: String,
+    // Typed title prefix for quick jumps in the wheel
+    pub type_ahead: TypeAhead,
 
     // UI state
     pub in_menu: bool,
     pub in_editor: bool,
     pub show_result: bool,
     pub show_settings: bool,
+    // Session recap overlay (stats as of when it was opened)
+    pub session_recap: Option<SessionStats>,
 
     // Playback rate
     pub rate: f64,
@@ -101,6 +126,20 @@ pub struct MenuState {
     // On-demand difficulty cache (in RAM only!)
     pub difficulty_cache: DifficultyCache,
 
+    // Background difficulty jobs for the visible song wheel
+    pub difficulty_queue: DifficultyQueue,
+
+    // Thread running the calculations (started by the first task, shared
+    // by the clones), and the tasks it hasn't answered yet
+    difficulty_worker: Arc<OnceLock<DifficultyWorker>>,
+    pub pending_difficulty: HashMap<DifficultyTaskKey, PendingDifficulty>,
+
+    // Size/time bounds of a single difficulty calculation
+    pub analysis_limits: AnalysisLimits,
+
+    // Rates rated by the all-rates analysis
+    pub rate_range: RateRange,
+
     // Active difficulty calculator
     pub active_calculator: String,
 
@@ -113,9 +152,16 @@ pub struct MenuState {
     // Leaderboard
     pub leaderboard_scores: Vec<Replay>,
     pub leaderboard_hash: Option<String>,
+    pub leaderboard_filter: LeaderboardFilter,
 
     // Chart cache for gameplay - Arc for O(1) clones
-    pub chart_cache: Arc<Option<ChartCache>>,
+    pub chart_cache: Option<Arc<ChartCache>>,
+
+    // Recently selected charts, so switching back doesn't re-parse
+    pub recent_charts: ChartLruCache,
+
+    // Audio preview of the selected song
+    pub preview: SongPreview,
 
     // Database Status
     pub db_status: database::DbStatus,
@@ -140,25 +186,33 @@ impl MenuState {
             selected_index: 0,
             selected_difficulty_index: 0,
             visible_count: 10,
+            difficulty_filter: String::new(),
+            type_ahead: TypeAhead::new(),
             in_menu: true,
             in_editor: false,
             show_result: false,
             show_settings: false,
+            session_recap: None,
             rate: 1.0,
             last_result: None,
             should_close_result: false,
             rate_cache: Arc::new(HashMap::new()),
             failed_rate_hashes: HashSet::new(),
             difficulty_cache: DifficultyCache::new(),
+            difficulty_queue: DifficultyQueue::new(),
+            difficulty_worker: Arc::new(OnceLock::new()),
+            pending_difficulty: HashMap::new(),
+            analysis_limits: AnalysisLimits::default(),
+            rate_range: RateRange::default(),
             active_calculator: "etterna".to_string(),
-            available_calculators: vec![
-                CalculatorOption::new("etterna", "Etterna"),
-                CalculatorOption::new("osu", "osu!"),
-            ],
+            available_calculators: default_calculators(),
             search_filters: MenuSearchFilters::default(),
             leaderboard_scores: Vec::new(),
             leaderboard_hash: None,
-            chart_cache: Arc::new(None),
+            leaderboard_filter: LeaderboardFilter::default(),
+            chart_cache: None,
+            recent_charts: ChartLruCache::new(),
+            preview: SongPreview::new(),
             db_status: database::DbStatus::Idle,
             active_mods: ActiveMods::new(),
         }
@@ -166,7 +220,8 @@ impl MenuState {
 
     /// Loads the currently selected beatmap's chart into cache.
     ///
-    /// Returns `true` if a new chart was loaded, `false` if already cached.
+    /// Returns `true` if a new chart was loaded, `false` if already cached
+    /// (either current or recently viewed).
     pub fn ensure_chart_cache(&mut self) -> bool {
         let selected = match self.get_selected_beatmap() {
             Some(bm) => bm,
@@ -176,50 +231,80 @@ impl MenuState {
         let beatmap_hash = selected.beatmap.hash.clone();
         let beatmap_path = PathBuf::from(&selected.beatmap.path);
 
-        if let Some(ref cache) = *self.chart_cache {
-            if cache.beatmap_hash == beatmap_hash {
-                return false;
-            }
+        if let Some(ref cache) = self.chart_cache
+            && cache.beatmap_hash == beatmap_hash
+        {
+            return false;
+        }
+
+        if let Some(cache) = self.recent_charts.get(&beatmap_hash) {
+            self.chart_cache = Some(cache);
+            return false;
         }
 
-        match engine::load_map_safe(&beatmap_path) {
-            Some((audio_path, chart, key_count)) => {
+        match engine::load_map_full(&beatmap_path) {
+            Ok(map) => {
                 log::info!(
                     "MENU: Chart cached for {} ({} notes, {}K)",
                     beatmap_hash,
-                    chart.len(),
-                    key_count
+                    map.notes.len(),
+                    map.key_count
                 );
-                self.chart_cache = Arc::new(Some(ChartCache {
+                let cache = Arc::new(ChartCache {
                     beatmap_hash,
-                    chart,
-                    audio_path,
+                    chart: map.notes,
+                    audio_path: map.audio_path,
                     map_path: beatmap_path,
-                    key_count,
-                }));
+                    key_count: map.key_count,
+                    metadata: map.metadata,
+                });
+                self.recent_charts.insert(Arc::clone(&cache));
+                self.chart_cache = Some(cache);
                 true
             }
-            None => {
-                log::error!("MENU: Failed to load chart for caching");
-                self.chart_cache = Arc::new(None);
+            Err(e) => {
+                log::error!("MENU: Failed to load chart for caching: {}", e);
+                self.chart_cache = None;
                 false
             }
         }
     }
 
     pub fn get_cached_chart(&self) -> Option<&ChartCache> {
-        (*self.chart_cache).as_ref()
+        self.chart_cache.as_deref()
+    }
+
+    /// Audio preview of the cached chart, starting at its preview offset.
+    pub fn selected_preview_track(&self) -> Option<PreviewTrack> {
+        self.get_cached_chart().map(|cache| PreviewTrack {
+            audio_path: cache.audio_path.clone(),
+            start_us: cache.metadata.preview_time_us,
+        })
     }
 
     pub fn get_cached_chart_note_count(&self) -> usize {
-        (*self.chart_cache)
+        self.chart_cache
             .as_ref()
             .map(|c| c.chart.len())
             .unwrap_or(0)
     }
 
-    /// Calculates difficulty for the currently selected beatmap on-demand.
-    /// Results are cached in memory (not DB).
+    /// Judgeable notes (mines excluded) of the cached chart, if it is `beatmap_hash`.
+    pub fn cached_judgeable_notes(&self, beatmap_hash: &str) -> Option<usize> {
+        self.chart_cache
+            .as_ref()
+            .filter(|cache| cache.beatmap_hash == beatmap_hash)
+            .map(|cache| cache.chart.iter().filter(|note| !note.is_mine()).count())
+    }
+
+    /// Drops the current chart and all recently viewed charts.
+    pub fn clear_chart_cache(&mut self) {
+        self.chart_cache = None;
+        self.recent_charts.clear();
+    }
+
+    /// Difficulty of the currently selected beatmap, requesting it from the
+    /// worker if it isn't cached yet. Results are cached in memory (not DB).
     pub fn ensure_difficulty_calculated(&mut self) -> Option<BeatmapSsr> {
         let selected = self.get_selected_beatmap()?;
         let beatmap_hash = selected.beatmap.hash.clone();
@@ -231,29 +316,298 @@ impl MenuState {
         if let Some(cached) = self.difficulty_cache.get(&beatmap_hash, &calculator, rate) {
             return Some(cached.clone());
         }
+        if self.difficulty_cache.is_too_large(&beatmap_hash) {
+            return None;
+        }
 
-        // Load any format via ROX -> encode to .osu -> parse with rosu_map
-        let map = match chart::load_as_rosu_beatmap(std::path::Path::new(&beatmap_path)) {
-            Ok(map) => map,
-            Err(err) => {
-                log::error!("MENU: Failed to load beatmap for difficulty calc: {}", err);
-                return None;
+        self.request_difficulty(
+            beatmap_hash,
+            beatmap_path,
+            DifficultyRequest::Rating { calculator, rate },
+            false,
+        );
+        None
+    }
+
+    /// Hands a calculation to the worker, unless it is already pending.
+    fn request_difficulty(
+        &mut self,
+        beatmap_hash: String,
+        beatmap_path: String,
+        request: DifficultyRequest,
+        background: bool,
+    ) {
+        let task = DifficultyTask {
+            beatmap_hash,
+            beatmap_path,
+            limits: self.analysis_limits,
+            request,
+        };
+        let key = task.key();
+        if self.pending_difficulty.contains_key(&key) {
+            return;
+        }
+        let worker = self.difficulty_worker.get_or_init(DifficultyWorker::spawn);
+        if let Some(id) = worker.submit(task) {
+            self.pending_difficulty
+                .insert(key, PendingDifficulty { id, background });
+        }
+    }
+
+    /// Cancels the pending calculations (only the song wheel ones if
+    /// `background_only`). Results already on their way are dropped.
+    pub fn cancel_pending_difficulty(&mut self, background_only: bool) {
+        let worker = self.difficulty_worker.get();
+        self.pending_difficulty.retain(|_, pending| {
+            let cancel = pending.background || !background_only;
+            if cancel && let Some(worker) = worker {
+                worker.cancel(pending.id);
             }
+            !cancel
+        });
+    }
+
+    /// Task the worker is running, and for how long.
+    pub fn running_difficulty(&self) -> Option<(u64, Duration)> {
+        self.difficulty_worker
+            .get()
+            .and_then(DifficultyWorker::running)
+    }
+
+    /// Gives up on the calculation the worker is running if it has taken
+    /// longer than the soft timeout: the chart is flagged as timed out and
+    /// not requested again this session.
+    ///
+    /// `running` is [`DifficultyWorker::running`].
+    pub fn expire_difficulty_timeout(&mut self, running: Option<(u64, Duration)>) {
+        let timeout = self.analysis_limits.soft_timeout;
+        let Some((id, elapsed)) = running.filter(|(_, elapsed)| *elapsed >= timeout) else {
+            return;
         };
+        let Some(key) = self
+            .pending_difficulty
+            .iter()
+            .find(|(_, pending)| pending.id == id)
+            .map(|(key, _)| key.clone())
+        else {
+            return;
+        };
+        self.pending_difficulty.remove(&key);
+
+        let beatmap_hash = key.beatmap_hash();
+        log::warn!(
+            "MENU: Difficulty of {} still running after {:.1}s, giving up",
+            beatmap_hash,
+            elapsed.as_secs_f64()
+        );
+        self.record_calc_result::<()>(beatmap_hash, &Err(CalcError::TimedOut(timeout)));
+        match &key {
+            DifficultyTaskKey::Rating(_) => self.difficulty_queue.mark_failed(beatmap_hash),
+            DifficultyTaskKey::AllRates(_) => {
+                self.failed_rate_hashes.insert(beatmap_hash.to_string());
+            }
+        }
+    }
+
+    /// Applies the results the worker finished since the last tick.
+    ///
+    /// Returns the 1.0x ratings among them, to be saved to the database.
+    pub fn poll_difficulty_results(&mut self) -> Vec<SaveRatingCommand> {
+        self.difficulty_worker
+            .get()
+            .map(DifficultyWorker::poll)
+            .unwrap_or_default()
+            .into_iter()
+            .flat_map(|result| self.apply_difficulty_result(result))
+            .collect()
+    }
+
+    /// Stores a finished calculation in the caches, returning its 1.0x
+    /// ratings (what the database keeps for filters, sorting and cards).
+    ///
+    /// Results of cancelled or timed-out tasks are stale and dropped.
+    pub fn apply_difficulty_result(&mut self, result: DifficultyResult) -> Vec<SaveRatingCommand> {
+        let DifficultyResult { id, task, output } = result;
+        let key = task.key();
+        if self.pending_difficulty.get(&key).map(|pending| pending.id) != Some(id) {
+            log::debug!("MENU: Dropping stale difficulty of {}", task.beatmap_hash);
+            return Vec::new();
+        }
+        self.pending_difficulty.remove(&key);
+        let beatmap_hash = task.beatmap_hash.as_str();
+        let mut to_save = Vec::new();
+
+        match (task.request, output) {
+            (DifficultyRequest::Rating { calculator, rate }, DifficultyOutput::Rating(result)) => {
+                self.record_calc_result(beatmap_hash, &result);
+                match result {
+                    Ok(ssr) => {
+                        if rate == 1.0 {
+                            to_save.push(SaveRatingCommand {
+                                beatmap_hash: beatmap_hash.to_string(),
+                                calculator_name: calculator.clone(),
+                                ssr: ssr.clone(),
+                            });
+                        }
+                        self.difficulty_cache
+                            .insert(beatmap_hash, &calculator, rate, ssr);
+                    }
+                    // Not queued again this session
+                    Err(_) => self.difficulty_queue.mark_failed(beatmap_hash),
+                }
+            }
+            (DifficultyRequest::AllRates { .. }, DifficultyOutput::AllRates(result)) => {
+                self.record_calc_result(beatmap_hash, &result);
+                match result {
+                    Ok(rate_data) => {
+                        if let Some((_, values)) = rate_data
+                            .ratings_by_rate
+                            .iter()
+                            .find(|(rate, _)| *rate == 1.0)
+                        {
+                            to_save.extend(values.iter().map(|value| SaveRatingCommand {
+                                beatmap_hash: beatmap_hash.to_string(),
+                                calculator_name: value.name.clone(),
+                                ssr: value.ssr.clone(),
+                            }));
+                        }
+                        let entry = RateCacheEntry::from_analysis(beatmap_hash, rate_data);
+                        Arc::make_mut(&mut self.rate_cache).insert(task.beatmap_hash, entry);
+                    }
+                    // Not retried this session (7K maps, broken charts, ...)
+                    Err(_) => {
+                        self.failed_rate_hashes.insert(task.beatmap_hash);
+                    }
+                }
+            }
+            (request, output) => {
+                log::error!(
+                    "MENU: Mismatched difficulty result {:?} / {:?}",
+                    request,
+                    output
+                );
+            }
+        }
+        to_save
+    }
 
-        match chart::calculate_on_demand(&map, &calculator, rate) {
-            Ok(ssr) => {
-                self.difficulty_cache
-                    .insert(&beatmap_hash, &calculator, rate, ssr.clone());
-                Some(ssr)
+    /// Records the outcome of a difficulty calculation for a beatmap.
+    ///
+    /// Failures are kept so the UI can flag the chart instead of showing
+    /// blank ratings; a later success clears them. Charts skipped for their
+    /// size are flagged separately.
+    pub fn record_calc_result<T>(&mut self, beatmap_hash: &str, result: &Result<T, CalcError>) {
+        match result {
+            Ok(_) => self.difficulty_cache.clear_error(beatmap_hash),
+            Err(err @ CalcError::TooLarge { .. }) => {
+                log::info!("MENU: Skipping difficulty of {}: {}", beatmap_hash, err);
+                self.difficulty_cache.mark_too_large(beatmap_hash);
             }
             Err(err) => {
-                log::error!("MENU: Failed to calculate difficulty: {}", err);
-                None
+                log::warn!("MENU: Difficulty calc failed for {}: {}", beatmap_hash, err);
+                self.difficulty_cache.record_error(beatmap_hash, err);
             }
         }
     }
 
+    /// Rebuilds the background difficulty queue when the visible window,
+    /// selection, calculator or rate changed.
+    pub fn refresh_difficulty_queue(&mut self) {
+        let end = self.end_index.min(self.beatmapsets.len());
+        let window = self.start_index.min(end)..end;
+        let selected = self.selected_index;
+        let calculator = self.active_calculator.clone();
+        let rate = self.rate;
+
+        if self
+            .difficulty_queue
+            .is_current(&window, selected, &calculator, rate)
+        {
+            return;
+        }
+
+        // Jobs of the previous window are no longer wanted
+        self.cancel_pending_difficulty(true);
+
+        let beatmapsets = Arc::clone(&self.beatmapsets);
+        let cache = &self.difficulty_cache;
+        self.difficulty_queue
+            .enqueue_window(window, selected, &calculator, rate, |index| {
+                beatmapsets[index]
+                    .1
+                    .iter()
+                    .filter(|bm| !cache.contains(&bm.beatmap.hash, &calculator, rate))
+                    .map(|bm| DifficultyJob {
+                        beatmap_hash: bm.beatmap.hash.clone(),
+                        beatmap_path: bm.beatmap.path.clone(),
+                    })
+                    .collect()
+            });
+    }
+
+    /// Hands queued jobs to the worker while fewer than `in_flight` tasks
+    /// are pending, so the selection never waits behind a long queue.
+    pub fn process_difficulty_queue(&mut self, in_flight: usize) {
+        let calculator = self.active_calculator.clone();
+        let rate = self.rate;
+
+        while self.pending_difficulty.len() < in_flight {
+            let Some(job) = self.difficulty_queue.pop() else {
+                break;
+            };
+            if self
+                .difficulty_cache
+                .contains(&job.beatmap_hash, &calculator, rate)
+            {
+                continue;
+            }
+            self.request_difficulty(
+                job.beatmap_hash,
+                job.beatmap_path,
+                DifficultyRequest::Rating {
+                    calculator: calculator.clone(),
+                    rate,
+                },
+                true,
+            );
+        }
+    }
+
+    /// Applies new analysis bounds, giving previously skipped charts another try.
+    pub fn set_analysis_limits(&mut self, limits: AnalysisLimits) {
+        if self.analysis_limits == limits {
+            return;
+        }
+        self.analysis_limits = limits;
+        self.cancel_pending_difficulty(false);
+        self.difficulty_cache.clear_too_large();
+        self.difficulty_queue.clear();
+        self.failed_rate_hashes.clear();
+    }
+
+    /// Applies a new rate range, re-rating charts on their next selection.
+    pub fn set_rate_range(&mut self, range: RateRange) {
+        if self.rate_range == range {
+            return;
+        }
+        self.rate_range = range;
+        self.cancel_pending_difficulty(false);
+        self.rate_cache = Arc::new(HashMap::new());
+        self.failed_rate_hashes.clear();
+    }
+
+    /// Difficulty calc error of the selected beatmap, if its last calc failed.
+    pub fn selected_calc_error(&self) -> Option<&str> {
+        let selected = self.get_selected_beatmap()?;
+        self.difficulty_cache.error(&selected.beatmap.hash)
+    }
+
+    /// Whether the selected beatmap was skipped for being over the note limit.
+    pub fn is_selected_too_large(&self) -> bool {
+        self.get_selected_beatmap()
+            .is_some_and(|bm| self.difficulty_cache.is_too_large(&bm.beatmap.hash))
+    }
+
     /// Gets the cached difficulty for the selected beatmap at the current rate.
     pub fn get_current_difficulty(&self) -> Option<&BeatmapSsr> {
         let selected = self.get_selected_beatmap()?;
@@ -261,6 +615,14 @@ impl MenuState {
             .get(&selected.beatmap.hash, &self.active_calculator, self.rate)
     }
 
+    /// Rating scale of the active calculator, if known.
+    pub fn active_calculator_scale(&self) -> Option<&RatingScale> {
+        self.available_calculators
+            .iter()
+            .find(|calc| calc.id == self.active_calculator)
+            .and_then(|calc| calc.scale.as_ref())
+    }
+
     pub fn increase_rate(&mut self) {
         let next_rate = {
             let current = self.rate;
@@ -291,15 +653,15 @@ impl MenuState {
         let _ = self.ensure_selected_rate_entry();
     }
 
+    /// Ratings of a beatmap at `rate`, interpolated between analyzed rates.
     pub fn get_cached_ratings_for(
         &self,
         beatmap_hash: &str,
         rate: f64,
-    ) -> Option<&[BeatmapRating]> {
+    ) -> Option<Cow<'_, [BeatmapRating]>> {
         self.rate_cache
             .get(beatmap_hash)
             .and_then(|entry| entry.get_ratings(rate))
-            .map(|list| list.as_slice())
     }
 
     fn ensure_selected_rate_entry(&mut self) -> Option<&RateCacheEntry> {
@@ -313,36 +675,19 @@ impl MenuState {
         }
 
         if !self.rate_cache.contains_key(&beatmap_hash) {
-            let map = match chart::load_as_rosu_beatmap(std::path::Path::new(&beatmap_path)) {
-                Ok(map) => map,
-                Err(err) => {
-                    log::debug!(
-                        "MENU: Failed to load beatmap {} to compute rates: {}",
-                        beatmap_hash,
-                        err
-                    );
-                    self.failed_rate_hashes.insert(beatmap_hash);
-                    return None;
-                }
-            };
-
-            match chart::analyze_all_rates(&map) {
-                Ok(rate_data) => {
-                    let entry = RateCacheEntry::from_analysis(&beatmap_hash, rate_data);
-                    let adjusted_rate = entry.closest_rate(self.rate);
-                    if let Some(rate) = adjusted_rate {
-                        self.rate = rate;
-                    }
-                    Arc::make_mut(&mut self.rate_cache).insert(beatmap_hash.clone(), entry);
-                }
-                Err(_) => {
-                    // Silently skip unsupported maps (7K, etc.)
-                    self.failed_rate_hashes.insert(beatmap_hash);
-                    return None;
-                }
-            }
-        } else if let Some(entry) = self.rate_cache.get(&beatmap_hash)
-            && !entry.contains_rate(self.rate)
+            // Available once the worker is done with it
+            let range = self.rate_range;
+            self.request_difficulty(
+                beatmap_hash,
+                beatmap_path,
+                DifficultyRequest::AllRates { range },
+                false,
+            );
+            return None;
+        }
+        // Rates between analyzed points are interpolated, only clamp to the range
+        if let Some(entry) = self.rate_cache.get(&beatmap_hash)
+            && !entry.covers_rate(self.rate)
             && let Some(rate) = entry.closest_rate(self.rate)
         {
             self.rate = rate;
@@ -369,12 +714,14 @@ impl MenuState {
             state.start_index = 0;
             Arc::make_mut(&mut state.rate_cache).clear();
             state.failed_rate_hashes.clear();
+            state.cancel_pending_difficulty(false);
             state.difficulty_cache.clear();
+            state.difficulty_queue.clear();
             state.rate = 1.0;
             state.search_filters = MenuSearchFilters::default();
             state.leaderboard_scores.clear();
             state.leaderboard_hash = None;
-            state.chart_cache = Arc::new(None);
+            state.clear_chart_cache();
         }
         Ok(())
     }
@@ -410,22 +757,11 @@ impl MenuState {
             .enumerate()
             .filter(|(_, (set, maps))| {
                 // Filter by search query
-                if !search_query.is_empty() {
-                    let matches_title = set
-                        .title
-                        .as_deref()
-                        .map(|s| s.to_lowercase().contains(&search_query))
-                        .unwrap_or(false);
-
-                    let matches_artist = set
-                        .artist
-                        .as_deref()
-                        .map(|s| s.to_lowercase().contains(&search_query))
-                        .unwrap_or(false);
-
-                    if !(matches_title || matches_artist) {
-                        return false;
-                    }
+                if !search_query.is_empty()
+                    && !matches_query(set.title.as_deref(), &search_query)
+                    && !matches_query(set.artist.as_deref(), &search_query)
+                {
+                    return false;
                 }
 
                 // Filter by active modes (key count)
@@ -510,10 +846,78 @@ impl MenuState {
         }
     }
 
+    /// Feeds a typed character and jumps to the next set whose title starts
+    /// with the typed prefix, wrapping around the filtered list.
+    ///
+    /// A single character moves past the current set so repeated presses
+    /// cycle through the matches. Returns whether the selection moved.
+    pub fn type_ahead(&mut self, c: char, now: Instant) -> bool {
+        let prefix = self.type_ahead.push(c, now).to_string();
+        if self.filtered_indices.is_empty() {
+            return false;
+        }
+
+        let current_pos = self
+            .filtered_indices
+            .iter()
+            .position(|&idx| idx == self.selected_index)
+            .unwrap_or(0);
+        let skip_current = prefix.chars().count() == 1;
+        let len = self.filtered_indices.len();
+        let found = (0..len)
+            .map(|offset| self.filtered_indices[(current_pos + offset) % len])
+            .filter(|&idx| !(skip_current && idx == self.selected_index))
+            .find(|&idx| {
+                self.beatmapsets[idx]
+                    .0
+                    .title
+                    .as_deref()
+                    .is_some_and(|title| title.to_lowercase().starts_with(&prefix))
+            });
+
+        match found {
+            Some(idx) if idx != self.selected_index => {
+                self.jump_to(idx);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Selects a uniformly random beatmapset among the filtered ones.
+    ///
+    /// Returns false when the filters leave nothing to pick from.
+    pub fn random_select(&mut self, rng: &mut impl Rng) -> bool {
+        if self.filtered_indices.is_empty() {
+            return false;
+        }
+        let idx = self.filtered_indices[rng.random_range(0..self.filtered_indices.len())];
+        self.jump_to(idx);
+        true
+    }
+
+    /// Selects a beatmapset and scrolls the visible window to it.
+    fn jump_to(&mut self, idx: usize) {
+        self.selected_index = idx;
+        self.selected_difficulty_index = 0;
+        if idx < self.start_index {
+            self.start_index = idx;
+            self.end_index = (self.start_index + self.visible_count).min(self.beatmapsets.len());
+        } else if idx >= self.end_index {
+            self.end_index = (idx + 1).min(self.beatmapsets.len());
+            self.start_index = self.end_index.saturating_sub(self.visible_count);
+        }
+    }
+
     pub fn get_selected_beatmapset(&self) -> Option<&(Beatmapset, Vec<BeatmapWithRatings>)> {
         self.beatmapsets.get(self.selected_index)
     }
 
+    /// Id of the selected beatmapset.
+    pub fn selected_set_id(&self) -> Option<i64> {
+        self.get_selected_beatmapset().map(|(set, _)| set.id)
+    }
+
     fn get_selected_beatmap(&self) -> Option<&BeatmapWithRatings> {
         self.get_selected_beatmapset().and_then(|(_, beatmaps)| {
             let idx = self
@@ -551,6 +955,36 @@ impl MenuState {
         }
     }
 
+    /// Indices of the selected set's difficulties matching the difficulty filter.
+    ///
+    /// Empty when no filter is typed or nothing matches.
+    pub fn matching_difficulties(&self) -> Vec<usize> {
+        self.get_selected_beatmapset()
+            .map(|(_, beatmaps)| filter_difficulties(beatmaps, &self.difficulty_filter))
+            .unwrap_or_default()
+    }
+
+    /// Sets the difficulty filter and jumps to the first matching difficulty.
+    ///
+    /// The selection is kept if it already matches or if nothing matches.
+    pub fn set_difficulty_filter(&mut self, query: &str) {
+        self.difficulty_filter = query.to_string();
+        let matches = self.matching_difficulties();
+        if let Some(&first) = matches.first()
+            && !matches.contains(&self.selected_difficulty_index)
+        {
+            self.selected_difficulty_index = first;
+        }
+    }
+
+    /// Clears the difficulty filter if another set was selected since
+    /// `previous_set`: it was typed for that set's difficulties.
+    pub fn reset_difficulty_filter_on_set_change(&mut self, previous_set: Option<i64>) {
+        if self.selected_set_id() != previous_set {
+            self.difficulty_filter.clear();
+        }
+    }
+
     pub fn get_selected_difficulty_name(&self) -> Option<String> {
         self.get_selected_beatmap()
             .and_then(|bm| bm.beatmap.difficulty_name.clone())
@@ -580,3 +1014,330 @@ impl MenuState {
         vec![("etterna", "Etterna (MinaCalc)"), ("osu", "osu! (rosu-pp)")]
     }
 }
+
+/// Case-insensitive substring match; `query` must already be lowercase.
+fn matches_query(text: Option<&str>, query: &str) -> bool {
+    text.is_some_and(|text| text.to_lowercase().contains(query))
+}
+
+/// Indices of the difficulties whose name contains `query` (case-insensitive).
+pub fn filter_difficulties(beatmaps: &[BeatmapWithRatings], query: &str) -> Vec<usize> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return Vec::new();
+    }
+
+    beatmaps
+        .iter()
+        .enumerate()
+        .filter(|(_, bm)| matches_query(bm.beatmap.difficulty_name.as_deref(), &query))
+        .map(|(i, _)| i)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use database::models::Beatmap;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    fn beatmap(name: &str) -> BeatmapWithRatings {
+        BeatmapWithRatings::new(
+            Beatmap {
+                hash: format!("hash-{name}"),
+                beatmapset_id: 1,
+                path: format!("{name}.osu"),
+                difficulty_name: Some(name.to_string()),
+                note_count: 100,
+                duration_ms: 60_000,
+                nps: 5.0,
+                peak_nps: 8.0,
+                bpm: 180.0,
+                key_count: 4,
+            },
+            Vec::new(),
+        )
+    }
+
+    fn beatmapset(id: i64, title: &str, names: &[&str]) -> (Beatmapset, Vec<BeatmapWithRatings>) {
+        let set = Beatmapset {
+            id,
+            path: format!("set{id}"),
+            image_path: None,
+            artist: Some("Artist".to_string()),
+            title: Some(title.to_string()),
+        };
+        (set, names.iter().map(|n| beatmap(n)).collect())
+    }
+
+    fn menu_with_sets(sets: Vec<(Beatmapset, Vec<BeatmapWithRatings>)>) -> MenuState {
+        let mut menu = MenuState::new();
+        menu.active_modes.clear();
+        menu.end_index = sets.len().min(menu.visible_count);
+        menu.beatmapsets = Arc::new(sets);
+        menu.update_filtered_indices();
+        menu
+    }
+
+    fn menu_with_set(names: &[&str]) -> MenuState {
+        menu_with_sets(vec![beatmapset(1, "Title", names)])
+    }
+
+    #[test]
+    fn test_filter_difficulties_by_substring() {
+        let names = ["Easy", "Normal", "Hard", "Insane", "Hard (Alt)"];
+        let maps: Vec<_> = names.iter().map(|n| beatmap(n)).collect();
+        assert_eq!(filter_difficulties(&maps, "hard"), vec![2, 4]);
+        assert_eq!(filter_difficulties(&maps, "  SANE "), vec![3]);
+        assert!(filter_difficulties(&maps, "expert").is_empty());
+        assert!(filter_difficulties(&maps, "").is_empty());
+
+        let mut menu = menu_with_set(&names);
+        menu.set_difficulty_filter("hard");
+        assert_eq!(menu.matching_difficulties(), vec![2, 4]);
+        assert_eq!(menu.selected_difficulty_index, 2);
+
+        // No match keeps the current difficulty
+        menu.set_difficulty_filter("expert");
+        assert_eq!(menu.selected_difficulty_index, 2);
+    }
+
+    #[test]
+    fn test_difficulty_filter_resets_with_the_set() {
+        let mut menu = menu_with_sets(vec![
+            beatmapset(1, "First", &["Easy", "Hard"]),
+            beatmapset(2, "Second", &["Easy", "Hard"]),
+        ]);
+        menu.set_difficulty_filter("hard");
+
+        let previous = menu.selected_set_id();
+        menu.reset_difficulty_filter_on_set_change(previous);
+        assert_eq!(menu.difficulty_filter, "hard");
+
+        menu.move_down();
+        menu.reset_difficulty_filter_on_set_change(previous);
+        assert!(menu.difficulty_filter.is_empty());
+        assert!(menu.matching_difficulties().is_empty());
+    }
+
+    #[test]
+    fn test_worker_starts_with_the_first_calculation() {
+        let mut menu = MenuState::new();
+        assert!(menu.difficulty_worker.get().is_none());
+        assert!(menu.poll_difficulty_results().is_empty());
+        menu.cancel_pending_difficulty(false);
+        assert!(menu.difficulty_worker.get().is_none());
+
+        menu.request_difficulty(
+            "hash".to_string(),
+            "missing/chart.osu".to_string(),
+            DifficultyRequest::Rating {
+                calculator: "etterna".to_string(),
+                rate: 1.0,
+            },
+            false,
+        );
+        assert!(menu.difficulty_worker.get().is_some());
+        assert!(menu.clone().difficulty_worker.get().is_some());
+    }
+
+    #[test]
+    fn test_type_ahead_jumps_to_matching_title() {
+        let titles = ["Airman", "Blue Zenith", "Bad Apple", "Big Black", "Dive"];
+        let mut menu = menu_with_sets(
+            titles
+                .iter()
+                .enumerate()
+                .map(|(i, title)| beatmapset(i as i64, title, &["Hard"]))
+                .collect(),
+        );
+        let start = Instant::now();
+        let ms = |n| start + std::time::Duration::from_millis(n);
+
+        assert!(menu.type_ahead('b', ms(0)));
+        assert_eq!(menu.selected_index, 1);
+        // Refining the prefix keeps the first match at or after the selection
+        assert!(menu.type_ahead('i', ms(100)));
+        assert_eq!(menu.selected_index, 3);
+
+        // After the timeout the same letter cycles to the next match
+        assert!(menu.type_ahead('b', ms(5_000)));
+        assert_eq!(menu.selected_index, 1);
+        assert!(menu.type_ahead('b', ms(10_000)));
+        assert_eq!(menu.selected_index, 2);
+
+        // No match leaves the selection alone
+        assert!(!menu.type_ahead('z', ms(15_000)));
+        assert_eq!(menu.selected_index, 2);
+
+        // Arrow navigation still works from the jumped-to set
+        menu.move_down();
+        assert_eq!(menu.selected_index, 3);
+    }
+
+    #[test]
+    fn test_random_select_respects_filters() {
+        let sets = (0..20)
+            .map(|i| {
+                let title = if i % 3 == 0 { "Kept" } else { "Hidden" };
+                beatmapset(i, title, &["Hard"])
+            })
+            .collect();
+        let mut menu = menu_with_sets(sets);
+        menu.search_filters.query = "kept".to_string();
+        menu.update_filtered_indices();
+        assert_eq!(menu.filtered_indices.len(), 7);
+
+        let mut rng = StdRng::seed_from_u64(42);
+        for _ in 0..200 {
+            assert!(menu.random_select(&mut rng));
+            assert!(menu.selected_index < menu.beatmapsets.len());
+            assert!(menu.filtered_indices.contains(&menu.selected_index));
+        }
+
+        menu.search_filters.query = "nothing".to_string();
+        menu.update_filtered_indices();
+        assert!(!menu.random_select(&mut rng));
+    }
+
+    #[test]
+    fn test_calc_errors_are_recorded_and_cleared() {
+        let mut menu = MenuState::new();
+
+        // Unreadable chart: the calc errors out and the error is kept
+        let task = DifficultyTask {
+            beatmap_hash: "hash-broken".to_string(),
+            beatmap_path: "missing/broken.osu".to_string(),
+            limits: AnalysisLimits::default(),
+            request: DifficultyRequest::Rating {
+                calculator: "etterna".to_string(),
+                rate: 1.0,
+            },
+        };
+        let pending = PendingDifficulty {
+            id: 0,
+            background: false,
+        };
+        menu.pending_difficulty.insert(task.key(), pending);
+        let output = task.run();
+        menu.apply_difficulty_result(DifficultyResult {
+            id: 0,
+            task,
+            output,
+        });
+        assert!(
+            !menu
+                .difficulty_cache
+                .contains("hash-broken", "etterna", 1.0)
+        );
+        assert!(menu.difficulty_cache.error("hash-broken").is_some());
+
+        // A later success clears it
+        menu.record_calc_result("hash-broken", &Ok::<(), CalcError>(()));
+        assert_eq!(menu.difficulty_cache.error("hash-broken"), None);
+
+        // Oversized charts are flagged, not reported as failures
+        let too_large = Err::<(), _>(CalcError::TooLarge {
+            notes: 50_000,
+            limit: 20_000,
+        });
+        menu.record_calc_result("hash-huge", &too_large);
+        assert!(menu.difficulty_cache.is_too_large("hash-huge"));
+        assert_eq!(menu.difficulty_cache.error("hash-huge"), None);
+    }
+
+    #[test]
+    fn test_calc_errors_are_kept_per_beatmap() {
+        let mut menu = MenuState::new();
+        let failed =
+            |message: &str| Err::<(), _>(CalcError::CalculationFailed(message.to_string()));
+
+        menu.record_calc_result("hash-a", &failed("first"));
+        menu.record_calc_result("hash-b", &failed("first"));
+        let first_error = menu.difficulty_cache.error("hash-a").map(str::to_string);
+
+        // A success only clears the error of its own beatmap
+        menu.record_calc_result("hash-b", &Ok::<(), CalcError>(()));
+        assert_eq!(menu.difficulty_cache.error("hash-b"), None);
+        assert_eq!(
+            menu.difficulty_cache.error("hash-a").map(str::to_string),
+            first_error
+        );
+
+        // A new failure replaces the previous message
+        menu.record_calc_result("hash-a", &failed("second"));
+        assert_ne!(
+            menu.difficulty_cache.error("hash-a").map(str::to_string),
+            first_error
+        );
+
+        // Clearing the cache forgets the errors too
+        menu.difficulty_cache.clear();
+        assert_eq!(menu.difficulty_cache.error("hash-a"), None);
+    }
+
+    fn rating_task(hash: &str) -> DifficultyTask {
+        DifficultyTask {
+            beatmap_hash: hash.to_string(),
+            beatmap_path: format!("{hash}.osu"),
+            limits: AnalysisLimits::default(),
+            request: DifficultyRequest::Rating {
+                calculator: "etterna".to_string(),
+                rate: 1.0,
+            },
+        }
+    }
+
+    #[test]
+    fn test_stale_and_timed_out_difficulty_results_are_dropped() {
+        let mut menu = MenuState::new();
+        let ssr = || DifficultyOutput::Rating(Ok(BeatmapSsr::default()));
+
+        // A result for a task that was cancelled (or resubmitted since)
+        let task = rating_task("hash-stale");
+        let pending = PendingDifficulty {
+            id: 7,
+            background: true,
+        };
+        menu.pending_difficulty.insert(task.key(), pending);
+        menu.apply_difficulty_result(DifficultyResult {
+            id: 6,
+            task: task.clone(),
+            output: ssr(),
+        });
+        assert!(!menu.difficulty_cache.contains("hash-stale", "etterna", 1.0));
+        let saved = menu.apply_difficulty_result(DifficultyResult {
+            id: 7,
+            task,
+            output: ssr(),
+        });
+        assert!(menu.difficulty_cache.contains("hash-stale", "etterna", 1.0));
+        // 1.0x ratings go to the database
+        assert_eq!(saved.len(), 1);
+        assert_eq!(saved[0].beatmap_hash, "hash-stale");
+        assert_eq!(saved[0].calculator_name, "etterna");
+        assert!(menu.pending_difficulty.is_empty());
+
+        // Running past the soft timeout: flagged, and the late result dropped
+        let task = rating_task("hash-slow");
+        let pending = PendingDifficulty {
+            id: 8,
+            background: false,
+        };
+        menu.pending_difficulty.insert(task.key(), pending);
+        let timeout = menu.analysis_limits.soft_timeout;
+        menu.expire_difficulty_timeout(Some((8, timeout / 2)));
+        assert_eq!(menu.pending_difficulty.len(), 1);
+        menu.expire_difficulty_timeout(Some((8, timeout)));
+        assert!(menu.pending_difficulty.is_empty());
+        assert!(menu.difficulty_cache.error("hash-slow").is_some());
+
+        menu.apply_difficulty_result(DifficultyResult {
+            id: 8,
+            task,
+            output: ssr(),
+        });
+        assert!(!menu.difficulty_cache.contains("hash-slow", "etterna", 1.0));
+    }
+}
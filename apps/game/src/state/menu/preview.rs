@@ -0,0 +1,149 @@
+//! Song preview played while browsing the song wheel.
+//!
+//! Scrolling through the wheel would restart the audio on every step, so a
+//! preview only starts once the selection has stayed the same for
+//! [`PREVIEW_SETTLE_DELAY`]. Moving away stops it right away.
+
+use crate::audio_sys::AudioManager;
+use engine::US_PER_SECOND;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// How long the selection must stay put before its preview starts.
+pub const PREVIEW_SETTLE_DELAY: Duration = Duration::from_millis(350);
+
+/// Preview loudness, as a fraction of the master volume.
+pub const PREVIEW_VOLUME: f32 = 0.6;
+
+/// Audio file to preview and where to start it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PreviewTrack {
+    pub audio_path: PathBuf,
+    /// Preview offset of the map (in µs).
+    pub start_us: i64,
+}
+
+/// What the audio thread should do with the preview.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PreviewCommand {
+    Play(PreviewTrack),
+    Stop,
+}
+
+impl PreviewCommand {
+    /// Forwards the command to the audio thread.
+    pub fn send(&self, audio: &mut AudioManager, master_volume: f32) {
+        match self {
+            PreviewCommand::Play(track) => {
+                audio.load_music(&track.audio_path);
+                audio.set_speed(1.0);
+                audio.set_volume(master_volume * PREVIEW_VOLUME);
+                audio.seek((track.start_us as f64 / US_PER_SECOND as f64) as f32);
+                audio.play();
+            }
+            PreviewCommand::Stop => audio.stop(),
+        }
+    }
+}
+
+/// Debounces the selection into preview start/stop commands.
+#[derive(Clone, Debug, Default)]
+pub struct SongPreview {
+    /// Selected track waiting to settle, and since when.
+    pending: Option<(PreviewTrack, Instant)>,
+    /// Track currently playing.
+    playing: Option<PreviewTrack>,
+}
+
+impl SongPreview {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feeds the current selection, returning a command when one is due.
+    ///
+    /// A new selection stops the playing preview at once; it starts playing
+    /// itself once it has been selected for [`PREVIEW_SETTLE_DELAY`].
+    pub fn update(
+        &mut self,
+        selected: Option<PreviewTrack>,
+        now: Instant,
+    ) -> Option<PreviewCommand> {
+        if selected == self.playing {
+            self.pending = None;
+            return None;
+        }
+        let stop = self.playing.take().map(|_| PreviewCommand::Stop);
+
+        let Some(track) = selected else {
+            self.pending = None;
+            return stop;
+        };
+        match &self.pending {
+            Some((pending, since)) if *pending == track => {
+                if now.saturating_duration_since(*since) >= PREVIEW_SETTLE_DELAY {
+                    self.pending = None;
+                    self.playing = Some(track.clone());
+                    return Some(PreviewCommand::Play(track));
+                }
+            }
+            _ => self.pending = Some((track, now)),
+        }
+        stop
+    }
+
+    /// Forgets the preview state (e.g. when leaving song select, whose next
+    /// screen takes over the audio).
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track(name: &str, start_us: i64) -> Option<PreviewTrack> {
+        Some(PreviewTrack {
+            audio_path: PathBuf::from(name),
+            start_us,
+        })
+    }
+
+    #[test]
+    fn test_preview_waits_for_selection_to_settle() {
+        let start = Instant::now();
+        let ms = |ms: u64| start + Duration::from_millis(ms);
+        let mut preview = SongPreview::new();
+
+        // Nothing until the selection has been stable long enough
+        assert_eq!(preview.update(track("a.mp3", 1_000), ms(0)), None);
+        assert_eq!(preview.update(track("a.mp3", 1_000), ms(100)), None);
+        assert_eq!(
+            preview.update(track("a.mp3", 1_000), start + PREVIEW_SETTLE_DELAY),
+            Some(PreviewCommand::Play(track("a.mp3", 1_000).unwrap()))
+        );
+        assert_eq!(preview.update(track("a.mp3", 1_000), ms(2_000)), None);
+
+        // Navigating stops the preview at once
+        assert_eq!(
+            preview.update(track("b.mp3", 0), ms(3_000)),
+            Some(PreviewCommand::Stop)
+        );
+        // Scrolling on restarts the settle timer
+        assert_eq!(preview.update(track("c.mp3", 0), ms(3_200)), None);
+        let settled = ms(3_200) + PREVIEW_SETTLE_DELAY;
+        assert_eq!(
+            preview.update(track("c.mp3", 0), settled - Duration::from_millis(1)),
+            None
+        );
+        assert_eq!(
+            preview.update(track("c.mp3", 0), settled),
+            Some(PreviewCommand::Play(track("c.mp3", 0).unwrap()))
+        );
+
+        // Losing the selection stops the preview
+        assert_eq!(preview.update(None, ms(5_000)), Some(PreviewCommand::Stop));
+        assert_eq!(preview.update(None, ms(6_000)), None);
+    }
+}
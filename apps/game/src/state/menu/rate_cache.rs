@@ -1,22 +1,22 @@
 //! Cache des ratings par rate.
 
+use chart::BeatmapSsr;
 use database::BeatmapRating;
-use chart;
-use std::collections::HashMap;
+use std::borrow::Cow;
 
 /// Cache des ratings calculés pour différents rates d'une beatmap.
 #[derive(Clone, Debug)]
 pub struct RateCacheEntry {
     available_rates: Vec<f64>,
-    ratings_by_rate: HashMap<i32, Vec<BeatmapRating>>,
+    /// Trié par rate croissant (comme l'analyse).
+    ratings_by_rate: Vec<(f64, Vec<BeatmapRating>)>,
 }
 
 impl RateCacheEntry {
     pub fn from_analysis(beatmap_hash: &str, analysis: chart::RateDifficultyCache) -> Self {
-        let mut ratings_by_rate = HashMap::new();
+        let mut ratings_by_rate = Vec::with_capacity(analysis.ratings_by_rate.len());
 
         for (rate, values) in analysis.ratings_by_rate.into_iter() {
-            let key = Self::normalize(rate);
             let converted = values
                 .into_iter()
                 .enumerate()
@@ -34,7 +34,7 @@ impl RateCacheEntry {
                     technical: value.ssr.technical,
                 })
                 .collect::<Vec<_>>();
-            ratings_by_rate.insert(key, converted);
+            ratings_by_rate.push((rate, converted));
         }
 
         Self {
@@ -43,29 +43,53 @@ impl RateCacheEntry {
         }
     }
 
-    pub fn get_ratings(&self, rate: f64) -> Option<&Vec<BeatmapRating>> {
-        let key = Self::normalize(rate);
-        self.ratings_by_rate.get(&key)
-    }
+    /// Ratings à `rate`, interpolés entre les deux rates calculés voisins
+    /// (pas de recalcul). Hors plage, ceux du rate le plus proche.
+    pub fn get_ratings(&self, rate: f64) -> Option<Cow<'_, [BeatmapRating]>> {
+        let above = self.ratings_by_rate.partition_point(|(r, _)| *r < rate);
+        let (Some((low_rate, low)), Some((high_rate, high))) = (
+            above.checked_sub(1).map(|idx| &self.ratings_by_rate[idx]),
+            self.ratings_by_rate.get(above),
+        ) else {
+            return self
+                .nearest(rate)
+                .map(|(_, ratings)| Cow::Borrowed(ratings.as_slice()));
+        };
+        if Self::normalize(*high_rate) == Self::normalize(rate) {
+            return Some(Cow::Borrowed(high.as_slice()));
+        }
+        if Self::normalize(*low_rate) == Self::normalize(rate) {
+            return Some(Cow::Borrowed(low.as_slice()));
+        }
 
-    pub fn contains_rate(&self, rate: f64) -> bool {
-        self.get_ratings(rate).is_some()
+        let t = (rate - low_rate) / (high_rate - low_rate);
+        let interpolated = low
+            .iter()
+            .map(|from| match high.iter().find(|to| to.name == from.name) {
+                Some(to) => with_ssr(from, BeatmapSsr::lerp(&ssr_of(from), &ssr_of(to), t)),
+                None => from.clone(),
+            })
+            .collect();
+        Some(Cow::Owned(interpolated))
     }
 
-    pub fn closest_rate(&self, desired: f64) -> Option<f64> {
-        if self.available_rates.is_empty() {
-            return None;
-        }
-        let mut best_rate = self.available_rates[0];
-        let mut best_diff = (best_rate - desired).abs();
-        for &candidate in &self.available_rates {
-            let diff = (candidate - desired).abs();
-            if diff < best_diff {
-                best_rate = candidate;
-                best_diff = diff;
+    /// Vrai si `rate` est dans la plage calculée (interpolable).
+    pub fn covers_rate(&self, rate: f64) -> bool {
+        match (self.ratings_by_rate.first(), self.ratings_by_rate.last()) {
+            (Some((min, _)), Some((max, _))) => {
+                Self::normalize(rate) >= Self::normalize(*min)
+                    && Self::normalize(rate) <= Self::normalize(*max)
             }
+            _ => false,
         }
-        Some(best_rate)
+    }
+
+    pub fn closest_rate(&self, desired: f64) -> Option<f64> {
+        self.nearest(desired).map(|(rate, _)| *rate)
+    }
+
+    fn nearest(&self, rate: f64) -> Option<&(f64, Vec<BeatmapRating>)> {
+        chart::nearest_rate_index(&self.ratings_by_rate, rate).map(|idx| &self.ratings_by_rate[idx])
     }
 
     pub fn next_rate(&self, current: f64) -> Option<f64> {
@@ -89,3 +113,30 @@ impl RateCacheEntry {
         (rate * 100.0).round() as i32
     }
 }
+
+fn ssr_of(rating: &BeatmapRating) -> BeatmapSsr {
+    BeatmapSsr {
+        overall: rating.overall,
+        stream: rating.stream,
+        jumpstream: rating.jumpstream,
+        handstream: rating.handstream,
+        stamina: rating.stamina,
+        jackspeed: rating.jackspeed,
+        chordjack: rating.chordjack,
+        technical: rating.technical,
+    }
+}
+
+fn with_ssr(rating: &BeatmapRating, ssr: BeatmapSsr) -> BeatmapRating {
+    BeatmapRating {
+        overall: ssr.overall,
+        stream: ssr.stream,
+        jumpstream: ssr.jumpstream,
+        handstream: ssr.handstream,
+        stamina: ssr.stamina,
+        jackspeed: ssr.jackspeed,
+        chordjack: ssr.chordjack,
+        technical: ssr.technical,
+        ..rating.clone()
+    }
+}
@@ -0,0 +1,71 @@
+//! Type-ahead buffer for jumping through the song wheel by title.
+//!
+//! Letters typed in quick succession accumulate ("fr" → "Freedom Dive"),
+//! and the buffer starts over once the player pauses for longer than
+//! [`TYPE_AHEAD_TIMEOUT`].
+
+use std::time::{Duration, Instant};
+
+/// Pause after which the next key starts a new search.
+pub const TYPE_AHEAD_TIMEOUT: Duration = Duration::from_millis(1000);
+
+/// Characters typed so far, lowercased.
+#[derive(Clone, Debug, Default)]
+pub struct TypeAhead {
+    buffer: String,
+    last_input: Option<Instant>,
+}
+
+impl TypeAhead {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a character, starting over if the previous one is too old.
+    ///
+    /// Returns the current search prefix.
+    pub fn push(&mut self, c: char, now: Instant) -> &str {
+        let expired = self
+            .last_input
+            .is_none_or(|last| now.saturating_duration_since(last) > TYPE_AHEAD_TIMEOUT);
+        if expired {
+            self.buffer.clear();
+        }
+        self.buffer.extend(c.to_lowercase());
+        self.last_input = Some(now);
+        &self.buffer
+    }
+
+    /// Current search prefix (may be stale, see [`push`](Self::push)).
+    pub fn buffer(&self) -> &str {
+        &self.buffer
+    }
+
+    /// Forgets the typed characters (e.g. after arrow navigation).
+    pub fn clear(&mut self) {
+        self.buffer.clear();
+        self.last_input = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_buffer_resets_after_timeout() {
+        let start = Instant::now();
+        let mut type_ahead = TypeAhead::new();
+        assert_eq!(type_ahead.push('F', start), "f");
+        assert_eq!(
+            type_ahead.push('r', start + Duration::from_millis(200)),
+            "fr"
+        );
+
+        let later = start + Duration::from_millis(200) + TYPE_AHEAD_TIMEOUT * 2;
+        assert_eq!(type_ahead.push('a', later), "a");
+
+        type_ahead.clear();
+        assert_eq!(type_ahead.buffer(), "");
+    }
+}
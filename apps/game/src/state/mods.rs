@@ -1,53 +1,12 @@
 //! Game modifiers system.
 //!
-//! This module defines gameplay mods that alter note behavior or visual effects.
+//! This module tracks the active gameplay mods and derives their visual effects.
+//! The mods themselves live in the engine crate so replays can record them.
 
+use crate::models::settings::{SettingsState, VisibilityBand};
 use std::collections::HashSet;
 
-/// Available gameplay modifiers.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
-pub enum GameMod {
-    /// Converts LN and burst to tap notes, removes mines.
-    NoSpecial,
-    /// Black overlay from bottom that grows with combo.
-    Hidden,
-    /// Screen hidden except for a thin horizontal strip.
-    Flashlight,
-    /// Notes visually rotate on themselves.
-    Spinner,
-}
-
-impl GameMod {
-    /// Returns a user-friendly display name for the mod.
-    pub fn display_name(&self) -> &'static str {
-        match self {
-            GameMod::NoSpecial => "NO SPECIAL",
-            GameMod::Hidden => "HIDDEN",
-            GameMod::Flashlight => "FLASHLIGHT",
-            GameMod::Spinner => "SPINNER",
-        }
-    }
-
-    /// Returns a short description of what the mod does.
-    pub fn description(&self) -> &'static str {
-        match self {
-            GameMod::NoSpecial => "Replaces LN/burst with taps, removes mines",
-            GameMod::Hidden => "Screen darkens from bottom as combo grows",
-            GameMod::Flashlight => "Only a thin strip is visible",
-            GameMod::Spinner => "Notes rotate visually",
-        }
-    }
-
-    /// Returns all available mods.
-    pub fn all() -> &'static [GameMod] {
-        &[
-            GameMod::NoSpecial,
-            GameMod::Hidden,
-            GameMod::Flashlight,
-            GameMod::Spinner,
-        ]
-    }
-}
+pub use engine::GameMod;
 
 /// Tracks which gameplay mods are currently active.
 #[derive(Clone, Debug, Default)]
@@ -82,6 +41,15 @@ impl ActiveMods {
         self.mods.iter()
     }
 
+    /// Returns the active mods in a stable order (as listed by [`GameMod::all`]).
+    pub fn to_vec(&self) -> Vec<GameMod> {
+        GameMod::all()
+            .iter()
+            .copied()
+            .filter(|m| self.mods.contains(m))
+            .collect()
+    }
+
     /// Returns true if no mods are active.
     pub fn is_empty(&self) -> bool {
         self.mods.is_empty()
@@ -92,3 +60,91 @@ impl ActiveMods {
         self.mods.clear();
     }
 }
+
+/// Per-note opacity rules derived from the active visibility mods.
+///
+/// This only affects rendering: scoring and replays never see it.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct NoteVisibility {
+    /// Fade band for Hidden, if active.
+    pub hidden: Option<VisibilityBand>,
+    /// Fade band for FadeIn, if active.
+    pub fade_in: Option<VisibilityBand>,
+}
+
+impl NoteVisibility {
+    /// Builds the visibility rules from the active mods and the user's band settings.
+    pub fn from_mods(mods: &ActiveMods, settings: &SettingsState) -> Self {
+        Self {
+            hidden: mods.has(GameMod::Hidden).then_some(settings.hidden_band),
+            fade_in: mods.has(GameMod::FadeIn).then_some(settings.fade_in_band),
+        }
+    }
+
+    /// Returns the opacity of a note at the given scroll progress
+    /// (`0.0` = receptor line, `1.0` = top of the visible area).
+    pub fn alpha(&self, progress: f32) -> f32 {
+        let hidden = self.hidden.map_or(1.0, |band| band_alpha(band, progress));
+        let fade_in = self.fade_in.map_or(1.0, |band| band_alpha(band, progress));
+        hidden * fade_in
+    }
+}
+
+/// Linear fade from `band.transparent_at` (alpha 0) to `band.opaque_at` (alpha 1).
+pub fn band_alpha(band: VisibilityBand, progress: f32) -> f32 {
+    let span = band.opaque_at - band.transparent_at;
+    if span.abs() <= f32::EPSILON {
+        // Degenerate band: hard cut at the threshold.
+        let visible = if band.opaque_at >= band.transparent_at {
+            progress >= band.opaque_at
+        } else {
+            progress <= band.opaque_at
+        };
+        return if visible { 1.0 } else { 0.0 };
+    }
+    ((progress - band.transparent_at) / span).clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hidden_alpha_curve() {
+        let visibility = NoteVisibility {
+            hidden: Some(VisibilityBand::hidden()),
+            fade_in: None,
+        };
+
+        // Inside the hidden zone (near the receptors)
+        assert_eq!(visibility.alpha(0.0), 0.0);
+        assert_eq!(visibility.alpha(0.2), 0.0);
+        // Above the fade band
+        assert_eq!(visibility.alpha(0.8), 1.0);
+        assert_eq!(visibility.alpha(1.0), 1.0);
+        // Partially faded in between
+        let mid = visibility.alpha(0.425);
+        assert!(mid > 0.0 && mid < 1.0);
+    }
+
+    #[test]
+    fn test_fade_in_alpha_curve() {
+        let visibility = NoteVisibility {
+            hidden: None,
+            fade_in: Some(VisibilityBand::fade_in()),
+        };
+
+        assert_eq!(visibility.alpha(1.0), 0.0);
+        assert_eq!(visibility.alpha(0.9), 0.0);
+        assert_eq!(visibility.alpha(0.5), 1.0);
+        assert_eq!(visibility.alpha(0.0), 1.0);
+    }
+
+    #[test]
+    fn test_no_mods_fully_visible() {
+        let visibility = NoteVisibility::default();
+        for progress in [-0.5, 0.0, 0.3, 0.7, 1.0, 1.5] {
+            assert_eq!(visibility.alpha(progress), 1.0);
+        }
+    }
+}
@@ -20,4 +20,6 @@ pub struct GameResultData {
     pub rate: f64,
     pub judge_text: String,
     pub show_settings: bool,
+    /// Partie arrêtée par une condition d'échec (ex. Sudden Death).
+    pub failed: bool,
 }
@@ -5,10 +5,10 @@
 
 use crate::input::events::{GameAction, InputCommand, RawInputEvent};
 use crate::shared::snapshot::RenderState;
-use crossbeam_channel::{Receiver, Sender, bounded, unbounded};
+use crossbeam_channel::{Receiver, Sender, TrySendError, bounded, unbounded};
 use std::path::PathBuf;
 use std::sync::Arc;
-use std::sync::atomic::AtomicU64;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 /// System-level events broadcast to all threads.
 #[derive(Debug, Clone)]
@@ -40,6 +40,69 @@ pub enum AudioCommand {
     SetSpeed { speed: f32 },
     /// Change volume level.
     SetVolume { volume: f32 },
+    /// Decode the samples (keysounds) of the loaded map ahead of play.
+    LoadSamples { paths: Vec<PathBuf> },
+    /// Play a one-shot sample (keysound) over the music.
+    PlaySample { path: PathBuf, volume: f32 },
+}
+
+/// Sending half of a single-slot channel: a new value replaces the one the
+/// receiver has not taken yet, so the receiver always gets the freshest one.
+///
+/// Values may be skipped, so they must carry state rather than one-off
+/// events (snapshots carry the id of the latest event instead of a flag).
+pub struct LatestSender<T> {
+    tx: Sender<T>,
+    /// Used to evict the stale value when the slot is full.
+    rx: Receiver<T>,
+    /// Values replaced before being received.
+    dropped: Arc<AtomicU64>,
+}
+
+/// Creates a single-slot "latest wins" channel.
+pub fn latest<T>() -> (LatestSender<T>, Receiver<T>) {
+    let (tx, rx) = bounded(1);
+    let sender = LatestSender {
+        tx,
+        rx: rx.clone(),
+        dropped: Arc::new(AtomicU64::new(0)),
+    };
+    (sender, rx)
+}
+
+impl<T> LatestSender<T> {
+    /// Sends `value`, replacing the one still waiting in the slot.
+    pub fn send(&self, mut value: T) {
+        loop {
+            match self.tx.try_send(value) {
+                Ok(()) => return,
+                Err(TrySendError::Full(rejected)) => {
+                    // The receiver may have emptied the slot in between
+                    if self.rx.try_recv().is_ok() {
+                        self.dropped.fetch_add(1, Ordering::Relaxed);
+                    }
+                    value = rejected;
+                }
+                // Unreachable: the sender holds a receiver
+                Err(TrySendError::Disconnected(_)) => return,
+            }
+        }
+    }
+
+    /// Number of values replaced since the last call.
+    pub fn take_dropped(&self) -> u64 {
+        self.dropped.swap(0, Ordering::Relaxed)
+    }
+}
+
+impl<T> Clone for LatestSender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            tx: self.tx.clone(),
+            rx: self.rx.clone(),
+            dropped: Arc::clone(&self.dropped),
+        }
+    }
 }
 
 /// Aggregates the cross-thread communication channels.
@@ -65,8 +128,8 @@ pub struct SystemBus {
     pub action_tx: Sender<GameAction>,
     pub action_rx: Receiver<GameAction>,
 
-    /// Logic → Render: game state snapshots.
-    pub render_tx: Sender<RenderState>,
+    /// Logic → Render: game state snapshots (only the latest is kept).
+    pub render_tx: LatestSender<RenderState>,
     pub render_rx: Receiver<RenderState>,
 
     /// Main → Logic: system events.
@@ -95,8 +158,9 @@ impl SystemBus {
         let (input_cmd_tx, input_cmd_rx) = unbounded();
         let (action_tx, action_rx) = unbounded();
 
-        // Bounded render channel: max 2 frames queued to limit latency
-        let (render_tx, render_rx) = bounded(2);
+        // Single-slot render channel: the renderer only needs the freshest
+        // snapshot, and a stale one would only add latency
+        let (render_tx, render_rx) = latest();
 
         let (sys_tx, sys_rx) = unbounded();
         let (audio_cmd_tx, audio_cmd_rx) = unbounded();
@@ -126,3 +190,26 @@ impl Default for SystemBus {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latest_channel_keeps_only_newest() {
+        let (tx, rx) = latest();
+        for tick in 0..5 {
+            tx.send(tick);
+        }
+
+        assert_eq!(rx.try_recv(), Ok(4));
+        assert!(rx.try_recv().is_err());
+        assert_eq!(tx.take_dropped(), 4);
+        assert_eq!(tx.take_dropped(), 0);
+
+        // Nothing is dropped when the receiver keeps up
+        tx.send(5);
+        assert_eq!(rx.try_recv(), Ok(5));
+        assert_eq!(tx.take_dropped(), 0);
+    }
+}
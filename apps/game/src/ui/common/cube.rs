@@ -1,13 +1,35 @@
 //! Rotating 3D wireframe cube component.
 //!
-//! A reusable wgpu component for rendering an animated 3D cube.
-//! Can be configured with custom size and colors.
+//! A reusable wgpu component for rendering an animated 3D wireframe.
+//! Can be configured with custom size, colors, line thickness and shape
+//! (cube by default, or a tetrahedron / icosahedron).
 
 use std::borrow::Cow;
 use wgpu::util::DeviceExt;
 
 use crate::shaders::constants::CUBE_SHADER_SRC;
 
+/// Wireframe shape drawn by the cube renderer
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WireframeShape {
+    #[default]
+    Cube,
+    Tetrahedron,
+    Icosahedron,
+}
+
+impl WireframeShape {
+    /// Corners (scaled so they fit in a cube of half-size `size`) and edges
+    /// as pairs of corner indices
+    pub fn geometry(&self, size: f32) -> (Vec<[f32; 3]>, Vec<(usize, usize)>) {
+        match self {
+            WireframeShape::Cube => cube_geometry(size),
+            WireframeShape::Tetrahedron => tetrahedron_geometry(size),
+            WireframeShape::Icosahedron => icosahedron_geometry(size),
+        }
+    }
+}
+
 /// Configuration for the cube renderer
 #[derive(Clone, Debug)]
 pub struct CubeConfig {
@@ -15,13 +37,25 @@ pub struct CubeConfig {
     pub size: f32,
     /// Rotation speed multiplier (default: 1.0)
     pub rotation_speed: f32,
+    /// Line color, RGBA (default: Prism red #ff003c)
+    pub color: [f32; 4],
+    /// Line thickness as a fraction of the screen height (default: 0.002)
+    pub line_thickness: f32,
+    /// Shape to draw (default: cube)
+    pub shape: WireframeShape,
 }
 
+const DEFAULT_COLOR: [f32; 4] = [1.0, 0.0, 0.235, 0.9];
+const DEFAULT_LINE_THICKNESS: f32 = 0.002;
+
 impl Default for CubeConfig {
     fn default() -> Self {
         Self {
             size: 0.25,
             rotation_speed: 1.0,
+            color: DEFAULT_COLOR,
+            line_thickness: DEFAULT_LINE_THICKNESS,
+            shape: WireframeShape::Cube,
         }
     }
 }
@@ -32,6 +66,7 @@ impl CubeConfig {
         Self {
             size: 0.65,
             rotation_speed: 1.8,
+            ..Self::default()
         }
     }
 
@@ -40,6 +75,7 @@ impl CubeConfig {
         Self {
             size: 0.15,
             rotation_speed: 1.5,
+            ..Self::default()
         }
     }
 
@@ -47,9 +83,15 @@ impl CubeConfig {
     pub fn with_size(size: f32) -> Self {
         Self {
             size,
-            rotation_speed: 1.0,
+            ..Self::default()
         }
     }
+
+    /// Same configuration with another shape
+    pub fn with_shape(mut self, shape: WireframeShape) -> Self {
+        self.shape = shape;
+        self
+    }
 }
 
 /// Uniform data for the cube shader
@@ -58,21 +100,29 @@ impl CubeConfig {
 struct CubeUniforms {
     time: f32,
     aspect: f32,
-    _padding: [f32; 2],
+    line_thickness: f32,
+    _padding: f32,
+    color: [f32; 4],
 }
 
-/// Cube vertex with position and edge factor
+/// Corner of a line quad: its endpoint, the other endpoint of the edge
+/// and the side of the line it is pushed to (lines are drawn as quads so
+/// they can be thicker than one pixel)
 #[repr(C)]
 #[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
 struct CubeVertex {
     position: [f32; 3],
     edge_factor: f32,
+    other: [f32; 3],
+    side: f32,
 }
 
 impl CubeVertex {
-    const ATTRIBS: [wgpu::VertexAttribute; 2] = wgpu::vertex_attr_array![
+    const ATTRIBS: [wgpu::VertexAttribute; 4] = wgpu::vertex_attr_array![
         0 => Float32x3,
-        1 => Float32
+        1 => Float32,
+        2 => Float32x3,
+        3 => Float32
     ];
 
     fn desc() -> wgpu::VertexBufferLayout<'static> {
@@ -115,7 +165,9 @@ impl CubeRenderer {
         let uniforms = CubeUniforms {
             time: 0.0,
             aspect: 16.0 / 9.0,
-            _padding: [0.0; 2],
+            line_thickness: config.line_thickness,
+            _padding: 0.0,
+            color: config.color,
         };
         let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("Cube Uniform Buffer"),
@@ -128,7 +180,7 @@ impl CubeRenderer {
             label: Some("Cube Bind Group Layout"),
             entries: &[wgpu::BindGroupLayoutEntry {
                 binding: 0,
-                visibility: wgpu::ShaderStages::VERTEX,
+                visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
                 ty: wgpu::BindingType::Buffer {
                     ty: wgpu::BufferBindingType::Uniform,
                     has_dynamic_offset: false,
@@ -176,7 +228,7 @@ impl CubeRenderer {
                 compilation_options: Default::default(),
             }),
             primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::LineList,
+                topology: wgpu::PrimitiveTopology::TriangleList,
                 ..Default::default()
             },
             depth_stencil: None,
@@ -185,8 +237,8 @@ impl CubeRenderer {
             cache: None,
         });
 
-        // Create cube vertices (wireframe edges)
-        let vertices = Self::create_cube_vertices(config.size);
+        // Create wireframe vertices (one quad per edge)
+        let vertices = Self::create_wireframe_vertices(config.shape, config.size);
         let vertex_count = vertices.len() as u32;
         let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("Cube Vertex Buffer"),
@@ -205,51 +257,37 @@ impl CubeRenderer {
         }
     }
 
-    /// Create cube wireframe vertices (12 edges = 24 vertices for LineList)
-    fn create_cube_vertices(size: f32) -> Vec<CubeVertex> {
-        let s = size;
-        // 8 corners of the cube
-        let corners = [
-            [-s, -s, -s], // 0: back-bottom-left
-            [s, -s, -s],  // 1: back-bottom-right
-            [s, s, -s],   // 2: back-top-right
-            [-s, s, -s],  // 3: back-top-left
-            [-s, -s, s],  // 4: front-bottom-left
-            [s, -s, s],   // 5: front-bottom-right
-            [s, s, s],    // 6: front-top-right
-            [-s, s, s],   // 7: front-top-left
-        ];
-
-        // 12 edges as pairs of corner indices
-        let edges: [(usize, usize); 12] = [
-            // Back face
-            (0, 1),
-            (1, 2),
-            (2, 3),
-            (3, 0),
-            // Front face
-            (4, 5),
-            (5, 6),
-            (6, 7),
-            (7, 4),
-            // Connecting edges
-            (0, 4),
-            (1, 5),
-            (2, 6),
-            (3, 7),
-        ];
-
-        let mut vertices = Vec::with_capacity(24);
+    /// Create wireframe vertices: each edge is a quad of two triangles
+    fn create_wireframe_vertices(shape: WireframeShape, size: f32) -> Vec<CubeVertex> {
+        let (corners, edges) = shape.geometry(size);
+        let last_edge = edges.len().saturating_sub(1).max(1) as f32;
+
+        let mut vertices = Vec::with_capacity(edges.len() * 6);
         for (i, (a, b)) in edges.iter().enumerate() {
-            let edge_factor = i as f32 / 11.0;
-            vertices.push(CubeVertex {
-                position: corners[*a],
+            let edge_factor = i as f32 / last_edge;
+            let (a, b) = (corners[*a], corners[*b]);
+            // The normal seen from `b` points the other way, hence the flipped sides
+            let a_left = CubeVertex {
+                position: a,
                 edge_factor,
-            });
-            vertices.push(CubeVertex {
-                position: corners[*b],
+                other: b,
+                side: 1.0,
+            };
+            let a_right = CubeVertex {
+                side: -1.0,
+                ..a_left
+            };
+            let b_left = CubeVertex {
+                position: b,
                 edge_factor,
-            });
+                other: a,
+                side: -1.0,
+            };
+            let b_right = CubeVertex {
+                side: 1.0,
+                ..b_left
+            };
+            vertices.extend([a_left, a_right, b_right, a_left, b_right, b_left]);
         }
 
         vertices
@@ -272,7 +310,9 @@ impl CubeRenderer {
         let uniforms = CubeUniforms {
             time: elapsed,
             aspect: aspect_ratio,
-            _padding: [0.0; 2],
+            line_thickness: self.config.line_thickness,
+            _padding: 0.0,
+            color: self.config.color,
         };
         queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniforms]));
 
@@ -288,3 +328,102 @@ impl CubeRenderer {
         &self.config
     }
 }
+
+/// 8 corners, 12 edges
+fn cube_geometry(size: f32) -> (Vec<[f32; 3]>, Vec<(usize, usize)>) {
+    let s = size;
+    let corners = vec![
+        [-s, -s, -s], // 0: back-bottom-left
+        [s, -s, -s],  // 1: back-bottom-right
+        [s, s, -s],   // 2: back-top-right
+        [-s, s, -s],  // 3: back-top-left
+        [-s, -s, s],  // 4: front-bottom-left
+        [s, -s, s],   // 5: front-bottom-right
+        [s, s, s],    // 6: front-top-right
+        [-s, s, s],   // 7: front-top-left
+    ];
+
+    let edges = vec![
+        // Back face
+        (0, 1),
+        (1, 2),
+        (2, 3),
+        (3, 0),
+        // Front face
+        (4, 5),
+        (5, 6),
+        (6, 7),
+        (7, 4),
+        // Connecting edges
+        (0, 4),
+        (1, 5),
+        (2, 6),
+        (3, 7),
+    ];
+
+    (corners, edges)
+}
+
+/// 4 alternate corners of the cube, every pair joined (6 edges)
+fn tetrahedron_geometry(size: f32) -> (Vec<[f32; 3]>, Vec<(usize, usize)>) {
+    let s = size;
+    let corners = vec![[s, s, s], [s, -s, -s], [-s, s, -s], [-s, -s, s]];
+    let edges = vec![(0, 1), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3)];
+    (corners, edges)
+}
+
+/// 12 corners on three golden rectangles, joined to their 5 nearest neighbours (30 edges)
+fn icosahedron_geometry(size: f32) -> (Vec<[f32; 3]>, Vec<(usize, usize)>) {
+    let phi = (1.0 + 5.0_f32.sqrt()) / 2.0;
+    let s = size / phi;
+    let (a, b) = (s, s * phi);
+    let corners = vec![
+        [0.0, a, b],
+        [0.0, a, -b],
+        [0.0, -a, b],
+        [0.0, -a, -b],
+        [a, b, 0.0],
+        [a, -b, 0.0],
+        [-a, b, 0.0],
+        [-a, -b, 0.0],
+        [b, 0.0, a],
+        [-b, 0.0, a],
+        [b, 0.0, -a],
+        [-b, 0.0, -a],
+    ];
+
+    // Neighbouring corners are exactly one edge (2a) apart
+    let edge_length_sq = (2.0 * a) * (2.0 * a);
+    let mut edges = Vec::with_capacity(30);
+    for (i, p) in corners.iter().enumerate() {
+        for (j, q) in corners.iter().enumerate().skip(i + 1) {
+            let d: f32 = p.iter().zip(q).map(|(x, y)| (x - y).powi(2)).sum();
+            if (d - edge_length_sq).abs() < edge_length_sq * 1e-3 {
+                edges.push((i, j));
+            }
+        }
+    }
+
+    (corners, edges)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shape_vertex_and_edge_counts() {
+        let counts = |shape: WireframeShape| {
+            let (corners, edges) = shape.geometry(0.5);
+            (corners.len(), edges.len())
+        };
+        assert_eq!(counts(WireframeShape::Cube), (8, 12));
+        assert_eq!(counts(WireframeShape::Tetrahedron), (4, 6));
+        assert_eq!(counts(WireframeShape::Icosahedron), (12, 30));
+        assert_eq!(CubeConfig::default().shape, WireframeShape::Cube);
+
+        // Every edge becomes a quad of 6 vertices
+        let vertices = CubeRenderer::create_wireframe_vertices(WireframeShape::Icosahedron, 0.5);
+        assert_eq!(vertices.len(), 30 * 6);
+    }
+}
@@ -7,5 +7,5 @@
 pub mod cube;
 pub mod particles;
 
-pub use cube::{CubeConfig, CubeRenderer};
-pub use particles::{ParticleConfig, ParticleSystem};
+pub use cube::{CubeConfig, CubeRenderer, WireframeShape};
+pub use particles::{ParticleConfig, ParticleField, ParticleSystem};
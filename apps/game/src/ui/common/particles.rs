@@ -3,12 +3,17 @@
 //! A reusable wgpu component for rendering animated particles
 //! with connection lines between nearby particles.
 
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use std::borrow::Cow;
+use std::collections::HashMap;
 use wgpu::util::DeviceExt;
 
 use crate::shaders::constants::PARTICLE_SHADER_SRC;
 
+/// Frame rate the per-step particle velocities were tuned for.
+const REFERENCE_FPS: f32 = 60.0;
+
 /// Configuration for the particle system
 #[derive(Clone, Debug)]
 pub struct ParticleConfig {
@@ -22,6 +27,8 @@ pub struct ParticleConfig {
     pub min_size: f32,
     /// Maximum particle size (default: 3.0)
     pub max_size: f32,
+    /// RNG seed for the initial layout (default: None, random each launch)
+    pub seed: Option<u64>,
 }
 
 impl Default for ParticleConfig {
@@ -32,6 +39,7 @@ impl Default for ParticleConfig {
             speed: 0.3,
             min_size: 1.0,
             max_size: 3.0,
+            seed: None,
         }
     }
 }
@@ -45,6 +53,7 @@ impl ParticleConfig {
             speed: 0.8,
             min_size: 1.0,
             max_size: 2.5,
+            seed: None,
         }
     }
 
@@ -56,6 +65,7 @@ impl ParticleConfig {
             speed: 0.5,
             min_size: 1.5,
             max_size: 4.0,
+            seed: None,
         }
     }
 
@@ -66,6 +76,12 @@ impl ParticleConfig {
             ..Default::default()
         }
     }
+
+    /// Use a fixed seed so the particle layout is reproducible
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
 }
 
 /// Uniform data for the particle shader
@@ -98,18 +114,178 @@ struct LineInstance {
     _padding: f32,
 }
 
+/// Initialize particles with random positions and velocities.
+///
+/// Uses the config seed when set so the layout is reproducible.
+fn spawn_particles(config: &ParticleConfig, width: f32, height: f32) -> Vec<Particle> {
+    let mut rng = match config.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_rng(&mut rand::rng()),
+    };
+    let base_speed = 0.4 * config.speed;
+    (0..config.count)
+        .map(|_| Particle {
+            position: [rng.random::<f32>() * width, rng.random::<f32>() * height],
+            velocity: [
+                (rng.random::<f32>() - 0.5) * base_speed,
+                (rng.random::<f32>() - 0.5) * base_speed,
+            ],
+            size: rng.random::<f32>() * (config.max_size - config.min_size) + config.min_size,
+            _padding: 0.0,
+        })
+        .collect()
+}
+
+/// Line alpha between two particles, if they are close enough to connect.
+fn connection_alpha(a: &Particle, b: &Particle, connection_dist: f32) -> Option<f32> {
+    let dx = a.position[0] - b.position[0];
+    let dy = a.position[1] - b.position[1];
+    let dist = (dx * dx + dy * dy).sqrt();
+    (dist < connection_dist).then(|| 1.0 - dist / connection_dist)
+}
+
+/// Calculate line connections between nearby particles.
+///
+/// Particles are bucketed in a grid of `connection_dist` cells so only
+/// neighbouring cells are compared. Lines are sorted by index pair, in the
+/// same order as a pairwise scan would produce.
+fn calculate_lines(particles: &[Particle], connection_dist: f32) -> Vec<LineInstance> {
+    let mut lines = Vec::new();
+    if connection_dist <= 0.0 {
+        return lines;
+    }
+
+    let cell_of = |p: &Particle| {
+        (
+            (p.position[0] / connection_dist).floor() as i32,
+            (p.position[1] / connection_dist).floor() as i32,
+        )
+    };
+
+    let mut grid: HashMap<(i32, i32), Vec<usize>> = HashMap::new();
+    for (i, p) in particles.iter().enumerate() {
+        grid.entry(cell_of(p)).or_default().push(i);
+    }
+
+    for (i, p) in particles.iter().enumerate() {
+        let (cx, cy) = cell_of(p);
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                let Some(bucket) = grid.get(&(cx + dx, cy + dy)) else {
+                    continue;
+                };
+                for &j in bucket.iter().filter(|&&j| j > i) {
+                    if let Some(alpha) = connection_alpha(p, &particles[j], connection_dist) {
+                        lines.push(LineInstance {
+                            start_idx: i as u32,
+                            end_idx: j as u32,
+                            alpha,
+                            _padding: 0.0,
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    lines.sort_unstable_by_key(|l| (l.start_idx, l.end_idx));
+    lines
+}
+
+/// Particle positions and connections, stepped on the logic thread.
+///
+/// The render thread receives a copy in each main menu snapshot and only
+/// uploads it.
+#[derive(Clone, Debug)]
+pub struct ParticleField {
+    particles: Vec<Particle>,
+    config: ParticleConfig,
+    // Connections computed by the last tick
+    lines: Vec<LineInstance>,
+    // Screen size for bounds
+    width: f32,
+    height: f32,
+}
+
+impl ParticleField {
+    /// Spawn the particles of `config` over a `width` x `height` screen.
+    pub fn new(config: ParticleConfig, width: f32, height: f32) -> Self {
+        Self {
+            particles: spawn_particles(&config, width, height),
+            config,
+            lines: Vec::new(),
+            width,
+            height,
+        }
+    }
+
+    /// Advance the simulation by `dt` seconds and recompute connections.
+    pub fn tick(&mut self, dt: f32) {
+        let step = dt * REFERENCE_FPS;
+        for p in &mut self.particles {
+            // Update position
+            p.position[0] += p.velocity[0] * step;
+            p.position[1] += p.velocity[1] * step;
+
+            // Bounce at edges
+            if p.position[0] < 0.0 || p.position[0] > self.width {
+                p.velocity[0] *= -1.0;
+                p.position[0] = p.position[0].clamp(0.0, self.width);
+            }
+            if p.position[1] < 0.0 || p.position[1] > self.height {
+                p.velocity[1] *= -1.0;
+                p.position[1] = p.position[1].clamp(0.0, self.height);
+            }
+        }
+
+        self.lines = calculate_lines(&self.particles, self.config.connection_distance);
+    }
+
+    /// Resize the field to new dimensions.
+    ///
+    /// A minimized window reports a zero size, which is ignored.
+    pub fn resize(&mut self, width: f32, height: f32) {
+        if width <= 0.0 || height <= 0.0 {
+            return;
+        }
+
+        // Scale existing particle positions to new size
+        let scale_x = width / self.width;
+        let scale_y = height / self.height;
+
+        for p in &mut self.particles {
+            p.position[0] *= scale_x;
+            p.position[1] *= scale_y;
+        }
+
+        self.width = width;
+        self.height = height;
+    }
+
+    /// Get the current configuration.
+    pub fn config(&self) -> &ParticleConfig {
+        &self.config
+    }
+
+    /// Get the current screen dimensions.
+    pub fn dimensions(&self) -> (f32, f32) {
+        (self.width, self.height)
+    }
+}
+
 /// Animated particle system renderer.
 ///
+/// Holds the GPU resources only; the simulation lives in [`ParticleField`].
+///
 /// # Example
 /// ```ignore
-/// let particles = ParticleSystem::new(&device, format, 1280.0, 720.0, ParticleConfig::default());
-/// // In render loop:
-/// particles.render(&mut render_pass, &queue);
+/// let particles = ParticleSystem::new(&device, format, &ParticleConfig::default());
+/// // Each frame, with the field of the latest snapshot:
+/// particles.render(&mut render_pass, &queue, &field);
 /// ```
 pub struct ParticleSystem {
-    // Particle data (updated on CPU)
-    particles: Vec<Particle>,
-    config: ParticleConfig,
+    // Number of particles the storage buffer holds
+    capacity: u32,
 
     // GPU resources for particles
     particle_buffer: wgpu::Buffer,
@@ -120,14 +296,9 @@ pub struct ParticleSystem {
     line_buffer: wgpu::Buffer,
     line_pipeline: wgpu::RenderPipeline,
     line_bind_group: wgpu::BindGroup,
-    line_count: u32,
 
     // Shared uniform buffer
     uniform_buffer: wgpu::Buffer,
-
-    // Screen size for bounds
-    width: f32,
-    height: f32,
 }
 
 impl ParticleSystem {
@@ -135,31 +306,14 @@ impl ParticleSystem {
     pub fn new(
         device: &wgpu::Device,
         format: wgpu::TextureFormat,
-        width: f32,
-        height: f32,
-        config: ParticleConfig,
+        config: &ParticleConfig,
     ) -> Self {
-        // Initialize particles with random positions and velocities
-        let mut rng = rand::rng();
-        let base_speed = 0.4 * config.speed;
-        let particles: Vec<Particle> = (0..config.count)
-            .map(|_| Particle {
-                position: [rng.random::<f32>() * width, rng.random::<f32>() * height],
-                velocity: [
-                    (rng.random::<f32>() - 0.5) * base_speed,
-                    (rng.random::<f32>() - 0.5) * base_speed,
-                ],
-                size: rng.random::<f32>() * (config.max_size - config.min_size) + config.min_size,
-                _padding: 0.0,
-            })
-            .collect();
-
         // Create uniform buffer
         let uniforms = ParticleUniforms {
             time: 0.0,
-            width,
-            height,
-            particle_count: config.count,
+            width: 1.0,
+            height: 1.0,
+            particle_count: 0,
         };
         let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("Particle Uniform Buffer"),
@@ -167,11 +321,12 @@ impl ParticleSystem {
             usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
         });
 
-        // Create particle storage buffer
-        let particle_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        // Create particle storage buffer, filled by each render
+        let particle_buffer = device.create_buffer(&wgpu::BufferDescriptor {
             label: Some("Particle Storage Buffer"),
-            contents: bytemuck::cast_slice(&particles),
+            size: (config.count as usize * std::mem::size_of::<Particle>()) as u64,
             usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
         });
 
         // Create line instance buffer (max possible connections)
@@ -304,130 +459,140 @@ impl ParticleSystem {
         });
 
         Self {
-            particles,
-            config,
+            capacity: config.count,
             particle_buffer,
             particle_pipeline,
             particle_bind_group: bind_group.clone(),
             line_buffer,
             line_pipeline,
             line_bind_group: bind_group,
-            line_count: 0,
             uniform_buffer,
-            width,
-            height,
         }
     }
 
-    /// Update particle positions (CPU simulation).
-    pub fn update(&mut self) {
-        for p in &mut self.particles {
-            // Update position
-            p.position[0] += p.velocity[0];
-            p.position[1] += p.velocity[1];
-
-            // Bounce at edges
-            if p.position[0] < 0.0 || p.position[0] > self.width {
-                p.velocity[0] *= -1.0;
-                p.position[0] = p.position[0].clamp(0.0, self.width);
-            }
-            if p.position[1] < 0.0 || p.position[1] > self.height {
-                p.velocity[1] *= -1.0;
-                p.position[1] = p.position[1].clamp(0.0, self.height);
-            }
-        }
-    }
-
-    /// Calculate line connections between nearby particles.
-    fn calculate_lines(&self) -> Vec<LineInstance> {
-        let mut lines = Vec::new();
-        let connection_dist = self.config.connection_distance;
-
-        for i in 0..self.particles.len() {
-            for j in (i + 1)..self.particles.len() {
-                let dx = self.particles[i].position[0] - self.particles[j].position[0];
-                let dy = self.particles[i].position[1] - self.particles[j].position[1];
-                let dist = (dx * dx + dy * dy).sqrt();
-
-                if dist < connection_dist {
-                    let alpha = 1.0 - dist / connection_dist;
-                    lines.push(LineInstance {
-                        start_idx: i as u32,
-                        end_idx: j as u32,
-                        alpha,
-                        _padding: 0.0,
-                    });
-                }
-            }
-        }
-
-        lines
-    }
-
-    /// Resize the particle system to new dimensions.
-    pub fn resize(&mut self, width: f32, height: f32) {
-        // Scale existing particle positions to new size
-        let scale_x = width / self.width;
-        let scale_y = height / self.height;
-
-        for p in &mut self.particles {
-            p.position[0] *= scale_x;
-            p.position[1] *= scale_y;
-        }
-
-        self.width = width;
-        self.height = height;
-    }
-
-    /// Render the particle system.
-    pub fn render<'a>(&'a mut self, render_pass: &mut wgpu::RenderPass<'a>, queue: &wgpu::Queue) {
-        // Update particles on CPU
-        self.update();
+    /// Upload `field` and draw it.
+    pub fn render<'a>(
+        &'a self,
+        render_pass: &mut wgpu::RenderPass<'a>,
+        queue: &wgpu::Queue,
+        field: &ParticleField,
+    ) {
+        // A field spawned with another config must not overflow the buffers
+        let count = field.particles.len().min(self.capacity as usize);
+        let particles = &field.particles[..count];
 
         // Update uniforms
         let uniforms = ParticleUniforms {
             time: 0.0, // Not used in CPU version
-            width: self.width,
-            height: self.height,
-            particle_count: self.config.count,
+            width: field.width,
+            height: field.height,
+            particle_count: count as u32,
         };
         queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniforms]));
 
         // Update particle buffer
-        queue.write_buffer(
-            &self.particle_buffer,
-            0,
-            bytemuck::cast_slice(&self.particles),
-        );
-
-        // Calculate and update line buffer
-        let lines = self.calculate_lines();
-        self.line_count = lines.len() as u32;
-        if !lines.is_empty() {
-            queue.write_buffer(&self.line_buffer, 0, bytemuck::cast_slice(&lines));
-        }
+        queue.write_buffer(&self.particle_buffer, 0, bytemuck::cast_slice(particles));
 
         // Draw lines first (behind particles)
-        if self.line_count > 0 {
+        // (skipped if the field was cut, its lines may point past the buffer)
+        let lines = if count == field.particles.len() {
+            &field.lines[..]
+        } else {
+            &[]
+        };
+        if !lines.is_empty() {
+            queue.write_buffer(&self.line_buffer, 0, bytemuck::cast_slice(lines));
             render_pass.set_pipeline(&self.line_pipeline);
             render_pass.set_bind_group(0, &self.line_bind_group, &[]);
             render_pass.set_vertex_buffer(0, self.line_buffer.slice(..));
-            render_pass.draw(0..2, 0..self.line_count);
+            render_pass.draw(0..2, 0..lines.len() as u32);
         }
 
         // Draw particles
         render_pass.set_pipeline(&self.particle_pipeline);
         render_pass.set_bind_group(0, &self.particle_bind_group, &[]);
-        render_pass.draw(0..6, 0..self.config.count);
+        render_pass.draw(0..6, 0..count as u32);
     }
+}
 
-    /// Get the current configuration.
-    pub fn config(&self) -> &ParticleConfig {
-        &self.config
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn positions(particles: &[Particle]) -> Vec<[f32; 2]> {
+        particles.iter().map(|p| p.position).collect()
     }
 
-    /// Get the current screen dimensions.
-    pub fn dimensions(&self) -> (f32, f32) {
-        (self.width, self.height)
+    #[test]
+    fn test_same_seed_same_layout() {
+        let config = ParticleConfig::default().with_seed(42);
+        let a = spawn_particles(&config, 1280.0, 720.0);
+        let b = spawn_particles(&config, 1280.0, 720.0);
+        assert_eq!(a.len(), config.count as usize);
+        assert_eq!(positions(&a), positions(&b));
+
+        let other = spawn_particles(&ParticleConfig::default().with_seed(43), 1280.0, 720.0);
+        assert_ne!(positions(&a), positions(&other));
+    }
+
+    #[test]
+    fn test_field_ticks_without_gpu() {
+        let mut field = ParticleField::new(ParticleConfig::default().with_seed(7), 1280.0, 720.0);
+        let before = positions(&field.particles);
+        field.tick(1.0 / 200.0);
+        assert_ne!(positions(&field.particles), before);
+        assert_eq!(
+            line_keys(&field.lines),
+            line_keys(&calculate_lines(
+                &field.particles,
+                field.config.connection_distance
+            ))
+        );
+
+        // A minimized window must not collapse the field
+        field.resize(0.0, 0.0);
+        assert_eq!(field.dimensions(), (1280.0, 720.0));
+        assert!(field.particles.iter().all(|p| p.position[0].is_finite()));
+    }
+
+    fn brute_force_lines(particles: &[Particle], connection_dist: f32) -> Vec<LineInstance> {
+        let mut lines = Vec::new();
+        for i in 0..particles.len() {
+            for j in (i + 1)..particles.len() {
+                if let Some(alpha) = connection_alpha(&particles[i], &particles[j], connection_dist)
+                {
+                    lines.push(LineInstance {
+                        start_idx: i as u32,
+                        end_idx: j as u32,
+                        alpha,
+                        _padding: 0.0,
+                    });
+                }
+            }
+        }
+        lines
+    }
+
+    fn line_keys(lines: &[LineInstance]) -> Vec<(u32, u32, f32)> {
+        lines
+            .iter()
+            .map(|l| (l.start_idx, l.end_idx, l.alpha))
+            .collect()
+    }
+
+    #[test]
+    fn test_grid_lines_match_brute_force() {
+        for config in [
+            ParticleConfig::default().with_seed(1),
+            ParticleConfig::dense().with_seed(2),
+            ParticleConfig::sparse().with_seed(3),
+            ParticleConfig::with_count(600).with_seed(4),
+        ] {
+            let particles = spawn_particles(&config, 1920.0, 1080.0);
+            let brute = brute_force_lines(&particles, config.connection_distance);
+            let grid = calculate_lines(&particles, config.connection_distance);
+            assert!(!brute.is_empty());
+            assert_eq!(line_keys(&grid), line_keys(&brute));
+        }
     }
 }
@@ -77,6 +77,7 @@ impl Column {
         InstanceRaw {
             offset: [center_x, hit_line_y],
             scale: [receptor_width, receptor_height],
+            alpha: 1.0,
         }
     }
 
@@ -117,6 +118,7 @@ impl Column {
                 instance: InstanceRaw {
                     offset: [center_x, y_pos],
                     scale: [note_width, note_height],
+                    alpha: 1.0,
                 },
             });
         } else if note.is_mine() {
@@ -125,6 +127,7 @@ impl Column {
                 instance: InstanceRaw {
                     offset: [center_x, y_pos],
                     scale: [note_width, note_height],
+                    alpha: 1.0,
                 },
             });
         } else if note.is_hold() {
@@ -202,6 +205,7 @@ impl Column {
                 instance: InstanceRaw {
                     offset: [center_x, body_center_y],
                     scale: [ln_width, body_height],
+                    alpha: 1.0,
                 },
             });
         }
@@ -213,6 +217,7 @@ impl Column {
                 instance: InstanceRaw {
                     offset: [center_x, y_pos],
                     scale: [note_width, note_height],
+                    alpha: 1.0,
                 },
             });
         }
@@ -223,6 +228,7 @@ impl Column {
             instance: InstanceRaw {
                 offset: [center_x, end_y],
                 scale: [ln_width, note_height],
+                alpha: 1.0,
             },
         });
     }
@@ -0,0 +1,227 @@
+//! UI strings and their translations.
+//!
+//! Strings are looked up by [`TextKey`] with [`t`]. English is built in; a
+//! locale file (`assets/locales/<language>.toml`) overrides any of them:
+//!
+//! ```toml
+//! settings_title = "Paramètres"
+//! ```
+//!
+//! Keys missing from the file keep their English text.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Directory of the locale files.
+pub const LOCALES_DIR: &str = "assets/locales";
+
+/// Language of the built-in strings.
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+/// A translatable UI string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TextKey {
+    SettingsTitle,
+    Skin,
+    Audio,
+    Gameplay,
+    VisibilityMods,
+    Judgement,
+    Keybinds,
+    Language,
+    Rebind,
+    Cancel,
+    ResetKeybinds,
+    Save,
+    SessionRecap,
+}
+
+impl TextKey {
+    /// Returns all keys.
+    pub fn all() -> &'static [TextKey] {
+        &[
+            TextKey::SettingsTitle,
+            TextKey::Skin,
+            TextKey::Audio,
+            TextKey::Gameplay,
+            TextKey::VisibilityMods,
+            TextKey::Judgement,
+            TextKey::Keybinds,
+            TextKey::Language,
+            TextKey::Rebind,
+            TextKey::Cancel,
+            TextKey::ResetKeybinds,
+            TextKey::Save,
+            TextKey::SessionRecap,
+        ]
+    }
+
+    /// Key of the string in locale files.
+    pub fn id(&self) -> &'static str {
+        match self {
+            TextKey::SettingsTitle => "settings_title",
+            TextKey::Skin => "skin",
+            TextKey::Audio => "audio",
+            TextKey::Gameplay => "gameplay",
+            TextKey::VisibilityMods => "visibility_mods",
+            TextKey::Judgement => "judgement",
+            TextKey::Keybinds => "keybinds",
+            TextKey::Language => "language",
+            TextKey::Rebind => "rebind",
+            TextKey::Cancel => "cancel",
+            TextKey::ResetKeybinds => "reset_keybinds",
+            TextKey::Save => "save",
+            TextKey::SessionRecap => "session_recap",
+        }
+    }
+
+    /// Parses a locale file key.
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::all().iter().copied().find(|key| key.id() == id)
+    }
+
+    /// Built-in English text.
+    pub fn english(&self) -> &'static str {
+        match self {
+            TextKey::SettingsTitle => "Settings",
+            TextKey::Skin => "Skin",
+            TextKey::Audio => "Audio",
+            TextKey::Gameplay => "Gameplay",
+            TextKey::VisibilityMods => "Visibility Mods",
+            TextKey::Judgement => "Judgement",
+            TextKey::Keybinds => "Keybinds",
+            TextKey::Language => "Language (restart to apply)",
+            TextKey::Rebind => "Rebind",
+            TextKey::Cancel => "Cancel",
+            TextKey::ResetKeybinds => "Reset keybinds to defaults",
+            TextKey::Save => "Save",
+            TextKey::SessionRecap => "Session recap",
+        }
+    }
+}
+
+/// Translated strings of a language, over the English ones.
+#[derive(Debug, Clone, Default)]
+pub struct Locale {
+    strings: HashMap<TextKey, String>,
+}
+
+impl Locale {
+    /// The built-in English strings.
+    pub fn english() -> Self {
+        Self::default()
+    }
+
+    /// Parses a locale file. Unknown keys are skipped with a warning.
+    pub fn from_toml(content: &str) -> Result<Self, toml::de::Error> {
+        let entries: HashMap<String, String> = toml::from_str(content)?;
+        let mut strings = HashMap::new();
+        for (id, text) in entries {
+            match TextKey::from_id(&id) {
+                Some(key) => {
+                    strings.insert(key, text);
+                }
+                None => log::warn!("I18N: Unknown string '{}'", id),
+            }
+        }
+        Ok(Self { strings })
+    }
+
+    /// Loads the file of `language` from [`LOCALES_DIR`], English if it is
+    /// missing or invalid.
+    pub fn load(language: &str) -> Self {
+        if language == DEFAULT_LANGUAGE {
+            return Self::english();
+        }
+        let path = locale_path(language);
+        match std::fs::read_to_string(&path) {
+            Ok(content) => Self::from_toml(&content).unwrap_or_else(|e| {
+                log::warn!("I18N: Invalid locale {:?}: {}", path, e);
+                Self::english()
+            }),
+            Err(e) => {
+                log::warn!("I18N: Locale {:?} not loaded: {}", path, e);
+                Self::english()
+            }
+        }
+    }
+
+    /// Text of `key`, English if the locale does not translate it.
+    pub fn get(&self, key: TextKey) -> &str {
+        self.strings
+            .get(&key)
+            .map(String::as_str)
+            .unwrap_or_else(|| key.english())
+    }
+}
+
+fn locale_path(language: &str) -> PathBuf {
+    Path::new(LOCALES_DIR).join(format!("{language}.toml"))
+}
+
+/// Languages with a locale file, English first.
+pub fn available_languages() -> Vec<String> {
+    let mut languages: Vec<String> = std::fs::read_dir(LOCALES_DIR)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension()? != "toml" {
+                return None;
+            }
+            Some(path.file_stem()?.to_str()?.to_string())
+        })
+        .filter(|language| language != DEFAULT_LANGUAGE)
+        .collect();
+    languages.sort();
+    languages.insert(0, DEFAULT_LANGUAGE.to_string());
+    languages
+}
+
+static LOCALE: OnceLock<Locale> = OnceLock::new();
+
+/// Sets the locale used by [`t`]. Only the first call has an effect.
+pub fn set_locale(locale: Locale) {
+    if LOCALE.set(locale).is_err() {
+        log::warn!("I18N: Locale already set");
+    }
+}
+
+/// Text of `key` in the current locale (English until one is set).
+pub fn t(key: TextKey) -> &'static str {
+    match LOCALE.get() {
+        Some(locale) => locale.get(key),
+        None => key.english(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locale_overrides_and_falls_back() {
+        let locale = Locale::from_toml(
+            r#"
+            settings_title = "Paramètres"
+            not_a_string = "ignored"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(locale.get(TextKey::SettingsTitle), "Paramètres");
+        // Missing from the file: English
+        assert_eq!(locale.get(TextKey::Save), "Save");
+        assert_eq!(Locale::english().get(TextKey::SettingsTitle), "Settings");
+
+        assert!(Locale::from_toml("settings_title = ").is_err());
+    }
+
+    #[test]
+    fn test_text_key_ids_roundtrip() {
+        for key in TextKey::all() {
+            assert_eq!(TextKey::from_id(key.id()), Some(*key));
+        }
+    }
+}
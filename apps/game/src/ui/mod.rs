@@ -4,10 +4,14 @@
 //! - `common/`: Reusable wgpu components (cube, particles)
 //! - `page/`: Full-screen page layouts (main_menu, etc.)
 //! - `gameplay/`: In-game UI (playfield, HUD, etc.)
+//! - `theme`: egui visuals built from the skin colors
+//! - `i18n`: translatable UI strings
 
 pub mod common;
 pub mod gameplay;
+pub mod i18n;
 pub mod page;
 pub mod song_select;
+pub mod theme;
 // pub mod menu;
 // pub mod editor;
@@ -6,7 +6,7 @@
 //! - Background: Particle animation
 
 use crate::graphics::theme::{PRISM_PRIMARY, PRISM_PRIMARY_HOVER, PRISM_TEXT};
-use crate::ui::common::{CubeConfig, CubeRenderer, ParticleConfig, ParticleSystem};
+use crate::ui::common::{CubeConfig, CubeRenderer, ParticleConfig, ParticleField, ParticleSystem};
 use egui::epaint::StrokeKind;
 
 use egui::{Color32, Vec2};
@@ -34,13 +34,7 @@ impl MainMenuPage {
     }
 
     /// Initialize GPU resources (call once after device is available)
-    pub fn init_gpu(
-        &mut self,
-        device: &wgpu::Device,
-        format: wgpu::TextureFormat,
-        width: f32,
-        height: f32,
-    ) {
+    pub fn init_gpu(&mut self, device: &wgpu::Device, format: wgpu::TextureFormat) {
         if self.cube.is_none() {
             self.cube = Some(CubeRenderer::new(device, format, CubeConfig::large()));
         }
@@ -48,31 +42,26 @@ impl MainMenuPage {
             self.particles = Some(ParticleSystem::new(
                 device,
                 format,
-                width,
-                height,
-                ParticleConfig::default(),
+                &ParticleConfig::default(),
             ));
         }
     }
 
-    /// Resize the particle system
-    pub fn resize(&mut self, width: f32, height: f32) {
-        if let Some(ref mut particles) = self.particles {
-            particles.resize(width, height);
-        }
-    }
-
     /// Render the 3D elements (cube + particles) to the render pass.
     /// Call this BEFORE rendering egui.
+    ///
+    /// `field` is the particle state of the latest snapshot; the logic
+    /// thread animates it.
     pub fn render_3d<'a>(
-        &'a mut self,
+        &'a self,
         render_pass: &mut wgpu::RenderPass<'a>,
         queue: &wgpu::Queue,
         aspect_ratio: f32,
+        field: &ParticleField,
     ) {
         // Render particles first (background)
-        if let Some(ref mut particles) = self.particles {
-            particles.render(render_pass, queue);
+        if let Some(ref particles) = self.particles {
+            particles.render(render_pass, queue, field);
         }
 
         // Render cube
@@ -296,3 +285,73 @@ impl Default for MainMenuPage {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::capture::capture_frame;
+
+    #[test]
+    fn test_3d_layer_reaches_the_screen_with_the_frame_submit() {
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::default());
+        let Ok(adapter) =
+            pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions::default()))
+        else {
+            eprintln!("No GPU adapter, test skipped");
+            return;
+        };
+        let (device, queue) =
+            pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor::default()))
+                .expect("Failed to create device");
+
+        let format = wgpu::TextureFormat::Rgba8Unorm;
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Main Menu Test Target"),
+            size: wgpu::Extent3d {
+                width: 64,
+                height: 64,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        let mut page = MainMenuPage::new();
+        page.init_gpu(&device, format);
+        let field = ParticleField::new(ParticleConfig::default(), 64.0, 64.0);
+
+        // Same layout as the renderer: the page records into the frame's pass
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        {
+            let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Main Menu Test Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                        store: wgpu::StoreOp::Store,
+                    },
+                    depth_slice: None,
+                })],
+                ..Default::default()
+            });
+            page.render_3d(&mut pass, &queue, 1.0, &field);
+        }
+
+        // Nothing was submitted yet: the target is still blank
+        let before = capture_frame(&device, &queue, &texture).expect("capture");
+        assert!(before.pixels().all(|pixel| pixel.0 == [0, 0, 0, 0]));
+
+        // The single frame submit draws the clear and the 3D layer
+        queue.submit(std::iter::once(encoder.finish()));
+        let after = capture_frame(&device, &queue, &texture).expect("capture");
+        assert!(after.pixels().any(|pixel| pixel.0 == [0, 0, 0, 255]));
+        assert!(after.pixels().any(|pixel| pixel.0 != [0, 0, 0, 255]));
+    }
+}
@@ -1,13 +1,16 @@
 //! Song selection screen page.
 use crate::input::events::GameAction;
+use crate::state::global::SessionStats;
 use crate::state::menu::SongSelectMode;
 use crate::state::{GameResultData, MenuState};
+use crate::ui::i18n::{TextKey, t};
 use crate::ui::song_select::beatmap_info::{BeatmapInfo, InfoTab};
-use crate::ui::song_select::leaderboard::{Leaderboard, ScoreCard};
+use crate::ui::song_select::leaderboard::{Leaderboard, LeaderboardEvent, ScoreCard};
 use crate::ui::song_select::search_panel::{SearchPanel, SearchPanelEvent};
 use crate::ui::song_select::song_list::SongList;
-use database::MenuSearchFilters;
+use database::{LeaderboardFilter, MenuSearchFilters};
 use egui::{Color32, RichText, TextureId};
+use skin::menus::song_select::DEFAULT_RATING_THRESHOLDS;
 use wgpu::TextureView;
 
 /// Textures for UI panel backgrounds
@@ -32,6 +35,10 @@ pub struct SongSelectScreen {
     leaderboard: Leaderboard,
     beatmap_info: BeatmapInfo,
     search_panel: SearchPanel,
+    /// Text typed in the difficulty name filter.
+    difficulty_filter: String,
+    /// Set the difficulty filter was typed for.
+    difficulty_filter_set: Option<i64>,
 }
 
 impl SongSelectScreen {
@@ -41,6 +48,8 @@ impl SongSelectScreen {
             leaderboard: Leaderboard::new(),
             beatmap_info: BeatmapInfo::new(),
             search_panel: SearchPanel::new(),
+            difficulty_filter: String::new(),
+            difficulty_filter_set: None,
         }
     }
 
@@ -121,6 +130,23 @@ impl SongSelectScreen {
                         // Search Panel moved to Mods tab
                         ui.add_space(8.0);
 
+                        // The filter is typed for one set: start over on another
+                        let selected_set = menu_state.selected_set_id();
+                        if selected_set != self.difficulty_filter_set {
+                            self.difficulty_filter.clear();
+                            self.difficulty_filter_set = selected_set;
+                        }
+
+                        // Difficulty name filter, only useful for sets with several difficulties
+                        let has_many_difficulties = menu_state
+                            .get_selected_beatmapset()
+                            .is_some_and(|(_, beatmaps)| beatmaps.len() > 1);
+                        if has_many_difficulties
+                            && let Some(act) = self.render_difficulty_filter(ui, menu_state)
+                        {
+                            action_triggered = Some(act);
+                        }
+
                         ui.add_space(10.0);
 
                         // Song Wheel
@@ -166,11 +192,10 @@ impl SongSelectScreen {
 
                             // Update leaderboard logic
                             if let Some(bm) = beatmap.as_ref() {
-                                self.refresh_leaderboard(
-                                    menu_state,
-                                    &bm.beatmap.hash,
-                                    bm.beatmap.note_count as usize,
-                                );
+                                let total_notes = menu_state
+                                    .cached_judgeable_notes(&bm.beatmap.hash)
+                                    .unwrap_or(bm.beatmap.note_count as usize);
+                                self.refresh_leaderboard(menu_state, &bm.beatmap.hash, total_notes);
                             } else {
                                 self.leaderboard.update_scores(Vec::new());
                             }
@@ -185,14 +210,28 @@ impl SongSelectScreen {
                                 rate,
                                 hit_window_mode,
                                 hit_window_value,
-                                rate_specific_ratings,
+                                rate_specific_ratings.as_deref(),
                                 panel_textures.beatmap_info_bg,
                                 &menu_state.available_calculators,
                                 &menu_state.active_calculator,
                                 current_ssr,
+                                rating_colors.map_or(&DEFAULT_RATING_THRESHOLDS, |colors| {
+                                    &colors.thresholds
+                                }),
                             ) {
                                 calculator_changed = Some(new_calc);
                             }
+                            if menu_state.is_selected_too_large() {
+                                ui.label(
+                                    RichText::new("Chart too large to rate").color(Color32::GRAY),
+                                );
+                            } else if let Some(err) = menu_state.selected_calc_error() {
+                                ui.label(
+                                    RichText::new("⚠ Difficulty calc failed")
+                                        .color(Color32::from_rgb(220, 90, 90)),
+                                )
+                                .on_hover_text(err);
+                            }
                             ui.add_space(10.0);
                         }
 
@@ -221,18 +260,34 @@ impl SongSelectScreen {
 
                                 match self.beatmap_info.active_tab {
                                     InfoTab::Scores => {
+                                        if let Some(action) = Self::render_leaderboard_filters(
+                                            ui,
+                                            menu_state,
+                                            hit_window_mode,
+                                            hit_window_value,
+                                        ) {
+                                            action_triggered = Some(action);
+                                        }
+                                        ui.add_space(6.0);
+
                                         let cached_chart = menu_state
                                             .get_cached_chart()
                                             .map(|c| c.chart.as_slice());
 
-                                        let clicked_result = self.leaderboard.render(
+                                        match self.leaderboard.render(
                                             ui,
                                             diff_name.as_deref(),
                                             hit_window,
                                             cached_chart,
-                                        );
-                                        if let Some(result_data) = clicked_result {
-                                            result_data_triggered = Some(result_data);
+                                        ) {
+                                            Some(LeaderboardEvent::Open(result_data)) => {
+                                                result_data_triggered = Some(result_data);
+                                            }
+                                            Some(LeaderboardEvent::Delete(hash)) => {
+                                                action_triggered =
+                                                    Some(GameAction::DeleteReplay(hash));
+                                            }
+                                            None => {}
                                         }
                                     }
                                     InfoTab::Breakdown => {
@@ -246,8 +301,9 @@ impl SongSelectScreen {
                                         self.beatmap_info.render_breakdown_tab(
                                             ui,
                                             beatmap.as_ref(),
-                                            rate_specific_ratings,
+                                            rate_specific_ratings.as_deref(),
                                             &menu_state.active_calculator,
+                                            menu_state.active_calculator_scale(),
                                             current_ssr,
                                         );
                                     }
@@ -301,6 +357,10 @@ impl SongSelectScreen {
                     });
             });
 
+        if let Some(session) = &menu_state.session_recap {
+            Self::render_session_recap(ctx, session);
+        }
+
         (
             action_triggered,
             result_data_triggered,
@@ -309,6 +369,64 @@ impl SongSelectScreen {
         )
     }
 
+    /// Stats of the plays since launch, over the song wheel.
+    fn render_session_recap(ctx: &egui::Context, session: &SessionStats) {
+        egui::Window::new(t(TextKey::SessionRecap))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                egui::Grid::new("session_recap_grid")
+                    .num_columns(2)
+                    .spacing([24.0, 6.0])
+                    .show(ui, |ui| {
+                        ui.label("Plays");
+                        ui.label(session.plays.to_string());
+                        ui.end_row();
+                        ui.label("Notes hit");
+                        ui.label(session.notes_hit.to_string());
+                        ui.end_row();
+                        ui.label("Average accuracy");
+                        ui.label(match session.average_accuracy() {
+                            Some(accuracy) => format!("{:.2}%", accuracy),
+                            None => "-".to_string(),
+                        });
+                        ui.end_row();
+                        ui.label("Time played");
+                        ui.label(session.time_played_label());
+                        ui.end_row();
+                    });
+            });
+    }
+
+    fn render_difficulty_filter(
+        &mut self,
+        ui: &mut egui::Ui,
+        menu_state: &MenuState,
+    ) -> Option<GameAction> {
+        let mut action = None;
+        ui.horizontal(|ui| {
+            ui.add_space(20.0);
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut self.difficulty_filter)
+                    .hint_text("Find difficulty...")
+                    .desired_width(220.0),
+            );
+            if response.changed() {
+                action = Some(GameAction::FilterDifficulties(
+                    self.difficulty_filter.clone(),
+                ));
+            }
+
+            if !self.difficulty_filter.trim().is_empty()
+                && menu_state.matching_difficulties().is_empty()
+            {
+                ui.label(RichText::new("No matching difficulty").color(Color32::GRAY));
+            }
+        });
+        action
+    }
+
     fn render_tab_selector(&mut self, ui: &mut egui::Ui) {
         ui.horizontal(|ui| {
             ui.style_mut().spacing.item_spacing.x = 20.0;
@@ -436,6 +554,15 @@ impl SongSelectScreen {
                     }
                 }
 
+                ui.add_space(20.0);
+                if ui
+                    .add(egui::Button::new(RichText::new("RANDOM 🎲").size(18.0)))
+                    .on_hover_text("F7")
+                    .clicked()
+                {
+                    action = Some(GameAction::RandomSelect);
+                }
+
                 ui.add_space(20.0);
 
                 let is_scanning =
@@ -468,6 +595,40 @@ impl SongSelectScreen {
         action
     }
 
+    /// Leaderboard filter toggles (all off by default).
+    ///
+    /// The rate and judge filters follow the current selection, so a new
+    /// filter is emitted whenever the rate or hit window changes.
+    fn render_leaderboard_filters(
+        ui: &mut egui::Ui,
+        menu_state: &MenuState,
+        hit_window_mode: crate::models::settings::HitWindowMode,
+        hit_window_value: f64,
+    ) -> Option<GameAction> {
+        let current = &menu_state.leaderboard_filter;
+        let mut hide_modded = current.hide_modded;
+        let mut by_rate = current.rate.is_some();
+        let mut by_judge = current.hit_window.is_some();
+        let mut include_incomplete = current.include_incomplete;
+
+        let judge_label = hit_window_mode.short_label(hit_window_value);
+
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut hide_modded, "Hide modded");
+            ui.checkbox(&mut by_rate, format!("{:.2}x only", menu_state.rate));
+            ui.checkbox(&mut by_judge, format!("{judge_label} only"));
+            ui.checkbox(&mut include_incomplete, "Show abandoned");
+        });
+
+        let filter = LeaderboardFilter {
+            hide_modded,
+            rate: by_rate.then_some(menu_state.rate),
+            hit_window: by_judge.then_some((hit_window_mode.into(), hit_window_value)),
+            include_incomplete,
+        };
+        (filter != *current).then_some(GameAction::SetLeaderboardFilter(filter))
+    }
+
     fn refresh_leaderboard(
         &mut self,
         menu_state: &MenuState,
@@ -9,6 +9,7 @@ use super::hexagon_chart::HexagonChart;
 use crate::models::settings::HitWindowMode;
 use chart::BeatmapSsr;
 use database::models::{BeatmapRating, BeatmapWithRatings, Beatmapset};
+use skin::menus::song_select::rating_tier;
 
 /// UI color configuration for the beatmap info panel.
 #[derive(Clone)]
@@ -52,11 +53,31 @@ impl Default for BeatmapInfoColors {
     }
 }
 
+/// Numeric range and unit of a calculator's ratings.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RatingScale {
+    /// Rating of the hardest charts the calculator is expected to rate.
+    pub max: f64,
+    /// Unit shown next to ratings (e.g. "MSD", "★").
+    pub unit: String,
+}
+
+impl RatingScale {
+    pub fn new(max: f64, unit: impl Into<String>) -> Self {
+        Self {
+            max,
+            unit: unit.into(),
+        }
+    }
+}
+
 /// Calculator info for the dropdown.
 #[derive(Clone, Debug)]
 pub struct CalculatorOption {
     pub id: String,
     pub display_name: String,
+    /// Rating scale, when known (unknown calculators are shown unitless).
+    pub scale: Option<RatingScale>,
 }
 
 impl CalculatorOption {
@@ -64,13 +85,21 @@ impl CalculatorOption {
         Self {
             id: id.into(),
             display_name: display_name.into(),
+            scale: None,
         }
     }
+
+    pub fn with_scale(mut self, scale: RatingScale) -> Self {
+        self.scale = Some(scale);
+        self
+    }
 }
 
 pub struct BeatmapInfo {
     pub colors: BeatmapInfoColors,
     pub active_tab: InfoTab,
+    /// Fixed scale of the breakdown hexagon (`None` = scale to the highest metric).
+    pub hexagon_fixed_max: Option<f32>,
 }
 
 #[derive(Clone, Copy, PartialEq, Eq)]
@@ -85,6 +114,7 @@ impl BeatmapInfo {
         Self {
             colors: BeatmapInfoColors::default(),
             active_tab: InfoTab::Scores,
+            hexagon_fixed_max: None,
         }
     }
 
@@ -97,6 +127,7 @@ impl BeatmapInfo {
     ///
     /// `active_calculator` - the currently selected calculator ID from MenuState
     /// `current_ssr` - the calculated SSR for the active calculator (from difficulty_cache)
+    /// `rating_thresholds` - tier bounds of the skin's rating color ramp
     /// Returns the new calculator ID if the user changed it via dropdown
     pub fn render(
         &mut self,
@@ -111,8 +142,13 @@ impl BeatmapInfo {
         available_calculators: &[CalculatorOption],
         active_calculator: &str,
         current_ssr: Option<&BeatmapSsr>,
+        rating_thresholds: &[f64; 4],
     ) -> Option<String> {
         let colors = self.colors.clone();
+        let scale = available_calculators
+            .iter()
+            .find(|calc| calc.id == active_calculator)
+            .and_then(|calc| calc.scale.as_ref());
         let rounding = CornerRadius::same(12);
         let margin = Margin::symmetric(8, 6);
         let mut calculator_changed: Option<String> = None;
@@ -198,13 +234,25 @@ impl BeatmapInfo {
                     .show(ui, |ui| {
                         // 1. Overall Rating (Top)
                         if let Some(ssr) = current_ssr {
-                            self.render_overall_rating_from_ssr(ui, ssr, &colors);
+                            self.render_overall_rating_from_ssr(
+                                ui,
+                                ssr,
+                                scale,
+                                rating_thresholds,
+                                &colors,
+                            );
                         } else if let Some(bm) = beatmap {
                             // Fallback to finding rating in list
                             let ratings_slice =
                                 override_ratings.or_else(|| Some(bm.ratings.as_slice()));
                             if let Some(rating) = find_rating(ratings_slice, active_calculator) {
-                                self.render_overall_rating(ui, rating, &colors);
+                                self.render_overall_rating(
+                                    ui,
+                                    rating,
+                                    scale,
+                                    rating_thresholds,
+                                    &colors,
+                                );
                             }
                         }
 
@@ -266,6 +314,7 @@ impl BeatmapInfo {
         beatmap: Option<&BeatmapWithRatings>,
         override_ratings: Option<&[BeatmapRating]>,
         active_calculator: &str,
+        scale: Option<&RatingScale>,
         current_ssr: Option<&BeatmapSsr>,
     ) {
         let ratings_slice = override_ratings.or_else(|| beatmap.map(|bm| bm.ratings.as_slice()));
@@ -298,14 +347,18 @@ impl BeatmapInfo {
         };
 
         if let Some(metrics) = metrics {
-            // Find max value for scaling
+            // Find max value for scaling, with a floor relative to the calculator's range
+            let min_max = scale.map_or(10.0, |s| s.max as f32 / 4.0);
             let max_val = metrics
                 .iter()
                 .map(|(_, v, _)| *v)
                 .fold(0.0f32, |a, b| a.max(b as f32))
-                .max(10.0);
+                .max(min_max);
 
             let mut chart = HexagonChart::new(ui.available_width().min(350.0));
+            if let Some(fixed_max) = self.hexagon_fixed_max {
+                chart = chart.with_fixed_max(fixed_max);
+            }
 
             for (name, val, color) in metrics {
                 chart = chart.add_axis(name, val as f32, max_val, color);
@@ -349,7 +402,7 @@ impl BeatmapInfo {
                     colors,
                 );
 
-                // Display BPM adjusted for current rate
+                // Display BPM, length and density adjusted for current rate
                 let effective_bpm = bm.beatmap.bpm * rate;
                 self.render_badge(
                     ui,
@@ -358,6 +411,34 @@ impl BeatmapInfo {
                     badge_bg,
                     colors,
                 );
+
+                let length_secs = (bm.beatmap.duration_ms as f64 / 1000.0 / rate) as u64;
+                self.render_badge(
+                    ui,
+                    "⏱",
+                    &format!("{}:{:02}", length_secs / 60, length_secs % 60),
+                    badge_bg,
+                    colors,
+                );
+
+                self.render_badge(
+                    ui,
+                    "NPS",
+                    &format!("{:.1}", bm.beatmap.nps * rate),
+                    badge_bg,
+                    colors,
+                );
+                // Peak density says more about a map than its average.
+                // Rows scanned before the column existed read 0 until rescanned.
+                if bm.beatmap.peak_nps > 0.0 {
+                    self.render_badge(
+                        ui,
+                        "Peak",
+                        &format!("{:.0}", bm.beatmap.peak_nps * rate),
+                        badge_bg,
+                        colors,
+                    );
+                }
             }
         });
     }
@@ -450,10 +531,7 @@ impl BeatmapInfo {
         colors: &BeatmapInfoColors,
         has_bg: bool,
     ) {
-        let hit_window_text = match hit_window_mode {
-            HitWindowMode::OsuOD => format!("OD {:.1}", hit_window_value),
-            HitWindowMode::EtternaJudge => format!("J{}", hit_window_value as u8),
-        };
+        let hit_window_text = hit_window_mode.short_label(hit_window_value);
 
         let bg = if has_bg {
             Color32::from_rgba_unmultiplied(0, 0, 0, 120)
@@ -506,21 +584,32 @@ impl BeatmapInfo {
         &self,
         ui: &mut Ui,
         rating: &BeatmapRating,
+        scale: Option<&RatingScale>,
+        thresholds: &[f64; 4],
         colors: &BeatmapInfoColors,
     ) {
-        self.render_overall_value(ui, rating.overall, colors);
+        self.render_overall_value(ui, rating.overall, scale, thresholds, colors);
     }
 
     fn render_overall_rating_from_ssr(
         &self,
         ui: &mut Ui,
         ssr: &BeatmapSsr,
+        scale: Option<&RatingScale>,
+        thresholds: &[f64; 4],
         colors: &BeatmapInfoColors,
     ) {
-        self.render_overall_value(ui, ssr.overall, colors);
+        self.render_overall_value(ui, ssr.overall, scale, thresholds, colors);
     }
 
-    fn render_overall_value(&self, ui: &mut Ui, overall: f64, colors: &BeatmapInfoColors) {
+    fn render_overall_value(
+        &self,
+        ui: &mut Ui,
+        overall: f64,
+        scale: Option<&RatingScale>,
+        thresholds: &[f64; 4],
+        colors: &BeatmapInfoColors,
+    ) {
         ui.horizontal(|ui| {
             ui.label(
                 RichText::new("Overall")
@@ -529,24 +618,32 @@ impl BeatmapInfo {
             );
 
             ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if let Some(scale) = scale {
+                    ui.label(
+                        RichText::new(&scale.unit)
+                            .size(14.0)
+                            .color(colors.text_secondary),
+                    );
+                }
                 ui.label(
                     RichText::new(format!("{:.2}", overall))
                         .size(26.0)
                         .strong()
-                        .color(self.get_difficulty_color(overall, colors)),
+                        .color(difficulty_color(overall, thresholds, colors)),
                 );
             });
         });
     }
+}
 
-    fn get_difficulty_color(&self, rating: f64, colors: &BeatmapInfoColors) -> Color32 {
-        match rating {
-            r if r < 15.0 => colors.rating_stream,
-            r if r < 22.0 => colors.rating_jumpstream,
-            r if r < 28.0 => colors.rating_handstream,
-            r if r < 34.0 => colors.rating_stamina,
-            _ => colors.rating_jackspeed,
-        }
+/// Color of an overall rating, by its tier in the skin's ramp.
+fn difficulty_color(rating: f64, thresholds: &[f64; 4], colors: &BeatmapInfoColors) -> Color32 {
+    match rating_tier(thresholds, rating) {
+        0 => colors.rating_stream,
+        1 => colors.rating_jumpstream,
+        2 => colors.rating_handstream,
+        3 => colors.rating_stamina,
+        _ => colors.rating_jackspeed,
     }
 }
 
@@ -563,7 +660,46 @@ fn find_rating<'a>(
 /// Default calculators (builtin).
 pub fn default_calculators() -> Vec<CalculatorOption> {
     vec![
-        CalculatorOption::new("etterna", "Etterna"),
-        CalculatorOption::new("osu", "osu!"),
+        CalculatorOption::new("etterna", "Etterna").with_scale(RatingScale::new(40.0, "MSD")),
+        CalculatorOption::new("osu", "osu!").with_scale(RatingScale::new(10.0, "★")),
     ]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use skin::menus::song_select::DEFAULT_RATING_THRESHOLDS;
+
+    #[test]
+    fn test_default_calculator_scales() {
+        let calculators = default_calculators();
+        let unit = |id: &str| {
+            calculators
+                .iter()
+                .find(|calc| calc.id == id)
+                .and_then(|calc| calc.scale.as_ref())
+                .map(|scale| scale.unit.as_str())
+        };
+        assert_eq!(unit("etterna"), Some("MSD"));
+        assert_eq!(unit("osu"), Some("★"));
+    }
+
+    #[test]
+    fn test_overall_color_follows_skin_thresholds() {
+        let colors = BeatmapInfoColors::default();
+        // 4.2★ is easy on the MSD ramp but hard on an osu! star ramp
+        let stars = [2.0, 3.5, 5.0, 6.5];
+        assert_eq!(
+            difficulty_color(4.2, &DEFAULT_RATING_THRESHOLDS, &colors),
+            colors.rating_stream
+        );
+        assert_eq!(
+            difficulty_color(4.2, &stars, &colors),
+            colors.rating_handstream
+        );
+        assert_eq!(
+            difficulty_color(8.0, &stars, &colors),
+            colors.rating_jackspeed
+        );
+    }
+}
@@ -1,8 +1,10 @@
 //! Shared difficulty utilities for song select UI components.
 
+use crate::state::menu::DifficultyCache;
 use database::models::BeatmapWithRatings;
 use skin::menus::song_select::RatingColorsConfig;
 use egui::Color32;
+use engine::Palette;
 
 /// Converts a skin Color ([f32; 4]) to egui Color32.
 pub fn color_to_egui(color: [f32; 4]) -> Color32 {
@@ -14,16 +16,46 @@ pub fn color_to_egui(color: [f32; 4]) -> Color32 {
     )
 }
 
+/// Skin rating colors, with the tier ramp of `palette` when it overrides it.
+pub fn rating_colors_with_palette(
+    colors: &RatingColorsConfig,
+    palette: Palette,
+) -> RatingColorsConfig {
+    let mut colors = colors.clone();
+    if let Some([stream, jumpstream, handstream, stamina, jackspeed]) = palette.rating_ramp() {
+        colors.stream = stream;
+        colors.jumpstream = jumpstream;
+        colors.handstream = handstream;
+        colors.stamina = stamina;
+        colors.jackspeed = jackspeed;
+    }
+    colors
+}
+
 /// Returns the appropriate color for a given difficulty rating.
-/// Uses thresholds based on Etterna-style difficulty scaling.
+/// Tier thresholds come from the skin (Etterna-style scaling by default).
 pub fn get_difficulty_color(rating: f64, colors: &RatingColorsConfig) -> Color32 {
-    match rating {
-        r if r < 15.0 => color_to_egui(colors.stream), // Easy (green)
-        r if r < 22.0 => color_to_egui(colors.jumpstream), // Normal (orange)
-        r if r < 28.0 => color_to_egui(colors.handstream), // Hard (red-orange)
-        r if r < 34.0 => color_to_egui(colors.stamina), // Expert (pink)
-        _ => color_to_egui(colors.jackspeed),          // Master (purple)
-    }
+    color_to_egui(colors.color_for_rating(rating))
+}
+
+/// Overall rating for the given calculator, from the stored ratings or,
+/// failing that, from the on-demand cache at the given rate.
+pub fn get_calculator_rating(
+    beatmap: &BeatmapWithRatings,
+    calculator: &str,
+    cache: &DifficultyCache,
+    rate: f64,
+) -> Option<f64> {
+    beatmap
+        .ratings
+        .iter()
+        .find(|r| r.name.eq_ignore_ascii_case(calculator))
+        .map(|r| r.overall)
+        .or_else(|| {
+            cache
+                .get(&beatmap.beatmap.hash, calculator, rate)
+                .map(|ssr| ssr.overall)
+        })
 }
 
 /// Computes the difficulty range (min, max) for a set of beatmaps.
@@ -32,15 +64,12 @@ pub fn get_difficulty_color(rating: f64, colors: &RatingColorsConfig) -> Color32
 pub fn get_difficulty_range(
     beatmaps: &[BeatmapWithRatings],
     calculator: &str,
+    cache: &DifficultyCache,
+    rate: f64,
 ) -> Option<(f64, f64)> {
     let ratings: Vec<f64> = beatmaps
         .iter()
-        .filter_map(|bm| {
-            bm.ratings
-                .iter()
-                .find(|r| r.name.eq_ignore_ascii_case(calculator))
-                .map(|r| r.overall)
-        })
+        .filter_map(|bm| get_calculator_rating(bm, calculator, cache, rate))
         .collect();
 
     if ratings.is_empty() {
@@ -64,12 +93,96 @@ pub fn get_difficulty_range(
     Some((min, max))
 }
 
+/// Rate the song card spread badge is computed at.
+pub const SPREAD_RATE: f64 = 1.0;
+
+/// Number of difficulties and rating range of a beatmapset.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DifficultySpread {
+    pub count: usize,
+    /// (min, max) overall rating at [`SPREAD_RATE`], `None` until rated.
+    pub range: Option<(f64, f64)>,
+}
+
+/// Computes the difficulty spread of a set at [`SPREAD_RATE`].
+pub fn get_difficulty_spread(
+    beatmaps: &[BeatmapWithRatings],
+    calculator: &str,
+    cache: &DifficultyCache,
+) -> DifficultySpread {
+    DifficultySpread {
+        count: beatmaps.len(),
+        range: get_difficulty_range(beatmaps, calculator, cache, SPREAD_RATE),
+    }
+}
+
 /// Get the overall rating for a specific beatmap and calculator.
-pub fn get_beatmap_rating(beatmap: &BeatmapWithRatings, calculator: &str) -> Option<f64> {
-    beatmap
-        .ratings
-        .iter()
-        .find(|r| r.name.eq_ignore_ascii_case(calculator))
-        .map(|r| r.overall)
+pub fn get_beatmap_rating(
+    beatmap: &BeatmapWithRatings,
+    calculator: &str,
+    cache: &DifficultyCache,
+    rate: f64,
+) -> Option<f64> {
+    get_calculator_rating(beatmap, calculator, cache, rate)
         .or_else(|| beatmap.ratings.first().map(|r| r.overall))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use database::BeatmapRating;
+    use database::models::Beatmap;
+
+    fn rated_beatmap(hash: &str, overall: Option<f64>) -> BeatmapWithRatings {
+        let ratings = overall
+            .map(|overall| BeatmapRating {
+                id: 0,
+                beatmap_hash: hash.to_string(),
+                name: "etterna".to_string(),
+                overall,
+                stream: 0.0,
+                jumpstream: 0.0,
+                handstream: 0.0,
+                stamina: 0.0,
+                jackspeed: 0.0,
+                chordjack: 0.0,
+                technical: 0.0,
+            })
+            .into_iter()
+            .collect();
+        BeatmapWithRatings::new(
+            Beatmap {
+                hash: hash.to_string(),
+                beatmapset_id: 1,
+                path: format!("{hash}.osu"),
+                difficulty_name: None,
+                note_count: 100,
+                duration_ms: 60_000,
+                nps: 5.0,
+                peak_nps: 8.0,
+                bpm: 180.0,
+                key_count: 4,
+            },
+            ratings,
+        )
+    }
+
+    #[test]
+    fn test_difficulty_spread() {
+        let cache = DifficultyCache::new();
+        let set = [
+            rated_beatmap("a", Some(18.2)),
+            rated_beatmap("b", Some(12.5)),
+            rated_beatmap("c", Some(27.9)),
+        ];
+        let spread = get_difficulty_spread(&set, "etterna", &cache);
+        assert_eq!(spread.count, 3);
+        assert_eq!(spread.range, Some((12.5, 27.9)));
+
+        // Unrated sets still report their difficulty count
+        let unrated = [rated_beatmap("d", None), rated_beatmap("e", None)];
+        let spread = get_difficulty_spread(&unrated, "etterna", &cache);
+        assert_eq!(spread.count, 2);
+        assert_eq!(spread.range, None);
+    }
+}
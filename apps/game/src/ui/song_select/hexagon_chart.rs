@@ -1,8 +1,16 @@
 use egui::{
     Color32, FontId, Painter, Pos2, Rect, Response, Sense, Shape, Stroke, Ui, Vec2, pos2, vec2,
 };
+use image::RgbaImage;
 use std::f32::consts::PI;
 
+/// Number of concentric webs behind the data polygon.
+const WEB_STEPS: usize = 4;
+const WEB_COLOR: Color32 = Color32::from_rgba_premultiplied(30, 30, 30, 30);
+const ACTIVE_COLOR: Color32 = Color32::from_rgb(255, 0, 60); // Prism Red
+const FILL_COLOR: Color32 = Color32::from_rgba_premultiplied(255, 0, 60, 50);
+const STROKE_COLOR: Color32 = Color32::from_rgb(255, 50, 100);
+
 /// Data for one axis of the hexagon chart.
 pub struct HexagonChartAxis {
     pub label: String,
@@ -11,9 +19,18 @@ pub struct HexagonChartAxis {
     pub color: Color32,
 }
 
+impl HexagonChartAxis {
+    /// Share of the axis filled by the value (slightly overflowing is allowed).
+    pub fn fill_fraction(&self) -> f32 {
+        (self.value / self.max_value).clamp(0.0, 1.1)
+    }
+}
+
 pub struct HexagonChart {
     pub axes: Vec<HexagonChartAxis>,
     pub size: f32,
+    /// Fixed scale shared by every axis; `None` keeps the per-axis max.
+    pub fixed_max: Option<f32>,
 }
 
 impl HexagonChart {
@@ -21,14 +38,25 @@ impl HexagonChart {
         Self {
             axes: Vec::new(),
             size,
+            fixed_max: None,
+        }
+    }
+
+    /// Scales every axis against `max` instead of the given max values,
+    /// so charts from different maps or calculators are comparable.
+    pub fn with_fixed_max(mut self, max: f32) -> Self {
+        self.fixed_max = Some(max);
+        for axis in &mut self.axes {
+            axis.max_value = max;
         }
+        self
     }
 
     pub fn add_axis(mut self, label: &str, value: f32, max_value: f32, color: Color32) -> Self {
         self.axes.push(HexagonChartAxis {
             label: label.to_string(),
             value,
-            max_value,
+            max_value: self.fixed_max.unwrap_or(max_value),
             color,
         });
         self
@@ -56,75 +84,97 @@ impl HexagonChart {
             return;
         }
 
-        let steps = 4;
-        let stroke = Stroke::new(1.0, Color32::from_white_alpha(30));
+        let stroke = Stroke::new(1.0, WEB_COLOR);
 
-        for s in 1..=steps {
-            let r = radius * (s as f32 / steps as f32);
-            let mut points = Vec::with_capacity(n);
-
-            for i in 0..n {
-                let angle = (i as f32 * 2.0 * PI / n as f32) - PI / 2.0;
-                points.push(center + vec2(r * angle.cos(), r * angle.sin()));
-            }
-
-            // Close the loop
-            points.push(points[0]);
-
-            painter.add(Shape::line(points, stroke));
+        for ring in web_rings(center, radius, n) {
+            painter.add(Shape::line(ring, stroke));
         }
 
         // Draw spokes
         for i in 0..n {
-            let angle = (i as f32 * 2.0 * PI / n as f32) - PI / 2.0;
-            let end_point = center + vec2(radius * angle.cos(), radius * angle.sin());
-            painter.line_segment([center, end_point], stroke);
+            painter.line_segment([center, axis_point(center, radius, i, n)], stroke);
         }
     }
 
     fn draw_data_polygon(&self, painter: &Painter, center: Pos2, radius: f32) {
-        let n = self.axes.len();
-        if n < 3 {
+        if self.axes.len() < 3 {
             return;
         }
 
-        let active_color = Color32::from_rgb(255, 0, 60); // Prism Red
-        let fill_color = Color32::from_rgba_premultiplied(255, 0, 60, 50);
-        let stroke_color = Color32::from_rgb(255, 50, 100);
-
-        let mut points = Vec::with_capacity(n);
-
-        for (i, axis) in self.axes.iter().enumerate() {
-            let angle = (i as f32 * 2.0 * PI / n as f32) - PI / 2.0;
-
-            // Normalized value 0.0 - 1.0 clamped
-            let t = (axis.value / axis.max_value).clamp(0.0, 1.1);
-            let r = radius * t;
-
-            points.push(center + vec2(r * angle.cos(), r * angle.sin()));
-        }
+        let points = self.data_points(center, radius);
 
         painter.add(Shape::convex_polygon(
             points.clone(),
-            fill_color,
-            Stroke::new(2.0, stroke_color),
+            FILL_COLOR,
+            Stroke::new(2.0, STROKE_COLOR),
         ));
 
         // Draw points at vertices
         for point in points {
-            painter.circle_filled(point, 3.0, active_color);
+            painter.circle_filled(point, 3.0, ACTIVE_COLOR);
         }
     }
 
+    /// Vertices of the data polygon, one per axis.
+    fn data_points(&self, center: Pos2, radius: f32) -> Vec<Pos2> {
+        let n = self.axes.len();
+        self.axes
+            .iter()
+            .enumerate()
+            .map(|(i, axis)| axis_point(center, radius * axis.fill_fraction(), i, n))
+            .collect()
+    }
+
+    /// Renders the webs and data polygon into an image, without an egui context.
+    ///
+    /// Labels are left out since there is no font rasterizer outside egui.
+    /// The background is transparent.
+    pub fn render_to_image(&self, width: u32, height: u32) -> RgbaImage {
+        let mut image = RgbaImage::new(width, height);
+        let n = self.axes.len();
+        if n < 3 {
+            return image;
+        }
+
+        let center = pos2(width as f32 / 2.0, height as f32 / 2.0);
+        let radius = width.min(height) as f32 * 0.4; // Same margin as the widget
+
+        let web = WEB_COLOR.to_srgba_unmultiplied();
+        for ring in web_rings(center, radius, n) {
+            for segment in ring.windows(2) {
+                draw_line(&mut image, segment[0], segment[1], 1.0, web);
+            }
+        }
+        for i in 0..n {
+            draw_line(
+                &mut image,
+                center,
+                axis_point(center, radius, i, n),
+                1.0,
+                web,
+            );
+        }
+
+        let points = self.data_points(center, radius);
+        fill_polygon(&mut image, &points, FILL_COLOR.to_srgba_unmultiplied());
+        let stroke = STROKE_COLOR.to_srgba_unmultiplied();
+        for (i, &start) in points.iter().enumerate() {
+            draw_line(&mut image, start, points[(i + 1) % n], 2.0, stroke);
+        }
+        for &point in &points {
+            fill_circle(&mut image, point, 3.0, ACTIVE_COLOR.to_srgba_unmultiplied());
+        }
+
+        image
+    }
+
     fn draw_labels(&self, painter: &Painter, center: Pos2, radius: f32) {
         let n = self.axes.len();
         let text_color = Color32::LIGHT_GRAY;
 
         for (i, axis) in self.axes.iter().enumerate() {
-            let angle = (i as f32 * 2.0 * PI / n as f32) - PI / 2.0;
             // Push label out a bit further
-            let r = radius + 15.0;
-            let pos = center + vec2(r * angle.cos(), r * angle.sin());
+            let pos = axis_point(center, radius + 15.0, i, n);
 
             painter.text(
                 pos,
@@ -146,3 +196,121 @@ impl HexagonChart {
         }
     }
 }
+
+/// Position at distance `r` along axis `i` of `n` (the first axis points up).
+fn axis_point(center: Pos2, r: f32, i: usize, n: usize) -> Pos2 {
+    let angle = (i as f32 * 2.0 * PI / n as f32) - PI / 2.0;
+    center + vec2(r * angle.cos(), r * angle.sin())
+}
+
+/// Closed outlines of the background webs, innermost first.
+fn web_rings(center: Pos2, radius: f32, n: usize) -> Vec<Vec<Pos2>> {
+    (1..=WEB_STEPS)
+        .map(|s| {
+            let r = radius * (s as f32 / WEB_STEPS as f32);
+            let mut points: Vec<Pos2> = (0..n).map(|i| axis_point(center, r, i, n)).collect();
+            // Close the loop
+            points.push(points[0]);
+            points
+        })
+        .collect()
+}
+
+/// Alpha-blends an unmultiplied sRGBA color over a pixel.
+fn blend_pixel(image: &mut RgbaImage, x: i64, y: i64, color: [u8; 4], coverage: f32) {
+    if x < 0 || y < 0 || x >= image.width() as i64 || y >= image.height() as i64 {
+        return;
+    }
+    let src_a = color[3] as f32 / 255.0 * coverage.clamp(0.0, 1.0);
+    if src_a <= 0.0 {
+        return;
+    }
+
+    let dst = image.get_pixel_mut(x as u32, y as u32);
+    let dst_a = dst[3] as f32 / 255.0;
+    let out_a = src_a + dst_a * (1.0 - src_a);
+    for c in 0..3 {
+        let blended = (color[c] as f32 * src_a + dst[c] as f32 * dst_a * (1.0 - src_a)) / out_a;
+        dst[c] = blended.round() as u8;
+    }
+    dst[3] = (out_a * 255.0).round() as u8;
+}
+
+/// Draws a line of the given width by stamping along it.
+fn draw_line(image: &mut RgbaImage, from: Pos2, to: Pos2, width: f32, color: [u8; 4]) {
+    let half = width / 2.0;
+    let min_x = (from.x.min(to.x) - half).floor() as i64;
+    let max_x = (from.x.max(to.x) + half).ceil() as i64;
+    let min_y = (from.y.min(to.y) - half).floor() as i64;
+    let max_y = (from.y.max(to.y) + half).ceil() as i64;
+    let dir = to - from;
+    let len_sq = dir.length_sq().max(f32::EPSILON);
+
+    for y in min_y..=max_y {
+        for x in min_x..=max_x {
+            let p = pos2(x as f32 + 0.5, y as f32 + 0.5);
+            let t = ((p - from).dot(dir) / len_sq).clamp(0.0, 1.0);
+            let distance = (p - (from + dir * t)).length();
+            // One pixel of antialiasing at the edge
+            blend_pixel(image, x, y, color, half + 0.5 - distance);
+        }
+    }
+}
+
+/// Fills a polygon using the even-odd rule, sampling pixel centers.
+fn fill_polygon(image: &mut RgbaImage, points: &[Pos2], color: [u8; 4]) {
+    let rect = Rect::from_points(points);
+    for y in rect.min.y.floor() as i64..=rect.max.y.ceil() as i64 {
+        for x in rect.min.x.floor() as i64..=rect.max.x.ceil() as i64 {
+            let p = pos2(x as f32 + 0.5, y as f32 + 0.5);
+            let mut inside = false;
+            for (i, a) in points.iter().enumerate() {
+                let b = points[(i + 1) % points.len()];
+                if (a.y > p.y) != (b.y > p.y) && p.x < a.x + (p.y - a.y) * (b.x - a.x) / (b.y - a.y)
+                {
+                    inside = !inside;
+                }
+            }
+            if inside {
+                blend_pixel(image, x, y, color, 1.0);
+            }
+        }
+    }
+}
+
+fn fill_circle(image: &mut RgbaImage, center: Pos2, radius: f32, color: [u8; 4]) {
+    draw_line(image, center, center, radius * 2.0, color);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixed_max_fill_fraction() {
+        let chart = HexagonChart::new(300.0)
+            .with_fixed_max(40.0)
+            .add_axis("Overall", 20.0, 25.0, Color32::WHITE)
+            .add_axis("Stream", 10.0, 25.0, Color32::WHITE);
+        assert_eq!(chart.axes[0].fill_fraction(), 0.5);
+        assert_eq!(chart.axes[1].fill_fraction(), 0.25);
+
+        // Auto-scaling keeps the per-axis max
+        let auto = HexagonChart::new(300.0).add_axis("Overall", 20.0, 25.0, Color32::WHITE);
+        assert_eq!(auto.axes[0].fill_fraction(), 0.8);
+    }
+
+    #[test]
+    fn test_render_to_image() {
+        let mut chart = HexagonChart::new(300.0);
+        for (name, value) in [("A", 20.0), ("B", 15.0), ("C", 30.0), ("D", 10.0)] {
+            chart = chart.add_axis(name, value, 30.0, Color32::WHITE);
+        }
+
+        let image = chart.render_to_image(200, 160);
+        assert_eq!(image.dimensions(), (200, 160));
+        assert!(image.pixels().any(|p| p[3] > 0));
+        // The polygon covers the center
+        assert!(image.get_pixel(100, 80)[3] > 0);
+    }
+}
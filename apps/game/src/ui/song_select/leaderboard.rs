@@ -5,14 +5,24 @@ use std::path::Path;
 use crate::state::GameResultData;
 use crate::ui::song_select::leaderboard_card::LeaderboardCard;
 use database::replay_storage;
-use egui::ScrollArea;
+use egui::{RichText, ScrollArea};
 use engine::HitStats;
 use engine::NoteData;
 use engine::hit_window::HitWindow;
-use replay::{ReplayData, ReplayResult, simulate};
+use replay::{ReplayData, ReplayResult, rejudge};
+
+/// Action demandée depuis le leaderboard.
+pub enum LeaderboardEvent {
+    /// Ouvre l'écran de résultat du score cliqué.
+    Open(GameResultData),
+    /// Supprime le replay (hash), après confirmation.
+    Delete(String),
+}
 
 #[derive(Clone)]
 pub struct ScoreCard {
+    /// Hash du replay en base.
+    pub replay_hash: String,
     pub timestamp: i64,
     pub rate: f64,
     pub replay_data: ReplayData,
@@ -32,6 +42,7 @@ impl ScoreCard {
             .unwrap_or_else(|_| ReplayData::default());
 
         Some(ScoreCard {
+            replay_hash: replay.hash.clone(),
             timestamp: replay.timestamp,
             rate: replay.rate,
             replay_data,
@@ -44,25 +55,37 @@ impl ScoreCard {
         })
     }
 
+    /// Vrai si le replay a été joué sur une version de la chart avec un autre
+    /// nombre de notes (l'accuracy affichée serait faussée).
+    pub fn is_stale(&self) -> bool {
+        self.replay_data.is_stale_for(self.total_notes)
+    }
+
     /// Simule le replay avec la chart et le hit window donnés.
     /// Met à jour le cache de résultat.
     pub fn simulate_with_chart(&mut self, chart: &[NoteData], hit_window: &HitWindow) {
-        let result = simulate(&self.replay_data, chart, hit_window);
+        let result = rejudge(&self.replay_data, chart, hit_window);
         self.cached_result = Some(result);
     }
 }
 
 pub struct Leaderboard {
     scores: Vec<ScoreCard>,
+    /// Score dont la suppression attend confirmation.
+    pending_delete: Option<usize>,
 }
 
 impl Leaderboard {
     pub fn new() -> Self {
-        Self { scores: Vec::new() }
+        Self {
+            scores: Vec::new(),
+            pending_delete: None,
+        }
     }
 
     pub fn update_scores(&mut self, scores: Vec<ScoreCard>) {
         self.scores = scores;
+        self.pending_delete = None;
     }
 
     /// Simule tous les replays avec la chart et le hit window donnés.
@@ -73,13 +96,13 @@ impl Leaderboard {
     }
 
     pub fn render(
-        &self,
+        &mut self,
         ui: &mut egui::Ui,
         _difficulty_name: Option<&str>,
         hit_window: &HitWindow,
         chart: Option<&[NoteData]>,
-    ) -> Option<GameResultData> {
-        let mut clicked_result = None;
+    ) -> Option<LeaderboardEvent> {
+        let mut event = None;
 
         if self.scores.is_empty() {
             ui.centered_and_justified(|ui| {
@@ -101,7 +124,7 @@ impl Leaderboard {
                                 )
                             } else if let Some(chart) = chart {
                                 // Simuler à la volée si on a la chart
-                                let result = simulate(&card.replay_data, chart, hit_window);
+                                let result = rejudge(&card.replay_data, chart, hit_window);
                                 (
                                     result.hit_stats.clone(),
                                     result.accuracy,
@@ -130,8 +153,18 @@ impl Leaderboard {
                             max_combo,
                             &hit_stats,
                             is_practice,
+                            card.is_stale(),
+                            card.replay_data.incomplete,
                         );
 
+                        // Clic droit : suppression du score
+                        response.context_menu(|ui| {
+                            if ui.button("Delete score").clicked() {
+                                self.pending_delete = Some(i);
+                                ui.close();
+                            }
+                        });
+
                         if response.clicked() {
                             let judge_text = if is_practice {
                                 "Practice Replay".to_string()
@@ -139,7 +172,7 @@ impl Leaderboard {
                                 "Replay View".to_string()
                             };
 
-                            clicked_result = Some(GameResultData {
+                            event = Some(LeaderboardEvent::Open(GameResultData {
                                 hit_stats: hit_stats.clone(),
                                 replay_data: card.replay_data.clone(),
                                 replay_result,
@@ -150,7 +183,8 @@ impl Leaderboard {
                                 rate: card.rate,
                                 judge_text,
                                 show_settings: false,
-                            });
+                                failed: false,
+                            }));
                         }
 
                         if i < self.scores.len().min(10).saturating_sub(1) {
@@ -160,6 +194,72 @@ impl Leaderboard {
                 });
         }
 
-        clicked_result
+        if let Some(delete) = self.render_delete_confirm(ui.ctx()) {
+            event = Some(delete);
+        }
+
+        event
+    }
+
+    /// Fenêtre de confirmation de suppression du score en attente.
+    fn render_delete_confirm(&mut self, ctx: &egui::Context) -> Option<LeaderboardEvent> {
+        let card = self.scores.get(self.pending_delete?)?;
+        let mut confirmed = false;
+        let mut cancelled = false;
+
+        let id = egui::Id::new("leaderboard_delete_confirm");
+        let modal = egui::Modal::new(id).show(ctx, |ui| {
+            ui.label(RichText::new("Delete this score?").strong().size(16.0));
+            ui.label(format!(
+                "{:.2}% at {:.2}x. The replay file will be removed.",
+                card.accuracy, card.rate
+            ));
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                confirmed = ui.button("Delete").clicked();
+                cancelled = ui.button("Cancel").clicked();
+            });
+        });
+
+        let hash = card.replay_hash.clone();
+        if confirmed {
+            self.pending_delete = None;
+            Some(LeaderboardEvent::Delete(hash))
+        } else {
+            if cancelled || modal.should_close() {
+                self.pending_delete = None;
+            }
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn card(judged_notes: usize, total_notes: usize) -> ScoreCard {
+        let mut replay_data = ReplayData::new(1.0);
+        replay_data.judged_notes = judged_notes;
+        ScoreCard {
+            replay_hash: String::new(),
+            timestamp: 0,
+            rate: 1.0,
+            replay_data,
+            total_notes,
+            score: 0,
+            accuracy: 0.0,
+            max_combo: 0,
+            beatmap_hash: String::new(),
+            cached_result: None,
+        }
+    }
+
+    #[test]
+    fn test_score_with_other_note_count_is_stale() {
+        assert!(card(500, 512).is_stale());
+        assert!(!card(512, 512).is_stale());
+        // Older replays did not record their note count
+        assert!(!card(0, 512).is_stale());
     }
 }
@@ -13,6 +13,8 @@ impl LeaderboardCard {
         max_combo: i32,
         hit_stats: &HitStats,
         is_practice: bool,
+        is_stale: bool,
+        is_incomplete: bool,
     ) -> egui::Response {
         let available_width = ui.available_width();
 
@@ -43,13 +45,14 @@ impl LeaderboardCard {
             bg_color
         };
 
+        // Bordure teintée par l'accuracy
         let frame_response = egui::Frame::default()
             .inner_margin(egui::Margin::symmetric(12, 8))
             .corner_radius(CornerRadius::same(8))
             .fill(card_bg)
             .stroke(Stroke::new(
                 1.0,
-                Color32::from_rgba_unmultiplied(100, 100, 100, 100),
+                accuracy_color(accuracy).gamma_multiply(0.6),
             ))
             .show(ui, |ui| {
                 ui.set_width(available_width - 24.0);
@@ -82,6 +85,46 @@ impl LeaderboardCard {
                             });
                     }
 
+                    // Stale badge: chart changed since this score was set
+                    if is_stale {
+                        ui.add_space(8.0);
+                        egui::Frame::default()
+                            .inner_margin(egui::Margin::symmetric(6, 2))
+                            .corner_radius(CornerRadius::same(4))
+                            .fill(Color32::from_rgb(200, 120, 40))
+                            .show(ui, |ui| {
+                                ui.label(
+                                    RichText::new("STALE")
+                                        .size(10.0)
+                                        .strong()
+                                        .color(Color32::WHITE),
+                                );
+                            })
+                            .response
+                            .on_hover_text(
+                                "The chart's note count changed since this score was set",
+                            );
+                    }
+
+                    // Abandoned badge: the run was quit before the end
+                    if is_incomplete {
+                        ui.add_space(8.0);
+                        egui::Frame::default()
+                            .inner_margin(egui::Margin::symmetric(6, 2))
+                            .corner_radius(CornerRadius::same(4))
+                            .fill(Color32::from_rgb(120, 120, 130))
+                            .show(ui, |ui| {
+                                ui.label(
+                                    RichText::new("ABANDONED")
+                                        .size(10.0)
+                                        .strong()
+                                        .color(Color32::WHITE),
+                                );
+                            })
+                            .response
+                            .on_hover_text("This run was quit before the end of the song");
+                    }
+
                     // Accuracy (right aligned)
                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                         let acc_color = accuracy_color(accuracy);
@@ -183,20 +226,29 @@ fn render_stat_pill(ui: &mut egui::Ui, count: u32, color: Color32) {
     );
 }
 
+/// Accuracy gradient: red up to 80%, green at 95%, gold at 100%.
+const ACCURACY_STOPS: [(f64, [u8; 3]); 3] = [
+    (80.0, [255, 100, 100]),
+    (95.0, [100, 255, 100]),
+    (100.0, [255, 215, 0]),
+];
+
+/// Color of an accuracy, interpolated between [`ACCURACY_STOPS`].
 fn accuracy_color(accuracy: f64) -> Color32 {
-    if accuracy >= 99.5 {
-        Color32::from_rgb(0, 255, 255) // Cyan - SS
-    } else if accuracy >= 98.0 {
-        Color32::from_rgb(255, 255, 100) // Yellow - S
-    } else if accuracy >= 95.0 {
-        Color32::from_rgb(100, 255, 100) // Green - A
-    } else if accuracy >= 90.0 {
-        Color32::from_rgb(100, 180, 255) // Blue - B
-    } else if accuracy >= 80.0 {
-        Color32::from_rgb(200, 150, 100) // Orange - C
-    } else {
-        Color32::from_rgb(255, 100, 100) // Red - D
-    }
+    let next = ACCURACY_STOPS.partition_point(|(stop, _)| *stop <= accuracy);
+    let [r, g, b] = match next {
+        0 => ACCURACY_STOPS[0].1,
+        n if n == ACCURACY_STOPS.len() => ACCURACY_STOPS[n - 1].1,
+        n => {
+            let (from_acc, from) = ACCURACY_STOPS[n - 1];
+            let (to_acc, to) = ACCURACY_STOPS[n];
+            let t = (accuracy - from_acc) / (to_acc - from_acc);
+            std::array::from_fn(|i| {
+                (from[i] as f64 + (to[i] as f64 - from[i] as f64) * t).round() as u8
+            })
+        }
+    };
+    Color32::from_rgb(r, g, b)
 }
 
 fn format_date(timestamp: i64) -> String {
@@ -219,3 +271,18 @@ fn format_date(timestamp: i64) -> String {
         format!("{}w ago", diff / 604800)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accuracy_color_gradient() {
+        assert_eq!(accuracy_color(80.0), Color32::from_rgb(255, 100, 100));
+        assert_eq!(accuracy_color(95.0), Color32::from_rgb(100, 255, 100));
+        assert_eq!(accuracy_color(99.9), Color32::from_rgb(252, 216, 2));
+        assert_eq!(accuracy_color(100.0), Color32::from_rgb(255, 215, 0));
+        // Clamped outside the gradient
+        assert_eq!(accuracy_color(42.0), accuracy_color(80.0));
+    }
+}
@@ -12,5 +12,5 @@ pub mod song_card;
 pub mod song_list;
 
 // Re-export CalculatorOption for use in MenuState and Page
-pub use beatmap_info::CalculatorOption;
+pub use beatmap_info::{CalculatorOption, RatingScale, default_calculators};
 pub mod hexagon_chart;
@@ -5,7 +5,8 @@ use egui::{
     Vec2,
 };
 
-use crate::ui::song_select::difficulty_utils::{get_difficulty_color, get_difficulty_range};
+use crate::state::menu::DifficultyCache;
+use crate::ui::song_select::difficulty_utils::{get_difficulty_color, get_difficulty_spread};
 use database::models::{BeatmapWithRatings, Beatmapset};
 use skin::menus::song_select::RatingColorsConfig;
 
@@ -25,6 +26,7 @@ impl SongCard {
         selected_color: Color32,
         rating_colors: Option<&RatingColorsConfig>,
         active_calculator: &str,
+        difficulty_cache: &DifficultyCache,
     ) -> egui::Response {
         let card_height = 80.0;
         let width = ui.available_width();
@@ -142,14 +144,24 @@ impl SongCard {
                     .selectable(false),
             );
 
-            // Difficulty range display
-            if let Some(colors) = rating_colors {
-                if let Some((min_rating, max_rating)) =
-                    get_difficulty_range(beatmaps, active_calculator)
-                {
-                    ui.horizontal(|ui| {
-                        ui.spacing_mut().item_spacing.x = 2.0;
+            // Difficulty count + range badge (always at 1.0x so cards don't shift with the rate)
+            let spread = get_difficulty_spread(beatmaps, active_calculator, difficulty_cache);
+            ui.horizontal(|ui| {
+                ui.spacing_mut().item_spacing.x = 2.0;
 
+                let count_label = if spread.count == 1 {
+                    "1 diff".to_string()
+                } else {
+                    format!("{} diffs", spread.count)
+                };
+                ui.add(
+                    Label::new(RichText::new(count_label).size(11.0).color(Color32::GRAY))
+                        .selectable(false),
+                );
+                ui.add_space(6.0);
+
+                if let Some(colors) = rating_colors {
+                    if let Some((min_rating, max_rating)) = spread.range {
                         // Get colors for min and max
                         let min_color = get_difficulty_color(min_rating, colors);
                         let max_color = get_difficulty_color(max_rating, colors);
@@ -191,21 +203,34 @@ impl SongCard {
                                 .selectable(false),
                             );
                         }
-                    });
+                    } else if beatmaps
+                        .iter()
+                        .any(|bm| difficulty_cache.error(&bm.beatmap.hash).is_some())
+                    {
+                        // Rating failed - flag it rather than waiting forever
+                        ui.add(
+                            Label::new(
+                                RichText::new("⚠ calc failed")
+                                    .size(11.0)
+                                    .color(Color32::from_rgb(220, 90, 90)),
+                            )
+                            .selectable(false),
+                        );
+                    } else {
+                        // No ratings cached yet - show placeholder
+                        ui.add(
+                            Label::new(RichText::new("★ --").size(12.0).color(Color32::DARK_GRAY))
+                                .selectable(false),
+                        );
+                    }
                 } else {
-                    // No ratings found - show placeholder
+                    // No rating colors config - shouldn't happen but fallback
                     ui.add(
-                        Label::new(RichText::new("★ --").size(12.0).color(Color32::DARK_GRAY))
+                        Label::new(RichText::new("★ ?").size(12.0).color(Color32::GRAY))
                             .selectable(false),
                     );
                 }
-            } else {
-                // No rating colors config - shouldn't happen but fallback
-                ui.add(
-                    Label::new(RichText::new("★ ?").size(12.0).color(Color32::GRAY))
-                        .selectable(false),
-                );
-            }
+            });
         });
 
         response
@@ -191,6 +191,7 @@ impl SongList {
                             animated_sel_color,
                             rating_colors,
                             active_calculator,
+                            &menu_state.difficulty_cache,
                         );
 
                         // Auto-center selected item when selection changes
@@ -208,24 +209,41 @@ impl SongList {
                         }
 
                         if is_selected && beatmaps.len() > 1 {
+                            let filter_active = !menu_state.difficulty_filter.trim().is_empty();
+                            let matching = menu_state.matching_difficulties();
+
                             for (diff_idx, beatmap) in beatmaps.iter().enumerate() {
                                 let is_diff_selected = diff_idx == selected_difficulty_index;
 
                                 // Get the difficulty rating and color for this beatmap
-                                let rating = get_beatmap_rating(beatmap, active_calculator);
+                                let rating = get_beatmap_rating(
+                                    beatmap,
+                                    active_calculator,
+                                    &menu_state.difficulty_cache,
+                                    menu_state.rate,
+                                );
                                 let diff_color =
                                     Self::get_diff_color_from_rating(rating, rating_colors);
 
-                                let diff_response = DifficultyCard::render(
-                                    ui,
-                                    beatmap,
-                                    is_diff_selected,
-                                    diff_tex,
-                                    diff_sel_tex,
-                                    diff_sel_color,
-                                    diff_color,
-                                    rating,
-                                );
+                                // Dim difficulties that don't match the name filter
+                                let dimmed = filter_active && !matching.contains(&diff_idx);
+                                let diff_response = ui
+                                    .scope(|ui| {
+                                        if dimmed {
+                                            ui.multiply_opacity(0.35);
+                                        }
+                                        DifficultyCard::render(
+                                            ui,
+                                            beatmap,
+                                            is_diff_selected,
+                                            diff_tex,
+                                            diff_sel_tex,
+                                            diff_sel_color,
+                                            diff_color,
+                                            rating,
+                                        )
+                                    })
+                                    .inner;
 
                                 let diff_sense = diff_response.interact(egui::Sense::click());
 
@@ -242,6 +260,11 @@ impl SongList {
                 self.max = max_visual_row;
             });
 
+        // Type-ahead: letters typed while no text field has focus
+        if action_triggered.is_none() && !ui.ctx().wants_keyboard_input() {
+            action_triggered = ui.input(|i| type_ahead_action(&i.events));
+        }
+
         action_triggered
     }
 
@@ -257,3 +280,20 @@ impl SongList {
         get_difficulty_color(r, colors)
     }
 }
+
+/// Type-ahead search for the text typed this frame, if any.
+///
+/// The input thread leaves letter keys unbound in song select, so typing
+/// a title never triggers a shortcut.
+pub fn type_ahead_action(events: &[egui::Event]) -> Option<GameAction> {
+    let typed: String = events
+        .iter()
+        .filter_map(|event| match event {
+            egui::Event::Text(text) => Some(text.as_str()),
+            _ => None,
+        })
+        .flat_map(str::chars)
+        .filter(|c| !c.is_control())
+        .collect();
+    (!typed.is_empty()).then_some(GameAction::TypeAhead(typed))
+}
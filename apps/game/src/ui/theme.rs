@@ -0,0 +1,81 @@
+//! egui base theme built from the skin panel colors.
+//!
+//! Custom panels already read `skin.menus.panels`; installing these visuals
+//! makes the stock egui widgets (buttons, sliders, combo boxes, windows)
+//! match them.
+
+use crate::ui::song_select::difficulty_utils::color_to_egui;
+use egui::{Stroke, Visuals};
+use skin::menus::PanelStyleConfig;
+
+/// Dark egui visuals recolored with the skin's panel theme.
+pub fn skin_visuals(panels: &PanelStyleConfig) -> Visuals {
+    let background = color_to_egui(panels.background);
+    let secondary = color_to_egui(panels.secondary);
+    let border = color_to_egui(panels.border);
+    let accent = color_to_egui(panels.accent);
+    let accent_dim = color_to_egui(panels.accent_dim);
+    let text_primary = color_to_egui(panels.text_primary);
+    let text_secondary = color_to_egui(panels.text_secondary);
+
+    let mut visuals = Visuals::dark();
+
+    visuals.panel_fill = background;
+    visuals.window_fill = background;
+    visuals.window_stroke.color = border;
+    visuals.extreme_bg_color = secondary;
+    visuals.faint_bg_color = secondary;
+    visuals.hyperlink_color = accent;
+
+    visuals.selection.bg_fill = accent;
+    visuals.selection.stroke = Stroke::new(1.0, text_primary);
+
+    let widgets = &mut visuals.widgets;
+    widgets.noninteractive.bg_fill = background;
+    widgets.noninteractive.bg_stroke.color = border;
+    widgets.noninteractive.fg_stroke.color = text_secondary;
+
+    widgets.inactive.bg_fill = secondary;
+    widgets.inactive.weak_bg_fill = secondary;
+    widgets.inactive.fg_stroke.color = text_primary;
+
+    widgets.hovered.bg_fill = accent_dim;
+    widgets.hovered.weak_bg_fill = accent_dim;
+    widgets.hovered.bg_stroke.color = accent;
+    widgets.hovered.fg_stroke.color = text_primary;
+
+    widgets.active.bg_fill = accent;
+    widgets.active.weak_bg_fill = accent;
+    widgets.active.bg_stroke.color = accent;
+    widgets.active.fg_stroke.color = text_primary;
+
+    widgets.open.bg_fill = secondary;
+    widgets.open.weak_bg_fill = accent_dim;
+    widgets.open.bg_stroke.color = accent;
+    widgets.open.fg_stroke.color = text_primary;
+
+    visuals
+}
+
+/// Installs the skin theme on an egui context.
+pub fn apply_skin_theme(ctx: &egui::Context, panels: &PanelStyleConfig) {
+    ctx.set_visuals(skin_visuals(panels));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use egui::Color32;
+
+    #[test]
+    fn test_selection_uses_skin_accent() {
+        let panels = PanelStyleConfig {
+            accent: [1.0, 0.0, 0.0, 1.0],
+            ..PanelStyleConfig::default()
+        };
+        let visuals = skin_visuals(&panels);
+        assert_eq!(visuals.selection.bg_fill, Color32::from_rgb(255, 0, 0));
+        assert_eq!(visuals.hyperlink_color, Color32::from_rgb(255, 0, 0));
+        assert_eq!(visuals.panel_fill, color_to_egui(panels.background));
+    }
+}
@@ -5,7 +5,7 @@
 use super::ColumnElementType;
 use super::common::*;
 use skin::Skin;
-use egui::Ui;
+use egui::{DragValue, Ui};
 
 /// Edit a single column element (Note or Receptor)
 /// col is 0-indexed
@@ -129,10 +129,33 @@ pub fn edit_columns(ui: &mut Ui, skin: &mut Skin, keymode: usize) -> bool {
     }
 
     // Edit each column
+    let playfield = &mut skin.gameplay.playfield;
+    let layout = playfield.layout();
     for col in 0..keymode {
         let col_name = format!("Column {} ({}K)", col + 1, keymode);
 
         ui.collapsing(&col_name, |ui| {
+            // Layout (widths/gaps are shared by all keymodes)
+            section_header(ui, "📏 Layout");
+            let mut width = layout.column_width(col);
+            ui.horizontal(|ui| {
+                ui.label("Width");
+                if ui.add(DragValue::new(&mut width).speed(1.0)).changed() {
+                    playfield.set_column_width(col, width);
+                    changed = true;
+                }
+            });
+            if col + 1 < keymode {
+                let mut gap = layout.column_gap(col);
+                ui.horizontal(|ui| {
+                    ui.label("Gap After");
+                    if ui.add(DragValue::new(&mut gap).speed(0.5)).changed() {
+                        playfield.set_column_gap(col, gap);
+                        changed = true;
+                    }
+                });
+            }
+
             // Note image
             section_header(ui, "🎵 Note");
             if let Some(note_cfg) = km_config.notes.get_mut(col) {
@@ -165,7 +188,7 @@ pub fn edit_columns(ui: &mut Ui, skin: &mut Skin, keymode: usize) -> bool {
     }
 
     ui.add_space(10.0);
-    hint(ui, "Each column can have different images and colors");
+    hint(ui, "Columns can have different widths, images and colors");
 
     changed
 }
@@ -73,6 +73,12 @@ pub fn edit_flash_all(ui: &mut Ui, skin: &mut Skin) -> bool {
         .changed();
     hint(ui, "- = early hit, + = late hit");
 
+    section_header(ui, "📍 Placement");
+    changed |= ui
+        .checkbox(&mut skin.hud.judgement.per_column, "Above the hit column")
+        .changed();
+    hint(ui, "Keeps the Y of each judgement, X follows the column");
+
     hint(ui, "This moves/resizes all judgement flashes together");
 
     changed
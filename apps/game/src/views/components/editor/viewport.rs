@@ -155,9 +155,7 @@ impl GamePreviewViewport {
 
         match id {
             "Notes - Default" | "Receptors - Default" => {
-                let col_w = gameplay.playfield.column_width;
-                let spacing = gameplay.playfield.receptor_spacing;
-                let total_w = (4.0 * col_w) + (3.0 * spacing);
+                let total_w = gameplay.playfield.layout().total_width(4);
                 let h = 600.0;
 
                 let center_x = gameplay.playfield.position.x;
@@ -172,8 +170,7 @@ impl GamePreviewViewport {
             }
 
             "📊 Hit Bar" => {
-                let col_w = gameplay.playfield.column_width;
-                let total_w = (4.0 * col_w) + (3.0 * gameplay.playfield.receptor_spacing);
+                let total_w = gameplay.playfield.layout().total_width(4);
                 let w = total_w * 0.8;
                 let h = hud.hit_bar.scale;
 
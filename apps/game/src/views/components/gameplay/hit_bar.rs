@@ -1,4 +1,5 @@
-use engine::Judgement;
+use crate::shared::snapshot::HitEvent;
+use engine::{Judgement, JudgementColors};
 use wgpu_text::glyph_brush::{Section, Text};
 
 #[derive(Clone)]
@@ -12,6 +13,8 @@ pub struct HitBarDisplay {
     size: (f32, f32),
     last_hits: Vec<HitMarker>,
     max_history: usize,
+    /// Id of the last hit added.
+    last_id: u64,
 }
 
 impl HitBarDisplay {
@@ -21,6 +24,7 @@ impl HitBarDisplay {
             size: (width_pixels, height_pixels),
             last_hits: Vec::with_capacity(10),
             max_history: 10,
+            last_id: 0,
         }
     }
 
@@ -35,18 +39,18 @@ impl HitBarDisplay {
         self.size = (width_pixels, height_pixels);
     }
 
-    fn push_hit(&mut self, timing: f64, judgement: Judgement) {
-        let is_new = self
-            .last_hits
-            .last()
-            .map(|hit| hit.timing != timing || hit.judgement != judgement)
-            .unwrap_or(true);
-
-        if is_new {
-            self.last_hits.push(HitMarker { timing, judgement });
-            if self.last_hits.len() > self.max_history {
-                self.last_hits.remove(0);
-            }
+    /// Adds the hits not shown yet, including those of skipped snapshots.
+    fn push_hits(&mut self, recent_hits: &[HitEvent]) {
+        for hit in recent_hits.iter().filter(|hit| hit.id > self.last_id) {
+            self.last_hits.push(HitMarker {
+                timing: hit.timing,
+                judgement: hit.judgement,
+            });
+            self.last_id = hit.id;
+        }
+        if self.last_hits.len() > self.max_history {
+            let excess = self.last_hits.len() - self.max_history;
+            self.last_hits.drain(..excess);
         }
     }
 
@@ -58,28 +62,14 @@ impl HitBarDisplay {
         center_x - (ratio * (width / 2.0))
     }
 
-    #[inline]
-    fn judgement_color(judgement: Judgement) -> [f32; 4] {
-        match judgement {
-            Judgement::Marv => [0.0, 1.0, 1.0, 1.0],
-            Judgement::Perfect => [1.0, 1.0, 0.0, 1.0],
-            Judgement::Great => [0.0, 1.0, 0.0, 1.0],
-            Judgement::Good => [0.0, 0.0, 1.0, 1.0],
-            Judgement::Bad => [1.0, 0.0, 1.0, 1.0],
-            Judgement::Miss => [1.0, 0.0, 0.0, 1.0],
-            Judgement::GhostTap => [0.5, 0.5, 0.5, 1.0],
-        }
-    }
-
     pub fn render(
         &mut self,
-        latest_hit: Option<(f64, Judgement)>,
+        recent_hits: &[HitEvent],
         screen_width: f32,
         screen_height: f32,
+        colors: &JudgementColors,
     ) -> Vec<Section<'_>> {
-        if let Some((timing, judgement)) = latest_hit {
-            self.push_hit(timing, judgement);
-        }
+        self.push_hits(recent_hits);
 
         let mut sections = Vec::new();
         let (width, height) = self.size;
@@ -103,7 +93,7 @@ impl HitBarDisplay {
                 text: vec![
                     Text::new("|")
                         .with_scale(height * 0.9)
-                        .with_color(Self::judgement_color(hit.judgement)),
+                        .with_color(colors.get(hit.judgement)),
                 ],
                 ..Default::default()
             });
@@ -1,5 +1,5 @@
 //! Displays judgement panels, combo text, and the center flash overlay.
-use skin::JudgementLabels;
+use skin::{JudgementLabels, JudgementPositions, Vec2Conf};
 use engine::{HitStats, Judgement, JudgementColors};
 use wgpu_text::glyph_brush::{Section, Text};
 
@@ -28,6 +28,9 @@ impl JudgementPanel {
     pub fn set_size(&mut self, size: f32) {
         self.text_size = size;
     }
+    pub fn set_colors(&mut self, colors: JudgementColors) {
+        self.colors = colors;
+    }
 
     /// Render ONLY the judgement counts, NO notes/speed (those are separate now)
     pub fn render(
@@ -88,51 +91,60 @@ impl JudgementPanel {
 
 /// The Judgement Flash displays a centered text when hitting notes
 pub struct JudgementFlash {
-    position: (f32, f32),
+    positions: JudgementPositions,
     text_buffer: String,
     /// If true, show +/- timing indicator (early = "-", late = "+")
     pub show_timing: bool,
+    /// If true, the flash is centered on the hit column (X only)
+    pub per_column: bool,
+}
+
+/// Text, color and anchor of the flash for the last judgement
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlashData<'a> {
+    pub text: &'a str,
+    pub color: [f32; 4],
+    /// Center of the text
+    pub position: (f32, f32),
 }
 
 impl JudgementFlash {
     pub fn new(x: f32, y: f32) -> Self {
         Self {
-            position: (x, y),
+            positions: JudgementPositions::splat(Vec2Conf { x, y }),
             text_buffer: String::new(),
             show_timing: false,
+            per_column: false,
         }
     }
-    pub fn set_position(&mut self, x: f32, y: f32) {
-        self.position = (x, y);
+
+    /// Each judgement is shown at its own position
+    pub fn set_positions(&mut self, positions: JudgementPositions) {
+        self.positions = positions;
     }
 
-    /// Render the flash with optional timing indicator
+    /// Build the flash for a judgement
     /// timing_ms: negative = early, positive = late (in milliseconds from perfect hit)
-    pub fn render(
+    /// column_x: screen X of the hit column, used when `per_column` is set
+    pub fn flash_data(
         &mut self,
-        last_judgement: Option<Judgement>,
+        judgement: Judgement,
         timing_ms: Option<f64>,
-        screen_width: f32,
-        screen_height: f32,
+        column_x: Option<f32>,
         colors: &JudgementColors,
         labels: &JudgementLabels,
-    ) -> Vec<Section<'_>> {
-        let Some(judgement) = last_judgement else {
-            return Vec::new();
-        };
-
-        let (label, color) = match judgement {
-            Judgement::Marv => (labels.marv.as_str(), colors.marv),
-            Judgement::Perfect => (labels.perfect.as_str(), colors.perfect),
-            Judgement::Great => (labels.great.as_str(), colors.great),
-            Judgement::Good => (labels.good.as_str(), colors.good),
-            Judgement::Bad => (labels.bad.as_str(), colors.bad),
-            Judgement::Miss => (labels.miss.as_str(), colors.miss),
-            Judgement::GhostTap => (labels.ghost_tap.as_str(), colors.ghost_tap),
+    ) -> FlashData<'_> {
+        let color = colors.get(judgement);
+        let (label, position) = match judgement {
+            Judgement::Marv => (labels.marv.as_str(), self.positions.marv),
+            Judgement::Perfect => (labels.perfect.as_str(), self.positions.perfect),
+            Judgement::Great => (labels.great.as_str(), self.positions.great),
+            Judgement::Good => (labels.good.as_str(), self.positions.good),
+            Judgement::Bad => (labels.bad.as_str(), self.positions.bad),
+            Judgement::Miss => (labels.miss.as_str(), self.positions.miss),
+            Judgement::GhostTap => (labels.ghost_tap.as_str(), self.positions.ghost_tap),
         };
 
-        let scale_ratio = screen_height / 1080.0;
-        let font_scale = 48.0 * scale_ratio;
         self.text_buffer.clear();
 
         // Add timing indicator if enabled
@@ -161,18 +173,114 @@ impl JudgementFlash {
             self.text_buffer.push_str(" +");
         }
 
-        let text_width = self.text_buffer.len() as f32 * 0.6 * font_scale;
-        let cx = self.position.0 - (text_width / 2.0);
+        // Falls back to the judgement's own position without a column
+        let x = match column_x {
+            Some(x) if self.per_column => x,
+            _ => position.x,
+        };
+
+        FlashData {
+            text: &self.text_buffer,
+            color,
+            position: (x, position.y),
+        }
+    }
+
+    /// Render the flash with optional timing indicator
+    /// timing_ms: negative = early, positive = late (in milliseconds from perfect hit)
+    pub fn render(
+        &mut self,
+        last_judgement: Option<Judgement>,
+        timing_ms: Option<f64>,
+        column_x: Option<f32>,
+        screen_width: f32,
+        screen_height: f32,
+        colors: &JudgementColors,
+        labels: &JudgementLabels,
+    ) -> Vec<Section<'_>> {
+        let Some(judgement) = last_judgement else {
+            return Vec::new();
+        };
+
+        let scale_ratio = screen_height / 1080.0;
+        let font_scale = 48.0 * scale_ratio;
+        let flash = self.flash_data(judgement, timing_ms, column_x, colors, labels);
+
+        let text_width = flash.text.len() as f32 * 0.6 * font_scale;
+        let cx = flash.position.0 - (text_width / 2.0);
 
         vec![Section {
-            screen_position: (cx, self.position.1),
+            screen_position: (cx, flash.position.1),
             bounds: (screen_width, screen_height),
             text: vec![
-                Text::new(&self.text_buffer)
+                Text::new(flash.text)
                     .with_scale(font_scale)
-                    .with_color(color),
+                    .with_color(flash.color),
             ],
             ..Default::default()
         }]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::views::components::gameplay::playfield::PlayfieldDisplay;
+    use engine::{PixelSystem, PlayfieldConfig};
+    use skin::Skin;
+
+    fn colors() -> JudgementColors {
+        JudgementColors {
+            marv: [0.0, 1.0, 1.0, 1.0],
+            perfect: [1.0, 1.0, 0.0, 1.0],
+            great: [0.0, 1.0, 0.0, 1.0],
+            good: [0.0, 0.0, 0.5, 1.0],
+            bad: [1.0, 0.4, 0.7, 1.0],
+            miss: [1.0, 0.0, 0.0, 1.0],
+            ghost_tap: [0.5, 0.5, 0.5, 1.0],
+        }
+    }
+
+    #[test]
+    fn test_skin_label_override() {
+        let mut skin = Skin::default();
+        skin.hud.judgement.marv.label = "Rainbow".to_string();
+        skin.hud.judgement.marv.position = Vec2Conf { x: 100.0, y: 200.0 };
+        // A blank label keeps the default text
+        skin.hud.judgement.miss.label = String::new();
+
+        let labels = skin.get_judgement_labels();
+        let mut flash = JudgementFlash::new(0.0, 0.0);
+        flash.set_positions(skin.get_judgement_positions());
+
+        let marv = flash.flash_data(Judgement::Marv, None, None, &colors(), &labels);
+        assert_eq!(marv.text, "Rainbow");
+        assert_eq!(marv.position, (100.0, 200.0));
+
+        let miss = flash.flash_data(Judgement::Miss, None, None, &colors(), &labels);
+        assert_eq!(miss.text, "Miss");
+        assert_eq!(miss.color, colors().miss);
+    }
+
+    #[test]
+    fn test_flash_anchored_at_hit_column() {
+        let playfield = PlayfieldDisplay::with_key_count(PlayfieldConfig::new(), 4);
+        let pixel_system = PixelSystem::new(1280, 720);
+        let column_x = playfield.column_center_screen_x(2, &pixel_system, 1280.0);
+        // 100px columns centered on 640: column 2 spans 640..740
+        assert!((column_x - 690.0).abs() < 1e-3);
+
+        let labels = Skin::default().get_judgement_labels();
+        let mut flash = JudgementFlash::new(640.0, 300.0);
+        flash.per_column = true;
+        let great = flash.flash_data(Judgement::Great, None, Some(column_x), &colors(), &labels);
+        assert_eq!(great.position, (column_x, 300.0));
+
+        // Central flash without a hit column, or when the skin keeps it
+        let ghost = flash.flash_data(Judgement::GhostTap, None, None, &colors(), &labels);
+        assert_eq!(ghost.position, (640.0, 300.0));
+        flash.per_column = false;
+        let great = flash.flash_data(Judgement::Great, None, Some(column_x), &colors(), &labels);
+        assert_eq!(great.position, (640.0, 300.0));
+    }
+}
@@ -0,0 +1,38 @@
+//! Life bar display component
+//! Thin health bar along the top of the screen
+
+use crate::views::components::common::primitives::{ProgressInstance, progress_from_rect};
+
+/// Bar size as a fraction of the screen width, and its height (px).
+const WIDTH_FRACTION: f32 = 0.4;
+const HEIGHT: f32 = 8.0;
+const TOP_MARGIN: f32 = 12.0;
+
+/// Health below which the bar turns red.
+const LOW_HEALTH: f32 = 0.3;
+
+const HEALTHY_COLOR: [f32; 4] = [0.3, 0.9, 0.4, 1.0];
+const LOW_COLOR: [f32; 4] = [0.95, 0.25, 0.25, 1.0];
+const EMPTY_COLOR: [f32; 4] = [0.15, 0.15, 0.15, 0.8];
+
+/// Bar instance for `health` (in [0, 1]), centered at the top of the screen.
+pub fn life_bar_instance(health: f32, screen_width: f32, screen_height: f32) -> ProgressInstance {
+    let width = screen_width * WIDTH_FRACTION;
+    let filled = if health < LOW_HEALTH {
+        LOW_COLOR
+    } else {
+        HEALTHY_COLOR
+    };
+    progress_from_rect(
+        (screen_width - width) / 2.0,
+        TOP_MARGIN,
+        width,
+        HEIGHT,
+        filled,
+        EMPTY_COLOR,
+        health.clamp(0.0, 1.0),
+        0, // Bar
+        screen_width,
+        screen_height,
+    )
+}
@@ -2,10 +2,12 @@ pub mod accuracy;
 pub mod combo;
 pub mod hit_bar;
 pub mod judgement;
+pub mod life_bar;
 pub mod notes_remaining;
 pub mod nps;
 pub mod playfield;
 pub mod practice;
+pub mod receptor_glow;
 pub mod score;
 pub mod scroll_speed;
 pub mod time_left;
@@ -6,6 +6,8 @@ pub struct NpsDisplay {
     position: (f32, f32),
     text_size: f32,
     nps_text: String,
+    /// Whether to display the smoothed NPS rather than the raw value.
+    pub smoothed: bool,
 }
 
 impl NpsDisplay {
@@ -14,6 +16,7 @@ impl NpsDisplay {
             position: (x, y),
             text_size: 24.0,
             nps_text: String::new(),
+            smoothed: false,
         }
     }
 
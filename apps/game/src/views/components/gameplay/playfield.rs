@@ -1,8 +1,32 @@
+use crate::state::mods::NoteVisibility;
 use engine::{
     HIT_LINE_Y, InstanceRaw, NUM_COLUMNS, NoteData, PixelSystem, PlayfieldConfig, US_PER_MS,
     VISIBLE_DISTANCE,
 };
 
+/// Opacity of a hold body that is not being pressed; held bodies draw at full opacity.
+const HOLD_BODY_IDLE_ALPHA: f32 = 0.7;
+
+/// Normalized on-screen length of a hold/burst body.
+///
+/// The body spans `head_time_ms..tail_time_ms` at the given scroll speed.
+/// Once the note is active and its head has passed the hit line, the body
+/// starts at the hit line instead (`song_time_ms`).
+pub fn hold_body_length(
+    head_time_ms: f64,
+    tail_time_ms: f64,
+    song_time_ms: f64,
+    scroll_speed_ms: f64,
+    active: bool,
+) -> f32 {
+    let start_ms = if active {
+        head_time_ms.max(song_time_ms)
+    } else {
+        head_time_ms
+    };
+    (VISIBLE_DISTANCE as f64 * (tail_time_ms - start_ms) / scroll_speed_ms).abs() as f32
+}
+
 /// Type of visual element to render.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum NoteVisual {
@@ -34,10 +58,12 @@ pub struct PlayfieldDisplay {
 
 impl PlayfieldDisplay {
     pub fn new(config: PlayfieldConfig) -> Self {
-        Self {
-            config,
-            key_count: NUM_COLUMNS,
-        }
+        Self::with_key_count(config, NUM_COLUMNS)
+    }
+
+    /// Creates a playfield laid out for `key_count` columns.
+    pub fn with_key_count(config: PlayfieldConfig, key_count: usize) -> Self {
+        Self { config, key_count }
     }
 
     /// Set the column count for this playfield.
@@ -46,9 +72,7 @@ impl PlayfieldDisplay {
     }
 
     pub fn get_total_width_pixels(&self) -> f32 {
-        let cols = self.key_count as f32;
-        let spaces = (cols - 1.0).max(0.0);
-        (cols * self.config.column_width_pixels) + (spaces * self.config.receptor_spacing_pixels)
+        self.config.total_width(self.key_count)
     }
 
     pub fn get_bounds(&self, pixel_system: &PixelSystem) -> (f32, f32) {
@@ -58,6 +82,41 @@ impl PlayfieldDisplay {
         (x, width_norm)
     }
 
+    /// Normalized X center of a column, including spacing and the global offset.
+    pub fn column_center_x(&self, column: usize, pixel_system: &PixelSystem) -> f32 {
+        let (playfield_left_x, _) = self.get_bounds(pixel_system);
+        let col_offset_px = self
+            .config
+            .column_offsets(column + 1)
+            .last()
+            .copied()
+            .unwrap_or(0.0);
+        let center_px = col_offset_px + self.config.column_width(column) / 2.0;
+        let x_offset_norm = pixel_system.x_pixels_to_normalized(self.config.x_offset_pixels);
+
+        playfield_left_x + pixel_system.x_pixels_to_normalized(center_px) + x_offset_norm
+    }
+
+    /// X center of a column in screen pixels (for text anchored to it).
+    pub fn column_center_screen_x(
+        &self,
+        column: usize,
+        pixel_system: &PixelSystem,
+        screen_width: f32,
+    ) -> f32 {
+        (self.column_center_x(column, pixel_system) + 1.0) / 2.0 * screen_width
+    }
+
+    /// Normalized Y of the hit line, where receptors sit and notes are due.
+    ///
+    /// Only affects drawing: judgements are based on time, not position.
+    pub fn hit_line_y(&self, pixel_system: &PixelSystem) -> f32 {
+        HIT_LINE_Y
+            + pixel_system.y_pixels_to_normalized(
+                self.config.y_offset_pixels + self.config.hit_line_offset_pixels,
+            )
+    }
+
     /// Calcule la position de chaque note visible.
     /// Returns (column, InstanceRaw) for backward compatibility.
     /// Use render_notes_typed for full note type support.
@@ -69,28 +128,31 @@ impl PlayfieldDisplay {
         pixel_system: &PixelSystem,
     ) -> Vec<(usize, InstanceRaw)> {
         // Convert typed instances to simple format for backward compatibility
-        self.render_notes_typed(visible_notes, song_time_ms, scroll_speed_ms, pixel_system)
-            .into_iter()
-            .filter(|n| n.visual == NoteVisual::Tap) // Only tap notes for old system
-            .map(|n| (n.column, n.instance))
-            .collect()
+        self.render_notes_typed(
+            visible_notes,
+            song_time_ms,
+            scroll_speed_ms,
+            pixel_system,
+            &NoteVisibility::default(),
+        )
+        .into_iter()
+        .filter(|n| n.visual == NoteVisual::Tap) // Only tap notes for old system
+        .map(|n| (n.column, n.instance))
+        .collect()
     }
 
     /// Calcule la position de chaque note visible avec le type visuel.
     /// song_time_ms and scroll_speed_ms are in milliseconds for renderer compatibility.
+    /// `visibility` fades notes according to the active visibility mods.
     pub fn render_notes_typed(
         &self,
         visible_notes: &[NoteData],
         song_time_ms: f64,
         scroll_speed_ms: f64,
         pixel_system: &PixelSystem,
+        visibility: &NoteVisibility,
     ) -> Vec<NoteInstance> {
-        let (playfield_left_x, _) = self.get_bounds(pixel_system);
-
         // Conversion pixels -> normalisé GPU
-        let column_width_norm =
-            pixel_system.x_pixels_to_normalized(self.config.column_width_pixels);
-        let spacing_norm = pixel_system.x_pixels_to_normalized(self.config.receptor_spacing_pixels);
         let note_width_norm = pixel_system.x_pixels_to_normalized(self.config.note_width_pixels);
         let note_height_norm = pixel_system.y_pixels_to_normalized(self.config.note_height_pixels);
 
@@ -98,13 +160,13 @@ impl PlayfieldDisplay {
         let ln_width_norm = note_width_norm * 0.95;
 
         // Offsets globaux
-        let x_offset_norm = pixel_system.x_pixels_to_normalized(self.config.x_offset_pixels);
-        let y_offset_norm = pixel_system.y_pixels_to_normalized(self.config.y_offset_pixels);
+        let hit_line_y = self.hit_line_y(pixel_system);
 
         let mut instances = Vec::with_capacity(visible_notes.len() * 2); // LNs can generate multiple
 
         for note in visible_notes {
-            if note.state.hit {
+            // Skip hit notes and notes outside this playfield's columns
+            if note.state.hit || note.column() >= self.key_count {
                 continue;
             }
 
@@ -113,17 +175,14 @@ impl PlayfieldDisplay {
             let note_duration_ms = note.duration_us() as f64 / US_PER_MS as f64;
 
             // Position X (commune à tous les types)
-            let col_offset = note.column() as f32 * (column_width_norm + spacing_norm);
-            let center_x =
-                playfield_left_x + col_offset + (column_width_norm / 2.0) + x_offset_norm;
+            let center_x = self.column_center_x(note.column(), pixel_system);
 
             // Physique de défilement : Distance = Temps / Vitesse
             let time_to_hit = note_time_ms - song_time_ms;
             let progress = time_to_hit / scroll_speed_ms;
 
-            let y_pos = (HIT_LINE_Y as f64
-                + y_offset_norm as f64
-                + (VISIBLE_DISTANCE as f64 * progress)) as f32;
+            let y_pos = (hit_line_y as f64 + (VISIBLE_DISTANCE as f64 * progress)) as f32;
+            let alpha = visibility.alpha(progress as f32);
 
             if note.is_tap() {
                 instances.push(NoteInstance {
@@ -132,6 +191,7 @@ impl PlayfieldDisplay {
                     instance: InstanceRaw {
                         offset: [center_x, y_pos],
                         scale: [note_width_norm, note_height_norm],
+                        alpha,
                     },
                 });
             } else if note.is_mine() {
@@ -141,27 +201,38 @@ impl PlayfieldDisplay {
                     instance: InstanceRaw {
                         offset: [center_x, y_pos],
                         scale: [note_width_norm, note_height_norm],
+                        alpha,
                     },
                 });
             } else if note.is_hold() {
                 let is_held = note.state.hold.is_held;
                 let end_time_ms = note_time_ms + note_duration_ms;
                 let end_progress = (end_time_ms - song_time_ms) / scroll_speed_ms;
-                let end_y_pos = (HIT_LINE_Y as f64
-                    + y_offset_norm as f64
-                    + (VISIBLE_DISTANCE as f64 * end_progress))
-                    as f32;
+                let end_y_pos =
+                    (hit_line_y as f64 + (VISIBLE_DISTANCE as f64 * end_progress)) as f32;
 
                 // If being held, clamp the start to the hit line (don't go below receptors)
-                let hit_line_y = HIT_LINE_Y + y_offset_norm;
                 let clamped_y_pos = if is_held && y_pos < hit_line_y {
                     hit_line_y
                 } else {
                     y_pos
                 };
 
-                let body_height = (end_y_pos - clamped_y_pos).abs();
+                let body_height = hold_body_length(
+                    note_time_ms,
+                    end_time_ms,
+                    song_time_ms,
+                    scroll_speed_ms,
+                    is_held,
+                );
                 let body_center_y = (clamped_y_pos + end_y_pos) / 2.0;
+                let end_alpha = visibility.alpha(end_progress as f32);
+                // Held bodies light up to full opacity
+                let body_alpha = if is_held {
+                    alpha.max(end_alpha)
+                } else {
+                    alpha.max(end_alpha) * HOLD_BODY_IDLE_ALPHA
+                };
 
                 // Body (stretched, 95% width)
                 if body_height > 0.001 {
@@ -171,6 +242,7 @@ impl PlayfieldDisplay {
                         instance: InstanceRaw {
                             offset: [center_x, body_center_y],
                             scale: [ln_width_norm, body_height],
+                            alpha: body_alpha,
                         },
                     });
                 }
@@ -183,6 +255,7 @@ impl PlayfieldDisplay {
                         instance: InstanceRaw {
                             offset: [center_x, y_pos],
                             scale: [note_width_norm, note_height_norm],
+                            alpha,
                         },
                     });
                 }
@@ -194,19 +267,17 @@ impl PlayfieldDisplay {
                     instance: InstanceRaw {
                         offset: [center_x, end_y_pos],
                         scale: [ln_width_norm, note_height_norm],
+                        alpha: end_alpha,
                     },
                 });
             } else if note.is_burst() {
                 let current_hits = note.state.burst.current_hits;
                 let end_time_ms = note_time_ms + note_duration_ms;
                 let end_progress = (end_time_ms - song_time_ms) / scroll_speed_ms;
-                let end_y_pos = (HIT_LINE_Y as f64
-                    + y_offset_norm as f64
-                    + (VISIBLE_DISTANCE as f64 * end_progress))
-                    as f32;
+                let end_y_pos =
+                    (hit_line_y as f64 + (VISIBLE_DISTANCE as f64 * end_progress)) as f32;
 
                 // If started hitting, clamp the start to the hit line
-                let hit_line_y = HIT_LINE_Y + y_offset_norm;
                 let started = current_hits > 0;
                 let clamped_y_pos = if started && y_pos < hit_line_y {
                     hit_line_y
@@ -214,8 +285,16 @@ impl PlayfieldDisplay {
                     y_pos
                 };
 
-                let body_height = (end_y_pos - clamped_y_pos).abs();
+                let body_height = hold_body_length(
+                    note_time_ms,
+                    end_time_ms,
+                    song_time_ms,
+                    scroll_speed_ms,
+                    started,
+                );
                 let body_center_y = (clamped_y_pos + end_y_pos) / 2.0;
+                let end_alpha = visibility.alpha(end_progress as f32);
+                let body_alpha = alpha.max(end_alpha);
 
                 // Body (stretched, 95% width)
                 if body_height > 0.001 {
@@ -225,6 +304,7 @@ impl PlayfieldDisplay {
                         instance: InstanceRaw {
                             offset: [center_x, body_center_y],
                             scale: [ln_width_norm, body_height],
+                            alpha: body_alpha,
                         },
                     });
                 }
@@ -237,6 +317,7 @@ impl PlayfieldDisplay {
                         instance: InstanceRaw {
                             offset: [center_x, y_pos],
                             scale: [note_width_norm, note_height_norm],
+                            alpha,
                         },
                     });
                 }
@@ -248,6 +329,7 @@ impl PlayfieldDisplay {
                     instance: InstanceRaw {
                         offset: [center_x, end_y_pos],
                         scale: [ln_width_norm, note_height_norm],
+                        alpha: end_alpha,
                     },
                 });
             }
@@ -257,31 +339,117 @@ impl PlayfieldDisplay {
 
     /// Génère les instances pour les récepteurs fixes (en bas)
     pub fn render_receptors(&self, pixel_system: &PixelSystem) -> Vec<InstanceRaw> {
-        let (playfield_left_x, _) = self.get_bounds(pixel_system);
-
-        let column_width_norm =
-            pixel_system.x_pixels_to_normalized(self.config.column_width_pixels);
-        let spacing_norm = pixel_system.x_pixels_to_normalized(self.config.receptor_spacing_pixels);
         let receptor_width_norm =
             pixel_system.x_pixels_to_normalized(self.config.receptor_width_pixels);
         let receptor_height_norm =
             pixel_system.y_pixels_to_normalized(self.config.receptor_height_pixels);
-        let x_offset_norm = pixel_system.x_pixels_to_normalized(self.config.x_offset_pixels);
-        let y_offset_norm = pixel_system.y_pixels_to_normalized(self.config.y_offset_pixels);
+        let center_y = self.hit_line_y(pixel_system);
 
         let mut instances = Vec::with_capacity(self.key_count);
 
         for col in 0..self.key_count {
-            let col_offset = col as f32 * (column_width_norm + spacing_norm);
-            let center_x =
-                playfield_left_x + col_offset + (column_width_norm / 2.0) + x_offset_norm;
-            let center_y = HIT_LINE_Y + y_offset_norm;
+            let center_x = self.column_center_x(col, pixel_system);
 
             instances.push(InstanceRaw {
                 offset: [center_x, center_y],
                 scale: [receptor_width_norm, receptor_height_norm],
+                alpha: 1.0,
             });
         }
         instances
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_7k_notes_evenly_spaced() {
+        let mut config = PlayfieldConfig::new();
+        config.receptor_spacing_pixels = 4.0;
+        let playfield = PlayfieldDisplay::with_key_count(config, 7);
+        let pixel_system = PixelSystem::new(1920, 1080);
+
+        let notes: Vec<NoteData> = (0..7).map(|col| NoteData::tap(1_000_000, col)).collect();
+        let instances = playfield.render_notes_typed(
+            &notes,
+            500.0,
+            1000.0,
+            &pixel_system,
+            &NoteVisibility::default(),
+        );
+        assert_eq!(instances.len(), 7);
+
+        let xs: Vec<f32> = instances.iter().map(|n| n.instance.offset[0]).collect();
+        let step = xs[1] - xs[0];
+        assert!(step > 0.0);
+        for pair in xs.windows(2) {
+            assert!((pair[1] - pair[0] - step).abs() < 1e-5);
+        }
+        // Centered playfield: the middle column sits on x = 0
+        assert!(xs[3].abs() < 1e-5);
+
+        // Receptors line up with the notes
+        let receptors = playfield.render_receptors(&pixel_system);
+        assert_eq!(receptors.len(), 7);
+        for (receptor, x) in receptors.iter().zip(&xs) {
+            assert!((receptor.offset[0] - x).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_hold_body_length_in_pixels() {
+        let pixel_system = PixelSystem::new(1920, 1080);
+        let to_px = |norm: f32| norm / pixel_system.pixel_size;
+        let full_screen_px = to_px(VISIBLE_DISTANCE);
+
+        // 500ms hold at 1000ms scroll covers half the visible distance
+        let idle = hold_body_length(1000.0, 1500.0, 0.0, 1000.0, false);
+        assert!((to_px(idle) - full_screen_px * 0.5).abs() < 0.01);
+
+        // Halving the scroll time doubles the length
+        let fast = hold_body_length(1000.0, 1500.0, 0.0, 500.0, false);
+        assert!((to_px(fast) - full_screen_px).abs() < 0.01);
+
+        // While held past the head, the body shrinks from the hit line
+        let held = hold_body_length(1000.0, 1500.0, 1200.0, 1000.0, true);
+        assert!((to_px(held) - full_screen_px * 0.3).abs() < 0.01);
+
+        // Not yet reached: being active doesn't change the length
+        let early = hold_body_length(1000.0, 1500.0, 800.0, 1000.0, true);
+        assert_eq!(early, idle);
+    }
+
+    #[test]
+    fn test_hit_line_offset_moves_receptors_and_notes() {
+        let pixel_system = PixelSystem::new(1920, 1080);
+        let mut playfield = PlayfieldDisplay::with_key_count(PlayfieldConfig::new(), 4);
+        // Note due right now: progress = 0, so it sits on the hit line
+        let notes = vec![NoteData::tap(1_000_000, 0)];
+        let positions = |playfield: &PlayfieldDisplay| {
+            let note = playfield.render_notes_typed(
+                &notes,
+                1000.0,
+                500.0,
+                &pixel_system,
+                &NoteVisibility::default(),
+            )[0]
+            .instance
+            .offset[1];
+            let receptor = playfield.render_receptors(&pixel_system)[0].offset[1];
+            (note, receptor)
+        };
+
+        let (note, receptor) = positions(&playfield);
+        assert!((note - HIT_LINE_Y).abs() < 1e-5);
+        assert!((receptor - HIT_LINE_Y).abs() < 1e-5);
+
+        playfield.config.hit_line_offset_pixels = 108.0;
+        let raised = HIT_LINE_Y + pixel_system.y_pixels_to_normalized(108.0);
+        let (note, receptor) = positions(&playfield);
+        assert!(raised > HIT_LINE_Y);
+        assert!((note - raised).abs() < 1e-5);
+        assert!((receptor - raised).abs() < 1e-5);
+    }
+}
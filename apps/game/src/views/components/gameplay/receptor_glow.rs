@@ -0,0 +1,78 @@
+//! Receptor glow component
+//! Brief flash of the judgement color on a receptor when its note is hit
+
+use crate::shared::snapshot::ReceptorHit;
+use crate::views::components::common::primitives::ProgressInstance;
+use engine::{InstanceRaw, JudgementColors};
+
+/// How long the glow lasts after a hit (ms of audio time).
+const GLOW_DURATION_MS: f64 = 150.0;
+
+/// Opacity of the glow right at the hit.
+const GLOW_MAX_ALPHA: f32 = 0.6;
+
+/// Glow intensity `elapsed_ms` after a hit, from 1.0 down to 0.0.
+///
+/// Eases out quadratically so the flash fades fast. Negative elapsed times
+/// (a hit ahead of the clock, e.g. after a practice retry) give no glow.
+pub fn receptor_glow(elapsed_ms: f64) -> f32 {
+    if !(0.0..GLOW_DURATION_MS).contains(&elapsed_ms) {
+        return 0.0;
+    }
+    let remaining = 1.0 - elapsed_ms / GLOW_DURATION_MS;
+    (remaining * remaining) as f32
+}
+
+/// Glow quads over the receptors that were hit recently.
+///
+/// `receptors` are the receptor instances, in column order.
+pub fn receptor_glow_instances(
+    receptors: &[InstanceRaw],
+    hits: &[Option<ReceptorHit>],
+    audio_time: f64,
+    colors: &JudgementColors,
+) -> Vec<ProgressInstance> {
+    receptors
+        .iter()
+        .zip(hits)
+        .filter_map(|(receptor, hit)| {
+            let hit = (*hit)?;
+            let glow = receptor_glow(audio_time - hit.time);
+            if glow <= 0.0 {
+                return None;
+            }
+            let mut color = colors.get(hit.judgement);
+            color[3] *= GLOW_MAX_ALPHA * glow;
+            Some(ProgressInstance {
+                center: receptor.offset,
+                size: receptor.scale,
+                filled_color: color,
+                empty_color: color,
+                progress: 1.0,
+                mode: 0, // Bar
+                padding: [0.0, 0.0],
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glow_decays_over_elapsed_time() {
+        assert_eq!(receptor_glow(0.0), 1.0);
+        assert_eq!(receptor_glow(75.0), 0.25);
+        let mut previous = 1.0;
+        for ms in (10..150).step_by(10) {
+            let glow = receptor_glow(ms as f64);
+            assert!(glow < previous);
+            previous = glow;
+        }
+        assert_eq!(receptor_glow(GLOW_DURATION_MS), 0.0);
+        assert_eq!(receptor_glow(1_000.0), 0.0);
+        // A hit ahead of the clock does not glow
+        assert_eq!(receptor_glow(-10.0), 0.0);
+    }
+}
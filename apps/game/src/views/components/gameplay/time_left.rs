@@ -10,6 +10,15 @@ pub enum TimeDisplayMode {
     Text,
 }
 
+/// Time left in the map (ms).
+///
+/// Pre-roll (negative elapsed time) reports the full duration, and
+/// anything past the end reports zero.
+pub fn time_left_ms(elapsed_ms: f64, total_ms: f64) -> f64 {
+    let total_ms = total_ms.max(0.0);
+    (total_ms - elapsed_ms.max(0.0)).clamp(0.0, total_ms)
+}
+
 pub struct TimeLeftDisplay {
     position: (f32, f32),
     size: (f32, f32),
@@ -94,9 +103,9 @@ impl TimeLeftDisplay {
         let scale_ratio = screen_height / 1080.0;
         let font_scale = self.text_scale * scale_ratio;
 
-        let remaining = (total_ms - elapsed_ms).max(0.0);
+        let remaining = time_left_ms(elapsed_ms, total_ms);
         let percent = if total_ms > 0.0 {
-            (elapsed_ms / total_ms * 100.0).min(100.0)
+            (elapsed_ms / total_ms * 100.0).clamp(0.0, 100.0)
         } else {
             0.0
         };
@@ -190,3 +199,21 @@ impl TimeLeftDisplay {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_time_left_ms() {
+        // Pre-roll: full duration left
+        assert_eq!(time_left_ms(-1500.0, 90_000.0), 90_000.0);
+        assert_eq!(time_left_ms(0.0, 90_000.0), 90_000.0);
+        // Mid-song
+        assert_eq!(time_left_ms(30_000.0, 90_000.0), 60_000.0);
+        // Past the last note
+        assert_eq!(time_left_ms(95_000.0, 90_000.0), 0.0);
+        // Empty map
+        assert_eq!(time_left_ms(1000.0, 0.0), 0.0);
+    }
+}
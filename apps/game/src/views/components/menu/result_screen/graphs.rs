@@ -1,6 +1,6 @@
 use engine::US_PER_MS;
 use engine::hit_window::HitWindow;
-use replay::ReplayResult;
+use replay::{ReplayResult, accuracy_over_time};
 use egui::{Align2, Color32, FontId, Painter, Pos2, Rect, Stroke, Ui, Vec2};
 
 /// Helper to convert µs to ms for display
@@ -30,6 +30,16 @@ pub fn render_graphs(ui: &mut Ui, replay_result: &ReplayResult, hit_window: &Hit
                     .allocate_painter(Vec2::new(ui.available_width(), 200.0), egui::Sense::hover());
                 render_timeline_graph(&painter, &response.rect, replay_result, hit_window);
             });
+        ui.add_space(20.0);
+        ui.label(egui::RichText::new("Accuracy").strong());
+        egui::Frame::canvas(ui.style())
+            .fill(Color32::from_black_alpha(50))
+            .stroke(Stroke::new(1.0, Color32::from_gray(60)))
+            .show(ui, |ui| {
+                let (response, painter) = ui
+                    .allocate_painter(Vec2::new(ui.available_width(), 120.0), egui::Sense::hover());
+                render_accuracy_graph(&painter, &response.rect, replay_result);
+            });
     });
 }
 
@@ -244,6 +254,66 @@ fn render_timeline_graph(
     }
 }
 
+fn render_accuracy_graph(painter: &Painter, rect: &Rect, replay_result: &ReplayResult) {
+    let samples = accuracy_over_time(&replay_result.hit_timings);
+    let (Some(first), Some(last)) = (samples.first(), samples.last()) else {
+        return;
+    };
+
+    let width = rect.width() - 40.0;
+    let graph_rect = Rect::from_min_max(
+        Pos2::new(rect.left(), rect.top() + 10.0),
+        Pos2::new(rect.left() + width, rect.bottom() - 10.0),
+    );
+
+    // Zoom the Y axis on the range actually reached, down to the lowest sample.
+    let min_acc = samples
+        .iter()
+        .map(|&(_, acc)| acc)
+        .fold(100.0_f64, f64::min)
+        .clamp(0.0, 99.0)
+        .floor();
+    let acc_range = (100.0 - min_acc).max(1.0);
+    let time_range = (last.0 - first.0).max(1.0);
+
+    let to_pos = |time_ms: f64, acc: f64| {
+        let x_ratio = ((time_ms - first.0) / time_range) as f32;
+        let y_ratio = ((acc - min_acc) / acc_range) as f32;
+        Pos2::new(
+            graph_rect.left() + x_ratio * graph_rect.width(),
+            graph_rect.bottom() - y_ratio * graph_rect.height(),
+        )
+    };
+
+    let font_id = FontId::monospace(10.0);
+    for acc in [100.0, min_acc] {
+        let y = to_pos(first.0, acc).y;
+        painter.line_segment(
+            [
+                Pos2::new(graph_rect.left(), y),
+                Pos2::new(graph_rect.right(), y),
+            ],
+            Stroke::new(1.0, Color32::WHITE.linear_multiply(0.15)),
+        );
+        painter.text(
+            Pos2::new(graph_rect.right() + 5.0, y),
+            Align2::LEFT_CENTER,
+            format!("{acc:.0}%"),
+            font_id.clone(),
+            Color32::from_gray(180),
+        );
+    }
+
+    let points: Vec<Pos2> = samples
+        .iter()
+        .map(|&(time_ms, acc)| to_pos(time_ms, acc))
+        .collect();
+    painter.add(egui::Shape::line(
+        points,
+        Stroke::new(1.5, Color32::from_rgb(0, 255, 255)),
+    ));
+}
+
 /// Get color based on timing offset (in ms) compared to hit window thresholds (in µs)
 fn get_color_for_timing_ms(timing_ms: f64, hit_window: &HitWindow) -> Color32 {
     // Convert timing from ms to µs for comparison with window thresholds
@@ -8,6 +8,15 @@ pub fn render_stats(ui: &mut Ui, data: &GameResultData) {
         ui.vertical_centered(|ui| {
             ui.add_space(10.0);
 
+            if data.failed {
+                ui.label(
+                    RichText::new("FAILED")
+                        .size(28.0)
+                        .strong()
+                        .color(Color32::from_rgb(255, 80, 80)),
+                );
+            }
+
             // Score in large font.
             ui.label(
                 RichText::new(format!("{:07}", data.score))
@@ -176,5 +185,15 @@ pub fn render_stats(ui: &mut Ui, data: &GameResultData) {
                     .color(Color32::WHITE),
             );
         });
+        if data.hit_stats.mine_hits > 0 {
+            ui.horizontal(|ui| {
+                ui.label(RichText::new("Mines Hit:").color(Color32::GRAY));
+                ui.label(
+                    RichText::new(data.hit_stats.mine_hits.to_string())
+                        .strong()
+                        .color(Color32::WHITE),
+                );
+            });
+        }
     });
 }
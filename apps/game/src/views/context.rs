@@ -1,7 +1,7 @@
 //! Render context structures.
 
-use engine::PixelSystem;
-use wgpu::{BindGroup, Buffer, Device, Queue, RenderPipeline, TextureView};
+use engine::{PixelSystem, Viewport};
+use wgpu::{BindGroup, Buffer, Device, Queue, RenderPass, RenderPipeline, TextureView};
 use wgpu_text::TextBrush;
 
 /// Contains all resources needed to render a game frame.
@@ -31,9 +31,25 @@ pub struct GameplayRenderContext<'a> {
 
     pub view: &'a TextureView,
     pub pixel_system: &'a PixelSystem,
+    /// Area of the target gameplay is drawn in (letterboxed for fixed ratios).
+    pub viewport: Viewport,
+    /// Whole target, for the text laid out in target coordinates.
+    pub target: Viewport,
 
     pub screen_width: f32,
     pub screen_height: f32,
     pub fps: f64,
     pub master_volume: f32,
 }
+
+/// Restricts the next draws of `pass` to `viewport`.
+pub fn set_viewport(pass: &mut RenderPass<'_>, viewport: Viewport) {
+    pass.set_viewport(
+        viewport.x,
+        viewport.y,
+        viewport.width,
+        viewport.height,
+        0.0,
+        1.0,
+    );
+}
@@ -10,13 +10,16 @@ use engine::{InstanceRaw, NUM_COLUMNS};
 use skin::JudgementLabels;
 use engine::JudgementColors;
 use crate::shared::snapshot::GameplaySnapshot;
+use crate::state::game::ComboMilestone;
+use crate::views::components::gameplay::life_bar::life_bar_instance;
 use crate::views::components::gameplay::playfield::NoteVisual;
+use crate::views::components::gameplay::receptor_glow::receptor_glow_instances;
 use crate::views::components::{
     AccuracyDisplay, ComboDisplay, HitBarDisplay, JudgementFlash, JudgementPanel,
     NotesRemainingDisplay, NpsDisplay, PlayfieldDisplay, ScoreDisplay, ScrollSpeedDisplay,
     TimeLeftDisplay,
 };
-use crate::views::context::GameplayRenderContext; // Import
+use crate::views::context::{GameplayRenderContext, set_viewport}; // Import
 
 pub struct GameplayView {
     playfield_component: PlayfieldDisplay,
@@ -27,6 +30,49 @@ pub struct GameplayView {
     hold_end_instances: Vec<InstanceRaw>,
     burst_body_instances: Vec<InstanceRaw>,
     burst_end_instances: Vec<InstanceRaw>,
+    /// Receptor shake amplitude (pixels) on combo break.
+    combo_break_shake_px: f32,
+    /// When the last combo break was received.
+    combo_break_at: Option<std::time::Instant>,
+    /// Combo break id of the last snapshot rendered.
+    seen_combo_break: u64,
+    /// Last combo milestone received and when.
+    combo_milestone: Option<(ComboMilestone, std::time::Instant)>,
+    /// Milestone id of the last snapshot rendered.
+    seen_milestone: u64,
+    /// Interpolate between the last two engine ticks instead of extrapolating.
+    snapshot_interpolation: bool,
+    /// Flash receptors in the judgement color on hit.
+    receptor_glow: bool,
+}
+
+/// Longest extrapolation past the latest snapshot (ms).
+const MAX_EXTRAPOLATION_MS: f64 = 50.0;
+
+/// Whether an event id is newer than the last one rendered.
+///
+/// Ids keep growing across runs, so this also holds for a new run.
+fn event_is_new(seen: &mut u64, id: u64) -> bool {
+    let new = id > *seen;
+    *seen = (*seen).max(id);
+    new
+}
+
+/// Position of `render` between two snapshots, in [0, 1].
+///
+/// Rendering runs one tick behind the logic: a frame drawn at `latest` shows
+/// `previous`, and one drawn a full tick later shows `latest`.
+fn interpolation_factor(
+    previous: std::time::Instant,
+    latest: std::time::Instant,
+    render: std::time::Instant,
+) -> f64 {
+    let tick = latest.saturating_duration_since(previous).as_secs_f64();
+    if tick <= 0.0 {
+        return 1.0;
+    }
+    let elapsed = render.saturating_duration_since(latest).as_secs_f64();
+    (elapsed / tick).clamp(0.0, 1.0)
 }
 
 impl GameplayView {
@@ -45,7 +91,77 @@ impl GameplayView {
             hold_end_instances: Vec::with_capacity(50),
             burst_body_instances: Vec::with_capacity(50),
             burst_end_instances: Vec::with_capacity(50),
+            combo_break_shake_px: 0.0,
+            combo_break_at: None,
+            seen_combo_break: 0,
+            combo_milestone: None,
+            seen_milestone: 0,
+            snapshot_interpolation: true,
+            receptor_glow: true,
+        }
+    }
+
+    /// Sets the receptor shake amplitude used on combo break (0 disables it).
+    pub fn set_combo_break_shake(&mut self, pixels: f32) {
+        self.combo_break_shake_px = pixels.max(0.0);
+    }
+
+    /// Enables interpolation between engine ticks (extrapolation otherwise).
+    pub fn set_snapshot_interpolation(&mut self, enabled: bool) {
+        self.snapshot_interpolation = enabled;
+    }
+
+    /// Enables the receptor flash on hit.
+    pub fn set_receptor_glow(&mut self, enabled: bool) {
+        self.receptor_glow = enabled;
+    }
+
+    /// Audio time to draw the notes at.
+    fn render_audio_time(&self, snapshot: &GameplaySnapshot, now: std::time::Instant) -> f64 {
+        if self.snapshot_interpolation
+            && let Some((previous_at, previous_time)) = snapshot.previous_tick
+            && snapshot.audio_time >= previous_time
+            && snapshot.timestamp.saturating_duration_since(previous_at)
+                <= std::time::Duration::from_secs_f64(MAX_EXTRAPOLATION_MS / 1000.0)
+        {
+            let factor = interpolation_factor(previous_at, snapshot.timestamp, now);
+            return previous_time + (snapshot.audio_time - previous_time) * factor;
+        }
+
+        // No interpolation (first snapshot, seek, pause): extrapolate
+        let delta_time_ms = now.duration_since(snapshot.timestamp).as_secs_f64() * 1000.0;
+        let clamped_delta = delta_time_ms.min(MAX_EXTRAPOLATION_MS);
+        snapshot.audio_time + (clamped_delta * snapshot.rate)
+    }
+
+    /// Horizontal receptor offset in pixels for the combo-break shake.
+    fn combo_break_offset_px(&self, now: std::time::Instant) -> f32 {
+        const SHAKE_DURATION_S: f32 = 0.2;
+        const SHAKE_FREQUENCY_HZ: f32 = 30.0;
+
+        let Some(start) = self.combo_break_at else {
+            return 0.0;
+        };
+        let elapsed = now.duration_since(start).as_secs_f32();
+        if elapsed >= SHAKE_DURATION_S {
+            return 0.0;
         }
+        let decay = 1.0 - elapsed / SHAKE_DURATION_S;
+        self.combo_break_shake_px
+            * decay
+            * (elapsed * SHAKE_FREQUENCY_HZ * std::f32::consts::TAU).sin()
+    }
+
+    /// Label and opacity of the milestone popup, if one is still showing.
+    fn combo_milestone_popup(&self, now: std::time::Instant) -> Option<(String, f32)> {
+        const POPUP_DURATION_S: f32 = 1.0;
+
+        let (milestone, start) = self.combo_milestone?;
+        let elapsed = now.duration_since(start).as_secs_f32();
+        if elapsed >= POPUP_DURATION_S {
+            return None;
+        }
+        Some((milestone.label(), 1.0 - elapsed / POPUP_DURATION_S))
     }
 
     pub fn playfield_component(&self) -> &PlayfieldDisplay {
@@ -89,15 +205,23 @@ impl GameplayView {
         let effective_scroll_speed = snapshot.scroll_speed * snapshot.rate;
 
         let now = std::time::Instant::now();
-        let delta_time_ms = now.duration_since(snapshot.timestamp).as_secs_f64() * 1000.0;
-        let clamped_delta = delta_time_ms.min(50.0);
-        let interpolated_time = snapshot.audio_time + (clamped_delta * snapshot.rate);
+        if event_is_new(&mut self.seen_combo_break, snapshot.combo_break_id) {
+            self.combo_break_at = Some(now);
+        }
+        let (milestone_id, last_milestone) = snapshot.combo_milestone;
+        if event_is_new(&mut self.seen_milestone, milestone_id)
+            && let Some(milestone) = last_milestone
+        {
+            self.combo_milestone = Some((milestone, now));
+        }
+        let interpolated_time = self.render_audio_time(snapshot, now);
 
         let typed_instances = self.playfield_component.render_notes_typed(
             &snapshot.visible_notes,
             interpolated_time,
             effective_scroll_speed,
             ctx.pixel_system,
+            &snapshot.visibility,
         );
 
         self.instance_cache.clear();
@@ -201,10 +325,34 @@ impl GameplayView {
             ctx.screen_height,
         ));
 
+        let milestone_popup = self.combo_milestone_popup(now);
+        if let Some((label, alpha)) = &milestone_popup {
+            let scale = 56.0;
+            let width = label.chars().count() as f32 * scale * 0.5;
+            text_sections.push(Section {
+                screen_position: ((ctx.screen_width - width) / 2.0, ctx.screen_height * 0.3),
+                bounds: (ctx.screen_width, ctx.screen_height),
+                text: vec![
+                    wgpu_text::glyph_brush::Text::new(label)
+                        .with_scale(scale)
+                        .with_color([1.0, 0.85, 0.3, *alpha]),
+                ],
+                ..Default::default()
+            });
+        }
+
         // PASSAGE DES COULEURS ET LABELS AU FLASH avec timing pour +/-
+        let flash_column_x = snapshot.last_hit_column.map(|column| {
+            self.playfield_component.column_center_screen_x(
+                column,
+                ctx.pixel_system,
+                ctx.screen_width,
+            )
+        });
         text_sections.extend(judgement_flash.render(
             snapshot.last_hit_judgement,
             snapshot.last_hit_timing, // timing in ms for +/- indicator
+            flash_column_x,
             ctx.screen_width,
             ctx.screen_height,
             colors,
@@ -212,11 +360,17 @@ impl GameplayView {
         ));
 
         text_sections.extend(hit_bar.render(
-            snapshot.last_hit_timing.zip(snapshot.last_hit_judgement),
+            &snapshot.recent_hits,
             ctx.screen_width,
             ctx.screen_height,
+            colors,
         ));
-        text_sections.extend(nps_display.render(snapshot.nps, ctx.screen_width, ctx.screen_height));
+        let nps = if nps_display.smoothed {
+            snapshot.smoothed_nps
+        } else {
+            snapshot.nps
+        };
+        text_sections.extend(nps_display.render(nps, ctx.screen_width, ctx.screen_height));
 
         // NEW: Separate display components
         text_sections.extend(notes_remaining_display.render(
@@ -236,11 +390,24 @@ impl GameplayView {
             ctx.screen_height,
         ));
 
+        // HUD is laid out in viewport space, shift it into the letterboxed area
+        for section in &mut text_sections {
+            section.screen_position.0 += ctx.viewport.x;
+            section.screen_position.1 += ctx.viewport.y;
+        }
+
         ctx.text_brush
             .queue(ctx.device, ctx.queue, text_sections)
             .map_err(|_| wgpu::SurfaceError::Lost)?;
 
-        let receptor_instances = self.playfield_component.render_receptors(ctx.pixel_system);
+        let mut receptor_instances = self.playfield_component.render_receptors(ctx.pixel_system);
+        let shake_px = self.combo_break_offset_px(now);
+        if shake_px != 0.0 {
+            let shake_norm = ctx.pixel_system.x_pixels_to_normalized(shake_px);
+            for receptor in &mut receptor_instances {
+                receptor.offset[0] += shake_norm;
+            }
+        }
         if !receptor_instances.is_empty() {
             ctx.queue.write_buffer(
                 ctx.receptor_buffer,
@@ -266,6 +433,7 @@ impl GameplayView {
                 occlusion_query_set: None,
             });
 
+            set_viewport(&mut render_pass, ctx.viewport);
             render_pass.set_pipeline(ctx.render_pipeline);
 
             if !receptor_instances.is_empty() {
@@ -369,26 +537,85 @@ impl GameplayView {
                 ctx.instance_buffer,
             );
 
-            // Render TimeLeft progress (Bar/Circle)
-            if let Some(instance) = time_left_display.get_progress_instance(
-                snapshot.audio_time,
-                snapshot.map_duration,
-                ctx.screen_width,
-                ctx.screen_height,
-            ) {
+            // Render TimeLeft progress (Bar/Circle), the life bar and the
+            // receptor glow
+            let life_bar = snapshot
+                .health
+                .map(|health| life_bar_instance(health, ctx.screen_width, ctx.screen_height));
+            let receptor_glow = if self.receptor_glow {
+                receptor_glow_instances(
+                    &receptor_instances,
+                    &snapshot.receptor_hits,
+                    interpolated_time,
+                    colors,
+                )
+            } else {
+                Vec::new()
+            };
+            let progress_instances: Vec<_> = time_left_display
+                .get_progress_instance(
+                    snapshot.audio_time,
+                    snapshot.map_duration,
+                    ctx.screen_width,
+                    ctx.screen_height,
+                )
+                .into_iter()
+                .chain(life_bar)
+                .chain(receptor_glow)
+                .collect();
+            if !progress_instances.is_empty() {
                 // Write to buffer
-                ctx.queue
-                    .write_buffer(ctx.progress_buffer, 0, bytemuck::bytes_of(&instance));
+                ctx.queue.write_buffer(
+                    ctx.progress_buffer,
+                    0,
+                    bytemuck::cast_slice(&progress_instances),
+                );
 
                 // Draw
                 render_pass.set_pipeline(ctx.progress_pipeline);
                 render_pass.set_vertex_buffer(0, ctx.progress_buffer.slice(..));
-                render_pass.draw(0..4, 0..1); // 4 vertices for triangle strip, 1 instance
+                // 4 vertices for triangle strip, one instance per bar
+                render_pass.draw(0..4, 0..progress_instances.len() as u32);
             }
 
+            // Text sections were already shifted into the viewport
+            set_viewport(&mut render_pass, ctx.target);
             ctx.text_brush.draw(&mut render_pass);
         }
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_interpolation_factor() {
+        let previous = Instant::now();
+        let latest = previous + Duration::from_millis(5);
+        let at = |us: u64| latest + Duration::from_micros(us);
+
+        assert_eq!(interpolation_factor(previous, latest, latest), 0.0);
+        assert!((interpolation_factor(previous, latest, at(2_500)) - 0.5).abs() < 1e-9);
+        assert_eq!(interpolation_factor(previous, latest, at(5_000)), 1.0);
+        // Clamped: never past the latest snapshot, nor before the previous one
+        assert_eq!(interpolation_factor(previous, latest, at(20_000)), 1.0);
+        assert_eq!(interpolation_factor(previous, latest, previous), 0.0);
+        // Two snapshots at the same instant
+        assert_eq!(interpolation_factor(latest, latest, at(1_000)), 1.0);
+    }
+
+    #[test]
+    fn test_event_is_new() {
+        let mut seen = 0;
+        assert!(!event_is_new(&mut seen, 0));
+        // Two breaks between rendered snapshots still trigger once
+        assert!(event_is_new(&mut seen, 2));
+        assert!(!event_is_new(&mut seen, 2));
+        // The next run goes on from the last id
+        assert!(event_is_new(&mut seen, 3));
+    }
+}
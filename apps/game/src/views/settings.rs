@@ -1,4 +1,9 @@
-use crate::models::settings::{HitWindowMode, SettingsState};
+use crate::models::settings::{
+    AspectRatioMode, BindableAction, DisplayMode, FrameCap, HitWindowMode, PLAYFIELD_SCALE_RANGE,
+    PlayfieldAlignment, SettingsState,
+};
+use crate::ui::i18n::{TextKey, available_languages, t};
+use engine::{ComboBreak, HealthPreset, Palette, ScoringModel};
 use log::info;
 
 #[derive(Clone)]
@@ -6,7 +11,17 @@ pub struct SettingsSnapshot {
     pub skin: String,
     pub hit_window_mode: HitWindowMode,
     pub hit_window_value: f64,
+    pub custom_hit_window_ms: [f64; 6],
     pub master_volume: f32,
+    pub playfield_alignment: PlayfieldAlignment,
+    pub receptor_offset: f32,
+    pub note_scale: f32,
+    pub column_width_scale: f32,
+    pub palette: Palette,
+    pub aspect_ratio_mode: AspectRatioMode,
+    pub frame_cap: FrameCap,
+    pub display_mode: DisplayMode,
+    pub monitor_index: usize,
 }
 
 impl SettingsSnapshot {
@@ -15,7 +30,17 @@ impl SettingsSnapshot {
             skin: settings.current_skin.clone(),
             hit_window_mode: settings.hit_window_mode,
             hit_window_value: settings.hit_window_value,
+            custom_hit_window_ms: settings.custom_hit_window_ms,
             master_volume: settings.master_volume,
+            playfield_alignment: settings.playfield_alignment,
+            receptor_offset: settings.receptor_offset,
+            note_scale: settings.note_scale,
+            column_width_scale: settings.column_width_scale,
+            palette: settings.palette,
+            aspect_ratio_mode: settings.aspect_ratio_mode,
+            frame_cap: settings.frame_cap,
+            display_mode: settings.display_mode,
+            monitor_index: settings.monitor_index,
         }
     }
 }
@@ -24,7 +49,7 @@ pub struct SettingsWindowResult {
     pub request_toggle: bool,
     pub volume_changed: Option<f32>,
     pub keybinds_updated: bool,
-    pub hit_window_changed: Option<(HitWindowMode, f64)>,
+    pub hit_window_changed: Option<(HitWindowMode, f64, [f64; 6])>,
 }
 
 pub fn render_settings_window(
@@ -38,10 +63,10 @@ pub fn render_settings_window(
     let mut open = true;
     let mut keybinds_updated = false;
 
-    egui::Window::new("Settings")
+    egui::Window::new(t(TextKey::SettingsTitle))
         .open(&mut open)
         .show(ctx, |ui| {
-            ui.heading("Skin");
+            ui.heading(t(TextKey::Skin));
             let mut skins = vec!["default".to_string()];
             if let Ok(entries) = std::fs::read_dir("skins") {
                 for entry in entries.flatten() {
@@ -64,9 +89,16 @@ pub fn render_settings_window(
                         );
                     }
                 });
+            egui::ComboBox::from_label(t(TextKey::Language))
+                .selected_text(&settings.language)
+                .show_ui(ui, |ui| {
+                    for language in available_languages() {
+                        ui.selectable_value(&mut settings.language, language.clone(), language);
+                    }
+                });
 
             ui.separator();
-            ui.heading("Audio");
+            ui.heading(t(TextKey::Audio));
             ui.add(
                 egui::Slider::new(&mut settings.master_volume, 0.0..=1.0)
                     .text("Master Volume")
@@ -80,12 +112,21 @@ pub fn render_settings_window(
             );
             ui.label("Adjust if notes and audio are out of sync.");
 
+            ui.checkbox(&mut settings.keysounds, "Keysounds (play note samples)");
+            ui.add_enabled(
+                settings.keysounds,
+                egui::Checkbox::new(
+                    &mut settings.mute_music_on_keysounds,
+                    "Mute music on keysounded maps",
+                ),
+            );
+
             if (settings.master_volume - snapshot.master_volume).abs() > f32::EPSILON {
                 volume_changed = Some(settings.master_volume);
             }
 
             ui.separator();
-            ui.heading("Gameplay");
+            ui.heading(t(TextKey::Gameplay));
             ui.horizontal(|ui| {
                 if ui.button("-50").clicked() {
                     settings.scroll_speed = (settings.scroll_speed - 50.0).max(100.0);
@@ -107,24 +148,112 @@ pub fn render_settings_window(
             });
             ui.label("Lower = faster notes, Higher = slower notes");
 
+            let milestones = &mut settings.combo_milestones;
+            ui.checkbox(&mut milestones.enabled, "Combo milestone popups");
+            ui.add_enabled_ui(milestones.enabled, |ui| {
+                ui.checkbox(&mut milestones.full_combo, "Celebrate full combos");
+                ui.label("Milestones (combo):");
+                let mut removed = None;
+                for (index, threshold) in milestones.thresholds.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        ui.add(egui::DragValue::new(threshold).range(1..=10_000).speed(10));
+                        if ui.small_button("Remove").clicked() {
+                            removed = Some(index);
+                        }
+                    });
+                }
+                if let Some(index) = removed {
+                    milestones.thresholds.remove(index);
+                }
+                if ui.button("Add milestone").clicked() {
+                    let next = milestones
+                        .thresholds
+                        .iter()
+                        .max()
+                        .map_or(100, |max| max + 100);
+                    milestones.thresholds.push(next);
+                }
+            });
+
+            let dim = &mut settings.background_dim;
+            ui.add(
+                egui::Slider::new(&mut dim.dim, 0.0..=1.0)
+                    .text("Background Dim")
+                    .step_by(0.05),
+            );
+            ui.checkbox(&mut dim.combo_reactive, "Brighten background with combo");
+            ui.add_enabled_ui(dim.combo_reactive, |ui| {
+                ui.add(
+                    egui::Slider::new(&mut dim.min_dim, 0.0..=1.0)
+                        .text("Dim at full brightness")
+                        .step_by(0.05),
+                );
+                ui.add(
+                    egui::Slider::new(&mut dim.full_combo_at, 50..=2000)
+                        .text("Combo for full brightness")
+                        .step_by(50.0),
+                );
+            });
+
+            let menu_bg = &mut settings.menu_background;
+            ui.add(
+                egui::Slider::new(&mut menu_bg.dim, 0.0..=1.0)
+                    .text("Song Select Background Dim")
+                    .step_by(0.05),
+            );
+            ui.add(
+                egui::Slider::new(&mut menu_bg.blur, 0.0..=32.0)
+                    .text("Song Select Background Blur (px)")
+                    .step_by(1.0),
+            );
+            ui.add(
+                egui::Slider::new(&mut menu_bg.crossfade_ms, 0.0..=1000.0)
+                    .text("Background Crossfade (ms)")
+                    .step_by(50.0),
+            );
+
+            ui.checkbox(
+                &mut settings.save_abandoned_runs,
+                "Keep abandoned runs in score history",
+            );
+            ui.checkbox(&mut settings.track_achievements, "Unlock achievements");
+
             ui.separator();
-            ui.heading("Judgement");
+            ui.heading(t(TextKey::VisibilityMods));
+            ui.label("0 = receptors, 1 = top of the screen.");
+            ui.add(
+                egui::Slider::new(&mut settings.hidden_band.transparent_at, 0.0..=1.0)
+                    .text("Hidden: invisible below")
+                    .step_by(0.01),
+            );
+            ui.add(
+                egui::Slider::new(&mut settings.hidden_band.opaque_at, 0.0..=1.0)
+                    .text("Hidden: fully visible above")
+                    .step_by(0.01),
+            );
+            ui.add(
+                egui::Slider::new(&mut settings.fade_in_band.transparent_at, 0.0..=1.0)
+                    .text("Fade In: invisible above")
+                    .step_by(0.01),
+            );
+            ui.add(
+                egui::Slider::new(&mut settings.fade_in_band.opaque_at, 0.0..=1.0)
+                    .text("Fade In: fully visible below")
+                    .step_by(0.01),
+            );
+
+            ui.separator();
+            ui.heading(t(TextKey::Judgement));
             egui::ComboBox::from_label("Mode")
-                .selected_text(match settings.hit_window_mode {
-                    HitWindowMode::OsuOD => "Osu! Overall Diff",
-                    HitWindowMode::EtternaJudge => "Etterna Judge",
-                })
+                .selected_text(settings.hit_window_mode.name())
                 .show_ui(ui, |ui| {
-                    ui.selectable_value(
-                        &mut settings.hit_window_mode,
+                    for mode in [
                         HitWindowMode::OsuOD,
-                        "Osu! Overall Diff",
-                    );
-                    ui.selectable_value(
-                        &mut settings.hit_window_mode,
                         HitWindowMode::EtternaJudge,
-                        "Etterna Judge",
-                    );
+                        HitWindowMode::Custom,
+                    ] {
+                        ui.selectable_value(&mut settings.hit_window_mode, mode, mode.name());
+                    }
                 });
 
             match settings.hit_window_mode {
@@ -143,11 +272,183 @@ pub fn render_settings_window(
                     );
                     settings.hit_window_value = settings.hit_window_value.round();
                 }
+                HitWindowMode::Custom => {
+                    // Each window is at least as wide as the previous one
+                    let names = ["Marvelous", "Perfect", "Great", "Good", "Bad", "Miss"];
+                    let mut floor = 1.0;
+                    for (window, name) in settings.custom_hit_window_ms.iter_mut().zip(names) {
+                        ui.add(
+                            egui::Slider::new(window, floor..=500.0)
+                                .text(format!("{name} (ms)"))
+                                .step_by(1.0),
+                        );
+                        *window = window.max(floor);
+                        floor = *window;
+                    }
+                }
             }
 
+            egui::ComboBox::from_label("Scoring")
+                .selected_text(settings.scoring_model.display_name())
+                .show_ui(ui, |ui| {
+                    for model in ScoringModel::all() {
+                        ui.selectable_value(
+                            &mut settings.scoring_model,
+                            *model,
+                            model.display_name(),
+                        );
+                    }
+                });
+
+            egui::ComboBox::from_label("Combo")
+                .selected_text(settings.combo_break_rule.display_name())
+                .show_ui(ui, |ui| {
+                    for rule in ComboBreak::all() {
+                        ui.selectable_value(
+                            &mut settings.combo_break_rule,
+                            *rule,
+                            rule.display_name(),
+                        );
+                    }
+                });
+
+            egui::ComboBox::from_label("Life bar")
+                .selected_text(settings.health_preset.display_name())
+                .show_ui(ui, |ui| {
+                    for preset in HealthPreset::all() {
+                        ui.selectable_value(
+                            &mut settings.health_preset,
+                            *preset,
+                            preset.display_name(),
+                        );
+                    }
+                });
+            ui.add_enabled(
+                settings.health_preset != HealthPreset::Off,
+                egui::Checkbox::new(&mut settings.fail_on_empty_health, "Fail when empty"),
+            );
+
+            egui::ComboBox::from_label("Playfield alignment")
+                .selected_text(settings.playfield_alignment.display_name())
+                .show_ui(ui, |ui| {
+                    for alignment in PlayfieldAlignment::all() {
+                        ui.selectable_value(
+                            &mut settings.playfield_alignment,
+                            *alignment,
+                            alignment.display_name(),
+                        );
+                    }
+                });
+
+            ui.add(
+                egui::Slider::new(&mut settings.receptor_offset, 0.0..=400.0)
+                    .text("Receptor Offset (px)")
+                    .step_by(1.0),
+            );
+            ui.add(
+                egui::Slider::new(&mut settings.note_scale, PLAYFIELD_SCALE_RANGE)
+                    .text("Note size")
+                    .step_by(0.05),
+            );
+            ui.add(
+                egui::Slider::new(&mut settings.column_width_scale, PLAYFIELD_SCALE_RANGE)
+                    .text("Column width")
+                    .step_by(0.05),
+            );
+
+            egui::ComboBox::from_label("Frame cap")
+                .selected_text(settings.frame_cap.display_name())
+                .show_ui(ui, |ui| {
+                    for cap in FrameCap::all() {
+                        ui.selectable_value(&mut settings.frame_cap, *cap, cap.display_name());
+                    }
+                });
+            ui.checkbox(
+                &mut settings.snapshot_interpolation,
+                "Smooth note movement (interpolation)",
+            );
+            ui.checkbox(&mut settings.receptor_glow, "Flash receptors on hit");
+            egui::ComboBox::from_label("Color palette")
+                .selected_text(settings.palette.display_name())
+                .show_ui(ui, |ui| {
+                    for palette in Palette::all() {
+                        ui.selectable_value(
+                            &mut settings.palette,
+                            *palette,
+                            palette.display_name(),
+                        );
+                    }
+                });
+
+            egui::ComboBox::from_label("Display mode")
+                .selected_text(settings.display_mode.display_name())
+                .show_ui(ui, |ui| {
+                    for mode in DisplayMode::all() {
+                        ui.selectable_value(&mut settings.display_mode, *mode, mode.display_name());
+                    }
+                });
+            ui.add_enabled_ui(settings.display_mode != DisplayMode::Windowed, |ui| {
+                ui.add(
+                    egui::DragValue::new(&mut settings.monitor_index)
+                        .range(0..=7)
+                        .prefix("Monitor "),
+                );
+            });
+            ui.add(
+                egui::Slider::new(&mut settings.ui_scale, settings::UI_SCALE_RANGE)
+                    .text("UI scale")
+                    .suffix("x")
+                    .step_by(0.05),
+            );
+
+            ui.add(
+                egui::Slider::new(&mut settings.max_analyzed_notes, 1_000..=100_000)
+                    .text("Max notes to rate")
+                    .step_by(1_000.0),
+            );
+
+            ui.checkbox(
+                &mut settings.watch_songs,
+                "Watch songs folder for new maps (restart to apply)",
+            );
+
+            ui.add(
+                egui::Slider::new(&mut settings.tick_rate, settings::TICK_RATE_RANGE)
+                    .text("Logic tick rate (restart to apply)")
+                    .suffix(" TPS")
+                    .step_by(50.0),
+            );
+
+            ui.label("Rated rates");
+            let rate_range = &mut settings.rate_range;
+            ui.horizontal(|ui| {
+                ui.add(
+                    egui::DragValue::new(&mut rate_range.min)
+                        .range(0.5..=rate_range.max)
+                        .speed(0.05)
+                        .prefix("from ")
+                        .suffix("x"),
+                );
+                ui.add(
+                    egui::DragValue::new(&mut rate_range.max)
+                        .range(rate_range.min..=3.0)
+                        .speed(0.05)
+                        .prefix("to ")
+                        .suffix("x"),
+                );
+                ui.add(
+                    egui::DragValue::new(&mut rate_range.step)
+                        .range(0.05..=0.5)
+                        .speed(0.05)
+                        .prefix("by ")
+                        .suffix("x"),
+                );
+            });
+
             ui.separator();
-            ui.heading("Keybinds");
+            ui.heading(t(TextKey::Keybinds));
             ui.label("Choose a keymode below, then press the required keys in order.");
+            ui.label("Extra keys can be added to a single column, e.g. both D and Left.");
             let mut columns: Vec<_> = settings.keybinds.keys().cloned().collect();
             columns.sort_by_key(|key| key.parse::<usize>().unwrap_or(0));
             for column in columns {
@@ -175,31 +476,86 @@ pub fn render_settings_window(
                             settings.remapping_buffer.len(),
                             column_count
                         ));
-                        if ui.button("Cancel").clicked() {
+                        if ui.button(t(TextKey::Cancel)).clicked() {
                             settings.cancel_keybind_capture();
                         }
-                    } else if ui.button("Rebind").clicked() {
+                    } else if ui.button(t(TextKey::Rebind)).clicked() {
                         settings.begin_keybind_capture(column_count);
                     }
                 });
+
+                egui::CollapsingHeader::new("Extra keys per column")
+                    .id_salt(("column_keys", column_count))
+                    .show(ui, |ui| {
+                        for (idx, keys) in settings.column_keys(column_count).iter().enumerate() {
+                            ui.horizontal(|ui| {
+                                ui.label(format!("Column {}", idx + 1));
+                                for key in keys {
+                                    if ui
+                                        .small_button(format!("{key} ✕"))
+                                        .on_hover_text("Unbind")
+                                        .clicked()
+                                    {
+                                        settings.remove_column_key(column_count, idx, key);
+                                    }
+                                }
+
+                                if settings.adding_column_key == Some((column_count, idx)) {
+                                    ui.label("Press a key...");
+                                    if ui.small_button("Cancel").clicked() {
+                                        settings.cancel_keybind_capture();
+                                    }
+                                } else if ui.small_button("+").clicked() {
+                                    settings.begin_column_key_capture(column_count, idx);
+                                }
+                            });
+                        }
+                    });
+            }
+
+            ui.label("Actions (press a single key):");
+            for &action in BindableAction::all() {
+                let existing = settings.action_keys(action).join(", ");
+                ui.horizontal(|ui| {
+                    ui.label(action.display_name());
+                    let label = if existing.is_empty() {
+                        "(no keys set)".to_string()
+                    } else {
+                        existing
+                    };
+                    ui.label(label);
+
+                    if settings.remapping_action == Some(action) {
+                        ui.label("Listening...");
+                        if ui.button(t(TextKey::Cancel)).clicked() {
+                            settings.cancel_keybind_capture();
+                        }
+                    } else if ui.button(t(TextKey::Rebind)).clicked() {
+                        settings.begin_action_capture(action);
+                    }
+                });
             }
-            if ui.button("Reset keybinds to defaults").clicked() {
+            if ui.button(t(TextKey::ResetKeybinds)).clicked() {
                 settings.reset_keybinds();
                 settings.cancel_keybind_capture();
             }
 
-            if ui.button("Save").clicked() {
+            if ui.button(t(TextKey::Save)).clicked() {
                 settings.save();
 
                 if settings.hit_window_mode != snapshot.hit_window_mode
                     || (settings.hit_window_value - snapshot.hit_window_value).abs() > f64::EPSILON
+                    || settings.custom_hit_window_ms != snapshot.custom_hit_window_ms
                 {
                     info!(
                         "Settings: Hit window updated -> mode {:?}, value {:.2}",
                         settings.hit_window_mode, settings.hit_window_value
                     );
-                    hit_window_changed =
-                        Some((settings.hit_window_mode, settings.hit_window_value));
+                    hit_window_changed = Some((
+                        settings.hit_window_mode,
+                        settings.hit_window_value,
+                        settings.custom_hit_window_ms,
+                    ));
                 }
 
                 if (settings.master_volume - snapshot.master_volume).abs() > f32::EPSILON {
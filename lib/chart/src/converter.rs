@@ -4,12 +4,44 @@
 //! 1. Decoding the source file with ROX (any format: .osu, .qua, .sm, .json)
 //! 2. Encoding to .osu format in memory using OsuEncoder
 //! 3. Parsing the .osu bytes with rosu_map for difficulty calculation
+//!
+//! [`verify_roundtrip`] checks that the notes survive the conversion, and
+//! [`chart_to_osu_string`] writes gameplay notes back out as an .osu file.
 
+use engine::{ChartMetadata, NoteData};
 use rhythm_open_exchange::codec::formats::osu::OsuEncoder;
 use rhythm_open_exchange::codec::{Encoder, auto_decode};
+use rhythm_open_exchange::{Note as RoxNote, NoteType, RoxChart, TimingPoint};
 use rosu_map::Beatmap;
+use rosu_map::section::hit_objects::HitObjectKind;
+use std::collections::HashMap;
 use std::path::Path;
 
+/// Largest timing change tolerated by [`verify_roundtrip`] (in µs).
+/// The .osu format stores whole milliseconds.
+pub const ROUNDTRIP_TOLERANCE_US: i64 = 1_000;
+
+/// Width of the osu! playfield, which mania splits into columns.
+const OSU_PLAYFIELD_WIDTH: f32 = 512.0;
+
+/// BPM written when the chart has no timing point.
+const DEFAULT_BPM: f64 = 120.0;
+
+/// A note the rosu conversion did not preserve.
+///
+/// Notes are paired by nearest time within their column; `None` on one side
+/// means the note is missing from that side.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NoteDiff {
+    /// Position of the note in the sorted source notes (in the converted
+    /// notes when `expected` is `None`).
+    pub index: usize,
+    /// (time in µs, column) in the source chart.
+    pub expected: Option<(i64, usize)>,
+    /// (time in µs, column) after conversion.
+    pub actual: Option<(i64, usize)>,
+}
+
 /// Load any supported chart format and convert to rosu_map::Beatmap.
 ///
 /// This allows difficulty calculators (MinaCalc, rosu-pp) that require
@@ -38,7 +70,7 @@ pub fn load_as_rosu_beatmap(path: &Path) -> Result<Beatmap, String> {
 ///
 /// This is useful when you already have a decoded chart (e.g., during scanning)
 /// and want to calculate difficulty without re-reading the file.
-pub fn rox_chart_to_rosu(chart: &rhythm_open_exchange::RoxChart) -> Result<Beatmap, String> {
+pub fn rox_chart_to_rosu(chart: &RoxChart) -> Result<Beatmap, String> {
     // Encode to .osu format string
     let osu_content =
         OsuEncoder::encode_to_string(chart).map_err(|e| format!("OsuEncoder failed: {}", e))?;
@@ -49,3 +81,277 @@ pub fn rox_chart_to_rosu(chart: &rhythm_open_exchange::RoxChart) -> Result<Beatm
 
     Ok(beatmap)
 }
+
+/// Converts `chart` to rosu and reads its notes back, reporting every note
+/// whose time moved by more than [`ROUNDTRIP_TOLERANCE_US`] or which is
+/// missing from one side. Mines have no osu! equivalent and are skipped.
+/// A conversion failure is reported as every note missing.
+pub fn verify_roundtrip(chart: &RoxChart) -> Vec<NoteDiff> {
+    let expected: Vec<(i64, usize)> = chart
+        .notes
+        .iter()
+        .filter(|n| !matches!(n.note_type, NoteType::Mine))
+        .map(|n| (n.time_us, n.column as usize))
+        .collect();
+
+    let actual = match rox_chart_to_rosu(chart) {
+        Ok(beatmap) => rosu_notes(&beatmap),
+        Err(e) => {
+            log::warn!("CONVERTER: round-trip conversion failed: {}", e);
+            Vec::new()
+        }
+    };
+
+    diff_notes(expected, actual)
+}
+
+/// Pairs every expected note with the nearest converted note of its column.
+///
+/// Notes within the tolerance are paired first, so a single moved note is
+/// reported once instead of shifting every pair after it.
+fn diff_notes(mut expected: Vec<(i64, usize)>, mut actual: Vec<(i64, usize)>) -> Vec<NoteDiff> {
+    expected.sort_unstable();
+    actual.sort_unstable();
+
+    // Indices into `actual` per column, in time order
+    let mut by_column: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (j, &(_, column)) in actual.iter().enumerate() {
+        by_column.entry(column).or_default().push(j);
+    }
+
+    let mut used = vec![false; actual.len()];
+    // Expected index -> (actual index, within tolerance)
+    let mut pairs: Vec<Option<(usize, bool)>> = vec![None; expected.len()];
+    for within_tolerance in [true, false] {
+        for (i, &(time, column)) in expected.iter().enumerate() {
+            if pairs[i].is_some() {
+                continue;
+            }
+            let candidates = by_column.get(&column).map_or(&[][..], Vec::as_slice);
+            let Some(j) = nearest_unused(&actual, candidates, &used, time) else {
+                continue;
+            };
+            let close = (actual[j].0 - time).abs() <= ROUNDTRIP_TOLERANCE_US;
+            if close || !within_tolerance {
+                used[j] = true;
+                pairs[i] = Some((j, close));
+            }
+        }
+    }
+
+    let mut diffs: Vec<NoteDiff> = expected
+        .iter()
+        .zip(&pairs)
+        .enumerate()
+        .filter_map(|(index, (&expected, pair))| match *pair {
+            Some((_, true)) => None,
+            Some((j, false)) => Some(NoteDiff {
+                index,
+                expected: Some(expected),
+                actual: Some(actual[j]),
+            }),
+            None => Some(NoteDiff {
+                index,
+                expected: Some(expected),
+                actual: None,
+            }),
+        })
+        .collect();
+    diffs.extend(
+        actual
+            .iter()
+            .enumerate()
+            .filter(|&(j, _)| !used[j])
+            .map(|(index, &actual)| NoteDiff {
+                index,
+                expected: None,
+                actual: Some(actual),
+            }),
+    );
+    diffs
+}
+
+/// Index in `actual` of the unused note nearest to `time` among `candidates`.
+fn nearest_unused(
+    actual: &[(i64, usize)],
+    candidates: &[usize],
+    used: &[bool],
+    time: i64,
+) -> Option<usize> {
+    let split = candidates.partition_point(|&j| actual[j].0 < time);
+    let before = candidates[..split]
+        .iter()
+        .rev()
+        .copied()
+        .find(|&j| !used[j]);
+    let after = candidates[split..].iter().copied().find(|&j| !used[j]);
+    match (before, after) {
+        (Some(b), Some(a)) => Some(if time - actual[b].0 <= actual[a].0 - time {
+            b
+        } else {
+            a
+        }),
+        (b, a) => b.or(a),
+    }
+}
+
+/// Writes notes as an osu!mania beatmap (.osu file content).
+///
+/// Holds and bursts become hold objects; mines have no osu! equivalent and
+/// are left out. The file references no audio: set `AudioFilename` before
+/// opening it in osu!.
+pub fn chart_to_osu_string(
+    notes: &[NoteData],
+    meta: &ChartMetadata,
+    key_count: usize,
+) -> Result<String, String> {
+    let mut chart = RoxChart::new(key_count.clamp(1, u8::MAX as usize) as u8);
+    chart.metadata.title = meta.title.clone();
+    chart.metadata.artist = meta.artist.clone();
+    chart.metadata.creator = meta.creator.clone();
+    chart.metadata.difficulty_name = meta.difficulty_name.clone();
+    chart.metadata.preview_time_us = meta.preview_time_us;
+
+    let bpm = if meta.bpm > 0.0 {
+        meta.bpm
+    } else {
+        DEFAULT_BPM
+    };
+    chart.timing_points.push(TimingPoint::bpm(0, bpm as f32));
+    chart.notes = notes
+        .iter()
+        .filter_map(|note| {
+            let inner = note.inner();
+            match inner.note_type {
+                NoteType::Mine => None,
+                NoteType::Burst { duration_us } => {
+                    Some(RoxNote::hold(inner.time_us, duration_us, inner.column))
+                }
+                _ => Some(inner.clone()),
+            }
+        })
+        .collect();
+
+    OsuEncoder::encode_to_string(&chart).map_err(|e| format!("OsuEncoder failed: {}", e))
+}
+
+/// (time in µs, column) of the notes of a mania beatmap.
+fn rosu_notes(beatmap: &Beatmap) -> Vec<(i64, usize)> {
+    let key_count = beatmap.circle_size.round().max(1.0);
+    beatmap
+        .hit_objects
+        .iter()
+        .map(|ho| {
+            let x = match &ho.kind {
+                HitObjectKind::Circle(circle) => circle.pos.x,
+                HitObjectKind::Slider(slider) => slider.pos.x,
+                HitObjectKind::Spinner(spinner) => spinner.pos.x,
+                HitObjectKind::Hold(hold) => hold.pos_x,
+            };
+            let column = (x * key_count / OSU_PLAYFIELD_WIDTH)
+                .floor()
+                .clamp(0.0, key_count - 1.0) as usize;
+            ((ho.start_time * 1000.0).round() as i64, column)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixture_roundtrips_without_diffs() {
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../engine/fixtures/metadata_4k.osu"
+        );
+        let chart = auto_decode(Path::new(path)).expect("fixture decodes");
+        // Taps and a hold over the four columns
+        assert_eq!(chart.notes.len(), 4);
+        assert_eq!(verify_roundtrip(&chart), Vec::new());
+    }
+
+    #[test]
+    fn test_roundtrip_skips_mines() {
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../engine/fixtures/metadata_4k.osu"
+        );
+        let mut chart = auto_decode(Path::new(path)).expect("fixture decodes");
+        let time_us = chart.notes[0].time_us;
+        chart
+            .notes
+            .push(rhythm_open_exchange::Note::mine(time_us, 1));
+        assert_eq!(verify_roundtrip(&chart), Vec::new());
+    }
+
+    #[test]
+    fn test_diff_pairs_notes_by_column_and_time() {
+        let expected = vec![(1_000, 0), (1_000, 1), (2_000_000, 0), (3_000_000, 0)];
+        // Chord written in another order, one note moved by 500 ms
+        let actual = vec![(1_000, 1), (1_500, 0), (2_500_000, 0), (3_000_000, 0)];
+        assert_eq!(
+            diff_notes(expected, actual),
+            vec![NoteDiff {
+                index: 2,
+                expected: Some((2_000_000, 0)),
+                actual: Some((2_500_000, 0)),
+            }]
+        );
+
+        // A note that changed column is missing from one and extra in the other
+        assert_eq!(
+            diff_notes(vec![(1_000_000, 0)], vec![(1_000_000, 2)]),
+            vec![
+                NoteDiff {
+                    index: 0,
+                    expected: Some((1_000_000, 0)),
+                    actual: None,
+                },
+                NoteDiff {
+                    index: 0,
+                    expected: None,
+                    actual: Some((1_000_000, 2)),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_osu_export_parses_back() {
+        let notes = vec![
+            NoteData::tap(1_000_000, 0),
+            NoteData::hold(1_250_000, 6, 500_000),
+            NoteData::mine(1_500_000, 3),
+            NoteData::tap(2_000_000, 3),
+        ];
+        let meta = ChartMetadata {
+            title: "Export".to_string(),
+            difficulty_name: "7K Test".to_string(),
+            bpm: 150.0,
+            ..ChartMetadata::default()
+        };
+
+        let osu = chart_to_osu_string(&notes, &meta, 7).expect("export encodes");
+        let beatmap = Beatmap::from_bytes(osu.as_bytes()).expect("export parses");
+        assert_eq!(beatmap.title, "Export");
+        assert_eq!(beatmap.version, "7K Test");
+        assert_eq!(beatmap.circle_size, 7.0);
+
+        // The mine is dropped, the rest keeps its time and column
+        assert_eq!(
+            rosu_notes(&beatmap),
+            vec![(1_000_000, 0), (1_250_000, 6), (2_000_000, 3)]
+        );
+        let hold_durations: Vec<f64> = beatmap
+            .hit_objects
+            .iter()
+            .filter_map(|ho| match &ho.kind {
+                HitObjectKind::Hold(hold) => Some(hold.duration),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(hold_durations, vec![500.0]);
+    }
+}
@@ -29,9 +29,11 @@ where
     let calc_arc = GLOBAL_CALC
         .get()
         .ok_or_else(|| CalcError::Other("Global MinaCalc not initialized".to_string()))?;
-    let calc_guard = calc_arc
-        .lock()
-        .map_err(|_| CalcError::Other("Calc lock poisoned".to_string()))?;
+    // A panic elsewhere can't leave the calculator half-updated: keep using it
+    let calc_guard = calc_arc.lock().unwrap_or_else(|poisoned| {
+        log::warn!("DIFFICULTY: Calc lock poisoned, recovering");
+        poisoned.into_inner()
+    });
     f(&calc_guard.0)
 }
 
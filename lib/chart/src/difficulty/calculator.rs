@@ -1,5 +1,7 @@
 //! Error type for difficulty calculations.
 
+use std::time::Duration;
+
 /// Error type for difficulty calculation failures.
 #[derive(Debug, Clone)]
 pub enum CalcError {
@@ -9,6 +11,10 @@ pub enum CalcError {
     CalculationFailed(String),
     /// The requested rate is not supported.
     UnsupportedRate(f64),
+    /// The chart has more notes than the analysis limit and was skipped.
+    TooLarge { notes: usize, limit: usize },
+    /// The calculation didn't finish within the soft timeout.
+    TimedOut(Duration),
     /// Generic error with message.
     Other(String),
 }
@@ -19,6 +25,20 @@ impl std::fmt::Display for CalcError {
             CalcError::InvalidBeatmap(msg) => write!(f, "Invalid beatmap: {}", msg),
             CalcError::CalculationFailed(msg) => write!(f, "Calculation failed: {}", msg),
             CalcError::UnsupportedRate(rate) => write!(f, "Unsupported rate: {}", rate),
+            CalcError::TooLarge { notes, limit } => {
+                write!(
+                    f,
+                    "Chart too large to analyze ({} notes, limit {})",
+                    notes, limit
+                )
+            }
+            CalcError::TimedOut(timeout) => {
+                write!(
+                    f,
+                    "Calculation timed out after {:.1}s",
+                    timeout.as_secs_f64()
+                )
+            }
             CalcError::Other(msg) => write!(f, "{}", msg),
         }
     }
@@ -10,11 +10,14 @@ pub use crate::converter::{load_as_rosu_beatmap, rox_chart_to_rosu};
 pub use builtin::{EtternaCalculator, OsuCalculator};
 pub use calculator::CalcError;
 
+use engine::{ChartMetadata, RoxChart, dominant_bpm};
 use minacalc_rs::Calc;
+use rhythm_open_exchange::{NoteType, TimingPoint};
 use rosu_map::Beatmap;
 use rosu_map::section::hit_objects::{HitObject, HitObjectKind};
 use std::cmp::Ordering;
 use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
 
 struct CalcHolder(Calc);
 
@@ -33,6 +36,23 @@ pub struct BeatmapSsr {
     pub technical: f64,
 }
 
+impl BeatmapSsr {
+    /// Linear interpolation of every skillset (`t` = 0 gives `a`, 1 gives `b`).
+    pub fn lerp(a: &BeatmapSsr, b: &BeatmapSsr, t: f64) -> BeatmapSsr {
+        let mix = |from: f64, to: f64| from + (to - from) * t;
+        BeatmapSsr {
+            overall: mix(a.overall, b.overall),
+            stream: mix(a.stream, b.stream),
+            jumpstream: mix(a.jumpstream, b.jumpstream),
+            handstream: mix(a.handstream, b.handstream),
+            stamina: mix(a.stamina, b.stamina),
+            jackspeed: mix(a.jackspeed, b.jackspeed),
+            chordjack: mix(a.chordjack, b.chordjack),
+            technical: mix(a.technical, b.technical),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct BeatmapRatingValue {
     pub name: String,
@@ -50,13 +70,123 @@ impl BeatmapRatingValue {
 
 /// Basic info about a beatmap (without ratings).
 /// Used during scan phase - ratings are calculated on-demand later.
-#[derive(Debug, Clone)]
+///
+/// Carries everything the song card shows before ratings are known, and is
+/// what the database stores for each beatmap.
+#[derive(Debug, Clone, Default)]
 pub struct BeatmapBasicInfo {
+    pub title: String,
+    pub artist: String,
+    pub difficulty_name: String,
+    /// Dominant BPM (longest duration in chart).
+    pub bpm: f64,
+    pub key_count: i32,
+    /// From the first note to the end of the last one.
     pub duration_ms: i32,
+    /// Average notes per second over the duration.
     pub nps: f64,
+    /// Most notes starting within [`PEAK_NPS_WINDOW_US`].
+    pub peak_nps: f64,
+    /// Every judgeable note, holds included and mines left out.
     pub note_count: i32,
 }
 
+/// Window the peak NPS is measured over (1 second).
+pub const PEAK_NPS_WINDOW_US: i64 = 1_000_000;
+
+/// Density figures shared by both extractors.
+struct NoteDensity {
+    duration_ms: i32,
+    nps: f64,
+    peak_nps: f64,
+}
+
+impl NoteDensity {
+    /// Computes the density of notes given as (start, end) in µs.
+    ///
+    /// The peak slides a [`PEAK_NPS_WINDOW_US`] window over the sorted note
+    /// starts, so its cost doesn't depend on how far apart the notes are.
+    fn from_spans(spans: Vec<(i64, i64)>) -> Self {
+        let first_us = spans.iter().map(|(start, _)| *start).min().unwrap_or(0);
+        let last_us = spans
+            .iter()
+            .map(|(start, end)| (*start).max(*end))
+            .max()
+            .unwrap_or(first_us);
+        let duration_ms = ((last_us - first_us).max(0) / 1000) as i32;
+        let duration_secs = duration_ms as f64 / 1000.0;
+
+        let total = spans.len();
+        let nps = if duration_secs > 0.0 {
+            total as f64 / duration_secs
+        } else {
+            0.0
+        };
+
+        let peak = Self::busiest_window(spans.into_iter().map(|(start, _)| start).collect());
+        let peak_nps = peak as f64 * 1_000_000.0 / PEAK_NPS_WINDOW_US as f64;
+
+        Self {
+            duration_ms,
+            nps,
+            peak_nps,
+        }
+    }
+
+    /// Most note starts within [`PEAK_NPS_WINDOW_US`] of one of them.
+    fn busiest_window(mut starts: Vec<i64>) -> usize {
+        starts.sort_unstable();
+        let mut peak = 0;
+        let mut end = 0;
+        for (begin, start) in starts.iter().enumerate() {
+            let limit = start.saturating_add(PEAK_NPS_WINDOW_US);
+            while end < starts.len() && starts[end] < limit {
+                end += 1;
+            }
+            peak = peak.max(end - begin);
+        }
+        peak
+    }
+}
+
+/// Bounds on a single difficulty analysis, so huge charts can't stall the caller.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AnalysisLimits {
+    /// Charts with more hit objects are flagged instead of analyzed.
+    pub max_notes: usize,
+    /// How long a caller running the analysis in the background waits for
+    /// it before reporting [`CalcError::TimedOut`].
+    ///
+    /// The calculation itself can't be interrupted: it runs to the end and
+    /// its result is dropped.
+    pub soft_timeout: Duration,
+}
+
+impl AnalysisLimits {
+    pub const DEFAULT_MAX_NOTES: usize = 20_000;
+
+    /// Errors with [`CalcError::TooLarge`] if the chart is over the note limit.
+    pub fn check(&self, map: &Beatmap) -> Result<(), CalcError> {
+        let notes = map.hit_objects.len();
+        if notes > self.max_notes {
+            return Err(CalcError::TooLarge {
+                notes,
+                limit: self.max_notes,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl Default for AnalysisLimits {
+    fn default() -> Self {
+        Self {
+            max_notes: Self::DEFAULT_MAX_NOTES,
+            soft_timeout: Duration::from_secs(3),
+        }
+    }
+}
+
 static GLOBAL_CALC: OnceLock<Arc<Mutex<CalcHolder>>> = OnceLock::new();
 
 pub fn init_global_calc() -> Result<(), Box<dyn std::error::Error>> {
@@ -76,9 +206,11 @@ where
     let calc_arc = GLOBAL_CALC
         .get()
         .ok_or_else(|| std::io::Error::other("Global MinaCalc not initialized"))?;
-    let calc_guard = calc_arc
-        .lock()
-        .map_err(|_| std::io::Error::other("Calc lock poisoned"))?;
+    // A panic elsewhere can't leave the calculator half-updated: keep using it
+    let calc_guard = calc_arc.lock().unwrap_or_else(|poisoned| {
+        log::warn!("DIFFICULTY: Calc lock poisoned, recovering");
+        poisoned.into_inner()
+    });
     f(&calc_guard.0)
 }
 
@@ -89,48 +221,149 @@ pub fn extract_basic_info(map: &Beatmap) -> Result<BeatmapBasicInfo, Box<dyn std
         return Err(Box::new(std::io::Error::other("No hit objects found")));
     }
 
-    let first = map.hit_objects.first().map(|h| h.start_time).unwrap_or(0.0);
-    let last = map
-        .hit_objects
-        .last()
-        .map(|h| h.start_time.max(resolve_end_time(h)))
-        .unwrap_or(first);
-
-    let duration = (last - first).max(0.0);
-    let duration_secs = duration / 1000.0;
-    let nps = if duration_secs > 0.0 {
-        map.hit_objects.len() as f64 / duration_secs
-    } else {
-        0.0
-    };
-
-    let note_count = map
+    let spans: Vec<(i64, i64)> = map
         .hit_objects
         .iter()
-        .filter(|ho| matches!(ho.kind, HitObjectKind::Circle(_)))
-        .count() as i32;
+        .map(|h| {
+            let end = h.start_time.max(resolve_end_time(h));
+            ((h.start_time * 1000.0) as i64, (end * 1000.0) as i64)
+        })
+        .collect();
+    let last_us = spans.iter().map(|(_, end)| *end).max().unwrap_or(0);
+    let density = NoteDensity::from_spans(spans);
+
+    // Uninherited points only: rosu keeps SV changes apart
+    let timing_points: Vec<TimingPoint> = map
+        .control_points
+        .timing_points
+        .iter()
+        .filter(|point| point.beat_len > 0.0)
+        .map(|point| {
+            TimingPoint::bpm(
+                (point.time * 1000.0) as i64,
+                (60_000.0 / point.beat_len) as f32,
+            )
+        })
+        .collect();
 
     Ok(BeatmapBasicInfo {
-        duration_ms: duration as i32,
-        nps,
-        note_count,
+        title: map.title.clone(),
+        artist: map.artist.clone(),
+        difficulty_name: map.version.clone(),
+        bpm: dominant_bpm(&timing_points, last_us),
+        key_count: map.circle_size.round() as i32,
+        duration_ms: density.duration_ms,
+        nps: density.nps,
+        peak_nps: density.peak_nps,
+        note_count: map.hit_objects.len() as i32,
     })
 }
 
+/// Extracts basic metadata straight from a decoded chart.
+///
+/// Nothing is converted or handed to a calculator, which keeps the first
+/// scan of a large library fast; MSD is computed once a map is selected.
+pub fn extract_chart_basic_info(chart: &RoxChart) -> BeatmapBasicInfo {
+    let metadata = ChartMetadata::from_chart(chart);
+    let spans: Vec<(i64, i64)> = chart
+        .notes
+        .iter()
+        .map(|n| (n.time_us, n.end_time_us()))
+        .collect();
+    let density = NoteDensity::from_spans(spans);
+
+    // Key count from the highest column used
+    let key_count = chart
+        .notes
+        .iter()
+        .map(|n| n.column)
+        .max()
+        .map(|c| c as i32 + 1)
+        .unwrap_or(4);
+
+    BeatmapBasicInfo {
+        title: metadata.title,
+        artist: metadata.artist,
+        difficulty_name: metadata.difficulty_name,
+        bpm: metadata.bpm,
+        key_count,
+        duration_ms: density.duration_ms,
+        nps: density.nps,
+        peak_nps: density.peak_nps,
+        // Mines are never judged (and osu! has none, see extract_basic_info)
+        note_count: chart
+            .notes
+            .iter()
+            .filter(|n| !matches!(n.note_type, NoteType::Mine))
+            .count() as i32,
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct RateDifficultyCache {
     pub available_rates: Vec<f64>,
     pub ratings_by_rate: Vec<(f64, Vec<BeatmapRatingValue>)>,
 }
 
+impl RateDifficultyCache {
+    /// Ratings of the analyzed rate closest to `rate`.
+    ///
+    /// Rates outside the analyzed range resolve to the nearest end.
+    pub fn nearest(&self, rate: f64) -> Option<&(f64, Vec<BeatmapRatingValue>)> {
+        nearest_rate_index(&self.ratings_by_rate, rate).map(|idx| &self.ratings_by_rate[idx])
+    }
+}
+
+/// Index of the entry closest to `rate` in `sorted` (ascending by rate).
+///
+/// Binary search; a rate halfway between two entries picks the faster one.
+pub fn nearest_rate_index<T>(sorted: &[(f64, T)], rate: f64) -> Option<usize> {
+    let above = sorted.partition_point(|(r, _)| *r < rate);
+    match (above.checked_sub(1), sorted.get(above)) {
+        (Some(below), Some((above_rate, _))) => {
+            if rate - sorted[below].0 < above_rate - rate {
+                Some(below)
+            } else {
+                Some(above)
+            }
+        }
+        (Some(below), None) => Some(below),
+        (None, Some(_)) => Some(above),
+        (None, None) => None,
+    }
+}
+
 pub fn analyze_all_rates(map: &Beatmap) -> Result<RateDifficultyCache, Box<dyn std::error::Error>> {
+    analyze_rates(map, |_| true)
+}
+
+/// Like [`analyze_all_rates`], keeping only the rates accepted by `keep_rate`.
+///
+/// MinaCalc rates every rate in one pass, but the osu! ratings of the
+/// other rates are never computed.
+pub fn analyze_rates(
+    map: &Beatmap,
+    keep_rate: impl Fn(f64) -> bool,
+) -> Result<RateDifficultyCache, Box<dyn std::error::Error>> {
     init_global_calc()?;
-    with_global_calc(|calc| analyze_all_rates_with_calc(map, calc))
+    with_global_calc(|calc| analyze_all_rates_with_calc(map, calc, keep_rate))
+}
+
+/// [`analyze_rates`] within `limits`: oversized charts are flagged
+/// without being analyzed.
+pub fn analyze_all_rates_bounded(
+    map: &Beatmap,
+    limits: &AnalysisLimits,
+    keep_rate: impl Fn(f64) -> bool,
+) -> Result<RateDifficultyCache, CalcError> {
+    limits.check(map)?;
+    analyze_rates(map, keep_rate).map_err(|e| CalcError::CalculationFailed(e.to_string()))
 }
 
 fn analyze_all_rates_with_calc(
     map: &Beatmap,
     _calc: &Calc,
+    keep_rate: impl Fn(f64) -> bool,
 ) -> Result<RateDifficultyCache, Box<dyn std::error::Error>> {
     // Use the new builtin calculators
     let etterna_rates = EtternaCalculator::calculate_all_rates(map)
@@ -139,6 +372,9 @@ fn analyze_all_rates_with_calc(
     let mut per_rate: Vec<(f64, Vec<BeatmapRatingValue>)> = Vec::new();
 
     for (rate_value, etterna_ssr) in etterna_rates {
+        if !keep_rate(rate_value) {
+            continue;
+        }
         let osu_ssr = OsuCalculator::calculate_from_beatmap(map, &etterna_ssr, rate_value)
             .map_err(|e| std::io::Error::other(e.to_string()))?;
 
@@ -188,3 +424,243 @@ pub fn calculate_on_demand(
         ))),
     }
 }
+
+/// [`calculate_on_demand`] within `limits`: oversized charts are flagged
+/// without being analyzed.
+pub fn calculate_on_demand_bounded(
+    map: &Beatmap,
+    calculator_id: &str,
+    rate: f64,
+    limits: &AnalysisLimits,
+) -> Result<BeatmapSsr, CalcError> {
+    limits.check(map)?;
+    calculate_on_demand(map, calculator_id, rate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_beatmap() -> Beatmap {
+        let mut osu = String::from(
+            "osu file format v14\n\n[General]\nMode: 3\n\n[Difficulty]\nCircleSize:4\nOverallDifficulty:8\n\n[TimingPoints]\n0,500,4,2,0,50,1,0\n\n[HitObjects]\n",
+        );
+        for i in 0..400 {
+            let x = [64, 192, 320, 448][i % 4];
+            osu.push_str(&format!("{},192,{},1,0,0:0:0:0:\n", x, 1000 + i * 125));
+        }
+        Beatmap::from_bytes(osu.as_bytes()).expect("valid test beatmap")
+    }
+
+    #[test]
+    fn test_chart_basic_info_from_fixture() {
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../engine/fixtures/metadata_4k.osu"
+        );
+        let chart = rhythm_open_exchange::codec::auto_decode(std::path::Path::new(path))
+            .expect("fixture decodes");
+
+        // Pure metadata: no conversion, no calculator involved
+        let info = extract_chart_basic_info(&chart);
+        assert_eq!(info.title, "Fixture Song");
+        assert_eq!(info.artist, "Fixture Artist");
+        assert_eq!(info.difficulty_name, "Normal");
+        assert_eq!(info.note_count, 4);
+        // First note at 1000ms, last one (a hold tail and a tap) at 2500ms
+        assert_eq!(info.duration_ms, 1500);
+        assert!((info.nps - 4.0 / 1.5).abs() < 1e-9);
+        // Notes start every 500ms, so two at most within one second
+        assert_eq!(info.peak_nps, 2.0);
+        assert_eq!(info.bpm, 120.0);
+        assert_eq!(info.key_count, 4);
+    }
+
+    #[test]
+    fn test_chart_note_count_leaves_mines_out() {
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../engine/fixtures/metadata_4k.osu"
+        );
+        let mut chart = rhythm_open_exchange::codec::auto_decode(std::path::Path::new(path))
+            .expect("fixture decodes");
+        chart
+            .notes
+            .push(rhythm_open_exchange::Note::mine(1_250_000, 2));
+
+        // Same count as a play judges, which the leaderboard compares against
+        assert_eq!(extract_chart_basic_info(&chart).note_count, 4);
+    }
+
+    #[test]
+    fn test_dense_burst_peak_above_average() {
+        // One note per second for a minute, with 20 notes packed in one second
+        let mut spans: Vec<(i64, i64)> = (0..60).map(|s| (s * 1_000_000, s * 1_000_000)).collect();
+        spans.extend((0..20).map(|i| (30_000_000 + i * 50_000, 30_000_000 + i * 50_000)));
+
+        let density = NoteDensity::from_spans(spans);
+        assert_eq!(density.duration_ms, 59_000);
+        assert!((density.nps - 80.0 / 59.0).abs() < 1e-9);
+        // The burst and the regular note starting its window
+        assert_eq!(density.peak_nps, 21.0);
+        assert!(density.peak_nps > density.nps * 10.0);
+    }
+
+    #[test]
+    fn test_far_outlier_note_keeps_peak() {
+        // A stray note hours away must not blow up the peak measurement
+        let mut spans: Vec<(i64, i64)> = (0..4).map(|i| (i * 250_000, i * 250_000)).collect();
+        spans.push((i64::MAX / 2, i64::MAX / 2));
+
+        let density = NoteDensity::from_spans(spans);
+        assert_eq!(density.peak_nps, 4.0);
+    }
+
+    #[test]
+    fn test_basic_info_same_from_rosu_and_rox() {
+        let path = std::path::Path::new(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../engine/fixtures/metadata_4k.osu"
+        ));
+        let chart = rhythm_open_exchange::codec::auto_decode(path).expect("fixture decodes");
+        let map = Beatmap::from_path(path).expect("fixture parses");
+
+        let from_rox = extract_chart_basic_info(&chart);
+        let from_rosu = extract_basic_info(&map).unwrap();
+        assert_eq!(from_rosu.note_count, from_rox.note_count);
+        assert_eq!(from_rosu.duration_ms, from_rox.duration_ms);
+        assert_eq!(from_rosu.nps, from_rox.nps);
+        assert_eq!(from_rosu.peak_nps, from_rox.peak_nps);
+        assert_eq!(from_rosu.bpm, from_rox.bpm);
+        assert_eq!(from_rosu.key_count, from_rox.key_count);
+    }
+
+    #[test]
+    fn test_analyze_after_calc_lock_poisoned() {
+        init_global_calc().unwrap();
+        let calc = GLOBAL_CALC.get().unwrap();
+
+        // Unrelated panic while holding the calculator
+        let _ = std::thread::spawn(|| {
+            let _guard = GLOBAL_CALC.get().unwrap().lock().unwrap();
+            panic!("unrelated panic");
+        })
+        .join();
+        assert!(calc.is_poisoned());
+
+        let cache = analyze_all_rates(&test_beatmap()).expect("difficulty still works");
+        assert!(!cache.available_rates.is_empty());
+    }
+
+    #[test]
+    fn test_ssr_lerp_halfway_is_midpoint() {
+        let a = BeatmapSsr {
+            overall: 10.0,
+            stream: 8.0,
+            jumpstream: 6.0,
+            handstream: 4.0,
+            stamina: 2.0,
+            jackspeed: 0.0,
+            chordjack: 12.0,
+            technical: 14.0,
+        };
+        let b = BeatmapSsr {
+            overall: 20.0,
+            stream: 10.0,
+            jumpstream: 10.0,
+            handstream: 4.0,
+            stamina: 6.0,
+            jackspeed: 8.0,
+            chordjack: 10.0,
+            technical: 20.0,
+        };
+        let mid = BeatmapSsr::lerp(&a, &b, 0.5);
+
+        assert_eq!(mid.overall, 15.0);
+        assert_eq!(mid.stream, 9.0);
+        assert_eq!(mid.jumpstream, 8.0);
+        assert_eq!(mid.handstream, 4.0);
+        assert_eq!(mid.stamina, 4.0);
+        assert_eq!(mid.jackspeed, 4.0);
+        assert_eq!(mid.chordjack, 11.0);
+        assert_eq!(mid.technical, 17.0);
+        assert_eq!(BeatmapSsr::lerp(&a, &b, 0.0).overall, a.overall);
+        assert_eq!(BeatmapSsr::lerp(&a, &b, 1.0).overall, b.overall);
+    }
+
+    #[test]
+    fn test_nearest_rate_lookup() {
+        let cache = RateDifficultyCache {
+            available_rates: vec![1.0, 1.1, 1.15, 1.2],
+            ratings_by_rate: [1.0, 1.1, 1.15, 1.2]
+                .into_iter()
+                .map(|rate| (rate, Vec::new()))
+                .collect(),
+        };
+        let nearest = |rate: f64| cache.nearest(rate).map(|(rate, _)| *rate);
+
+        assert_eq!(nearest(1.13), Some(1.15));
+        assert_eq!(nearest(1.11), Some(1.1));
+        assert_eq!(nearest(1.1), Some(1.1));
+        assert_eq!(nearest(1.2), Some(1.2));
+        // Out of range rates clamp to the ends
+        assert_eq!(nearest(0.5), Some(1.0));
+        assert_eq!(nearest(3.0), Some(1.2));
+
+        let empty = RateDifficultyCache {
+            available_rates: Vec::new(),
+            ratings_by_rate: Vec::new(),
+        };
+        assert!(empty.nearest(1.0).is_none());
+    }
+
+    #[test]
+    fn test_rate_filter_restricts_ratings_by_rate() {
+        let in_range = |rate: f64| (1.0..=1.5).contains(&rate);
+        let cache = analyze_rates(&test_beatmap(), in_range).unwrap();
+
+        assert!(!cache.ratings_by_rate.is_empty());
+        assert!(
+            cache
+                .ratings_by_rate
+                .iter()
+                .all(|(rate, _)| in_range(*rate))
+        );
+        let rates: Vec<f64> = cache
+            .ratings_by_rate
+            .iter()
+            .map(|(rate, _)| *rate)
+            .collect();
+        assert_eq!(cache.available_rates, rates);
+
+        let all = analyze_all_rates(&test_beatmap()).unwrap();
+        assert!(all.available_rates.len() > cache.available_rates.len());
+    }
+
+    #[test]
+    fn test_chart_over_note_limit_is_flagged() {
+        let limits = AnalysisLimits {
+            max_notes: 100,
+            ..AnalysisLimits::default()
+        };
+        // 400 notes: rejected before reaching the calculator
+        let result = calculate_on_demand_bounded(&test_beatmap(), "etterna", 1.0, &limits);
+        assert!(matches!(
+            result,
+            Err(CalcError::TooLarge {
+                notes: 400,
+                limit: 100
+            })
+        ));
+        assert!(matches!(
+            analyze_all_rates_bounded(&test_beatmap(), &limits, |_| true),
+            Err(CalcError::TooLarge { .. })
+        ));
+
+        let generous = AnalysisLimits {
+            max_notes: 400,
+            ..limits
+        };
+        assert!(generous.check(&test_beatmap()).is_ok());
+    }
+}
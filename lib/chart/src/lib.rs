@@ -6,9 +6,13 @@
 pub mod converter;
 pub mod difficulty;
 
-pub use converter::{load_as_rosu_beatmap, rox_chart_to_rosu};
+pub use converter::{
+    NoteDiff, ROUNDTRIP_TOLERANCE_US, chart_to_osu_string, load_as_rosu_beatmap, rox_chart_to_rosu,
+    verify_roundtrip,
+};
 pub use difficulty::{
-    BeatmapBasicInfo, BeatmapRatingValue, BeatmapSsr, CalcError, EtternaCalculator, OsuCalculator,
-    RateDifficultyCache, analyze_all_rates, calculate_on_demand, extract_basic_info,
-    init_global_calc,
+    AnalysisLimits, BeatmapBasicInfo, BeatmapRatingValue, BeatmapSsr, CalcError, EtternaCalculator,
+    OsuCalculator, PEAK_NPS_WINDOW_US, RateDifficultyCache, analyze_all_rates,
+    analyze_all_rates_bounded, analyze_rates, calculate_on_demand, calculate_on_demand_bounded,
+    extract_basic_info, extract_chart_basic_info, init_global_calc, nearest_rate_index,
 };
@@ -0,0 +1,271 @@
+//! Lifetime achievements - milestones unlocked by saved replays.
+//!
+//! Each achievement is unlocked once, by the first replay reaching it, and
+//! kept in the `achievement` table. Incomplete and practice runs never
+//! unlock anything, nor do runs of players who turned tracking off.
+
+use sqlx::SqlitePool;
+
+/// Lifetime milestones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Achievement {
+    /// A run played to the end.
+    FirstClear,
+    /// A run without a combo break.
+    FirstFullCombo,
+    /// A 500 combo.
+    Combo500,
+    /// A 1000 combo.
+    Combo1000,
+    /// A run at 95% accuracy or more.
+    Accuracy95,
+    /// A run at 99% accuracy or more.
+    Accuracy99,
+}
+
+impl Achievement {
+    /// Returns all achievements, in display order.
+    pub fn all() -> &'static [Achievement] {
+        &[
+            Achievement::FirstClear,
+            Achievement::FirstFullCombo,
+            Achievement::Combo500,
+            Achievement::Combo1000,
+            Achievement::Accuracy95,
+            Achievement::Accuracy99,
+        ]
+    }
+
+    /// Stable identifier used for storage.
+    pub fn id(&self) -> &'static str {
+        match self {
+            Achievement::FirstClear => "first_clear",
+            Achievement::FirstFullCombo => "first_full_combo",
+            Achievement::Combo500 => "combo_500",
+            Achievement::Combo1000 => "combo_1000",
+            Achievement::Accuracy95 => "accuracy_95",
+            Achievement::Accuracy99 => "accuracy_99",
+        }
+    }
+
+    /// Parses an achievement from its [`id`](Self::id).
+    pub fn from_id(id: &str) -> Option<Achievement> {
+        Self::all().iter().copied().find(|a| a.id() == id)
+    }
+
+    /// Returns a user-friendly display name.
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Achievement::FirstClear => "First Clear",
+            Achievement::FirstFullCombo => "First Full Combo",
+            Achievement::Combo500 => "500 Combo",
+            Achievement::Combo1000 => "1000 Combo",
+            Achievement::Accuracy95 => "95% Club",
+            Achievement::Accuracy99 => "99% Club",
+        }
+    }
+
+    /// Whether `run` reaches this milestone.
+    pub fn earned_by(&self, run: &RunOutcome) -> bool {
+        match self {
+            Achievement::FirstClear => true,
+            Achievement::FirstFullCombo => run.full_combo,
+            Achievement::Combo500 => run.max_combo >= 500,
+            Achievement::Combo1000 => run.max_combo >= 1000,
+            Achievement::Accuracy95 => run.accuracy >= 95.0,
+            Achievement::Accuracy99 => run.accuracy >= 99.0,
+        }
+    }
+}
+
+/// What a finished run reached, checked against the milestones.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RunOutcome {
+    pub accuracy: f64,
+    pub max_combo: u32,
+    /// Every note judged without a combo break.
+    pub full_combo: bool,
+}
+
+/// An unlocked achievement and the replay that unlocked it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnlockedAchievement {
+    pub achievement: Achievement,
+    /// Unix timestamp of the unlocking run.
+    pub unlocked_at: i64,
+    /// Replay that unlocked it (it may have been deleted since).
+    pub replay_hash: Option<String>,
+}
+
+/// Unlocks the achievements reached by a saved replay.
+///
+/// Returns the ones unlocked by this replay; those already unlocked are
+/// left as they were.
+pub async fn record_run(
+    pool: &SqlitePool,
+    replay_hash: &str,
+    timestamp: i64,
+    run: &RunOutcome,
+) -> Result<Vec<Achievement>, sqlx::Error> {
+    let mut unlocked = Vec::new();
+    for achievement in Achievement::all().iter().filter(|a| a.earned_by(run)) {
+        let result = sqlx::query(
+            "INSERT OR IGNORE INTO achievement (id, unlocked_at, replay_hash) VALUES (?1, ?2, ?3)",
+        )
+        .bind(achievement.id())
+        .bind(timestamp)
+        .bind(replay_hash)
+        .execute(pool)
+        .await?;
+        if result.rows_affected() > 0 {
+            unlocked.push(*achievement);
+        }
+    }
+    Ok(unlocked)
+}
+
+/// Every unlocked achievement, in unlock order.
+pub async fn unlocked_achievements(
+    pool: &SqlitePool,
+) -> Result<Vec<UnlockedAchievement>, sqlx::Error> {
+    let rows: Vec<(String, i64, Option<String>)> = sqlx::query_as(
+        "SELECT id, unlocked_at, replay_hash FROM achievement ORDER BY unlocked_at, rowid",
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(rows
+        .into_iter()
+        .filter_map(|(id, unlocked_at, replay_hash)| {
+            Some(UnlockedAchievement {
+                achievement: Achievement::from_id(&id)?,
+                unlocked_at,
+                replay_hash,
+            })
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::connection::Database;
+    use crate::manager::{DbManager, SaveReplayCommand};
+    use crate::query::{insert_beatmap, insert_beatmapset};
+    use crate::replay_storage;
+    use crate::search::LeaderboardFilter;
+    use chart::BeatmapBasicInfo;
+    use engine::HitWindowMode;
+    use replay::ReplayData;
+    use std::time::{Duration, Instant};
+
+    fn run(timestamp: i64, accuracy: f64, full_combo: bool) -> SaveReplayCommand {
+        SaveReplayCommand {
+            beatmap_hash: "achievement_map".to_string(),
+            timestamp,
+            score: 0,
+            accuracy,
+            max_combo: 300,
+            rate: 1.0,
+            hit_window_mode: HitWindowMode::OsuOD,
+            hit_window_value: 8.0,
+            full_combo,
+            track_achievements: true,
+            data: ReplayData::new(1.0),
+        }
+    }
+
+    /// Waits for the DB thread to unlock `achievement`.
+    fn wait_for(manager: &DbManager, achievement: Achievement) -> Vec<UnlockedAchievement> {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        loop {
+            let unlocked = manager.unlocked_achievements();
+            if unlocked.iter().any(|u| u.achievement == achievement) {
+                return unlocked;
+            }
+            assert!(
+                Instant::now() < deadline,
+                "{:?} never unlocked",
+                achievement
+            );
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_full_combo_unlocks_first_full_combo_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        let db = Database::new(&db_path).await.unwrap();
+        let set_id = insert_beatmapset(db.pool(), "set", None, None, None)
+            .await
+            .unwrap();
+        let info = BeatmapBasicInfo {
+            note_count: 4,
+            key_count: 4,
+            ..Default::default()
+        };
+        insert_beatmap(db.pool(), set_id, "achievement_map", "set/map.osu", &info)
+            .await
+            .unwrap();
+
+        let manager = DbManager::new(db_path, dir.path().join("songs"));
+        manager.init();
+
+        // Full combos that must not count, in the order the DB thread runs them
+        let mut incomplete = run(1_000, 90.0, true);
+        incomplete.data.incomplete = true;
+        manager.save_replay(incomplete);
+        let mut practice = run(2_000, 90.0, true);
+        practice.data.is_practice_mode = true;
+        manager.save_replay(practice);
+        let mut opted_out = run(3_000, 90.0, true);
+        opted_out.track_achievements = false;
+        manager.save_replay(opted_out);
+
+        manager.save_replay(run(4_000, 90.0, true));
+        wait_for(&manager, Achievement::FirstFullCombo);
+
+        // A second full combo unlocks nothing new; the 95% run that follows
+        // shows it was processed
+        manager.save_replay(run(5_000, 90.0, true));
+        manager.save_replay(run(6_000, 96.0, false));
+        let unlocked = wait_for(&manager, Achievement::Accuracy95);
+
+        let fc: Vec<_> = unlocked
+            .iter()
+            .filter(|u| u.achievement == Achievement::FirstFullCombo)
+            .collect();
+        assert_eq!(fc.len(), 1);
+        assert_eq!(fc[0].unlocked_at, 4_000);
+        assert_eq!(unlocked[0].unlocked_at, 4_000);
+        assert_eq!(
+            unlocked.iter().map(|u| u.achievement).collect::<Vec<_>>(),
+            vec![
+                Achievement::FirstClear,
+                Achievement::FirstFullCombo,
+                Achievement::Accuracy95
+            ]
+        );
+
+        let filter = LeaderboardFilter {
+            include_incomplete: true,
+            ..Default::default()
+        };
+        for replay in db
+            .get_replays_for_beatmap("achievement_map", &filter)
+            .await
+            .unwrap()
+        {
+            replay_storage::delete_replay(&replay.hash).unwrap();
+        }
+        db.pool().close().await;
+    }
+
+    #[test]
+    fn test_achievement_ids_roundtrip() {
+        for achievement in Achievement::all() {
+            assert_eq!(Achievement::from_id(achievement.id()), Some(*achievement));
+        }
+        assert_eq!(Achievement::from_id("unknown"), None);
+    }
+}
@@ -0,0 +1,320 @@
+//! Score backup - every stored replay in a single portable file.
+//!
+//! The backup holds the replay rows and their compressed replay files,
+//! serialized with rkyv then compressed with zstd. Importing skips the
+//! replays already present, so a backup can be imported more than once.
+
+use crate::models::Replay;
+use crate::query;
+use crate::replay_storage;
+use rkyv::rancor::Error;
+use sqlx::SqlitePool;
+use std::path::Path;
+use zstd::stream::{decode_all, encode_all};
+
+/// Format version written in new backups.
+pub const BACKUP_FORMAT_VERSION: u32 = 1;
+
+/// zstd level of the backup file (the replay blobs are already compressed).
+const BACKUP_COMPRESSION_LEVEL: i32 = 3;
+
+#[derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)]
+struct ScoreBackup {
+    version: u32,
+    replays: Vec<BackupReplay>,
+}
+
+/// A replay row with its compressed replay file.
+#[derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)]
+struct BackupReplay {
+    hash: String,
+    beatmap_hash: String,
+    timestamp: i64,
+    score: i32,
+    accuracy: f64,
+    max_combo: i32,
+    rate: f64,
+    key_count: i32,
+    mods: String,
+    hit_window_mode: String,
+    hit_window_value: f64,
+    /// Replay file as written by [`replay::compress`].
+    data: Vec<u8>,
+}
+
+/// Outcome of [`import_scores`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ImportSummary {
+    pub imported: usize,
+    /// Replays already in the database.
+    pub duplicates: usize,
+    /// Replays of a beatmap missing from the database (scan the songs first).
+    pub missing_beatmap: usize,
+    /// Replays whose hash is taken by a different stored replay.
+    pub conflicts: usize,
+}
+
+fn invalid_data(message: String) -> sqlx::Error {
+    sqlx::Error::Io(std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        message,
+    ))
+}
+
+/// Writes every stored replay to `path`. Returns how many were exported.
+///
+/// Replays whose file can no longer be read are skipped.
+pub async fn export_scores(pool: &SqlitePool, path: &Path) -> Result<usize, sqlx::Error> {
+    let mut replays = Vec::new();
+    for replay in query::get_all_replays(pool).await? {
+        let data = match std::fs::read(&replay.file_path) {
+            Ok(data) => data,
+            Err(e) => {
+                log::warn!("BACKUP: skipping replay {}: {}", replay.hash, e);
+                continue;
+            }
+        };
+        replays.push(BackupReplay {
+            hash: replay.hash,
+            beatmap_hash: replay.beatmap_hash,
+            timestamp: replay.timestamp,
+            score: replay.score,
+            accuracy: replay.accuracy,
+            max_combo: replay.max_combo,
+            rate: replay.rate,
+            key_count: replay.key_count,
+            mods: replay.mods,
+            hit_window_mode: replay.hit_window_mode,
+            hit_window_value: replay.hit_window_value,
+            data,
+        });
+    }
+
+    let count = replays.len();
+    let backup = ScoreBackup {
+        version: BACKUP_FORMAT_VERSION,
+        replays,
+    };
+    let binary = rkyv::to_bytes::<Error>(&backup)
+        .map_err(|e| invalid_data(format!("Serialization error: {}", e)))?;
+    let compressed = encode_all(&binary[..], BACKUP_COMPRESSION_LEVEL)?;
+    std::fs::write(path, compressed)?;
+
+    log::info!("BACKUP: exported {} replays to {:?}", count, path);
+    Ok(count)
+}
+
+/// Imports the replays of a backup written by [`export_scores`].
+///
+/// Replays already stored (same hash and timestamp) are skipped, as are the
+/// ones whose hash is taken by another replay, whose beatmap is unknown or
+/// whose replay file is corrupted.
+pub async fn import_scores(pool: &SqlitePool, path: &Path) -> Result<ImportSummary, sqlx::Error> {
+    let compressed = std::fs::read(path)?;
+    let binary = decode_all(&compressed[..])?;
+    let backup = rkyv::from_bytes::<ScoreBackup, Error>(&binary)
+        .map_err(|e| invalid_data(format!("Invalid score backup: {}", e)))?;
+    if backup.version > BACKUP_FORMAT_VERSION {
+        return Err(invalid_data(format!(
+            "Score backup version {} is newer than supported ({})",
+            backup.version, BACKUP_FORMAT_VERSION
+        )));
+    }
+
+    let mut summary = ImportSummary::default();
+    for entry in backup.replays {
+        match query::replay_timestamp(pool, &entry.hash).await? {
+            Some(timestamp) if timestamp == entry.timestamp => {
+                summary.duplicates += 1;
+                continue;
+            }
+            // Importing it would overwrite the other replay's file
+            Some(_) => {
+                log::warn!("BACKUP: skipping replay {}: hash already used", entry.hash);
+                summary.conflicts += 1;
+                continue;
+            }
+            None => {}
+        }
+        if !query::beatmap_exists(pool, &entry.beatmap_hash).await? {
+            summary.missing_beatmap += 1;
+            continue;
+        }
+        let data = match replay::decompress(&entry.data) {
+            Ok(data) => data,
+            Err(e) => {
+                log::warn!("BACKUP: skipping corrupted replay {}: {}", entry.hash, e);
+                continue;
+            }
+        };
+
+        let file_path = replay_storage::save_compressed(&entry.hash, &entry.data)?;
+        query::insert_replay_row(
+            pool,
+            &Replay {
+                hash: entry.hash,
+                beatmap_hash: entry.beatmap_hash,
+                timestamp: entry.timestamp,
+                score: entry.score,
+                accuracy: entry.accuracy,
+                max_combo: entry.max_combo,
+                rate: entry.rate,
+                file_path,
+                key_count: entry.key_count,
+                mods: entry.mods,
+                hit_window_mode: entry.hit_window_mode,
+                hit_window_value: entry.hit_window_value,
+                incomplete: data.incomplete,
+            },
+        )
+        .await?;
+        summary.imported += 1;
+    }
+
+    log::info!("BACKUP: imported {:?} from {:?}", summary, path);
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::connection::Database;
+    use crate::query::{insert_beatmap, insert_beatmapset};
+    use crate::search::LeaderboardFilter;
+    use chart::BeatmapBasicInfo;
+    use engine::HitWindowMode;
+    use replay::ReplayData;
+
+    async fn open_with_map(db_path: &std::path::Path) -> Database {
+        let db = Database::new(db_path).await.unwrap();
+        let set_id = insert_beatmapset(db.pool(), "set", None, None, None)
+            .await
+            .unwrap();
+        let info = BeatmapBasicInfo {
+            note_count: 4,
+            key_count: 4,
+            ..Default::default()
+        };
+        insert_beatmap(db.pool(), set_id, "backup_map", "set/map.osu", &info)
+            .await
+            .unwrap();
+        db
+    }
+
+    #[tokio::test]
+    async fn test_export_then_import_restores_scores_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = open_with_map(&dir.path().join("source.db")).await;
+        let j4 = (HitWindowMode::EtternaJudge, 4.0);
+        let mut hashes = Vec::new();
+        for (timestamp, rate) in [(1_000, 1.0), (2_000, 1.5)] {
+            let hash = source
+                .insert_replay(
+                    "backup_map",
+                    timestamp,
+                    0,
+                    95.0,
+                    10,
+                    rate,
+                    j4,
+                    &ReplayData::new(rate),
+                )
+                .await
+                .unwrap();
+            hashes.push(hash);
+        }
+
+        let backup_path = dir.path().join("scores.prsb");
+        assert_eq!(export_scores(source.pool(), &backup_path).await.unwrap(), 2);
+        source.pool().close().await;
+        // The import must restore the replay files from the backup alone
+        for hash in &hashes {
+            replay_storage::delete_replay(hash).unwrap();
+        }
+
+        let target = open_with_map(&dir.path().join("target.db")).await;
+        let summary = import_scores(target.pool(), &backup_path).await.unwrap();
+        assert_eq!(summary.imported, 2);
+
+        let again = import_scores(target.pool(), &backup_path).await.unwrap();
+        assert_eq!(again.imported, 0);
+        assert_eq!(again.duplicates, 2);
+
+        let restored = target
+            .get_replays_for_beatmap("backup_map", &LeaderboardFilter::default())
+            .await
+            .unwrap();
+        assert_eq!(restored.len(), 2);
+        for replay in &restored {
+            let data = replay_storage::load_replay(&replay.hash).unwrap();
+            assert_eq!(data, ReplayData::new(replay.rate));
+        }
+
+        target.pool().close().await;
+        for hash in &hashes {
+            replay_storage::delete_replay(hash).unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_import_skips_hash_taken_by_another_replay() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = open_with_map(&dir.path().join("source.db")).await;
+        let mut hashes = Vec::new();
+        for timestamp in [1_000, 2_000] {
+            let hash = source
+                .insert_replay(
+                    "backup_map",
+                    timestamp,
+                    0,
+                    95.0,
+                    10,
+                    1.0,
+                    (HitWindowMode::OsuOD, 5.0),
+                    &ReplayData::new(1.0),
+                )
+                .await
+                .unwrap();
+            hashes.push(hash);
+        }
+        let backup_path = dir.path().join("scores.prsb");
+        export_scores(source.pool(), &backup_path).await.unwrap();
+        source.pool().close().await;
+
+        // The target already holds a different score under the first hash
+        let target = open_with_map(&dir.path().join("target.db")).await;
+        let taken = Replay {
+            hash: hashes[0].clone(),
+            beatmap_hash: "backup_map".to_string(),
+            timestamp: 5_000,
+            score: 0,
+            accuracy: 80.0,
+            max_combo: 3,
+            rate: 1.0,
+            file_path: String::new(),
+            key_count: 4,
+            mods: String::new(),
+            hit_window_mode: String::new(),
+            hit_window_value: 0.0,
+            incomplete: false,
+        };
+        query::insert_replay_row(target.pool(), &taken)
+            .await
+            .unwrap();
+
+        let summary = import_scores(target.pool(), &backup_path).await.unwrap();
+        assert_eq!(summary.imported, 1);
+        assert_eq!(summary.conflicts, 1);
+        assert_eq!(
+            query::replay_timestamp(target.pool(), &hashes[0])
+                .await
+                .unwrap(),
+            Some(5_000)
+        );
+
+        target.pool().close().await;
+        for hash in &hashes {
+            replay_storage::delete_replay(hash).unwrap();
+        }
+    }
+}
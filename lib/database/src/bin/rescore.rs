@@ -0,0 +1,69 @@
+//! Headless batch re-scoring of the stored replays.
+//!
+//! Usage: `rescore <osu_od|etterna_judge> <value> [db_path]`, or
+//! `rescore custom <marv,perfect,great,good,bad,miss> [db_path]` with windows in ms.
+//!
+//! Prints a CSV of the old vs new accuracy of every replay to stdout.
+//! Built with the `cli` feature: `cargo run -p database --features cli --bin rescore`.
+
+use database::{Database, rescore_database, rows_to_csv};
+use engine::{HitWindow, HitWindowMode};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+const USAGE: &str = "Usage: rescore <osu_od|etterna_judge> <value> [db_path]\n       \
+                     rescore custom <marv,perfect,great,good,bad,miss> [db_path]";
+
+/// Parses the window argument: an OD or judge level, or the six custom windows in ms.
+fn parse_hit_window(mode: HitWindowMode, value: &str) -> Option<HitWindow> {
+    match mode {
+        HitWindowMode::OsuOD => value.parse().ok().map(HitWindow::from_osu_od),
+        HitWindowMode::EtternaJudge => value
+            .parse::<f64>()
+            .ok()
+            .map(|judge| HitWindow::from_etterna_judge(judge as u8)),
+        HitWindowMode::Custom => {
+            let windows: Vec<f64> = value
+                .split(',')
+                .map(|ms| ms.trim().parse().ok())
+                .collect::<Option<_>>()?;
+            windows.try_into().ok().map(HitWindow::from_windows_ms)
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    env_logger::init();
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let Some(hit_window) = args
+        .first()
+        .and_then(|id| HitWindowMode::from_id(id))
+        .zip(args.get(1))
+        .and_then(|(mode, value)| parse_hit_window(mode, value))
+    else {
+        eprintln!("{USAGE}");
+        return ExitCode::FAILURE;
+    };
+    let db_path = PathBuf::from(args.get(2).map_or("main.db", String::as_str));
+
+    let db = match Database::new(&db_path).await {
+        Ok(db) => db,
+        Err(e) => {
+            eprintln!("Failed to open {}: {e}", db_path.display());
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match rescore_database(&db, &hit_window).await {
+        Ok(rows) => {
+            print!("{}", rows_to_csv(&rows));
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("Failed to read replays: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -1,8 +1,12 @@
 //! Database connection helpers built on top of sqlx/SQLite.
 
-use crate::models::{BeatmapRating, BeatmapWithRatings, Beatmapset};
+use crate::achievements::{self, Achievement, RunOutcome, UnlockedAchievement};
+use crate::backup::{self, ImportSummary};
+use crate::models::{BeatmapRating, BeatmapWithRatings, Beatmapset, ReplayWithChart};
 use crate::query;
-use crate::search::MenuSearchFilters;
+use crate::search::{LeaderboardFilter, MenuSearchFilters};
+use chart::BeatmapBasicInfo;
+use engine::HitWindowMode;
 use sqlx::{SqlitePool, sqlite::SqliteConnectOptions};
 use std::path::{Path, PathBuf};
 
@@ -11,6 +15,21 @@ const MIGRATION_CREATE_BEATMAP: &str = include_str!("migrations/002_create_beatm
 const MIGRATION_CREATE_REPLAY: &str = include_str!("migrations/003_create_replay.sql");
 const MIGRATION_CREATE_BEATMAP_RATING: &str =
     include_str!("migrations/005_create_beatmap_rating.sql");
+const MIGRATION_CREATE_ACHIEVEMENT: &str = include_str!("migrations/006_create_achievement.sql");
+
+/// Columns added to the beatmap table after it was first created.
+/// Rows scanned before keep the default until their set is rescanned.
+const BEATMAP_ADDED_COLUMNS: &[(&str, &str)] = &[("peak_nps", "REAL NOT NULL DEFAULT 0.0")];
+
+/// Columns added to the replay table after it was first created.
+/// Existing databases get them through `ALTER TABLE` on startup.
+const REPLAY_ADDED_COLUMNS: &[(&str, &str)] = &[
+    ("key_count", "INTEGER NOT NULL DEFAULT 0"),
+    ("mods", "TEXT NOT NULL DEFAULT ''"),
+    ("hit_window_mode", "TEXT NOT NULL DEFAULT ''"),
+    ("hit_window_value", "REAL NOT NULL DEFAULT 0"),
+    ("incomplete", "INTEGER NOT NULL DEFAULT 0"),
+];
 
 pub struct Database {
     pool: SqlitePool,
@@ -58,9 +77,85 @@ impl Database {
             MIGRATION_CREATE_BEATMAP,
             MIGRATION_CREATE_REPLAY,
             MIGRATION_CREATE_BEATMAP_RATING,
+            MIGRATION_CREATE_ACHIEVEMENT,
         ] {
             sqlx::query(migration).execute(&self.pool).await?;
         }
+        self.add_missing_columns("beatmap", BEATMAP_ADDED_COLUMNS)
+            .await?;
+        self.add_missing_columns("replay", REPLAY_ADDED_COLUMNS)
+            .await?;
+        self.drop_replay_foreign_key().await?;
+
+        Ok(())
+    }
+
+    /// Rebuilds a replay table created with the old `ON DELETE CASCADE` key on
+    /// `beatmap`, so removing a beatmap (or a whole folder) keeps its scores.
+    /// SQLite cannot drop a foreign key in place.
+    async fn drop_replay_foreign_key(&self) -> Result<(), sqlx::Error> {
+        let schema: Option<String> = sqlx::query_scalar(
+            "SELECT sql FROM sqlite_master WHERE type = 'table' AND name = 'replay'",
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        if !schema.is_some_and(|sql| sql.contains("REFERENCES")) {
+            return Ok(());
+        }
+
+        log::info!("DB: Dropping the beatmap foreign key from replay");
+        let columns: Vec<String> =
+            sqlx::query_scalar("SELECT name FROM pragma_table_info('replay')")
+                .fetch_all(&self.pool)
+                .await?;
+        let columns = columns.join(", ");
+
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("ALTER TABLE replay RENAME TO replay_old")
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(MIGRATION_CREATE_REPLAY)
+            .execute(&mut *tx)
+            .await?;
+        for (name, definition) in REPLAY_ADDED_COLUMNS {
+            sqlx::query(&format!(
+                "ALTER TABLE replay ADD COLUMN {name} {definition}"
+            ))
+            .execute(&mut *tx)
+            .await?;
+        }
+        sqlx::query(&format!(
+            "INSERT INTO replay ({columns}) SELECT {columns} FROM replay_old"
+        ))
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query("DROP TABLE replay_old")
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await
+    }
+
+    /// Adds the columns missing from `table` (SQLite has no `ADD COLUMN IF NOT EXISTS`).
+    async fn add_missing_columns(
+        &self,
+        table: &str,
+        columns: &[(&str, &str)],
+    ) -> Result<(), sqlx::Error> {
+        let existing: Vec<String> =
+            sqlx::query_scalar(&format!("SELECT name FROM pragma_table_info('{table}')"))
+                .fetch_all(&self.pool)
+                .await?;
+
+        for (name, definition) in columns {
+            if !existing.iter().any(|column| column == *name) {
+                log::info!("DB: Adding column {table}.{name}");
+                sqlx::query(&format!(
+                    "ALTER TABLE {table} ADD COLUMN {name} {definition}"
+                ))
+                .execute(&self.pool)
+                .await?;
+            }
+        }
 
         Ok(())
     }
@@ -92,26 +187,9 @@ impl Database {
         beatmapset_id: i64,
         hash: &str,
         path: &str,
-        difficulty_name: Option<&str>,
-        note_count: i32,
-        duration_ms: i32,
-        nps: f64,
-        bpm: f64,
-        key_count: i32,
+        info: &BeatmapBasicInfo,
     ) -> Result<String, sqlx::Error> {
-        query::insert_beatmap(
-            &self.pool,
-            beatmapset_id,
-            hash,
-            path,
-            difficulty_name,
-            note_count,
-            duration_ms,
-            nps,
-            bpm,
-            key_count,
-        )
-        .await
+        query::insert_beatmap(&self.pool, beatmapset_id, hash, path, info).await
     }
 
     /// Fetches all ratings for a beatmap.
@@ -155,6 +233,7 @@ impl Database {
         accuracy: f64,
         max_combo: i32,
         rate: f64,
+        hit_window: (HitWindowMode, f64),
         data: &replay::ReplayData,
     ) -> Result<String, sqlx::Error> {
         query::insert_replay(
@@ -165,16 +244,58 @@ impl Database {
             accuracy,
             max_combo,
             rate,
+            hit_window,
             data,
         )
         .await
     }
 
-    /// Retrieves all replays for a given beatmap hash.
+    /// Retrieves the replays for a given beatmap hash matching the filter.
     pub async fn get_replays_for_beatmap(
         &self,
         beatmap_hash: &str,
+        filter: &LeaderboardFilter,
     ) -> Result<Vec<crate::models::Replay>, sqlx::Error> {
-        query::get_replays_for_beatmap(&self.pool, beatmap_hash).await
+        query::get_replays_for_beatmap(&self.pool, beatmap_hash, filter).await
+    }
+
+    /// Deletes a replay and its file. Returns false if it did not exist.
+    pub async fn delete_replay(&self, hash: &str) -> Result<bool, sqlx::Error> {
+        query::delete_replay(&self.pool, hash).await
+    }
+
+    /// Retrieves every stored replay with the chart path of its beatmap.
+    pub async fn get_all_replays_with_chart(&self) -> Result<Vec<ReplayWithChart>, sqlx::Error> {
+        query::get_all_replays_with_chart(&self.pool).await
+    }
+
+    // ========================================================================
+    // ACHIEVEMENT METHODS
+    // ========================================================================
+
+    /// Unlocks the achievements reached by a saved replay, returning the
+    /// new ones.
+    pub async fn record_run(
+        &self,
+        replay_hash: &str,
+        timestamp: i64,
+        run: &RunOutcome,
+    ) -> Result<Vec<Achievement>, sqlx::Error> {
+        achievements::record_run(&self.pool, replay_hash, timestamp, run).await
+    }
+
+    /// Every unlocked achievement, in unlock order.
+    pub async fn unlocked_achievements(&self) -> Result<Vec<UnlockedAchievement>, sqlx::Error> {
+        achievements::unlocked_achievements(&self.pool).await
+    }
+
+    /// Writes every stored replay to a portable backup file.
+    pub async fn export_scores(&self, path: &Path) -> Result<usize, sqlx::Error> {
+        backup::export_scores(&self.pool, path).await
+    }
+
+    /// Imports a backup written by [`Database::export_scores`].
+    pub async fn import_scores(&self, path: &Path) -> Result<ImportSummary, sqlx::Error> {
+        backup::import_scores(&self.pool, path).await
     }
 }
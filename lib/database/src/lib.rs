@@ -1,12 +1,19 @@
+pub mod achievements;
+pub mod backup;
 pub mod connection;
 pub mod manager;
 pub mod models;
 pub mod query;
 pub mod replay_storage;
+pub mod rescore;
 pub mod scanner;
 pub mod search;
+pub mod watcher;
 
+pub use achievements::{Achievement, RunOutcome, UnlockedAchievement};
+pub use backup::{ImportSummary, export_scores, import_scores};
 pub use connection::Database;
 pub use manager::{DbManager, DbStatus, SaveRatingCommand, SaveReplayCommand};
-pub use models::{BeatmapRating, BeatmapWithRatings, Beatmapset};
-pub use search::{MenuSearchFilters, RatingMetric, RatingSource};
+pub use models::{BeatmapRating, BeatmapWithRatings, Beatmapset, ReplayWithChart};
+pub use rescore::{RescoreRow, rescore_database, rescore_replays, rows_to_csv};
+pub use search::{LeaderboardFilter, MenuSearchFilters, RatingMetric, RatingSource};
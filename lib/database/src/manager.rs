@@ -1,15 +1,19 @@
 //! Database manager handling background operations.
 
+use crate::achievements::{RunOutcome, UnlockedAchievement};
 use crate::connection::Database;
 use crate::models::{BeatmapWithRatings, Beatmapset, Replay};
 use crate::query::{clear_all, get_all_beatmapsets, insert_beatmap_rating};
-use crate::scanner::scan_songs_directory;
-use crate::search::MenuSearchFilters;
+use crate::scanner::{rescan_beatmapset, scan_songs_directory};
+use crate::search::{LeaderboardFilter, MenuSearchFilters};
+use crate::watcher::RescanQueue;
 use chart::BeatmapSsr;
+use engine::HitWindowMode;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum DbStatus {
@@ -30,6 +34,8 @@ pub struct DbState {
     pub leaderboard: Vec<Replay>,
     pub leaderboard_hash: Option<String>,
     pub leaderboard_version: u64,
+    /// Lifetime achievements, in unlock order.
+    pub achievements: Vec<UnlockedAchievement>,
 }
 
 impl DbState {
@@ -42,6 +48,7 @@ impl DbState {
             leaderboard: Vec::new(),
             leaderboard_hash: None,
             leaderboard_version: 0,
+            achievements: Vec::new(),
         }
     }
 }
@@ -54,9 +61,24 @@ pub struct SaveReplayCommand {
     pub accuracy: f64,
     pub max_combo: i32,
     pub rate: f64,
+    /// Hit window the play was judged with.
+    pub hit_window_mode: HitWindowMode,
+    pub hit_window_value: f64,
+    /// Every note judged without a combo break.
+    pub full_combo: bool,
+    /// Whether the player tracks achievements (see [`Self::unlocks_achievements`]).
+    pub track_achievements: bool,
     pub data: replay::ReplayData,
 }
 
+impl SaveReplayCommand {
+    /// Whether the run can unlock achievements: tracking is on, and it was
+    /// neither incomplete nor played in practice mode.
+    pub fn unlocks_achievements(&self) -> bool {
+        self.track_achievements && !self.data.incomplete && !self.data.is_practice_mode
+    }
+}
+
 #[derive(Debug)]
 pub enum DbCommand {
     Init,
@@ -64,8 +86,13 @@ pub enum DbCommand {
     Rescan,
     Search(MenuSearchFilters),
     SaveReplay(SaveReplayCommand),
+    /// Deletes a replay by hash, then reloads the leaderboard.
+    DeleteReplay(String),
     SaveRating(SaveRatingCommand),
     FetchLeaderboard(String),
+    SetLeaderboardFilter(LeaderboardFilter),
+    /// Filesystem event from the songs folder watcher.
+    SongsChanged(notify::Event),
     Shutdown,
 }
 
@@ -80,6 +107,9 @@ pub struct SaveRatingCommand {
 pub struct DbManager {
     state: Arc<Mutex<DbState>>,
     command_sender: std::sync::mpsc::Sender<DbCommand>,
+    songs_path: PathBuf,
+    /// Songs folder watcher, kept alive while watching.
+    watcher: Option<RecommendedWatcher>,
     _handle: thread::JoinHandle<()>,
 }
 
@@ -89,15 +119,18 @@ impl DbManager {
         let (tx, rx) = std::sync::mpsc::channel();
 
         let state_clone = Arc::clone(&state);
+        let thread_songs_path = songs_path.clone();
         let handle = thread::spawn(move || {
             let rt = tokio::runtime::Runtime::new()
                 .expect("Failed to create tokio runtime for database thread");
-            rt.block_on(Self::db_thread(state_clone, rx, db_path, songs_path));
+            rt.block_on(Self::db_thread(state_clone, rx, db_path, thread_songs_path));
         });
 
         Self {
             state,
             command_sender: tx,
+            songs_path,
+            watcher: None,
             _handle: handle,
         }
     }
@@ -109,6 +142,8 @@ impl DbManager {
         songs_path: PathBuf,
     ) {
         let mut db: Option<Database> = None;
+        let mut leaderboard_filter = LeaderboardFilter::default();
+        let mut rescan_queue = RescanQueue::new(&songs_path);
 
         loop {
             // Check commands without blocking the loop.
@@ -127,6 +162,7 @@ impl DbManager {
                                 let mut s = state.lock().unwrap();
                                 s.status = DbStatus::Idle;
                             }
+                            Self::load_achievements(&state, db.as_ref().unwrap()).await;
 
                             // If the database already exists, eagerly load beatmaps.
                             if db_path.exists() {
@@ -159,14 +195,29 @@ impl DbManager {
                 Ok(DbCommand::SaveReplay(payload)) => {
                     if let Some(ref d) = db {
                         log::info!("DB: Saving replay for beatmap {}", payload.beatmap_hash);
-                        Self::persist_replay(&state, d, payload).await;
+                        Self::persist_replay(&state, d, payload, &leaderboard_filter).await;
                     } else {
                         log::error!("DB: Cannot save replay - database not initialized!");
                     }
                 }
+                Ok(DbCommand::DeleteReplay(hash)) => {
+                    if let Some(ref d) = db {
+                        Self::remove_replay(&state, d, &hash, &leaderboard_filter).await;
+                    }
+                }
                 Ok(DbCommand::FetchLeaderboard(hash)) => {
                     if let Some(ref d) = db {
-                        Self::load_leaderboard(&state, d, &hash).await;
+                        Self::load_leaderboard(&state, d, &hash, &leaderboard_filter).await;
+                    }
+                }
+                Ok(DbCommand::SetLeaderboardFilter(filter)) => {
+                    leaderboard_filter = filter;
+                    // Reload the current leaderboard with the new filter
+                    let hash = state.lock().unwrap().leaderboard_hash.clone();
+                    if let Some(ref d) = db
+                        && let Some(hash) = hash
+                    {
+                        Self::load_leaderboard(&state, d, &hash, &leaderboard_filter).await;
                     }
                 }
                 Ok(DbCommand::SaveRating(payload)) => {
@@ -174,6 +225,9 @@ impl DbManager {
                         Self::persist_rating(d, payload).await;
                     }
                 }
+                Ok(DbCommand::SongsChanged(event)) => {
+                    rescan_queue.record(&event, Instant::now());
+                }
                 Ok(DbCommand::Shutdown) => {
                     break;
                 }
@@ -186,6 +240,21 @@ impl DbManager {
                 }
             }
 
+            // Rescan the folders the watcher saw settle
+            if let Some(ref d) = db
+                && !rescan_queue.is_empty()
+            {
+                let folders = rescan_queue.take_ready(Instant::now());
+                if !folders.is_empty() {
+                    for folder in &folders {
+                        if let Err(e) = rescan_beatmapset(d, folder).await {
+                            log::error!("DB: Error rescanning {:?}: {}", folder, e);
+                        }
+                    }
+                    Self::load_maps(&state, d).await;
+                }
+            }
+
             // Small sleep to avoid pegging a CPU core.
             tokio::time::sleep(Duration::from_millis(10)).await;
         }
@@ -286,6 +355,7 @@ impl DbManager {
         state: &Arc<Mutex<DbState>>,
         db: &Database,
         payload: SaveReplayCommand,
+        leaderboard_filter: &LeaderboardFilter,
     ) {
         match db
             .insert_replay(
@@ -295,13 +365,17 @@ impl DbManager {
                 payload.accuracy,
                 payload.max_combo,
                 payload.rate,
+                (payload.hit_window_mode, payload.hit_window_value),
                 &payload.data,
             )
             .await
         {
-            Ok(_) => {
+            Ok(hash) => {
                 log::info!("DB: Replay saved successfully for {}", payload.beatmap_hash);
-                Self::load_leaderboard(state, db, &payload.beatmap_hash).await;
+                Self::load_leaderboard(state, db, &payload.beatmap_hash, leaderboard_filter).await;
+                if payload.unlocks_achievements() {
+                    Self::unlock_achievements(state, db, &hash, &payload).await;
+                }
             }
             Err(e) => {
                 log::error!(
@@ -313,8 +387,63 @@ impl DbManager {
         }
     }
 
-    async fn load_leaderboard(state: &Arc<Mutex<DbState>>, db: &Database, beatmap_hash: &str) {
-        match db.get_replays_for_beatmap(beatmap_hash).await {
+    async fn unlock_achievements(
+        state: &Arc<Mutex<DbState>>,
+        db: &Database,
+        replay_hash: &str,
+        payload: &SaveReplayCommand,
+    ) {
+        let run = RunOutcome {
+            accuracy: payload.accuracy,
+            max_combo: payload.max_combo.max(0) as u32,
+            full_combo: payload.full_combo,
+        };
+        match db.record_run(replay_hash, payload.timestamp, &run).await {
+            Ok(unlocked) if unlocked.is_empty() => {}
+            Ok(unlocked) => {
+                for achievement in &unlocked {
+                    log::info!("DB: Achievement unlocked: {}", achievement.display_name());
+                }
+                Self::load_achievements(state, db).await;
+            }
+            Err(e) => log::error!("DB: failed to record achievements: {}", e),
+        }
+    }
+
+    async fn load_achievements(state: &Arc<Mutex<DbState>>, db: &Database) {
+        match db.unlocked_achievements().await {
+            Ok(achievements) => state.lock().unwrap().achievements = achievements,
+            Err(e) => log::error!("DB: failed to load achievements: {}", e),
+        }
+    }
+
+    async fn remove_replay(
+        state: &Arc<Mutex<DbState>>,
+        db: &Database,
+        hash: &str,
+        leaderboard_filter: &LeaderboardFilter,
+    ) {
+        match db.delete_replay(hash).await {
+            Ok(true) => log::info!("DB: Replay {} deleted", hash),
+            Ok(false) => log::warn!("DB: Replay {} not found", hash),
+            Err(e) => {
+                log::error!("DB: failed to delete replay {}: {}", hash, e);
+                return;
+            }
+        }
+        let leaderboard_hash = state.lock().unwrap().leaderboard_hash.clone();
+        if let Some(beatmap_hash) = leaderboard_hash {
+            Self::load_leaderboard(state, db, &beatmap_hash, leaderboard_filter).await;
+        }
+    }
+
+    async fn load_leaderboard(
+        state: &Arc<Mutex<DbState>>,
+        db: &Database,
+        beatmap_hash: &str,
+        filter: &LeaderboardFilter,
+    ) {
+        match db.get_replays_for_beatmap(beatmap_hash, filter).await {
             Ok(replays) => {
                 let mut s = state.lock().unwrap();
                 s.leaderboard = replays;
@@ -350,18 +479,48 @@ impl DbManager {
         let _ = self.send_command(DbCommand::Rescan);
     }
 
+    /// Watches the songs folder, rescanning the beatmapsets added, changed
+    /// or removed while the game runs.
+    pub fn watch_songs(&mut self) -> notify::Result<()> {
+        let sender = self.command_sender.clone();
+        let mut watcher =
+            notify::recommended_watcher(move |res: notify::Result<notify::Event>| match res {
+                Ok(event) => {
+                    let _ = sender.send(DbCommand::SongsChanged(event));
+                }
+                Err(e) => log::warn!("DB: songs watcher error: {}", e),
+            })?;
+        watcher.watch(&self.songs_path, RecursiveMode::Recursive)?;
+        log::info!("DB: Watching {:?}", self.songs_path);
+        self.watcher = Some(watcher);
+        Ok(())
+    }
+
     pub fn search(&self, filters: MenuSearchFilters) {
         let _ = self.send_command(DbCommand::Search(filters));
     }
 
+    /// Lifetime achievements unlocked so far (e.g. for a profile screen).
+    pub fn unlocked_achievements(&self) -> Vec<UnlockedAchievement> {
+        self.state.lock().unwrap().achievements.clone()
+    }
+
     pub fn save_replay(&self, payload: SaveReplayCommand) {
         let _ = self.send_command(DbCommand::SaveReplay(payload));
     }
 
+    pub fn delete_replay(&self, hash: &str) {
+        let _ = self.send_command(DbCommand::DeleteReplay(hash.to_string()));
+    }
+
     pub fn fetch_leaderboard(&self, beatmap_hash: &str) {
         let _ = self.send_command(DbCommand::FetchLeaderboard(beatmap_hash.to_string()));
     }
 
+    pub fn set_leaderboard_filter(&self, filter: LeaderboardFilter) {
+        let _ = self.send_command(DbCommand::SetLeaderboardFilter(filter));
+    }
+
     pub fn save_rating(&self, payload: SaveRatingCommand) {
         let _ = self.send_command(DbCommand::SaveRating(payload));
     }
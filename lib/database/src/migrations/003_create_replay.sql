@@ -6,6 +6,5 @@ CREATE TABLE IF NOT EXISTS replay (
     accuracy REAL NOT NULL,
     max_combo INTEGER NOT NULL,
     rate REAL NOT NULL DEFAULT 1.0,
-    file_path TEXT NOT NULL,
-    FOREIGN KEY (beatmap_hash) REFERENCES beatmap(hash) ON DELETE CASCADE
+    file_path TEXT NOT NULL
 );
\ No newline at end of file
@@ -0,0 +1,5 @@
+CREATE TABLE IF NOT EXISTS achievement (
+    id TEXT PRIMARY KEY,
+    unlocked_at INTEGER NOT NULL,
+    replay_hash TEXT
+);
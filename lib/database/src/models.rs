@@ -1,5 +1,6 @@
 //! Data structures mirroring the SQLite tables.
 
+use engine::GameMod;
 use sqlx::FromRow;
 
 #[derive(Debug, Clone, FromRow)]
@@ -20,6 +21,7 @@ pub struct Beatmap {
     pub note_count: i32,
     pub duration_ms: i32,
     pub nps: f64,
+    pub peak_nps: f64,
     pub bpm: f64, // Dominant BPM (longest duration in chart)
     pub key_count: i32,
 }
@@ -94,6 +96,36 @@ pub struct Replay {
     pub score: i32,
     pub accuracy: f64,
     pub max_combo: i32,
-    pub rate: f64,         // Playback rate (1.0 = normal, 1.5 = 1.5x, etc.)
-    pub file_path: String, // Path to Brotli-compressed replay file (data/r/{hash}.r)
+    pub rate: f64,               // Playback rate (1.0 = normal, 1.5 = 1.5x, etc.)
+    pub file_path: String,       // Path to Brotli-compressed replay file (data/r/{hash}.r)
+    pub key_count: i32,          // 0 for replays saved before key count was recorded
+    pub mods: String,            // Comma-separated mod ids (see GameMod::id), empty if none
+    pub hit_window_mode: String, // HitWindowMode::id, empty if unknown
+    pub hit_window_value: f64,   // OD or judge level
+    pub incomplete: bool,        // Abandoned before the end, never a score
+}
+
+impl Replay {
+    /// Parses the stored mod list.
+    pub fn mod_list(&self) -> Vec<GameMod> {
+        self.mods.split(',').filter_map(GameMod::from_id).collect()
+    }
+
+    /// Whether any mod was active during the play.
+    pub fn is_modded(&self) -> bool {
+        !self.mods.is_empty()
+    }
+}
+
+/// A stored replay joined with the chart file of its beatmap.
+#[derive(Debug, Clone, FromRow)]
+pub struct ReplayWithChart {
+    #[sqlx(flatten)]
+    pub replay: Replay,
+    pub chart_path: String,
+}
+
+/// Formats mods for the `replay.mods` column.
+pub fn mods_to_column(mods: &[GameMod]) -> String {
+    mods.iter().map(GameMod::id).collect::<Vec<_>>().join(",")
 }
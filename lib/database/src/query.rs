@@ -2,8 +2,12 @@
 
 #![allow(clippy::too_many_arguments)]
 
-use crate::models::{Beatmap, BeatmapRating, BeatmapWithRatings, Beatmapset, Replay};
-use crate::search::MenuSearchFilters;
+use crate::models::{
+    Beatmap, BeatmapRating, BeatmapWithRatings, Beatmapset, Replay, ReplayWithChart, mods_to_column,
+};
+use crate::search::{LeaderboardFilter, MenuSearchFilters};
+use chart::BeatmapBasicInfo;
+use engine::HitWindowMode;
 use sqlx::SqlitePool;
 use std::collections::HashMap;
 
@@ -64,19 +68,52 @@ pub async fn insert_beatmapset(
     }
 }
 
-/// Inserts or updates a beatmap record.
+/// Deletes a beatmapset and its beatmaps (its folder was removed).
+pub async fn delete_beatmapset(pool: &SqlitePool, path: &str) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "DELETE FROM beatmap WHERE beatmapset_id IN (SELECT id FROM beatmapset WHERE path = ?1)",
+    )
+    .bind(path)
+    .execute(pool)
+    .await?;
+    sqlx::query("DELETE FROM beatmapset WHERE path = ?1")
+        .bind(path)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Deletes the beatmaps of a beatmapset whose chart file is not in `kept`.
+pub async fn delete_missing_beatmaps(
+    pool: &SqlitePool,
+    beatmapset_path: &str,
+    kept: &[&str],
+) -> Result<(), sqlx::Error> {
+    let paths: Vec<String> = sqlx::query_scalar(
+        "SELECT beatmap.path FROM beatmap JOIN beatmapset ON beatmapset.id = beatmap.beatmapset_id WHERE beatmapset.path = ?1",
+    )
+    .bind(beatmapset_path)
+    .fetch_all(pool)
+    .await?;
+    for path in paths.iter().filter(|path| !kept.contains(&path.as_str())) {
+        sqlx::query("DELETE FROM beatmap WHERE path = ?1")
+            .bind(path)
+            .execute(pool)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Inserts or updates a beatmap record with the chart's basic info.
 pub async fn insert_beatmap(
     pool: &SqlitePool,
     beatmapset_id: i64,
     hash: &str,
     path: &str,
-    difficulty_name: Option<&str>,
-    note_count: i32,
-    duration_ms: i32,
-    nps: f64,
-    bpm: f64,
-    key_count: i32,
+    info: &BeatmapBasicInfo,
 ) -> Result<String, sqlx::Error> {
+    let difficulty_name = Some(info.difficulty_name.as_str()).filter(|name| !name.is_empty());
+
     // Check whether a beatmap already exists for the given hash.
     let existing: Option<String> = sqlx::query_scalar("SELECT hash FROM beatmap WHERE hash = ?1")
         .bind(hash)
@@ -87,16 +124,17 @@ pub async fn insert_beatmap(
         Some(existing_hash) => {
             // Update the existing row.
             sqlx::query(
-                "UPDATE beatmap SET beatmapset_id = ?1, path = ?2, difficulty_name = ?3, note_count = ?4, duration_ms = ?5, nps = ?6, bpm = ?7, key_count = ?8 WHERE hash = ?9"
+                "UPDATE beatmap SET beatmapset_id = ?1, path = ?2, difficulty_name = ?3, note_count = ?4, duration_ms = ?5, nps = ?6, peak_nps = ?7, bpm = ?8, key_count = ?9 WHERE hash = ?10"
             )
             .bind(beatmapset_id)
             .bind(path)
             .bind(difficulty_name)
-            .bind(note_count)
-            .bind(duration_ms)
-            .bind(nps)
-            .bind(bpm)
-            .bind(key_count)
+            .bind(info.note_count)
+            .bind(info.duration_ms)
+            .bind(info.nps)
+            .bind(info.peak_nps)
+            .bind(info.bpm)
+            .bind(info.key_count)
             .bind(&existing_hash)
             .execute(pool)
             .await?;
@@ -105,17 +143,18 @@ pub async fn insert_beatmap(
         None => {
             // Insert a new row.
             sqlx::query(
-                "INSERT INTO beatmap (hash, beatmapset_id, path, difficulty_name, note_count, duration_ms, nps, bpm, key_count) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)"
+                "INSERT INTO beatmap (hash, beatmapset_id, path, difficulty_name, note_count, duration_ms, nps, peak_nps, bpm, key_count) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)"
             )
             .bind(hash)
             .bind(beatmapset_id)
             .bind(path)
             .bind(difficulty_name)
-            .bind(note_count)
-            .bind(duration_ms)
-            .bind(nps)
-            .bind(bpm)
-            .bind(key_count)
+            .bind(info.note_count)
+            .bind(info.duration_ms)
+            .bind(info.nps)
+            .bind(info.peak_nps)
+            .bind(info.bpm)
+            .bind(info.key_count)
             .execute(pool)
             .await?;
             Ok(hash.to_string())
@@ -214,7 +253,7 @@ pub async fn get_all_beatmapsets(
     for beatmapset in beatmapsets {
         // Query beatmaps with their ratings, ordered by overall rating (lowest to highest)
         let beatmaps: Vec<Beatmap> = sqlx::query_as(
-            "SELECT b.hash, b.beatmapset_id, b.path, b.difficulty_name, b.note_count, b.duration_ms, b.nps, b.bpm, b.key_count 
+            "SELECT b.hash, b.beatmapset_id, b.path, b.difficulty_name, b.note_count, b.duration_ms, b.nps, b.peak_nps, b.bpm, b.key_count 
              FROM beatmap b
              LEFT JOIN beatmap_rating br ON b.hash = br.beatmap_hash AND LOWER(br.name) = 'etterna'
              WHERE b.beatmapset_id = ?1 
@@ -306,7 +345,7 @@ pub async fn search_beatmapsets(
     for beatmapset in beatmapsets {
         // Query beatmaps with their ratings, ordered by overall rating (lowest to highest)
         let beatmaps: Vec<Beatmap> = sqlx::query_as(
-            "SELECT b.hash, b.beatmapset_id, b.path, b.difficulty_name, b.note_count, b.duration_ms, b.nps, b.bpm 
+            "SELECT b.hash, b.beatmapset_id, b.path, b.difficulty_name, b.note_count, b.duration_ms, b.nps, b.peak_nps, b.bpm, b.key_count 
              FROM beatmap b
              LEFT JOIN beatmap_rating br ON b.hash = br.beatmap_hash AND LOWER(br.name) = 'etterna'
              WHERE b.beatmapset_id = ?1 
@@ -332,6 +371,9 @@ pub async fn search_beatmapsets(
 // REPLAY QUERIES
 // ============================================================================
 
+/// Tolerance when matching stored rates and hit window values.
+const FILTER_EPSILON: f64 = 1e-3;
+
 /// Inserts a replay: compresses data with Brotli (binary), saves to file, stores path in DB.
 pub async fn insert_replay(
     pool: &SqlitePool,
@@ -341,6 +383,7 @@ pub async fn insert_replay(
     accuracy: f64,
     max_combo: i32,
     rate: f64,
+    hit_window: (HitWindowMode, f64),
     data: &replay::ReplayData,
 ) -> Result<String, sqlx::Error> {
     // Generate deterministic hash
@@ -362,7 +405,7 @@ pub async fn insert_replay(
 
     // Insert into database with file_path
     sqlx::query(
-        "INSERT INTO replay (hash, beatmap_hash, timestamp, score, accuracy, max_combo, rate, file_path) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)"
+        "INSERT INTO replay (hash, beatmap_hash, timestamp, score, accuracy, max_combo, rate, file_path, key_count, mods, hit_window_mode, hit_window_value, incomplete) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)"
     )
     .bind(&hash)
     .bind(beatmap_hash)
@@ -372,21 +415,335 @@ pub async fn insert_replay(
     .bind(max_combo)
     .bind(rate)
     .bind(&file_path)
+    .bind(data.key_count as i32)
+    .bind(mods_to_column(&data.mods))
+    .bind(hit_window.0.id())
+    .bind(hit_window.1)
+    .bind(data.incomplete)
     .execute(pool)
     .await?;
     Ok(hash)
 }
 
-/// Retrieves all replays for a beatmap, sorted by rate then accuracy (best first).
+/// Retrieves the replays for a beatmap matching the filter,
+/// sorted by rate then accuracy (best first).
+///
+/// Incomplete replays are left out unless the filter asks for them.
 pub async fn get_replays_for_beatmap(
     pool: &SqlitePool,
     beatmap_hash: &str,
+    filter: &LeaderboardFilter,
 ) -> Result<Vec<Replay>, sqlx::Error> {
     let replays: Vec<Replay> = sqlx::query_as(
-        "SELECT hash, beatmap_hash, timestamp, score, accuracy, max_combo, rate, file_path FROM replay WHERE beatmap_hash = ?1 ORDER BY rate DESC, accuracy DESC, timestamp DESC LIMIT 10"
+        "SELECT hash, beatmap_hash, timestamp, score, accuracy, max_combo, rate, file_path, key_count, mods, hit_window_mode, hit_window_value, incomplete FROM replay
+         WHERE beatmap_hash = ?1
+           AND (?2 = 0 OR mods = '')
+           AND (?3 IS NULL OR ABS(rate - ?3) < ?6)
+           AND (?4 IS NULL OR (hit_window_mode = ?4 AND ABS(hit_window_value - ?5) < ?6))
+           AND (?7 = 1 OR incomplete = 0)
+         ORDER BY rate DESC, accuracy DESC, timestamp DESC LIMIT 10"
     )
     .bind(beatmap_hash)
+    .bind(filter.hide_modded)
+    .bind(filter.rate)
+    .bind(filter.hit_window.map(|(mode, _)| mode.id()))
+    .bind(filter.hit_window.map(|(_, value)| value))
+    .bind(FILTER_EPSILON)
+    .bind(filter.include_incomplete)
     .fetch_all(pool)
     .await?;
     Ok(replays)
 }
+
+/// Inserts a replay row as is (the replay file must already be stored).
+pub async fn insert_replay_row(pool: &SqlitePool, replay: &Replay) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO replay (hash, beatmap_hash, timestamp, score, accuracy, max_combo, rate, file_path, key_count, mods, hit_window_mode, hit_window_value, incomplete) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)"
+    )
+    .bind(&replay.hash)
+    .bind(&replay.beatmap_hash)
+    .bind(replay.timestamp)
+    .bind(replay.score)
+    .bind(replay.accuracy)
+    .bind(replay.max_combo)
+    .bind(replay.rate)
+    .bind(&replay.file_path)
+    .bind(replay.key_count)
+    .bind(&replay.mods)
+    .bind(&replay.hit_window_mode)
+    .bind(replay.hit_window_value)
+    .bind(replay.incomplete)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Timestamp of the stored replay with this hash, if any.
+pub async fn replay_timestamp(pool: &SqlitePool, hash: &str) -> Result<Option<i64>, sqlx::Error> {
+    sqlx::query_scalar("SELECT timestamp FROM replay WHERE hash = ?1")
+        .bind(hash)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Whether a beatmap with this hash is stored.
+pub async fn beatmap_exists(pool: &SqlitePool, hash: &str) -> Result<bool, sqlx::Error> {
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM beatmap WHERE hash = ?1")
+        .bind(hash)
+        .fetch_one(pool)
+        .await?;
+    Ok(count > 0)
+}
+
+/// Deletes a replay row and its file. Returns false if no replay has this hash.
+pub async fn delete_replay(pool: &SqlitePool, hash: &str) -> Result<bool, sqlx::Error> {
+    let file_path: Option<String> =
+        sqlx::query_scalar("DELETE FROM replay WHERE hash = ?1 RETURNING file_path")
+            .bind(hash)
+            .fetch_optional(pool)
+            .await?;
+    let Some(file_path) = file_path else {
+        return Ok(false);
+    };
+
+    // The row is gone either way, a leftover file is only wasted space
+    if !file_path.is_empty()
+        && let Err(e) = std::fs::remove_file(&file_path)
+        && e.kind() != std::io::ErrorKind::NotFound
+    {
+        log::warn!("DB: failed to remove replay file {}: {}", file_path, e);
+    }
+    Ok(true)
+}
+
+/// Retrieves every stored replay, oldest first, incomplete ones included.
+pub async fn get_all_replays(pool: &SqlitePool) -> Result<Vec<Replay>, sqlx::Error> {
+    let replays: Vec<Replay> = sqlx::query_as(
+        "SELECT hash, beatmap_hash, timestamp, score, accuracy, max_combo, rate, file_path, key_count, mods, hit_window_mode, hit_window_value, incomplete FROM replay
+         ORDER BY timestamp ASC"
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(replays)
+}
+
+/// Retrieves every stored replay with the chart path of its beatmap, oldest first.
+pub async fn get_all_replays_with_chart(
+    pool: &SqlitePool,
+) -> Result<Vec<ReplayWithChart>, sqlx::Error> {
+    let replays: Vec<ReplayWithChart> = sqlx::query_as(
+        "SELECT replay.hash, replay.beatmap_hash, replay.timestamp, replay.score, replay.accuracy, replay.max_combo, replay.rate, replay.file_path, replay.key_count, replay.mods, replay.hit_window_mode, replay.hit_window_value, replay.incomplete, beatmap.path AS chart_path
+         FROM replay JOIN beatmap ON beatmap.hash = replay.beatmap_hash
+         ORDER BY replay.timestamp ASC"
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(replays)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::connection::Database;
+
+    fn test_info() -> BeatmapBasicInfo {
+        BeatmapBasicInfo {
+            note_count: 4,
+            key_count: 4,
+            ..Default::default()
+        }
+    }
+
+    async fn insert_test_replay(
+        pool: &SqlitePool,
+        hash: &str,
+        rate: f64,
+        hit_window: (HitWindowMode, f64),
+        mods: &str,
+    ) {
+        sqlx::query(
+            "INSERT INTO replay (hash, beatmap_hash, timestamp, score, accuracy, max_combo, rate, file_path, mods, hit_window_mode, hit_window_value) VALUES (?1, 'map', 0, 0, 95.0, 0, ?2, '', ?3, ?4, ?5)"
+        )
+        .bind(hash)
+        .bind(rate)
+        .bind(mods)
+        .bind(hit_window.0.id())
+        .bind(hit_window.1)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_leaderboard_filter_by_rate_and_judge() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::new(&dir.path().join("test.db")).await.unwrap();
+        let pool = db.pool();
+
+        // Replays reference their beatmap
+        let set_id = insert_beatmapset(pool, "set", None, None, None)
+            .await
+            .unwrap();
+        insert_beatmap(pool, set_id, "map", "set/map.osu", &test_info())
+            .await
+            .unwrap();
+
+        let j4 = (HitWindowMode::EtternaJudge, 4.0);
+        insert_test_replay(pool, "a", 1.0, j4, "").await;
+        insert_test_replay(pool, "b", 1.5, j4, "").await;
+        insert_test_replay(pool, "c", 1.0, (HitWindowMode::OsuOD, 8.0), "").await;
+        insert_test_replay(pool, "d", 1.0, j4, "hidden").await;
+
+        let hashes = |replays: Vec<Replay>| {
+            let mut hashes: Vec<String> = replays.into_iter().map(|r| r.hash).collect();
+            hashes.sort();
+            hashes
+        };
+
+        let all = get_replays_for_beatmap(pool, "map", &LeaderboardFilter::default())
+            .await
+            .unwrap();
+        assert_eq!(hashes(all), vec!["a", "b", "c", "d"]);
+
+        let rate_filter = LeaderboardFilter {
+            rate: Some(1.0),
+            ..Default::default()
+        };
+        let at_rate = get_replays_for_beatmap(pool, "map", &rate_filter)
+            .await
+            .unwrap();
+        assert_eq!(hashes(at_rate), vec!["a", "c", "d"]);
+
+        let j4_filter = LeaderboardFilter {
+            hide_modded: true,
+            rate: Some(1.0),
+            hit_window: Some(j4),
+            ..Default::default()
+        };
+        let j4_only = get_replays_for_beatmap(pool, "map", &j4_filter)
+            .await
+            .unwrap();
+        assert_eq!(hashes(j4_only), vec!["a"]);
+
+        db.pool().close().await;
+    }
+
+    #[tokio::test]
+    async fn test_delete_replay() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::new(&dir.path().join("test.db")).await.unwrap();
+        let pool = db.pool();
+
+        let set_id = insert_beatmapset(pool, "set", None, None, None)
+            .await
+            .unwrap();
+        insert_beatmap(pool, set_id, "map", "set/map.osu", &test_info())
+            .await
+            .unwrap();
+
+        let j4 = (HitWindowMode::EtternaJudge, 4.0);
+        insert_test_replay(pool, "a", 1.0, j4, "").await;
+        insert_test_replay(pool, "b", 1.0, j4, "").await;
+
+        // The file of "a" is on disk, the one of "b" is already missing
+        let replay_file = dir.path().join("a.r");
+        let missing_file = dir.path().join("b.r");
+        std::fs::write(&replay_file, b"replay").unwrap();
+        for (hash, path) in [("a", &replay_file), ("b", &missing_file)] {
+            sqlx::query("UPDATE replay SET file_path = ?1 WHERE hash = ?2")
+                .bind(path.to_string_lossy().into_owned())
+                .bind(hash)
+                .execute(pool)
+                .await
+                .unwrap();
+        }
+
+        assert!(delete_replay(pool, "a").await.unwrap());
+        assert!(!replay_file.exists());
+        let remaining = get_replays_for_beatmap(pool, "map", &LeaderboardFilter::default())
+            .await
+            .unwrap();
+        let hashes: Vec<String> = remaining.into_iter().map(|r| r.hash).collect();
+        assert_eq!(hashes, vec!["b"]);
+
+        // Already gone
+        assert!(!delete_replay(pool, "a").await.unwrap());
+
+        // A missing file does not keep the row
+        assert!(delete_replay(pool, "b").await.unwrap());
+        let remaining = get_replays_for_beatmap(pool, "map", &LeaderboardFilter::default())
+            .await
+            .unwrap();
+        assert!(remaining.is_empty());
+
+        db.pool().close().await;
+    }
+
+    #[tokio::test]
+    async fn test_incomplete_replay_only_in_full_history() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::new(&dir.path().join("test.db")).await.unwrap();
+        let pool = db.pool();
+
+        let set_id = insert_beatmapset(pool, "set", None, None, None)
+            .await
+            .unwrap();
+        insert_beatmap(pool, set_id, "map", "set/map.osu", &test_info())
+            .await
+            .unwrap();
+
+        let replay = |hash: &str, incomplete: bool| Replay {
+            hash: hash.to_string(),
+            beatmap_hash: "map".to_string(),
+            timestamp: 0,
+            score: 0,
+            accuracy: 95.0,
+            max_combo: 0,
+            rate: 1.0,
+            file_path: String::new(),
+            key_count: 4,
+            mods: String::new(),
+            hit_window_mode: HitWindowMode::EtternaJudge.id().to_string(),
+            hit_window_value: 4.0,
+            incomplete,
+        };
+        insert_replay_row(pool, &replay("finished", false))
+            .await
+            .unwrap();
+        insert_replay_row(pool, &replay("abandoned", true))
+            .await
+            .unwrap();
+
+        let hashes = |replays: Vec<Replay>| {
+            let mut hashes: Vec<String> = replays.into_iter().map(|r| r.hash).collect();
+            hashes.sort();
+            hashes
+        };
+
+        // Not a score: left out of the leaderboard by default
+        let scores = get_replays_for_beatmap(pool, "map", &LeaderboardFilter::default())
+            .await
+            .unwrap();
+        assert_eq!(hashes(scores), vec!["finished"]);
+
+        let with_abandoned = LeaderboardFilter {
+            include_incomplete: true,
+            ..Default::default()
+        };
+        let shown = get_replays_for_beatmap(pool, "map", &with_abandoned)
+            .await
+            .unwrap();
+        assert_eq!(hashes(shown), vec!["abandoned", "finished"]);
+
+        // Still part of the history
+        let history = get_all_replays(pool).await.unwrap();
+        assert!(
+            history
+                .iter()
+                .any(|r| r.hash == "abandoned" && r.incomplete)
+        );
+        assert_eq!(hashes(history), vec!["abandoned", "finished"]);
+
+        db.pool().close().await;
+    }
+}
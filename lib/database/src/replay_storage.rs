@@ -1,50 +1,83 @@
 //! Replay file storage with Zstd compression.
 //!
-//! Replays are stored as compressed binary files in `data/r/{hash}.r`.
+//! Replays are stored as compressed binary files in `{replay dir}/{hash}.r`,
+//! `data/r` unless the launcher selected another directory with
+//! [`set_replay_dir`].
 //! Data is serialized with `rkyv` before compression to minimize size.
+//! Encoding goes through the replay crate so older formats are migrated on load.
 
 use replay::ReplayData;
-use rkyv::rancor::Error;
 use std::fs::{self, File};
-use std::io::Write;
+use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
-use zstd::stream::{decode_all, encode_all};
+use std::sync::OnceLock;
+
+/// Replay directory used when none was selected.
+const DEFAULT_REPLAY_DIR: &str = "data/r";
+
+static REPLAY_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Selects the replay directory of this run (the profile's, see
+/// `settings::Profile::replay_dir`). Only the first call has an effect.
+pub fn set_replay_dir(dir: PathBuf) {
+    if let Err(dir) = REPLAY_DIR.set(dir) {
+        log::warn!("REPLAY: Directory already selected, ignoring {:?}", dir);
+    }
+}
 
 /// Base directory for replay files.
-const REPLAY_DIR: &str = "data/r";
+pub fn replay_dir() -> &'static Path {
+    REPLAY_DIR.get_or_init(default_replay_dir)
+}
+
+#[cfg(not(test))]
+fn default_replay_dir() -> PathBuf {
+    PathBuf::from(DEFAULT_REPLAY_DIR)
+}
+
+/// Tests never write into the working directory: their replays go to a
+/// temporary directory of their own.
+#[cfg(test)]
+fn default_replay_dir() -> PathBuf {
+    static TEST_DIR: OnceLock<tempfile::TempDir> = OnceLock::new();
+    TEST_DIR
+        .get_or_init(|| tempfile::tempdir().expect("temporary replay directory"))
+        .path()
+        .to_path_buf()
+}
 
 /// Get the path for a replay file given its hash.
 pub fn replay_path(hash: &str) -> PathBuf {
-    PathBuf::from(REPLAY_DIR).join(format!("{}.r", hash))
+    replay_dir().join(format!("{}.r", hash))
 }
 
 /// Ensure the replay directory exists.
 fn ensure_replay_dir() -> std::io::Result<()> {
-    fs::create_dir_all(REPLAY_DIR)
+    fs::create_dir_all(replay_dir())
 }
 
 /// Save replay data to a compressed binary file.
-/// Returns the relative path to the file.
+/// Returns the path to the file.
 pub fn save_replay(hash: &str, data: &ReplayData) -> std::io::Result<String> {
     ensure_replay_dir()?;
 
     let path = replay_path(hash);
     let mut file = File::create(&path)?;
 
-    // Serialize using rkyv
-    let binary_data = rkyv::to_bytes::<Error>(data).map_err(|e| {
-        std::io::Error::new(
-            std::io::ErrorKind::InvalidData,
-            format!("Serialization error: {}", e),
-        )
-    })?;
-
-    // Zstd compression (Level 21 - Maximum)
-    let compressed_data = encode_all(&binary_data[..], 21)?;
+    // rkyv + Zstd compression (Level 21 - Maximum)
+    let compressed_data = replay::compress(data)?;
     file.write_all(&compressed_data)?;
 
-    // Return relative path
-    Ok(format!("{}/{}.r", REPLAY_DIR, hash))
+    Ok(path.to_string_lossy().into_owned())
+}
+
+/// Save an already compressed replay (see [`replay::compress`]).
+/// Returns the path to the file.
+pub fn save_compressed(hash: &str, compressed: &[u8]) -> std::io::Result<String> {
+    ensure_replay_dir()?;
+    let path = replay_path(hash);
+    fs::write(&path, compressed)?;
+    Ok(path.to_string_lossy().into_owned())
 }
 
 /// Load and decompress replay data from file.
@@ -55,20 +88,11 @@ pub fn load_replay(hash: &str) -> std::io::Result<ReplayData> {
 
 /// Load replay data from a specific path.
 pub fn load_replay_from_path(path: &Path) -> std::io::Result<ReplayData> {
-    let file = File::open(path)?;
-
-    // Decompress with Zstd
-    let binary_data = decode_all(file)?;
-
-    // Deserialize using rkyv with validation
-    let data = rkyv::from_bytes::<ReplayData, Error>(&binary_data).map_err(|e| {
-        std::io::Error::new(
-            std::io::ErrorKind::InvalidData,
-            format!("Deserialization error: {}", e),
-        )
-    })?;
+    let mut compressed = Vec::new();
+    File::open(path)?.read_to_end(&mut compressed)?;
 
-    Ok(data)
+    // Decompress with Zstd, validate with rkyv and upgrade old formats
+    replay::decompress(&compressed)
 }
 
 /// Delete a replay file.
@@ -0,0 +1,197 @@
+//! Batch re-scoring of stored replays under a new hit window.
+//!
+//! Runs headless (no renderer, no audio): replay files and charts are read
+//! from the paths stored in the database and re-simulated with
+//! [`replay::rejudge`]. Used by the `rescore` binary.
+
+use crate::connection::Database;
+use crate::models::{Replay, ReplayWithChart};
+use crate::replay_storage::load_replay_from_path;
+use engine::{HitWindow, NoteData};
+use replay::ReplayData;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::path::Path;
+
+/// Header row of [`rows_to_csv`].
+pub const RESCORE_CSV_HEADER: &str =
+    "replay_hash,beatmap_hash,rate,old_accuracy,new_accuracy,delta";
+
+/// Accuracy of a replay before and after re-judging.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RescoreRow {
+    pub replay_hash: String,
+    pub beatmap_hash: String,
+    pub rate: f64,
+    /// Accuracy stored when the replay was recorded.
+    pub old_accuracy: f64,
+    /// Accuracy under the new hit window.
+    pub new_accuracy: f64,
+}
+
+/// Re-judges each replay with `hit_window`.
+///
+/// `load_replay` reads the replay data of a row and `load_chart` the notes
+/// of a chart path (each chart is loaded once). Replays whose data or chart
+/// can't be loaded are skipped with a warning.
+pub fn rescore_replays<'a>(
+    replays: impl IntoIterator<Item = &'a ReplayWithChart>,
+    hit_window: &HitWindow,
+    mut load_replay: impl FnMut(&Replay) -> std::io::Result<ReplayData>,
+    mut load_chart: impl FnMut(&str) -> Result<Vec<NoteData>, String>,
+) -> Vec<RescoreRow> {
+    let mut charts: HashMap<String, Option<Vec<NoteData>>> = HashMap::new();
+    let mut rows = Vec::new();
+
+    for entry in replays {
+        let chart = charts.entry(entry.chart_path.clone()).or_insert_with(|| {
+            match load_chart(&entry.chart_path) {
+                Ok(notes) => Some(notes),
+                Err(e) => {
+                    log::warn!("RESCORE: Skipping chart {}: {}", entry.chart_path, e);
+                    None
+                }
+            }
+        });
+        let Some(chart) = chart else {
+            continue;
+        };
+
+        let data = match load_replay(&entry.replay) {
+            Ok(data) => data,
+            Err(e) => {
+                log::warn!("RESCORE: Skipping replay {}: {}", entry.replay.hash, e);
+                continue;
+            }
+        };
+
+        let result = replay::rejudge(&data, chart, hit_window);
+        rows.push(RescoreRow {
+            replay_hash: entry.replay.hash.clone(),
+            beatmap_hash: entry.replay.beatmap_hash.clone(),
+            rate: entry.replay.rate,
+            old_accuracy: entry.replay.accuracy,
+            new_accuracy: result.accuracy,
+        });
+    }
+
+    rows
+}
+
+/// Re-judges every replay of the database with `hit_window`.
+pub async fn rescore_database(
+    db: &Database,
+    hit_window: &HitWindow,
+) -> Result<Vec<RescoreRow>, sqlx::Error> {
+    let mut replays = db.get_all_replays_with_chart().await?;
+    // Unplayed notes of an abandoned run would be rescored as misses
+    replays.retain(|stored| !stored.replay.incomplete);
+    Ok(rescore_replays(
+        &replays,
+        hit_window,
+        |replay| load_replay_from_path(Path::new(&replay.file_path)),
+        |path| engine::load_chart(Path::new(path)).map(|chart| engine::notes_from_chart(&chart)),
+    ))
+}
+
+/// Exports rescore rows as CSV, one row per replay.
+pub fn rows_to_csv(rows: &[RescoreRow]) -> String {
+    let mut csv = String::from(RESCORE_CSV_HEADER);
+    csv.push('\n');
+    for row in rows {
+        // Writing to a String cannot fail
+        let _ = writeln!(
+            csv,
+            "{},{},{:.2},{:.4},{:.4},{:.4}",
+            row.replay_hash,
+            row.beatmap_hash,
+            row.rate,
+            row.old_accuracy,
+            row.new_accuracy,
+            row.new_accuracy - row.old_accuracy
+        );
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stored(hash: &str, accuracy: f64) -> ReplayWithChart {
+        ReplayWithChart {
+            replay: Replay {
+                hash: hash.to_string(),
+                beatmap_hash: "map".to_string(),
+                timestamp: 0,
+                score: 0,
+                accuracy,
+                max_combo: 0,
+                rate: 1.0,
+                file_path: format!("data/r/{hash}.r"),
+                key_count: 4,
+                mods: String::new(),
+                hit_window_mode: "etterna_judge".to_string(),
+                hit_window_value: 4.0,
+                incomplete: false,
+            },
+            chart_path: "songs/set/map.osu".to_string(),
+        }
+    }
+
+    fn chart() -> Vec<NoteData> {
+        (0..8)
+            .map(|i| NoteData::tap(1_000_000 + i * 250_000, (i % 4) as u8))
+            .collect()
+    }
+
+    /// Hits every note of [`chart`] `offset_us` late.
+    fn replay_with_offset(offset_us: i64) -> ReplayData {
+        let mut data = ReplayData::new(1.0);
+        for note in chart() {
+            let press = note.time_us() + offset_us;
+            data.add_press(press, note.column());
+            data.add_release(press + 40_000, note.column());
+        }
+        data
+    }
+
+    #[test]
+    fn test_rescore_two_replays() {
+        let replays = vec![stored("exact", 100.0), stored("late", 100.0)];
+        let mut chart_loads = 0;
+        let judge_9 = HitWindow::from_etterna_judge(9);
+
+        let rows = rescore_replays(
+            &replays,
+            &judge_9,
+            |replay| {
+                Ok(match replay.hash.as_str() {
+                    "exact" => replay_with_offset(0),
+                    _ => replay_with_offset(20_000),
+                })
+            },
+            |path| {
+                assert_eq!(path, "songs/set/map.osu");
+                chart_loads += 1;
+                Ok(chart())
+            },
+        );
+
+        assert_eq!(chart_loads, 1);
+        let hashes: Vec<&str> = rows.iter().map(|r| r.replay_hash.as_str()).collect();
+        assert_eq!(hashes, vec!["exact", "late"]);
+
+        let expected = replay::simulate(&replay_with_offset(0), &chart(), &judge_9);
+        assert_eq!(rows[0].new_accuracy, expected.accuracy);
+        // 20ms late is far outside the Judge 9 marvelous window
+        assert!(rows[1].new_accuracy < rows[0].new_accuracy);
+        assert_eq!(rows[1].old_accuracy, 100.0);
+
+        let csv = rows_to_csv(&rows);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], RESCORE_CSV_HEADER);
+        assert!(lines[2].starts_with("late,map,1.00,100.0000,"));
+    }
+}
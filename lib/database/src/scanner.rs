@@ -6,9 +6,10 @@
 //! Difficulty ratings are calculated on-demand when a map is selected.
 
 use crate::connection::Database;
-use crate::query::insert_beatmap;
+use crate::query::{delete_beatmapset, delete_missing_beatmaps, insert_beatmap};
+use chart::extract_chart_basic_info;
+use engine::{ChartMetadata, RoxChart};
 use rhythm_open_exchange::codec::auto_decode;
-use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -17,7 +18,7 @@ const SUPPORTED_EXTENSIONS: &[&str] = &["osu", "qua", "sm", "ssc"];
 
 /// Scans the `songs/` directory and fills the database.
 ///
-/// Note: This scanner now only extracts basic metadata (hash, notes, duration, nps).
+/// Note: This scanner now only extracts basic metadata (hash, notes, duration, nps, bpm).
 /// Difficulty ratings are NOT calculated here - they are computed on-demand
 /// when the user selects a beatmap in the song select menu.
 pub async fn scan_songs_directory(
@@ -55,6 +56,28 @@ pub async fn scan_songs_directory(
     Ok(())
 }
 
+/// Rescans a single beatmapset folder after it was added, changed or removed.
+pub async fn rescan_beatmapset(
+    db: &Database,
+    folder: &Path,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(path_str) = folder.to_str() else {
+        return Ok(());
+    };
+    let chart_files = collect_chart_files(folder).unwrap_or_default();
+    if chart_files.is_empty() {
+        log::info!("DB: Removing beatmapset {:?}", folder);
+        delete_beatmapset(db.pool(), path_str).await?;
+        return Ok(());
+    }
+
+    log::info!("DB: Rescanning beatmapset {:?}", folder);
+    process_beatmapset(db, folder, &chart_files).await?;
+    let kept: Vec<&str> = chart_files.iter().filter_map(|p| p.to_str()).collect();
+    delete_missing_beatmaps(db.pool(), path_str, &kept).await?;
+    Ok(())
+}
+
 /// Collect all supported chart files from a directory.
 fn collect_chart_files(path: &Path) -> Option<Vec<PathBuf>> {
     let entries = fs::read_dir(path).ok()?;
@@ -79,20 +102,24 @@ async fn process_beatmapset(
     folder: &Path,
     chart_files: &[PathBuf],
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let Some(first_chart) = chart_files.first() else {
+    // Decode every chart once, metadata and notes come from the same parse
+    let charts: Vec<(&PathBuf, RoxChart)> = chart_files
+        .iter()
+        .filter_map(|chart_file| match auto_decode(chart_file) {
+            Ok(chart) => Some((chart_file, chart)),
+            Err(e) => {
+                log::error!("DB: Error processing {:?}: {}", chart_file, e);
+                None
+            }
+        })
+        .collect();
+    let Some((_, first_chart)) = charts.first() else {
         return Ok(());
     };
 
-    // Use ROX to decode the first chart for metadata
-    let chart = auto_decode(first_chart)?;
-
-    let title = chart.metadata.title.clone();
-    let artist = chart.metadata.artist.clone();
-    let image_path = chart
-        .metadata
-        .background_file
-        .as_ref()
-        .and_then(|bg| find_background_image(folder, Some(bg.as_str())));
+    let metadata = ChartMetadata::from_chart(first_chart);
+    let image_path = find_background_image(folder, metadata.background_file.as_deref())
+        .or_else(|| find_osu_event_background(folder, chart_files));
 
     let Some(path_str) = folder.to_str() else {
         return Ok(());
@@ -102,13 +129,13 @@ async fn process_beatmapset(
         .insert_beatmapset(
             path_str,
             image_path.as_deref(),
-            Some(artist.as_str()),
-            Some(title.as_str()),
+            Some(metadata.artist.as_str()),
+            Some(metadata.title.as_str()),
         )
         .await?;
 
-    for chart_file in chart_files {
-        if let Err(e) = process_chart_file(db, beatmapset_id, chart_file).await {
+    for (chart_file, chart) in &charts {
+        if let Err(e) = process_chart_file(db, beatmapset_id, chart_file, chart).await {
             log::error!("DB: Error processing {:?}: {}", chart_file, e);
         }
     }
@@ -119,130 +146,22 @@ async fn process_beatmapset(
 async fn process_chart_file(
     db: &Database,
     beatmapset_id: i64,
-    chart_file: &PathBuf,
+    chart_file: &Path,
+    chart: &RoxChart,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    // Use ROX to decode the chart
-    let chart = auto_decode(chart_file)?;
-
     // Use ROX's blake3 hash instead of MD5
     let hash = chart.hash();
 
-    // Extract basic info from ROX chart
-    let note_count = chart.notes.len() as i32;
-
-    // Calculate duration from first to last note
-    let first_time = chart.notes.first().map(|n| n.time_us).unwrap_or(0);
-    let last_time = chart
-        .notes
-        .iter()
-        .map(|n| n.end_time_us())
-        .max()
-        .unwrap_or(first_time);
-    let duration_us = last_time - first_time;
-    let duration_ms = (duration_us / 1000) as i32;
-    let duration_secs = duration_ms as f64 / 1000.0;
-    let nps = if duration_secs > 0.0 {
-        note_count as f64 / duration_secs
-    } else {
-        0.0
-    };
-
-    // Extract dominant BPM (the one that lasts the longest, ignoring SV changes)
-    let bpm = calculate_dominant_bpm(&chart.timing_points, last_time);
-
-    let difficulty_name = chart.metadata.difficulty_name.clone();
-
-    // Determine key count from max column index
-    let key_count = chart
-        .notes
-        .iter()
-        .map(|n| n.column)
-        .max()
-        .map(|c| c + 1)
-        .unwrap_or(4) as i32; // Default to 4 if no notes
+    // Metadata only: difficulty is calculated when the map is selected
+    let info = extract_chart_basic_info(chart);
 
     if let Some(chart_str) = chart_file.to_str() {
-        insert_beatmap(
-            db.pool(),
-            beatmapset_id,
-            &hash,
-            chart_str,
-            Some(&difficulty_name),
-            note_count,
-            duration_ms,
-            nps,
-            bpm,
-            key_count,
-        )
-        .await?;
-
-        // Calculate and save difficulty ratings during scan
-        calculate_and_save_ratings(db, &hash, &chart).await;
+        insert_beatmap(db.pool(), beatmapset_id, &hash, chart_str, &info).await?;
     }
 
     Ok(())
 }
 
-/// Calculate difficulty ratings using available calculators and save to DB.
-async fn calculate_and_save_ratings(
-    db: &Database,
-    hash: &str,
-    chart: &rhythm_open_exchange::RoxChart,
-) {
-    use chart::{calculate_on_demand, rox_chart_to_rosu};
-
-    // Convert RoxChart to rosu Beatmap format
-    let rosu_beatmap = match rox_chart_to_rosu(chart) {
-        Ok(bm) => bm,
-        Err(e) => {
-            log::warn!("Failed to convert chart {} for rating: {}", hash, e);
-            return;
-        }
-    };
-
-    // Calculate Etterna rating
-    if let Ok(ssr) = calculate_on_demand(&rosu_beatmap, "etterna", 1.0) {
-        if let Err(e) = crate::query::insert_beatmap_rating(
-            db.pool(),
-            hash,
-            "etterna",
-            ssr.overall,
-            ssr.stream,
-            ssr.jumpstream,
-            ssr.handstream,
-            ssr.stamina,
-            ssr.jackspeed,
-            ssr.chordjack,
-            ssr.technical,
-        )
-        .await
-        {
-            log::warn!("Failed to save Etterna rating for {}: {}", hash, e);
-        }
-    }
-
-    // Calculate Osu rating
-    if let Ok(ssr) = calculate_on_demand(&rosu_beatmap, "osu", 1.0) {
-        if let Err(e) = crate::query::insert_beatmap_rating(
-            db.pool(),
-            hash,
-            "osu",
-            ssr.overall,
-            ssr.stream,
-            ssr.jumpstream,
-            ssr.handstream,
-            ssr.stamina,
-            ssr.jackspeed,
-            ssr.chordjack,
-            ssr.technical,
-        )
-        .await
-        {
-            log::warn!("Failed to save Osu rating for {}: {}", hash, e);
-        }
-    }
-}
-
 fn find_background_image(beatmapset_path: &Path, filename: Option<&str>) -> Option<String> {
     filename.and_then(|fname| {
         let image_path = beatmapset_path.join(fname);
@@ -254,45 +173,128 @@ fn find_background_image(beatmapset_path: &Path, filename: Option<&str>) -> Opti
     })
 }
 
-/// Calculates the dominant BPM (the one that lasts the longest).
-/// Only considers timing points where is_inherited is false (actual BPM changes, not SV).
-fn calculate_dominant_bpm(
-    timing_points: &[rhythm_open_exchange::TimingPoint],
-    chart_end_time_us: i64,
-) -> f64 {
-    // Filter to only BPM timing points (not SV changes)
-    let bpm_points: Vec<_> = timing_points.iter().filter(|tp| !tp.is_inherited).collect();
-
-    if bpm_points.is_empty() {
-        return 0.0;
-    }
-
-    // If only one BPM point, return it
-    if bpm_points.len() == 1 {
-        return bpm_points[0].bpm as f64;
-    }
+/// Background declared in the `[Events]` of the set's .osu files.
+///
+/// Fallback for maps whose decoded metadata has no (existing) background.
+fn find_osu_event_background(beatmapset_path: &Path, chart_files: &[PathBuf]) -> Option<String> {
+    chart_files
+        .iter()
+        .filter(|p| p.extension().and_then(|s| s.to_str()) == Some("osu"))
+        .filter_map(|p| fs::read_to_string(p).ok())
+        .filter_map(|content| osu_background_from_events(&content))
+        .find_map(|bg| find_background_image(beatmapset_path, Some(&bg)))
+}
 
-    // Calculate duration for each BPM segment
-    let mut bpm_durations: HashMap<u32, i64> = HashMap::new();
+/// Extracts the background filename from the `[Events]` section of an .osu
+/// file (`0,0,"bg.jpg",0,0`).
+fn osu_background_from_events(content: &str) -> Option<String> {
+    let mut in_events = false;
+    for line in content.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_events = line == "[Events]";
+            continue;
+        }
+        if !in_events || line.starts_with("//") {
+            continue;
+        }
 
-    for (i, tp) in bpm_points.iter().enumerate() {
-        let start_time = tp.time_us;
-        let end_time = if i + 1 < bpm_points.len() {
-            bpm_points[i + 1].time_us
-        } else {
-            chart_end_time_us
+        // eventType,startTime,filename,...
+        let Some((kind, rest)) = line.split_once(',') else {
+            continue;
+        };
+        if !matches!(kind.trim(), "0" | "Background") {
+            continue;
+        }
+        let Some((_, rest)) = rest.split_once(',') else {
+            continue;
+        };
+        let filename = match rest.trim().strip_prefix('"') {
+            Some(quoted) => quoted.split('"').next(),
+            None => rest.split(',').next(),
         };
+        if let Some(filename) = filename.map(str::trim).filter(|f| !f.is_empty()) {
+            // Maps made on Windows may use backslashes
+            return Some(filename.replace('\\', "/"));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &str = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../engine/fixtures/metadata_4k.osu"
+    ));
+
+    #[test]
+    fn test_background_from_osu_events() {
+        assert_eq!(
+            osu_background_from_events(FIXTURE).as_deref(),
+            Some("bg.jpg")
+        );
+
+        // Videos are skipped, unquoted names and backslashes are handled
+        let events = "[Events]\nVideo,0,\"intro.mp4\"\n0,0,bg\\cover.png,0,0\n";
+        assert_eq!(
+            osu_background_from_events(events).as_deref(),
+            Some("bg/cover.png")
+        );
+        assert_eq!(osu_background_from_events("[Events]\n//nothing\n"), None);
+    }
 
-        let duration = (end_time - start_time).max(0);
-        // Round BPM to integer for grouping (handles floating point variations)
-        let bpm_key = (tp.bpm * 10.0) as u32; // Keep 1 decimal precision
-        *bpm_durations.entry(bpm_key).or_insert(0) += duration;
+    #[test]
+    fn test_event_background_must_exist_in_map_folder() {
+        let dir = tempfile::tempdir().unwrap();
+        let folder = dir.path();
+        let chart = folder.join("map.osu");
+        fs::write(&chart, FIXTURE).unwrap();
+        let charts = vec![chart];
+
+        assert_eq!(find_osu_event_background(folder, &charts), None);
+
+        fs::write(folder.join("bg.jpg"), b"").unwrap();
+        assert_eq!(
+            find_osu_event_background(folder, &charts),
+            folder.join("bg.jpg").to_str().map(str::to_string)
+        );
     }
 
-    // Find the BPM with the longest total duration
-    bpm_durations
-        .into_iter()
-        .max_by_key(|(_, duration)| *duration)
-        .map(|(bpm_key, _)| bpm_key as f64 / 10.0)
-        .unwrap_or(0.0)
+    #[tokio::test]
+    async fn test_rescan_of_removed_folder_keeps_replays() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Database::new(&dir.path().join("test.db")).await.unwrap();
+        let folder = dir.path().join("songs").join("gone");
+        let folder_str = folder.to_str().unwrap();
+
+        let set_id = db
+            .insert_beatmapset(folder_str, None, None, None)
+            .await
+            .unwrap();
+        let info = chart::BeatmapBasicInfo::default();
+        insert_beatmap(db.pool(), set_id, "map", "map.osu", &info)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO replay (hash, beatmap_hash, timestamp, score, accuracy, max_combo, file_path) VALUES ('r', 'map', 0, 0, 95.0, 0, '')",
+        )
+        .execute(db.pool())
+        .await
+        .unwrap();
+
+        // The folder was moved away: the set goes, the score stays
+        rescan_beatmapset(&db, &folder).await.unwrap();
+        let beatmaps: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM beatmap")
+            .fetch_one(db.pool())
+            .await
+            .unwrap();
+        let replays: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM replay")
+            .fetch_one(db.pool())
+            .await
+            .unwrap();
+        assert_eq!((beatmaps, replays), (0, 1));
+        db.pool().close().await;
+    }
 }
@@ -1,3 +1,5 @@
+use engine::HitWindowMode;
+
 #[derive(Debug, Clone, PartialEq, Default)]
 pub enum RatingSource {
     #[default]
@@ -75,3 +77,18 @@ impl MenuSearchFilters {
             || self.max_duration_seconds.is_some()
     }
 }
+
+/// Filters applied to the per-beatmap leaderboard query.
+///
+/// The default shows every score.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct LeaderboardFilter {
+    /// Hide scores set with any mod active.
+    pub hide_modded: bool,
+    /// Only show scores played at this rate.
+    pub rate: Option<f64>,
+    /// Only show scores played with this hit window (mode and OD/judge value).
+    pub hit_window: Option<(HitWindowMode, f64)>,
+    /// Also show abandoned runs (never counted as scores otherwise).
+    pub include_incomplete: bool,
+}
@@ -0,0 +1,112 @@
+//! Songs folder watcher - keeps the library in sync while the game runs.
+//!
+//! Filesystem events are reduced to the beatmapset folders they touch
+//! (`songs/<set>/...` -> `songs/<set>`). A folder is rescanned once it has
+//! been quiet for [`WATCH_DEBOUNCE`], so copying a whole set triggers a
+//! single rescan.
+
+use notify::{Event, EventKind};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// How long a folder must stay untouched before it is rescanned.
+pub const WATCH_DEBOUNCE: Duration = Duration::from_millis(1500);
+
+/// Beatmapset folders waiting for a rescan.
+#[derive(Debug)]
+pub struct RescanQueue {
+    /// Songs directory, as the scanner sees it.
+    root: PathBuf,
+    /// Same directory resolved, as some watcher backends report it.
+    canonical_root: Option<PathBuf>,
+    /// Folder -> last event time.
+    pending: HashMap<PathBuf, Instant>,
+}
+
+impl RescanQueue {
+    pub fn new(root: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            canonical_root: root.canonicalize().ok(),
+            pending: HashMap::new(),
+        }
+    }
+
+    /// Records a filesystem event.
+    pub fn record(&mut self, event: &Event, now: Instant) {
+        if matches!(event.kind, EventKind::Access(_)) {
+            return;
+        }
+        for path in &event.paths {
+            if let Some(folder) = self.set_folder(path) {
+                self.pending.insert(folder, now);
+            }
+        }
+    }
+
+    /// Takes the folders quiet for [`WATCH_DEBOUNCE`], sorted.
+    pub fn take_ready(&mut self, now: Instant) -> Vec<PathBuf> {
+        let mut ready: Vec<PathBuf> = self
+            .pending
+            .iter()
+            .filter(|(_, last)| now.saturating_duration_since(**last) >= WATCH_DEBOUNCE)
+            .map(|(folder, _)| folder.clone())
+            .collect();
+        for folder in &ready {
+            self.pending.remove(folder);
+        }
+        ready.sort();
+        ready
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Beatmapset folder containing `path` (the first level under the root).
+    fn set_folder(&self, path: &Path) -> Option<PathBuf> {
+        let relative = path.strip_prefix(&self.root).ok().or_else(|| {
+            self.canonical_root
+                .as_ref()
+                .and_then(|root| path.strip_prefix(root).ok())
+        })?;
+        let set = relative.components().next()?;
+        Some(self.root.join(set))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{AccessKind, CreateKind};
+
+    #[test]
+    fn test_added_file_enqueues_only_its_set() {
+        let root = Path::new("songs");
+        let mut queue = RescanQueue::new(root);
+        let start = Instant::now();
+
+        let created = Event::new(EventKind::Create(CreateKind::File))
+            .add_path(root.join("New Set").join("map.osu"));
+        queue.record(&created, start);
+        let folder =
+            Event::new(EventKind::Create(CreateKind::Folder)).add_path(root.join("New Set"));
+        queue.record(&folder, start + Duration::from_millis(200));
+        // Reads and paths outside the songs directory are ignored
+        queue.record(
+            &Event::new(EventKind::Access(AccessKind::Any)).add_path(root.join("Old Set")),
+            start,
+        );
+        queue.record(
+            &Event::new(EventKind::Create(CreateKind::File)).add_path("elsewhere/map.osu".into()),
+            start,
+        );
+
+        // Debounced from the last event of the burst
+        assert!(queue.take_ready(start + WATCH_DEBOUNCE).is_empty());
+        let ready = queue.take_ready(start + Duration::from_millis(200) + WATCH_DEBOUNCE);
+        assert_eq!(ready, vec![root.join("New Set")]);
+        assert!(queue.is_empty());
+    }
+}
@@ -0,0 +1,158 @@
+//! Life bar - health drained by poor judgements and refilled by good ones.
+//!
+//! Health goes from 0.0 (empty) to [`MAX_HEALTH`]. The life bar is off by
+//! default; a [`HealthPreset`] selects how fast it drains.
+
+use crate::stats::Judgement;
+use serde::{Deserialize, Serialize};
+
+/// Health of a full life bar (and at the start of a run).
+pub const MAX_HEALTH: f32 = 1.0;
+
+/// Drain/gain preset of the life bar.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HealthPreset {
+    /// No life bar.
+    #[default]
+    Off,
+    /// Slow drain: 16 misses in a row empty the bar.
+    Easy,
+    /// 8 misses in a row empty the bar.
+    Normal,
+}
+
+impl HealthPreset {
+    /// Returns all available presets.
+    pub fn all() -> &'static [HealthPreset] {
+        &[HealthPreset::Off, HealthPreset::Easy, HealthPreset::Normal]
+    }
+
+    /// Returns a user-friendly display name.
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            HealthPreset::Off => "Off",
+            HealthPreset::Easy => "Easy",
+            HealthPreset::Normal => "Normal",
+        }
+    }
+
+    /// Health change per judgement, `None` when the life bar is off.
+    ///
+    /// The weights are powers of two so the bar stays exact over long runs.
+    pub fn weights(&self) -> Option<HealthWeights> {
+        match self {
+            HealthPreset::Off => None,
+            HealthPreset::Easy => Some(HealthWeights {
+                marv: 1.0 / 128.0,
+                perfect: 1.0 / 128.0,
+                great: 1.0 / 256.0,
+                good: 0.0,
+                bad: -1.0 / 32.0,
+                miss: -1.0 / 16.0,
+            }),
+            HealthPreset::Normal => Some(HealthWeights {
+                marv: 1.0 / 128.0,
+                perfect: 1.0 / 128.0,
+                great: 1.0 / 256.0,
+                good: -1.0 / 64.0,
+                bad: -1.0 / 16.0,
+                miss: -1.0 / 8.0,
+            }),
+        }
+    }
+}
+
+/// Health change per judgement, as a fraction of a full bar.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HealthWeights {
+    pub marv: f32,
+    pub perfect: f32,
+    pub great: f32,
+    pub good: f32,
+    pub bad: f32,
+    pub miss: f32,
+}
+
+impl HealthWeights {
+    /// Health change for `judgement` (ghost taps leave the bar alone).
+    pub fn delta(&self, judgement: Judgement) -> f32 {
+        match judgement {
+            Judgement::Marv => self.marv,
+            Judgement::Perfect => self.perfect,
+            Judgement::Great => self.great,
+            Judgement::Good => self.good,
+            Judgement::Bad => self.bad,
+            Judgement::Miss => self.miss,
+            Judgement::GhostTap => 0.0,
+        }
+    }
+}
+
+/// Health of a run, starting full.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LifeBar {
+    health: f32,
+    weights: HealthWeights,
+}
+
+impl LifeBar {
+    pub fn new(weights: HealthWeights) -> Self {
+        Self {
+            health: MAX_HEALTH,
+            weights,
+        }
+    }
+
+    /// Life bar of `preset`, `None` when it is off.
+    pub fn from_preset(preset: HealthPreset) -> Option<Self> {
+        preset.weights().map(Self::new)
+    }
+
+    /// Adjusts the health for a judgement, within `[0, MAX_HEALTH]`.
+    pub fn apply(&mut self, judgement: Judgement) {
+        self.health = (self.health + self.weights.delta(judgement)).clamp(0.0, MAX_HEALTH);
+    }
+
+    pub fn health(&self) -> f32 {
+        self.health
+    }
+
+    /// Whether the bar was drained.
+    pub fn is_empty(&self) -> bool {
+        self.health <= 0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_misses_drain_health_at_preset_rate() {
+        let mut life = LifeBar::from_preset(HealthPreset::Normal).unwrap();
+        for miss in 1..=8 {
+            life.apply(Judgement::Miss);
+            assert_eq!(life.health(), MAX_HEALTH - miss as f32 / 8.0);
+        }
+        assert!(life.is_empty());
+        // Stays at zero
+        life.apply(Judgement::Miss);
+        assert_eq!(life.health(), 0.0);
+
+        // Easy drains half as fast
+        let mut life = LifeBar::from_preset(HealthPreset::Easy).unwrap();
+        for _ in 0..8 {
+            life.apply(Judgement::Miss);
+        }
+        assert_eq!(life.health(), MAX_HEALTH / 2.0);
+        assert!(!life.is_empty());
+
+        // Hits refill it, never above full
+        for _ in 0..1000 {
+            life.apply(Judgement::Marv);
+        }
+        assert_eq!(life.health(), MAX_HEALTH);
+
+        assert_eq!(LifeBar::from_preset(HealthPreset::Off), None);
+    }
+}
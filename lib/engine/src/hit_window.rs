@@ -3,13 +3,14 @@
 //! All thresholds are stored in **microseconds (i64)** for consistency
 //! with the rest of the timing system.
 
+use crate::note::US_PER_MS;
 use crate::stats::Judgement;
 
-/// Microseconds per millisecond.
-pub const US_PER_MS: i64 = 1000;
-
 /// Hit window timing thresholds in microseconds.
+///
+/// Built through its constructors only, so new thresholds can be added.
 #[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
 pub struct HitWindow {
     pub marv_us: i64,
     pub perfect_us: i64,
@@ -90,6 +91,16 @@ impl HitWindow {
         }
     }
 
+    /// Creates a HitWindow from thresholds in milliseconds, for judging
+    /// systems without a dedicated constructor (Quaver, O2Jam...).
+    ///
+    /// Order: `[marv, perfect, great, good, bad, miss]`.
+    pub fn from_windows_ms(windows_ms: [f64; 6]) -> Self {
+        let [marv, perf, great, good, bad, miss] =
+            windows_ms.map(|ms| (ms * US_PER_MS as f64).round() as i64);
+        Self::from_custom_us(marv, perf, great, good, bad, miss)
+    }
+
     /// Returns the miss threshold (already in µs).
     #[inline]
     pub fn miss_threshold(&self) -> i64 {
@@ -124,7 +135,7 @@ impl HitWindow {
 
     /// Judges a timing difference in milliseconds (for compatibility).
     pub fn judge_ms(&self, timing_diff_ms: f64) -> (Judgement, bool) {
-        self.judge((timing_diff_ms * US_PER_MS as f64) as i64)
+        self.judge((timing_diff_ms * US_PER_MS as f64).round() as i64)
     }
 
     /// Finds the best matching note for a hit input.
@@ -152,8 +163,8 @@ impl HitWindow {
                 break;
             }
 
-            // Skip if wrong column or already hit
-            if note.column() != input_column || note.is_hit() {
+            // Skip if wrong column, already hit, or a mine (mines are never hit by presses)
+            if note.column() != input_column || note.is_hit() || note.is_mine() {
                 continue;
             }
 
@@ -173,4 +184,30 @@ pub trait NoteAccessor {
     fn time_us(&self) -> i64;
     fn column(&self) -> usize;
     fn is_hit(&self) -> bool;
+
+    /// Whether this note is a mine. Mines are skipped by hit matching.
+    fn is_mine(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_custom_windows_from_ms() {
+        let window = HitWindow::from_windows_ms([18.0, 43.0, 76.0, 106.0, 127.0, 164.0]);
+        assert_eq!(window.marv_us, 18_000);
+        assert_eq!(window.miss_us, 164_000);
+
+        assert_eq!(window.judge_ms(0.0), (Judgement::Marv, true));
+        assert_eq!(window.judge_ms(-18.0), (Judgement::Marv, true));
+        assert_eq!(window.judge_ms(30.0), (Judgement::Perfect, true));
+        assert_eq!(window.judge_ms(-60.0), (Judgement::Great, true));
+        assert_eq!(window.judge_ms(100.0), (Judgement::Good, true));
+        assert_eq!(window.judge_ms(120.0), (Judgement::Bad, true));
+        assert_eq!(window.judge_ms(-150.0), (Judgement::Miss, true));
+        assert_eq!(window.judge_ms(165.0), (Judgement::GhostTap, false));
+    }
 }
@@ -23,6 +23,30 @@ pub enum HitWindowMode {
     OsuOD,
     /// Etterna/Quaver judge level based timing.
     EtternaJudge,
+    /// Windows given directly in milliseconds.
+    Custom,
+}
+
+impl HitWindowMode {
+    /// Stable identifier used for storage (e.g. in the database).
+    pub fn id(&self) -> &'static str {
+        match self {
+            HitWindowMode::OsuOD => "osu_od",
+            HitWindowMode::EtternaJudge => "etterna_judge",
+            HitWindowMode::Custom => "custom",
+        }
+    }
+
+    /// Parses a mode from its [`id`](Self::id).
+    pub fn from_id(id: &str) -> Option<HitWindowMode> {
+        [
+            HitWindowMode::OsuOD,
+            HitWindowMode::EtternaJudge,
+            HitWindowMode::Custom,
+        ]
+        .into_iter()
+        .find(|mode| mode.id() == id)
+    }
 }
 
 impl Default for HitWindowMode {
@@ -3,4 +3,6 @@
 pub struct InstanceRaw {
     pub offset: [f32; 2],
     pub scale: [f32; 2],
+    /// Opacity multiplier applied to the sampled texture (1.0 = fully visible).
+    pub alpha: f32,
 }
@@ -0,0 +1,109 @@
+//! Keysounds - audio samples attached to individual notes.
+//!
+//! ROX does not carry hit samples, so they are read back from the source
+//! file. In osu!mania the sample file name is the last field of a hit object
+//! (`normalSet:additionSet:index:volume:filename`, prefixed with `endTime:`
+//! on holds). Samples are matched to the decoded notes by time and column.
+
+use crate::note::{NoteData, US_PER_MS};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Width of the osu! playfield, which columns are spread across.
+const OSU_PLAYFIELD_WIDTH: f64 = 512.0;
+
+/// Attaches the keysounds of the chart file at `path` to its notes.
+///
+/// Formats without keysound support leave the notes untouched.
+pub fn attach_keysounds(path: &Path, notes: &mut [NoteData]) {
+    let is_osu = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("osu"));
+    if !is_osu {
+        return;
+    }
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return;
+    };
+    let samples = osu_keysounds(&content);
+    if samples.is_empty() {
+        return;
+    }
+    for note in notes {
+        if let Some(sample) = samples.get(&(note.time_us(), note.column())) {
+            note.sample = Some(sample.clone());
+        }
+    }
+}
+
+/// Keysound file of each hit object of an osu!mania file,
+/// keyed by (time in µs, column).
+pub fn osu_keysounds(content: &str) -> HashMap<(i64, usize), Arc<str>> {
+    let mut samples = HashMap::new();
+    let mut key_count = 0.0;
+    let mut section = "";
+    for line in content.lines().map(str::trim) {
+        if line.starts_with('[') && line.ends_with(']') {
+            section = line;
+            continue;
+        }
+        match section {
+            "[Difficulty]" => {
+                if let Some(value) = line.strip_prefix("CircleSize:") {
+                    key_count = value.trim().parse().unwrap_or(0.0);
+                }
+            }
+            "[HitObjects]" => {
+                if let Some((time_us, column, sample)) = parse_hit_object(line, key_count) {
+                    samples.insert((time_us, column), Arc::from(sample));
+                }
+            }
+            _ => {}
+        }
+    }
+    samples
+}
+
+/// (time in µs, column, sample file) of a hit object with a keysound.
+fn parse_hit_object(line: &str, key_count: f64) -> Option<(i64, usize, &str)> {
+    if key_count < 1.0 {
+        return None;
+    }
+    let fields: Vec<&str> = line.split(',').collect();
+    let hit_sample = fields.get(5)?;
+    let sample = hit_sample.rsplit(':').next()?.trim();
+    if sample.is_empty() {
+        return None;
+    }
+    let x: f64 = fields[0].trim().parse().ok()?;
+    let time_ms: f64 = fields[2].trim().parse().ok()?;
+    let column = (x * key_count / OSU_PLAYFIELD_WIDTH)
+        .floor()
+        .clamp(0.0, key_count - 1.0) as usize;
+    Some(((time_ms * US_PER_MS as f64).round() as i64, column, sample))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::note::load_map_full;
+
+    #[test]
+    fn test_note_samples_parsed_from_fixture() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/keysounds_4k.osu");
+        let map = load_map_full(&path).unwrap();
+        let samples: Vec<Option<&str>> = map.notes.iter().map(|n| n.sample.as_deref()).collect();
+
+        // Taps, holds (after the end time) and notes without a keysound
+        assert_eq!(
+            samples,
+            vec![
+                Some("kick.wav"),
+                Some("snare.ogg"),
+                Some("piano C4.wav"),
+                None
+            ]
+        );
+    }
+}
@@ -2,24 +2,70 @@
 //!
 //! This crate provides the fundamental data structures and logic
 //! for note timing, hit windows, judgements, and scoring.
+//!
+//! # Public API
+//!
+//! Tools outside the game (replay simulation, tests) should only rely on the
+//! items re-exported here:
+//!
+//! - [`HitWindow`] - Timing thresholds, [`HitWindow::judge`] and
+//!   [`HitWindow::find_best_note`] (the hit matching shared with replays)
+//! - [`NoteAccessor`] - What hit matching needs to know about a note
+//! - [`NoteData`] - A chart note and its gameplay state
+//! - [`Judgement`] and [`HitStats`] - Judgements and their counts
+//! - [`US_PER_MS`] and [`US_PER_SECOND`] - All times are in microseconds
+//!
+//! # Quick Start
+//!
+//! ```rust
+//! use engine::{HitStats, HitWindow, Judgement, US_PER_MS};
+//!
+//! // Etterna judge 4: 22.5 ms marvelous window
+//! let window = HitWindow::from_etterna_judge(4);
+//!
+//! // A press 12 ms late (times are note - input, in µs)
+//! let (judgement, hit) = window.judge(-12 * US_PER_MS);
+//! assert_eq!(judgement, Judgement::Marv);
+//! assert!(hit);
+//!
+//! // Past the miss window, a press is a ghost tap
+//! let (judgement, hit) = window.judge(window.miss_threshold() + 1);
+//! assert_eq!(judgement, Judgement::GhostTap);
+//! assert!(!hit);
+//!
+//! let mut stats = HitStats::new();
+//! stats.marv += 1;
+//! assert_eq!(stats.calculate_accuracy(), 100.0);
+//! ```
 
 pub mod constants;
+pub mod health;
 pub mod hit_window;
 pub mod hit_window_mode;
 pub mod instance;
+pub mod keysound;
+pub mod mods;
 pub mod note;
+pub mod palette;
 pub mod pixel_system;
 pub mod playfield;
+pub mod scoring;
 pub mod stats;
 
 pub use constants::*;
+pub use health::{HealthPreset, HealthWeights, LifeBar, MAX_HEALTH};
 pub use hit_window::{HitWindow, NoteAccessor};
 pub use hit_window_mode::HitWindowMode;
 pub use instance::InstanceRaw;
+pub use keysound::{attach_keysounds, osu_keysounds};
+pub use mods::{GameMod, apply_mods};
 pub use note::{
-    NoteData, NoteType, RoxChart, US_PER_MS, US_PER_SECOND, audio_path_from_chart, load_chart,
-    load_chart_safe, load_map, load_map_safe, ms_to_us, notes_from_chart, us_to_ms,
+    ChartMetadata, LoadedMap, NoteData, NoteType, RoxChart, US_PER_MS, US_PER_SECOND,
+    audio_path_from_chart, dominant_bpm, load_chart, load_chart_safe, load_map, load_map_full,
+    load_map_safe, ms_to_us, notes_from_chart, seconds_to_us, us_to_ms,
 };
-pub use pixel_system::PixelSystem;
+pub use palette::Palette;
+pub use pixel_system::{PixelSystem, Viewport};
 pub use playfield::PlayfieldConfig;
+pub use scoring::{ComboBreak, NORMALIZED_MAX_SCORE, ScoringModel};
 pub use stats::{HitStats, Judgement, JudgementColors};
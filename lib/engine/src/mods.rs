@@ -0,0 +1,157 @@
+//! Gameplay modifiers shared by the game, replays and the database.
+//!
+//! Only chart-altering mods are applied here; visual mods are handled by
+//! the renderer and never affect scoring.
+
+use crate::note::NoteData;
+use serde::{Deserialize, Serialize};
+
+/// Available gameplay modifiers.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    rkyv::Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+#[rkyv(compare(PartialEq), derive(Debug))]
+pub enum GameMod {
+    /// Converts LN and burst to tap notes, removes mines.
+    NoSpecial,
+    /// Notes fade out as they approach the receptors.
+    Hidden,
+    /// Notes only appear once they are close to the receptors (a.k.a. Sudden).
+    FadeIn,
+    /// Screen hidden except for a thin horizontal strip.
+    Flashlight,
+    /// Notes visually rotate on themselves.
+    Spinner,
+    /// The chart plays itself perfectly; scores are not saved.
+    Autoplay,
+    /// The run fails on the first miss.
+    SuddenDeath,
+}
+
+impl GameMod {
+    /// Returns a user-friendly display name for the mod.
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            GameMod::NoSpecial => "NO SPECIAL",
+            GameMod::Hidden => "HIDDEN",
+            GameMod::FadeIn => "FADE IN",
+            GameMod::Flashlight => "FLASHLIGHT",
+            GameMod::Spinner => "SPINNER",
+            GameMod::Autoplay => "AUTOPLAY",
+            GameMod::SuddenDeath => "SUDDEN DEATH",
+        }
+    }
+
+    /// Returns a short description of what the mod does.
+    pub fn description(&self) -> &'static str {
+        match self {
+            GameMod::NoSpecial => "Replaces LN/burst with taps, removes mines",
+            GameMod::Hidden => "Notes fade out before reaching the receptors",
+            GameMod::FadeIn => "Notes only appear close to the receptors",
+            GameMod::Flashlight => "Only a thin strip is visible",
+            GameMod::Spinner => "Notes rotate visually",
+            GameMod::Autoplay => "Watch the chart played perfectly (not saved)",
+            GameMod::SuddenDeath => "The run fails on the first miss",
+        }
+    }
+
+    /// Stable identifier used for storage (e.g. in the database).
+    pub fn id(&self) -> &'static str {
+        match self {
+            GameMod::NoSpecial => "no_special",
+            GameMod::Hidden => "hidden",
+            GameMod::FadeIn => "fade_in",
+            GameMod::Flashlight => "flashlight",
+            GameMod::Spinner => "spinner",
+            GameMod::Autoplay => "autoplay",
+            GameMod::SuddenDeath => "sudden_death",
+        }
+    }
+
+    /// Whether a play with this mod may be saved as a score.
+    pub fn is_submittable(&self) -> bool {
+        !matches!(self, GameMod::Autoplay)
+    }
+
+    /// Parses a mod from its [`id`](Self::id).
+    pub fn from_id(id: &str) -> Option<GameMod> {
+        Self::all().iter().copied().find(|m| m.id() == id)
+    }
+
+    /// Returns all available mods.
+    pub fn all() -> &'static [GameMod] {
+        &[
+            GameMod::NoSpecial,
+            GameMod::Hidden,
+            GameMod::FadeIn,
+            GameMod::Flashlight,
+            GameMod::Spinner,
+            GameMod::Autoplay,
+            GameMod::SuddenDeath,
+        ]
+    }
+}
+
+/// Applies the chart-altering mods to a chart.
+///
+/// Returns a fresh copy; applying the same mods twice is a no-op.
+pub fn apply_mods(chart: &[NoteData], mods: &[GameMod]) -> Vec<NoteData> {
+    if !mods.contains(&GameMod::NoSpecial) {
+        return chart.to_vec();
+    }
+
+    chart
+        .iter()
+        .filter(|note| !note.is_mine())
+        .map(|note| {
+            if note.is_tap() {
+                note.clone()
+            } else {
+                NoteData::tap(note.time_us(), note.column() as u8).with_sample(note.sample.clone())
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_special_converts_to_taps() {
+        let chart = vec![
+            NoteData::tap(1_000, 0),
+            NoteData::hold(2_000, 1, 500_000),
+            NoteData::mine(3_000, 2),
+            NoteData::burst(4_000, 3, 500_000),
+        ];
+
+        let modded = apply_mods(&chart, &[GameMod::NoSpecial]);
+        assert_eq!(modded.len(), 3);
+        assert!(modded.iter().all(NoteData::is_tap));
+        let placement: Vec<(i64, usize)> =
+            modded.iter().map(|n| (n.time_us(), n.column())).collect();
+        assert_eq!(placement, vec![(1_000, 0), (2_000, 1), (4_000, 3)]);
+
+        // Visual mods leave the chart untouched
+        assert_eq!(apply_mods(&chart, &[GameMod::Hidden]).len(), 4);
+    }
+
+    #[test]
+    fn test_mod_ids_roundtrip() {
+        for game_mod in GameMod::all() {
+            assert_eq!(GameMod::from_id(game_mod.id()), Some(*game_mod));
+        }
+        assert_eq!(GameMod::from_id("unknown"), None);
+    }
+}
@@ -14,8 +14,10 @@
 //! - `NoteData` - Wrapper that adds gameplay state (hit, is_held, current_hits)
 
 use rhythm_open_exchange::codec::auto_decode;
-use rhythm_open_exchange::{Note as RoxNote, NoteType as RoxNoteType};
+use rhythm_open_exchange::{Note as RoxNote, NoteType as RoxNoteType, TimingPoint};
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::Arc;
 
 // Re-export ROX types for external use
 pub use rhythm_open_exchange::NoteType;
@@ -73,6 +75,9 @@ pub struct NoteData {
     inner: RoxNote,
     /// Mutable gameplay state.
     pub state: NoteState,
+    /// Keysound file played on hit, relative to the chart folder.
+    /// Shared so that snapshot copies stay cheap.
+    pub sample: Option<Arc<str>>,
 }
 
 impl NoteData {
@@ -97,9 +102,16 @@ impl NoteData {
                     required_hits,
                 },
             },
+            sample: None,
         }
     }
 
+    /// Attaches a keysound to the note.
+    pub fn with_sample(mut self, sample: Option<Arc<str>>) -> Self {
+        self.sample = sample;
+        self
+    }
+
     // ========== Convenience constructors ==========
 
     /// Create a tap note at the given time and column.
@@ -279,25 +291,117 @@ pub fn audio_path_from_chart(chart_path: &std::path::Path, chart: &RoxChart) ->
         .map(|p| p.join(&chart.metadata.audio_file))
 }
 
+/// Display metadata of a chart.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ChartMetadata {
+    pub title: String,
+    pub artist: String,
+    pub creator: String,
+    pub difficulty_name: String,
+    /// Dominant BPM (see [`dominant_bpm`]), 0 if the chart has no timing point.
+    pub bpm: f64,
+    /// Start of the song preview (in µs).
+    pub preview_time_us: i64,
+    /// Background image file name, relative to the chart folder.
+    pub background_file: Option<String>,
+}
+
+impl ChartMetadata {
+    /// Extracts the metadata of a decoded chart.
+    pub fn from_chart(chart: &RoxChart) -> Self {
+        let end_time_us = chart
+            .notes
+            .iter()
+            .map(|n| n.end_time_us())
+            .max()
+            .unwrap_or(0);
+        Self {
+            title: chart.metadata.title.clone(),
+            artist: chart.metadata.artist.clone(),
+            creator: chart.metadata.creator.clone(),
+            difficulty_name: chart.metadata.difficulty_name.clone(),
+            bpm: dominant_bpm(&chart.timing_points, end_time_us),
+            preview_time_us: chart.metadata.preview_time_us,
+            background_file: chart.metadata.background_file.clone(),
+        }
+    }
+}
+
+/// A chart loaded for play, with its metadata.
+#[derive(Clone, Debug)]
+pub struct LoadedMap {
+    pub audio_path: PathBuf,
+    pub notes: Vec<NoteData>,
+    pub key_count: usize,
+    pub metadata: ChartMetadata,
+}
+
+/// Loads a map with its metadata in a single parse.
+pub fn load_map_full(path: &std::path::Path) -> Result<LoadedMap, String> {
+    let chart = load_chart(path)?;
+    let audio_path = audio_path_from_chart(path, &chart)
+        .ok_or_else(|| format!("Invalid path (no parent): {:?}", path))?;
+    let mut notes = notes_from_chart(&chart);
+    crate::keysound::attach_keysounds(path, &mut notes);
+    Ok(LoadedMap {
+        audio_path,
+        notes,
+        key_count: chart.key_count as usize,
+        metadata: ChartMetadata::from_chart(&chart),
+    })
+}
+
 /// Legacy function for backwards compatibility.
 /// Loads a map and returns (audio_path, notes, key_count).
 pub fn load_map(path: PathBuf) -> Result<(PathBuf, Vec<NoteData>, usize), String> {
-    let chart = load_chart(&path)?;
-    let audio_path = audio_path_from_chart(&path, &chart)
-        .ok_or_else(|| format!("Invalid path (no parent): {:?}", path))?;
-    let key_count = chart.key_count as usize;
-    let notes = notes_from_chart(&chart);
-    Ok((audio_path, notes, key_count))
+    let map = load_map_full(&path)?;
+    Ok((map.audio_path, map.notes, map.key_count))
 }
 
 /// Legacy function for backwards compatibility.
 /// Safe version that returns Option with (audio_path, notes, key_count).
 pub fn load_map_safe(path: &PathBuf) -> Option<(PathBuf, Vec<NoteData>, usize)> {
-    let chart = load_chart_safe(path)?;
-    let audio_path = audio_path_from_chart(path, &chart)?;
-    let key_count = chart.key_count as usize;
-    let notes = notes_from_chart(&chart);
-    Some((audio_path, notes, key_count))
+    load_map(path.clone()).ok()
+}
+
+/// Calculates the dominant BPM (the one that lasts the longest).
+/// Only considers timing points where is_inherited is false (actual BPM changes, not SV).
+pub fn dominant_bpm(timing_points: &[TimingPoint], chart_end_time_us: i64) -> f64 {
+    // Filter to only BPM timing points (not SV changes)
+    let bpm_points: Vec<_> = timing_points.iter().filter(|tp| !tp.is_inherited).collect();
+
+    if bpm_points.is_empty() {
+        return 0.0;
+    }
+
+    // If only one BPM point, return it
+    if bpm_points.len() == 1 {
+        return bpm_points[0].bpm as f64;
+    }
+
+    // Calculate duration for each BPM segment
+    let mut bpm_durations: HashMap<u32, i64> = HashMap::new();
+
+    for (i, tp) in bpm_points.iter().enumerate() {
+        let start_time = tp.time_us;
+        let end_time = if i + 1 < bpm_points.len() {
+            bpm_points[i + 1].time_us
+        } else {
+            chart_end_time_us
+        };
+
+        let duration = (end_time - start_time).max(0);
+        // Round BPM to integer for grouping (handles floating point variations)
+        let bpm_key = (tp.bpm * 10.0) as u32; // Keep 1 decimal precision
+        *bpm_durations.entry(bpm_key).or_insert(0) += duration;
+    }
+
+    // Find the BPM with the longest total duration
+    bpm_durations
+        .into_iter()
+        .max_by_key(|(_, duration)| *duration)
+        .map(|(bpm_key, _)| bpm_key as f64 / 10.0)
+        .unwrap_or(0.0)
 }
 
 // ========== Conversion helpers ==========
@@ -308,8 +412,48 @@ pub fn us_to_ms(us: i64) -> f64 {
     us as f64 / US_PER_MS as f64
 }
 
-/// Convert milliseconds to microseconds.
+/// Convert milliseconds to microseconds, rounded to the nearest µs.
+///
+/// Rounding (rather than truncating) keeps values like `0.3` ms from
+/// becoming 299 µs through float error.
 #[inline]
 pub fn ms_to_us(ms: f64) -> i64 {
-    (ms * US_PER_MS as f64) as i64
+    (ms * US_PER_MS as f64).round() as i64
+}
+
+/// Convert seconds (audio clock, frame delta) to microseconds.
+#[inline]
+pub fn seconds_to_us(seconds: f64) -> i64 {
+    (seconds * US_PER_SECOND as f64).round() as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn test_load_map_full_metadata() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/metadata_4k.osu");
+        let map = load_map_full(&path).unwrap();
+
+        assert_eq!(map.key_count, 4);
+        assert_eq!(map.notes.len(), 4);
+        assert_eq!(map.audio_path, path.parent().unwrap().join("audio.mp3"));
+
+        let metadata = &map.metadata;
+        assert_eq!(metadata.title, "Fixture Song");
+        assert_eq!(metadata.artist, "Fixture Artist");
+        assert_eq!(metadata.creator, "Fixture Mapper");
+        assert_eq!(metadata.difficulty_name, "Normal");
+        assert_eq!(metadata.bpm, 120.0);
+        assert_eq!(metadata.preview_time_us, 1_500_000);
+        assert_eq!(metadata.background_file.as_deref(), Some("bg.jpg"));
+
+        // The legacy loader returns the same chart
+        let (audio_path, notes, key_count) = load_map(path).unwrap();
+        assert_eq!(audio_path, map.audio_path);
+        assert_eq!(notes.len(), map.notes.len());
+        assert_eq!(key_count, map.key_count);
+    }
 }
@@ -0,0 +1,130 @@
+//! Colorblind-friendly palettes for judgements and difficulty ratings.
+//!
+//! The palettes are fixed, built from the Okabe-Ito colors, which stay
+//! distinct under red-green color blindness. Marv and great also differ in
+//! brightness, so they remain readable without hue.
+
+use crate::stats::JudgementColors;
+use serde::{Deserialize, Serialize};
+
+// Okabe-Ito colors (RGB / 255)
+const ORANGE: [f32; 4] = [0.902, 0.624, 0.0, 1.0];
+const SKY_BLUE: [f32; 4] = [0.337, 0.706, 0.914, 1.0];
+const BLUISH_GREEN: [f32; 4] = [0.0, 0.620, 0.451, 1.0];
+const YELLOW: [f32; 4] = [0.941, 0.894, 0.259, 1.0];
+const BLUE: [f32; 4] = [0.0, 0.447, 0.698, 1.0];
+const VERMILLION: [f32; 4] = [0.835, 0.369, 0.0, 1.0];
+const REDDISH_PURPLE: [f32; 4] = [0.800, 0.475, 0.655, 1.0];
+
+const WHITE: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+const GRAY: [f32; 4] = [0.5, 0.5, 0.5, 1.0];
+
+/// Color palette of judgements and ratings.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Palette {
+    /// Colors of the skin.
+    #[default]
+    Skin,
+    /// For green-weak vision (the most common color blindness).
+    Deuteranopia,
+    /// For red-weak vision: misses are not drawn in red.
+    Protanopia,
+}
+
+impl Palette {
+    /// Returns all available palettes.
+    pub fn all() -> &'static [Palette] {
+        &[Palette::Skin, Palette::Deuteranopia, Palette::Protanopia]
+    }
+
+    /// Returns a user-friendly display name.
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Palette::Skin => "Skin colors",
+            Palette::Deuteranopia => "Deuteranopia",
+            Palette::Protanopia => "Protanopia",
+        }
+    }
+
+    /// Colors of the five rating tiers, easiest first (`None` keeps the
+    /// skin's ramp).
+    pub fn rating_ramp(&self) -> Option<[[f32; 4]; 5]> {
+        match self {
+            Palette::Skin => None,
+            Palette::Deuteranopia | Palette::Protanopia => {
+                Some([SKY_BLUE, BLUE, YELLOW, ORANGE, REDDISH_PURPLE])
+            }
+        }
+    }
+}
+
+impl JudgementColors {
+    /// Judgement colors of a palette (`None` keeps the skin's colors).
+    ///
+    /// | Judgement | Deuteranopia   | Protanopia     |
+    /// |-----------|----------------|----------------|
+    /// | Marv      | white          | white          |
+    /// | Perfect   | yellow         | yellow         |
+    /// | Great     | sky blue       | sky blue       |
+    /// | Good      | bluish green   | blue           |
+    /// | Bad       | reddish purple | reddish purple |
+    /// | Miss      | vermillion     | orange         |
+    pub fn palette(palette: Palette) -> Option<Self> {
+        match palette {
+            Palette::Skin => None,
+            Palette::Deuteranopia => Some(Self {
+                marv: WHITE,
+                perfect: YELLOW,
+                great: SKY_BLUE,
+                good: BLUISH_GREEN,
+                bad: REDDISH_PURPLE,
+                miss: VERMILLION,
+                ghost_tap: GRAY,
+            }),
+            // Reds look dark to protanopes: the miss color is the brighter orange
+            Palette::Protanopia => Some(Self {
+                marv: WHITE,
+                perfect: YELLOW,
+                great: SKY_BLUE,
+                good: BLUE,
+                bad: REDDISH_PURPLE,
+                miss: ORANGE,
+                ghost_tap: GRAY,
+            }),
+        }
+    }
+
+    /// These colors, or the palette's if it overrides them.
+    pub fn with_palette(self, palette: Palette) -> Self {
+        Self::palette(palette).unwrap_or(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Relative luminance (Rec. 709 weights).
+    fn luminance(color: [f32; 4]) -> f32 {
+        0.2126 * color[0] + 0.7152 * color[1] + 0.0722 * color[2]
+    }
+
+    #[test]
+    fn test_palette_marv_and_great_are_distinct() {
+        for &palette in &[Palette::Deuteranopia, Palette::Protanopia] {
+            let colors = JudgementColors::palette(palette).unwrap();
+            // Documented colors
+            assert_eq!(colors.marv, WHITE);
+            assert_eq!(colors.great, SKY_BLUE);
+            assert_ne!(colors.marv, colors.great);
+            // Told apart by brightness too, not only by hue
+            assert!((luminance(colors.marv) - luminance(colors.great)).abs() >= 0.2);
+            // Misses never use a pure red
+            assert_ne!(colors.miss, [1.0, 0.0, 0.0, 1.0]);
+        }
+
+        assert!(JudgementColors::palette(Palette::Skin).is_none());
+        let skin = JudgementColors::new();
+        assert_eq!(skin.clone().with_palette(Palette::Skin).marv, skin.marv);
+    }
+}
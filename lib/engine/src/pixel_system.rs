@@ -45,3 +45,82 @@ impl PixelSystem {
         self.aspect_ratio = forced_ratio.unwrap_or(width as f32 / height as f32);
     }
 }
+
+/// Sub-rectangle of the render target, in pixels.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Viewport {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Viewport {
+    /// Viewport covering the whole target.
+    pub fn full(width: u32, height: u32) -> Self {
+        Self {
+            x: 0.0,
+            y: 0.0,
+            width: width as f32,
+            height: height as f32,
+        }
+    }
+
+    /// Largest centered viewport with the given aspect ratio (width / height).
+    ///
+    /// The remaining space forms black bars on the sides (pillarbox) or
+    /// top and bottom (letterbox). `None` fills the whole target.
+    pub fn letterbox(width: u32, height: u32, ratio: Option<f32>) -> Self {
+        let full = Self::full(width, height);
+        let Some(ratio) = ratio else {
+            return full;
+        };
+        if width == 0 || height == 0 || ratio <= 0.0 {
+            return full;
+        }
+
+        if full.width / full.height > ratio {
+            let inner_width = (full.height * ratio).round();
+            Self {
+                x: ((full.width - inner_width) / 2.0).floor(),
+                width: inner_width,
+                ..full
+            }
+        } else {
+            let inner_height = (full.width / ratio).round();
+            Self {
+                y: ((full.height - inner_height) / 2.0).floor(),
+                height: inner_height,
+                ..full
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_letterbox_4_3_in_16_9_window() {
+        let viewport = Viewport::letterbox(1920, 1080, Some(4.0 / 3.0));
+        assert_eq!(
+            viewport,
+            Viewport {
+                x: 240.0,
+                y: 0.0,
+                width: 1440.0,
+                height: 1080.0,
+            }
+        );
+
+        // A taller window gets bars at the top and bottom instead
+        let viewport = Viewport::letterbox(1280, 1024, Some(16.0 / 9.0));
+        assert_eq!((viewport.y, viewport.height), (152.0, 720.0));
+
+        assert_eq!(
+            Viewport::letterbox(1920, 1080, None),
+            Viewport::full(1920, 1080)
+        );
+    }
+}
@@ -1,9 +1,19 @@
 //! Playfield configuration and layout.
 
+/// Size change of one note size step, in pixels.
+const SIZE_STEP_PIXELS: f32 = 5.0;
+/// Bounds of the note size, in pixels.
+const MIN_SIZE_PIXELS: f32 = 10.0;
+const MAX_SIZE_PIXELS: f32 = 200.0;
+
 /// Configuration for the playfield layout.
 #[derive(Clone)]
 pub struct PlayfieldConfig {
     pub column_width_pixels: f32,
+    /// Per-column widths; missing entries fall back to `column_width_pixels`.
+    pub column_widths: Vec<f32>,
+    /// Gap after each column; missing entries fall back to `receptor_spacing_pixels`.
+    pub column_gaps: Vec<f32>,
     pub note_width_pixels: f32,
     pub note_height_pixels: f32,
     pub receptor_width_pixels: f32,
@@ -11,12 +21,16 @@ pub struct PlayfieldConfig {
     pub receptor_spacing_pixels: f32,
     pub x_offset_pixels: f32,
     pub y_offset_pixels: f32,
+    /// Extra height of the hit line (receptors and note target), in pixels.
+    pub hit_line_offset_pixels: f32,
 }
 
 impl PlayfieldConfig {
     pub fn new() -> Self {
         Self {
             column_width_pixels: 100.0,
+            column_widths: Vec::new(),
+            column_gaps: Vec::new(),
             note_width_pixels: 90.0,
             note_height_pixels: 90.0,
             receptor_width_pixels: 90.0,
@@ -24,16 +38,80 @@ impl PlayfieldConfig {
             receptor_spacing_pixels: 0.0,
             x_offset_pixels: 0.0,
             y_offset_pixels: 0.0,
+            hit_line_offset_pixels: 0.0,
+        }
+    }
+
+    /// Width of a column in pixels.
+    pub fn column_width(&self, column: usize) -> f32 {
+        self.column_widths
+            .get(column)
+            .copied()
+            .unwrap_or(self.column_width_pixels)
+    }
+
+    /// Gap between a column and the next one, in pixels.
+    pub fn column_gap(&self, column: usize) -> f32 {
+        self.column_gaps
+            .get(column)
+            .copied()
+            .unwrap_or(self.receptor_spacing_pixels)
+    }
+
+    /// Left edge of each column relative to the playfield's left edge, in pixels.
+    pub fn column_offsets(&self, key_count: usize) -> Vec<f32> {
+        let mut offsets = Vec::with_capacity(key_count);
+        let mut x = 0.0;
+        for column in 0..key_count {
+            offsets.push(x);
+            x += self.column_width(column) + self.column_gap(column);
         }
+        offsets
+    }
+
+    /// Total width of `key_count` columns and the gaps between them, in pixels.
+    pub fn total_width(&self, key_count: usize) -> f32 {
+        let columns: f32 = (0..key_count).map(|c| self.column_width(c)).sum();
+        let gaps: f32 = (0..key_count.saturating_sub(1))
+            .map(|c| self.column_gap(c))
+            .sum();
+        columns + gaps
     }
+
+    /// Shrinks the notes, leaving the column spacing as it is.
     pub fn decrease_note_size(&mut self) {
-        self.note_width_pixels = (self.note_width_pixels - 5.0).max(10.0);
-        self.note_height_pixels = self.note_width_pixels;
-        self.column_width_pixels = self.note_width_pixels;
+        self.set_note_size(self.note_width_pixels - SIZE_STEP_PIXELS);
     }
+    /// Grows the notes, leaving the column spacing as it is.
     pub fn increase_note_size(&mut self) {
-        self.note_width_pixels = (self.note_width_pixels + 5.0).min(200.0);
+        self.set_note_size(self.note_width_pixels + SIZE_STEP_PIXELS);
+    }
+
+    fn set_note_size(&mut self, size: f32) {
+        self.note_width_pixels = size.clamp(MIN_SIZE_PIXELS, MAX_SIZE_PIXELS);
         self.note_height_pixels = self.note_width_pixels;
-        self.column_width_pixels = self.note_width_pixels;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_column_offsets_with_custom_widths() {
+        let mut config = PlayfieldConfig::new();
+        config.column_width_pixels = 80.0;
+        config.receptor_spacing_pixels = 2.0;
+        config.column_widths = vec![100.0, 60.0];
+        config.column_gaps = vec![10.0];
+
+        // Widths 100, 60, 80, 80 with gaps 10, 2, 2
+        assert_eq!(config.column_offsets(4), vec![0.0, 110.0, 172.0, 254.0]);
+        assert_eq!(config.total_width(4), 334.0);
+        assert_eq!(config.total_width(0), 0.0);
+
+        // Uniform fallback
+        let uniform = PlayfieldConfig::new();
+        assert_eq!(uniform.column_offsets(3), vec![0.0, 100.0, 200.0]);
     }
 }
@@ -0,0 +1,144 @@
+//! Scoring models.
+//!
+//! The score is derived from the hit statistics and the max combo, so the
+//! live engine and replay simulation always agree on the final value.
+
+use crate::stats::{HitStats, Judgement};
+use serde::{Deserialize, Serialize};
+
+/// Maximum score reachable with the normalized model.
+pub const NORMALIZED_MAX_SCORE: u32 = 1_000_000;
+
+/// Share of the normalized score awarded for judgement accuracy.
+const NORMALIZED_ACCURACY_PORTION: u64 = 700_000;
+/// Share of the normalized score awarded for max combo.
+const NORMALIZED_COMBO_PORTION: u64 = 300_000;
+
+/// Points awarded for the best judgement.
+const MAX_JUDGEMENT_POINTS: u64 = 300;
+
+/// How the score is computed from judgements.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    rkyv::Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+#[rkyv(compare(PartialEq), derive(Debug))]
+pub enum ScoringModel {
+    /// Unbounded sum of judgement points (300/300/200/100/50).
+    #[default]
+    Raw,
+    /// osu!mania-style score capped at 1,000,000:
+    /// 70% from judgement points, 30% from max combo.
+    Normalized,
+}
+
+impl ScoringModel {
+    /// Returns all available scoring models.
+    pub fn all() -> &'static [ScoringModel] {
+        &[ScoringModel::Raw, ScoringModel::Normalized]
+    }
+
+    /// Returns a user-friendly display name.
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            ScoringModel::Raw => "Raw",
+            ScoringModel::Normalized => "Normalized (1M)",
+        }
+    }
+
+    /// Points awarded for a single judgement.
+    pub fn judgement_points(judgement: Judgement) -> u32 {
+        match judgement {
+            Judgement::Marv | Judgement::Perfect => 300,
+            Judgement::Great => 200,
+            Judgement::Good => 100,
+            Judgement::Bad => 50,
+            Judgement::Miss | Judgement::GhostTap => 0,
+        }
+    }
+
+    /// Computes the score for the given stats.
+    ///
+    /// `total_notes` is the number of judgeable notes in the chart and is
+    /// only used by the normalized model.
+    pub fn compute(&self, stats: &HitStats, max_combo: u32, total_notes: usize) -> u32 {
+        let points = (stats.marv + stats.perfect) as u64 * 300
+            + stats.great as u64 * 200
+            + stats.good as u64 * 100
+            + stats.bad as u64 * 50;
+
+        match self {
+            ScoringModel::Raw => points.min(u32::MAX as u64) as u32,
+            ScoringModel::Normalized => {
+                if total_notes == 0 {
+                    return 0;
+                }
+                let total = total_notes as u64;
+                let accuracy_part =
+                    NORMALIZED_ACCURACY_PORTION * points / (total * MAX_JUDGEMENT_POINTS);
+                let combo_part = NORMALIZED_COMBO_PORTION * (max_combo as u64).min(total) / total;
+                ((accuracy_part + combo_part) as u32).min(NORMALIZED_MAX_SCORE)
+            }
+        }
+    }
+}
+
+/// Lowest judgement that breaks combo.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    rkyv::Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+#[rkyv(compare(PartialEq), derive(Debug))]
+pub enum ComboBreak {
+    /// Only misses break combo.
+    #[default]
+    Miss,
+    /// Bads and misses break combo.
+    Bad,
+    /// Goods, bads and misses break combo.
+    Good,
+}
+
+impl ComboBreak {
+    /// Returns all available combo break rules.
+    pub fn all() -> &'static [ComboBreak] {
+        &[ComboBreak::Miss, ComboBreak::Bad, ComboBreak::Good]
+    }
+
+    /// Returns a user-friendly display name.
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            ComboBreak::Miss => "Break on Miss",
+            ComboBreak::Bad => "Break on Bad",
+            ComboBreak::Good => "Break on Good",
+        }
+    }
+
+    /// Whether `judgement` resets the combo under this rule.
+    pub fn breaks_combo(&self, judgement: Judgement) -> bool {
+        match judgement {
+            Judgement::Miss => true,
+            Judgement::Bad => matches!(self, ComboBreak::Bad | ComboBreak::Good),
+            Judgement::Good => matches!(self, ComboBreak::Good),
+            Judgement::Marv | Judgement::Perfect | Judgement::Great | Judgement::GhostTap => false,
+        }
+    }
+}
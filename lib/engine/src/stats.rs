@@ -28,6 +28,19 @@ impl JudgementColors {
             ghost_tap: [0.5, 0.5, 0.5, 1.0], // Gray
         }
     }
+
+    /// Color of `judgement`.
+    pub fn get(&self, judgement: Judgement) -> [f32; 4] {
+        match judgement {
+            Judgement::Marv => self.marv,
+            Judgement::Perfect => self.perfect,
+            Judgement::Great => self.great,
+            Judgement::Good => self.good,
+            Judgement::Bad => self.bad,
+            Judgement::Miss => self.miss,
+            Judgement::GhostTap => self.ghost_tap,
+        }
+    }
 }
 
 impl Default for JudgementColors {
@@ -37,6 +50,9 @@ impl Default for JudgementColors {
 }
 
 /// Hit judgement types from best to worst.
+///
+/// Deliberately exhaustive: a new judgement changes scoring, and callers
+/// matching on it should fail to compile rather than silently ignore it.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum Judgement {
     /// Perfect timing (best).
@@ -56,7 +72,10 @@ pub enum Judgement {
 }
 
 /// Accumulated hit statistics for a play session.
+///
+/// Start from [`HitStats::new`]; new counters may be added.
 #[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[non_exhaustive]
 pub struct HitStats {
     pub marv: u32,
     pub perfect: u32,
@@ -65,6 +84,10 @@ pub struct HitStats {
     pub bad: u32,
     pub miss: u32,
     pub ghost_tap: u32,
+    /// Mines that crossed the receptor while their key was held.
+    /// Not counted in accuracy.
+    #[serde(default)]
+    pub mine_hits: u32,
 }
 
 impl HitStats {
@@ -78,6 +101,7 @@ impl HitStats {
             bad: 0,
             miss: 0,
             ghost_tap: 0,
+            mine_hits: 0,
         }
     }
 
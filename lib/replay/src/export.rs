@@ -0,0 +1,81 @@
+//! Plain-text exports of replay data for external tools.
+
+use crate::types::HitTiming;
+use engine::{Judgement, US_PER_MS};
+use std::fmt::Write;
+
+/// Header row of [`timings_to_csv`].
+pub const TIMINGS_CSV_HEADER: &str = "note_index,note_time_ms,offset_ms,judgement,column";
+
+/// Exports hit timings as CSV, one row per timing.
+///
+/// Times are converted from µs to milliseconds. An empty slice yields the
+/// header only.
+pub fn timings_to_csv(timings: &[HitTiming]) -> String {
+    let mut csv = String::from(TIMINGS_CSV_HEADER);
+    csv.push('\n');
+    for timing in timings {
+        let note_time_ms = timing.note_time_us as f64 / US_PER_MS as f64;
+        // Writing to a String cannot fail
+        let _ = writeln!(
+            csv,
+            "{},{:.3},{:.3},{},{}",
+            timing.note_index,
+            note_time_ms,
+            timing.timing_ms(),
+            judgement_label(timing.judgement),
+            timing.column
+        );
+    }
+    csv
+}
+
+/// Lowercase judgement name, matching the summary JSON keys.
+fn judgement_label(judgement: Judgement) -> &'static str {
+    match judgement {
+        Judgement::Marv => "marv",
+        Judgement::Perfect => "perfect",
+        Judgement::Great => "great",
+        Judgement::Good => "good",
+        Judgement::Bad => "bad",
+        Judgement::Miss => "miss",
+        Judgement::GhostTap => "ghost_tap",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timings_to_csv() {
+        assert_eq!(timings_to_csv(&[]), format!("{TIMINGS_CSV_HEADER}\n"));
+
+        let timings = vec![
+            HitTiming {
+                note_index: 0,
+                timing_us: -12_500,
+                judgement: Judgement::Great,
+                note_time_us: 1_000_000,
+                column: 2,
+            },
+            HitTiming {
+                note_index: 1,
+                timing_us: 180_000,
+                judgement: Judgement::Miss,
+                note_time_us: 1_250_750,
+                column: 0,
+            },
+        ];
+        let csv = timings_to_csv(&timings);
+        let rows: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            rows,
+            vec![
+                TIMINGS_CSV_HEADER,
+                "0,1000.000,-12.500,great,2",
+                "1,1250.750,180.000,miss,0",
+            ]
+        );
+    }
+}
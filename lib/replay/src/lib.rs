@@ -5,6 +5,9 @@
 //! - [`types`] - Core data structures (ReplayData, ReplayInput, etc.)
 //! - [`simulation`] - Deterministic score calculation from replays
 //! - [`storage`] - Compression and file I/O
+//! - [`export`] - CSV export for external tools
+//! - [`validation`] - Sanity checks before storing a replay
+//! - [`autoplay`] - Perfect replays generated from a chart
 //!
 //! # Quick Start
 //!
@@ -23,22 +26,35 @@
 //! let loaded = decompress(&bytes).unwrap();
 //! ```
 
+pub mod autoplay;
+pub mod export;
 pub mod simulation;
 pub mod storage;
 pub mod types;
+pub mod validation;
 
 // Re-export types
 pub use types::{
-    CHECKPOINT_MIN_INTERVAL_US, GhostTap, HitTiming, REPLAY_FORMAT_VERSION, ReplayData,
-    ReplayInput, ReplayResult,
+    CHECKPOINT_MIN_INTERVAL_US, ColumnSummary, GhostTap, HitTiming, JudgementCounts,
+    REPLAY_FORMAT_VERSION, ReplayData, ReplayInput, ReplayResult, ReplaySummary,
+    SUMMARY_FORMAT_VERSION,
 };
 
 // Re-export simulation functions
-pub use simulation::{rejudge, rejudge_timings, simulate};
+pub use simulation::{accuracy_over_time, rejudge, rejudge_timings, simulate, simulate_notes};
+
+// Re-export export functions
+pub use export::timings_to_csv;
 
 // Re-export storage functions
 pub use storage::{compress, decompress};
 
+// Re-export validation
+pub use validation::{ReplayError, validate_against_chart, validate_result};
+
+// Re-export autoplay
+pub use autoplay::generate_autoplay;
+
 // Legacy aliases for backwards compatibility
 #[deprecated(since = "0.2.0", note = "Use `simulate` instead")]
 pub fn simulate_replay(
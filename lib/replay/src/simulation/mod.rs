@@ -3,16 +3,19 @@
 //! Uses the engine's hit matching algorithm for 1:1 consistency
 //! with live gameplay.
 
-use crate::types::{GhostTap, HitTiming, ReplayData, ReplayResult};
-use engine::{HitStats, HitWindow, Judgement, NoteAccessor, NoteData};
+use crate::types::{GhostTap, HitTiming, ReplayData, ReplayInput, ReplayResult};
+use engine::{
+    ComboBreak, HitStats, HitWindow, Judgement, NoteAccessor, NoteData, ScoringModel, US_PER_MS,
+    apply_mods,
+};
 
 /// Wrapper for simulation that tracks hit state separately.
-struct SimNote<'a> {
-    note: &'a NoteData,
+struct SimNote<'a, N> {
+    note: &'a N,
     hit: bool,
 }
 
-impl NoteAccessor for SimNote<'_> {
+impl<N: NoteAccessor> NoteAccessor for SimNote<'_, N> {
     fn time_us(&self) -> i64 {
         self.note.time_us()
     }
@@ -24,34 +27,162 @@ impl NoteAccessor for SimNote<'_> {
     fn is_hit(&self) -> bool {
         self.hit
     }
+
+    fn is_mine(&self) -> bool {
+        self.note.is_mine()
+    }
+}
+
+/// Resolves every mine that crossed the receptor before `time_us`.
+///
+/// A mine is hit if its column is held when it crosses, mirroring the
+/// live engine: it breaks combo without counting as a judged note.
+fn resolve_mines<N: NoteAccessor>(
+    sim_notes: &mut [SimNote<N>],
+    head_index: usize,
+    time_us: i64,
+    keys_held: &[bool],
+    result: &mut ReplayResult,
+    combo: &mut u32,
+) {
+    for sim_note in sim_notes.iter_mut().skip(head_index) {
+        if sim_note.note.time_us() >= time_us {
+            break;
+        }
+        if sim_note.note.is_mine() && !sim_note.hit {
+            sim_note.hit = true;
+            if keys_held
+                .get(sim_note.note.column())
+                .copied()
+                .unwrap_or(false)
+            {
+                result.hit_stats.mine_hits += 1;
+                *combo = 0;
+            }
+        }
+    }
 }
 
-/// Simulates a replay on a chart with the given hit window.
+/// Processing order of inputs: by time, presses before releases on ties.
+fn input_order(input: &ReplayInput) -> (i64, bool) {
+    (input.time_us, !input.is_press())
+}
+
+/// Simulates a replay on a chart.
+///
+/// The hit window recorded in the replay is preferred, so a replay always
+/// re-simulates the way it was played; `hit_window` is used for replays
+/// that did not record theirs. Use [`rejudge`] to force another window.
 ///
 /// Uses the engine's `find_best_note` algorithm for 1:1 consistency
-/// with live gameplay scoring.
+/// with live gameplay scoring. The mods recorded in the replay are applied
+/// to `chart`, and inputs outside its key count are ignored.
+///
+/// Inputs are processed in [`input_order`]; merged or edited replays whose
+/// inputs are out of order are stably sorted first, so the result doesn't
+/// depend on how the input list was assembled.
+///
+/// See [`simulate_notes`] for charts of other note types.
 pub fn simulate(
     replay_data: &ReplayData,
     chart: &[NoteData],
     hit_window: &HitWindow,
 ) -> ReplayResult {
+    match replay_data.recorded_hit_window() {
+        Some(recorded) => simulate_with(replay_data, chart, &recorded),
+        None => simulate_with(replay_data, chart, hit_window),
+    }
+}
+
+/// Simulates a replay on notes of any type.
+///
+/// `hit` holds the hit state of each note of `notes`: notes already hit
+/// are skipped, and it holds the final state on return (every note is hit
+/// or missed by then). Unlike [`simulate`], the replay mods are not applied:
+/// `notes` are played as given. The hit window is chosen like in [`simulate`].
+///
+/// # Panics
+///
+/// If `hit` and `notes` have different lengths.
+pub fn simulate_notes<N: NoteAccessor>(
+    replay_data: &ReplayData,
+    notes: &[N],
+    hit: &mut [bool],
+    hit_window: &HitWindow,
+) -> ReplayResult {
+    match replay_data.recorded_hit_window() {
+        Some(recorded) => simulate_notes_with(replay_data, notes, hit, &recorded),
+        None => simulate_notes_with(replay_data, notes, hit, hit_window),
+    }
+}
+
+fn simulate_with(
+    replay_data: &ReplayData,
+    chart: &[NoteData],
+    hit_window: &HitWindow,
+) -> ReplayResult {
+    let modded_chart;
+    let chart = if replay_data.is_modded() {
+        modded_chart = apply_mods(chart, &replay_data.mods);
+        &modded_chart[..]
+    } else {
+        chart
+    };
+    let mut hit = vec![false; chart.len()];
+    simulate_notes_with(replay_data, chart, &mut hit, hit_window)
+}
+
+fn simulate_notes_with<N: NoteAccessor>(
+    replay_data: &ReplayData,
+    chart: &[N],
+    hit: &mut [bool],
+    hit_window: &HitWindow,
+) -> ReplayResult {
+    assert_eq!(chart.len(), hit.len(), "one hit state per note");
+
+    let sorted_inputs;
+    let inputs = if replay_data.inputs.is_sorted_by_key(input_order) {
+        &replay_data.inputs[..]
+    } else {
+        let mut inputs = replay_data.inputs.clone();
+        inputs.sort_by_key(input_order);
+        sorted_inputs = inputs;
+        &sorted_inputs[..]
+    };
+
     let mut result = ReplayResult::new();
     let mut combo: u32 = 0;
     let miss_us = hit_window.miss_us;
 
     // Create simulation notes with mutable hit tracking
-    let mut sim_notes: Vec<SimNote> = chart
+    let mut sim_notes: Vec<SimNote<N>> = chart
         .iter()
-        .map(|n| SimNote {
-            note: n,
-            hit: false,
-        })
+        .zip(hit.iter())
+        .map(|(n, &hit)| SimNote { note: n, hit })
         .collect();
     let mut head_index: usize = 0;
+    let mut keys_held: Vec<bool> = Vec::new();
 
-    for input in &replay_data.inputs {
+    for input in inputs {
         let (input_column, is_press) = input.unpack();
         let input_time_us = input.time_us;
+        if replay_data.key_count > 0 && input_column >= replay_data.key_count {
+            continue;
+        }
+
+        // Mines that crossed before this input see the previous key state
+        resolve_mines(
+            &mut sim_notes,
+            head_index,
+            input_time_us,
+            &keys_held,
+            &mut result,
+            &mut combo,
+        );
+        if input_column >= keys_held.len() {
+            keys_held.resize(input_column + 1, false);
+        }
+        keys_held[input_column] = is_press;
 
         // Advance head_index and check for missed notes
         while head_index < sim_notes.len() {
@@ -73,6 +204,7 @@ pub fn simulate(
                     timing_us: miss_us,
                     judgement: Judgement::Miss,
                     note_time_us: note.time_us(),
+                    column: note.column(),
                 });
 
                 head_index += 1;
@@ -93,13 +225,14 @@ pub fn simulate(
             sim_notes[idx].hit = true;
             let (judgement, _) = hit_window.judge(timing_diff);
 
-            apply_judgement(&mut result, &mut combo, judgement);
+            apply_judgement(&mut result, &mut combo, judgement, replay_data.combo_break);
 
             result.hit_timings.push(HitTiming {
                 note_index: idx,
                 timing_us: timing_diff,
                 judgement,
                 note_time_us: sim_notes[idx].note.time_us(),
+                column: sim_notes[idx].note.column(),
             });
         } else {
             // Ghost tap - no note matched
@@ -111,67 +244,74 @@ pub fn simulate(
         }
     }
 
+    // Mines after the last input see the final key state
+    resolve_mines(
+        &mut sim_notes,
+        head_index,
+        i64::MAX,
+        &keys_held,
+        &mut result,
+        &mut combo,
+    );
+
     // Mark remaining unhit notes as misses
-    for (idx, sim_note) in sim_notes.iter().enumerate() {
+    for (idx, sim_note) in sim_notes.iter_mut().enumerate() {
         if !sim_note.hit {
+            sim_note.hit = true;
             result.hit_stats.miss += 1;
             result.hit_timings.push(HitTiming {
                 note_index: idx,
                 timing_us: miss_us,
                 judgement: Judgement::Miss,
                 note_time_us: sim_note.note.time_us(),
+                column: sim_note.note.column(),
             });
         }
     }
 
+    for (state, sim_note) in hit.iter_mut().zip(&sim_notes) {
+        *state = sim_note.hit;
+    }
+
     result.accuracy = result.hit_stats.calculate_accuracy();
+    let total_notes = chart.iter().filter(|n| !n.is_mine()).count();
+    let scoring_model = replay_data.scoring_model;
+    result.score = scoring_model.compute(&result.hit_stats, result.max_combo, total_notes);
     result
 }
 
 /// Apply a judgement to the result and update combo.
-fn apply_judgement(result: &mut ReplayResult, combo: &mut u32, judgement: Judgement) {
+///
+/// The score itself is computed once at the end from the final stats,
+/// using the replay's scoring model. Combo is reset by the judgements
+/// `combo_break` covers, like in the live engine.
+fn apply_judgement(
+    result: &mut ReplayResult,
+    combo: &mut u32,
+    judgement: Judgement,
+    combo_break: ComboBreak,
+) {
     match judgement {
-        Judgement::Miss => {
-            result.hit_stats.miss += 1;
-            *combo = 0;
-        }
+        Judgement::Miss => result.hit_stats.miss += 1,
         Judgement::GhostTap => {
             result.hit_stats.ghost_tap += 1;
+            return;
         }
-        Judgement::Marv => {
-            result.hit_stats.marv += 1;
-            *combo += 1;
-            result.max_combo = result.max_combo.max(*combo);
-            result.score += 300;
-        }
-        Judgement::Perfect => {
-            result.hit_stats.perfect += 1;
-            *combo += 1;
-            result.max_combo = result.max_combo.max(*combo);
-            result.score += 300;
-        }
-        Judgement::Great => {
-            result.hit_stats.great += 1;
-            *combo += 1;
-            result.max_combo = result.max_combo.max(*combo);
-            result.score += 200;
-        }
-        Judgement::Good => {
-            result.hit_stats.good += 1;
-            *combo += 1;
-            result.max_combo = result.max_combo.max(*combo);
-            result.score += 100;
-        }
-        Judgement::Bad => {
-            result.hit_stats.bad += 1;
-            *combo += 1;
-            result.max_combo = result.max_combo.max(*combo);
-            result.score += 50;
-        }
+        Judgement::Marv => result.hit_stats.marv += 1,
+        Judgement::Perfect => result.hit_stats.perfect += 1,
+        Judgement::Great => result.hit_stats.great += 1,
+        Judgement::Good => result.hit_stats.good += 1,
+        Judgement::Bad => result.hit_stats.bad += 1,
+    }
+    if combo_break.breaks_combo(judgement) {
+        *combo = 0;
+    } else {
+        *combo += 1;
+        result.max_combo = result.max_combo.max(*combo);
     }
 }
 
-/// Re-judges a replay with a new hit window.
+/// Re-judges a replay with a new hit window, ignoring the recorded one.
 ///
 /// Useful for comparing scores under different timing systems
 /// (e.g., Etterna Judge 4 vs Judge 9).
@@ -180,7 +320,7 @@ pub fn rejudge(
     chart: &[NoteData],
     new_hit_window: &HitWindow,
 ) -> ReplayResult {
-    simulate(replay_data, chart, new_hit_window)
+    simulate_with(replay_data, chart, new_hit_window)
 }
 
 /// Recalculates stats from existing hit timings with a new hit window.
@@ -208,10 +348,43 @@ pub fn rejudge_timings(hit_timings: &[HitTiming], hit_window: &HitWindow) -> (Hi
     (stats, accuracy)
 }
 
+/// Builds the running accuracy curve from hit timings.
+///
+/// Timings are replayed in chart order and each judged note emits a
+/// `(note_time_ms, accuracy)` sample, using the same weighting as
+/// [`HitStats::calculate_accuracy`]. The last sample therefore matches
+/// the final accuracy of the play.
+pub fn accuracy_over_time(hit_timings: &[HitTiming]) -> Vec<(f64, f64)> {
+    let mut ordered: Vec<&HitTiming> = hit_timings.iter().collect();
+    ordered.sort_by_key(|h| (h.note_time_us, h.note_index));
+
+    let mut stats = HitStats::new();
+    let mut samples = Vec::with_capacity(ordered.len());
+
+    for hit in ordered {
+        match hit.judgement {
+            Judgement::Marv => stats.marv += 1,
+            Judgement::Perfect => stats.perfect += 1,
+            Judgement::Great => stats.great += 1,
+            Judgement::Good => stats.good += 1,
+            Judgement::Bad => stats.bad += 1,
+            Judgement::Miss => stats.miss += 1,
+            Judgement::GhostTap => continue,
+        }
+        samples.push((
+            hit.note_time_us as f64 / US_PER_MS as f64,
+            stats.calculate_accuracy(),
+        ));
+    }
+
+    samples
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::types::ReplayData;
+    use engine::GameMod;
 
     #[test]
     fn test_empty_replay() {
@@ -237,6 +410,32 @@ mod tests {
         assert_eq!(result.max_combo, 1);
     }
 
+    #[test]
+    fn test_sub_millisecond_offset_round_trip() {
+        // Marvelous window is 16ms: 15.6ms late is Marv, 16.4ms late is not
+        let chart = vec![NoteData::tap(1_000_000, 0), NoteData::tap(1_500_000, 1)];
+        let mut replay = ReplayData::new(1.0);
+        for (note, offset_ms) in chart.iter().zip([15.6, 16.4]) {
+            let press = note.time_us() + engine::ms_to_us(offset_ms);
+            replay.add_press(press, note.column());
+            replay.add_release(press + 40_000, note.column());
+        }
+
+        let bytes = crate::storage::compress(&replay).unwrap();
+        let restored = crate::storage::decompress(&bytes).unwrap();
+        assert_eq!(restored.inputs, replay.inputs);
+
+        let result = simulate(&restored, &chart, &HitWindow::new());
+        let offsets: Vec<i64> = result
+            .hit_timings
+            .iter()
+            .map(|h| h.timing_us.abs())
+            .collect();
+        assert_eq!(offsets, vec![15_600, 16_400]);
+        assert_eq!(result.hit_stats.marv, 1);
+        assert_eq!(result.hit_stats.perfect, 1);
+    }
+
     #[test]
     fn test_ghost_tap() {
         let mut replay = ReplayData::new(1.0);
@@ -249,4 +448,245 @@ mod tests {
         assert_eq!(result.hit_stats.ghost_tap, 1);
         assert_eq!(result.hit_stats.miss, 1); // Note was never hit
     }
+
+    #[test]
+    fn test_normalized_full_perfect_score() {
+        let mut replay = ReplayData::new(1.0);
+        replay.scoring_model = ScoringModel::Normalized;
+
+        let mut chart = Vec::new();
+        for i in 0..7 {
+            let time_us = 1_000_000 + i * 250_000;
+            let column = (i % 4) as u8;
+            chart.push(NoteData::tap(time_us, column));
+            replay.add_press(time_us, column as usize);
+            replay.add_release(time_us + 50_000, column as usize);
+        }
+        let hit_window = HitWindow::new();
+
+        let result = simulate(&replay, &chart, &hit_window);
+        assert_eq!(result.hit_stats.marv, 7);
+        assert_eq!(result.score, engine::NORMALIZED_MAX_SCORE);
+
+        // Raw model keeps the plain sum
+        replay.scoring_model = ScoringModel::Raw;
+        let result = simulate(&replay, &chart, &hit_window);
+        assert_eq!(result.score, 7 * 300);
+    }
+
+    #[test]
+    fn test_accuracy_curve_ends_at_final_accuracy() {
+        let mut replay = ReplayData::new(1.0);
+        let mut chart = Vec::new();
+        for i in 0..8 {
+            let time_us = 1_000_000 + i * 200_000;
+            let column = (i % 4) as u8;
+            chart.push(NoteData::tap(time_us, column));
+            // Skip two notes so misses land at the end of hit_timings,
+            // and hit the rest with increasing offsets.
+            if i != 2 && i != 5 {
+                let press = time_us + i * 15_000;
+                replay.add_press(press, column as usize);
+                replay.add_release(press + 30_000, column as usize);
+            }
+        }
+        let hit_window = HitWindow::new();
+
+        let result = simulate(&replay, &chart, &hit_window);
+        let curve = accuracy_over_time(&result.hit_timings);
+
+        assert_eq!(curve.len(), chart.len());
+        assert!(curve.windows(2).all(|w| w[0].0 <= w[1].0));
+        assert_eq!(curve.last().unwrap().1, result.accuracy);
+    }
+
+    #[test]
+    fn test_simulate_applies_recorded_mods() {
+        let mut replay = ReplayData::new(1.0);
+        replay.key_count = 4;
+        replay.mods = vec![GameMod::NoSpecial];
+        // Hold column 1 across where the mine was
+        replay.add_press(1_450_000, 1);
+        replay.add_release(1_600_000, 1);
+        replay.add_press(2_000_000, 0);
+        // Outside the key count, ignored
+        replay.add_press(2_100_000, 5);
+
+        let chart = vec![
+            NoteData::mine(1_500_000, 1),
+            NoteData::hold(2_000_000, 0, 400_000),
+        ];
+        let result = simulate(&replay, &chart, &HitWindow::new());
+        assert_eq!(result.hit_stats.mine_hits, 0);
+        assert_eq!(result.hit_stats.marv, 1);
+        assert_eq!(result.hit_stats.ghost_tap, 1);
+        assert_eq!(result.hit_timings.len(), 1);
+    }
+
+    #[test]
+    fn test_recorded_hit_window_is_preferred() {
+        let chart = vec![NoteData::tap(1_000_000, 0), NoteData::tap(2_000_000, 1)];
+        let mut replay = ReplayData::new(1.0);
+        replay.add_press(1_030_000, 0);
+        replay.add_release(1_080_000, 0);
+        replay.add_press(1_960_000, 1);
+        replay.add_release(2_010_000, 1);
+
+        let played_with = HitWindow::from_osu_od(8.0);
+        let expected = simulate(&replay, &chart, &played_with);
+        replay.set_hit_window(&played_with);
+
+        // Same outcome whatever the current derivation of the window gives
+        let changed_formula = HitWindow::from_etterna_judge(9);
+        let result = simulate(&replay, &chart, &changed_formula);
+        assert_eq!(result.hit_stats, expected.hit_stats);
+        assert_eq!(result.accuracy, expected.accuracy);
+        assert_eq!(result.score, expected.score);
+
+        // Re-judging still applies the new window
+        let rejudged = rejudge(&replay, &chart, &changed_formula);
+        assert_ne!(rejudged.hit_stats, expected.hit_stats);
+    }
+
+    #[test]
+    fn test_mine_hit_breaks_combo() {
+        let mut replay = ReplayData::new(1.0);
+        replay.add_press(1_000_000, 0);
+        replay.add_release(1_050_000, 0);
+        // Hold column 1 across the mine
+        replay.add_press(1_450_000, 1);
+        replay.add_release(1_600_000, 1);
+
+        let chart = vec![NoteData::tap(1_000_000, 0), NoteData::mine(1_500_000, 1)];
+        let hit_window = HitWindow::new();
+
+        let result = simulate(&replay, &chart, &hit_window);
+        assert_eq!(result.hit_stats.marv, 1);
+        assert_eq!(result.hit_stats.mine_hits, 1);
+        assert_eq!(result.hit_stats.miss, 0);
+        assert_eq!(result.hit_stats.ghost_tap, 1);
+        assert_eq!(result.max_combo, 1);
+        assert_eq!(result.hit_timings.len(), 1);
+    }
+
+    #[test]
+    fn test_combo_break_rule() {
+        let chart: Vec<NoteData> = (1..=4).map(|s| NoteData::tap(s * 1_000_000, 0)).collect();
+        let mut replay = ReplayData::new(1.0);
+        // Bad, Good, Marv, Marv
+        for press in [1_120_000, 2_080_000, 3_000_000, 4_000_000] {
+            replay.add_press(press, 0);
+            replay.add_release(press + 20_000, 0);
+        }
+        let hit_window = HitWindow::new();
+
+        let max_combo = |combo_break| {
+            let mut replay = replay.clone();
+            replay.combo_break = combo_break;
+            simulate(&replay, &chart, &hit_window).max_combo
+        };
+        assert_eq!(max_combo(ComboBreak::Miss), 4);
+        // The Bad resets the combo, the Good keeps it
+        assert_eq!(max_combo(ComboBreak::Bad), 3);
+        assert_eq!(max_combo(ComboBreak::Good), 2);
+    }
+
+    /// Note type of an external tool (e.g. an editor), times in ms.
+    struct EditorNote {
+        time_ms: i64,
+        lane: usize,
+    }
+
+    impl NoteAccessor for EditorNote {
+        fn time_us(&self) -> i64 {
+            self.time_ms * US_PER_MS
+        }
+
+        fn column(&self) -> usize {
+            self.lane
+        }
+
+        fn is_hit(&self) -> bool {
+            false
+        }
+    }
+
+    #[test]
+    fn test_simulate_custom_note_type() {
+        let notes = vec![
+            EditorNote {
+                time_ms: 1_000,
+                lane: 0,
+            },
+            EditorNote {
+                time_ms: 1_500,
+                lane: 1,
+            },
+            EditorNote {
+                time_ms: 2_000,
+                lane: 2,
+            },
+        ];
+        let mut replay = ReplayData::new(1.0);
+        replay.add_press(1_000_000, 0);
+        replay.add_release(1_050_000, 0);
+        replay.add_press(1_530_000, 1);
+        replay.add_release(1_580_000, 1);
+        let hit_window = HitWindow::new();
+
+        let mut hit = vec![false; notes.len()];
+        let result = simulate_notes(&replay, &notes, &mut hit, &hit_window);
+        assert_eq!(result.hit_stats.marv, 1);
+        assert_eq!(result.hit_stats.perfect, 1);
+        assert_eq!(result.hit_stats.miss, 1);
+        assert_eq!(hit, vec![true; 3]);
+
+        // Same outcome as the NoteData chart
+        let chart: Vec<NoteData> = notes
+            .iter()
+            .map(|n| NoteData::tap(n.time_us(), n.lane as u8))
+            .collect();
+        assert_eq!(simulate(&replay, &chart, &hit_window), result);
+
+        // Notes already hit are left alone
+        let mut hit = vec![true, false, false];
+        let result = simulate_notes(&replay, &notes, &mut hit, &hit_window);
+        assert_eq!(result.hit_stats.marv, 0);
+        assert_eq!(result.hit_stats.ghost_tap, 1);
+        assert_eq!(result.hit_stats.perfect, 1);
+    }
+
+    #[test]
+    fn test_unordered_inputs_match_sorted() {
+        let chart = vec![
+            NoteData::tap(1_000_000, 0),
+            NoteData::tap(1_000_000, 1),
+            NoteData::hold(1_400_000, 2, 300_000),
+            NoteData::mine(1_600_000, 3),
+            NoteData::tap(2_000_000, 3),
+        ];
+        let mut sorted = ReplayData::new(1.0);
+        sorted.add_press(1_004_000, 0);
+        sorted.add_press(1_004_000, 1);
+        sorted.add_release(1_060_000, 0);
+        sorted.add_release(1_060_000, 1);
+        sorted.add_press(1_390_000, 2);
+        sorted.add_release(1_700_000, 2);
+        sorted.add_press(1_990_000, 3);
+        sorted.add_release(2_050_000, 3);
+
+        // Same inputs, reversed and with a release before its press on a tie
+        let mut unordered = sorted.clone();
+        unordered.inputs.reverse();
+        unordered.add_release(2_500_000, 0);
+        unordered.add_press(2_500_000, 0);
+        sorted.add_press(2_500_000, 0);
+        sorted.add_release(2_500_000, 0);
+
+        let hit_window = HitWindow::new();
+        let expected = simulate(&sorted, &chart, &hit_window);
+        assert_eq!(simulate(&unordered, &chart, &hit_window), expected);
+        assert_eq!(expected.hit_stats.miss, 0);
+        assert_eq!(expected.hit_timings.len(), 4);
+    }
 }
@@ -1,8 +1,10 @@
 //! Replay compression and file storage utilities.
 //!
 //! Provides zstd compression with rkyv serialization for efficient replay storage.
+//! Replays written by older format versions are upgraded on load.
 
-use crate::types::ReplayData;
+use crate::types::{REPLAY_FORMAT_VERSION, ReplayData, ReplayInput};
+use engine::{ComboBreak, GameMod, ScoringModel};
 use rkyv::rancor::Error;
 use zstd::stream::{decode_all, encode_all};
 
@@ -23,18 +25,293 @@ pub fn compress(data: &ReplayData) -> std::io::Result<Vec<u8>> {
     encode_all(&binary_data[..], COMPRESSION_LEVEL)
 }
 
+/// Last format version without a stored scoring model.
+const LEGACY_V5_VERSION: u8 = 5;
+/// Last format version without key count and mods.
+const LEGACY_V6_VERSION: u8 = 6;
+/// Last format version without a judged note count.
+const LEGACY_V7_VERSION: u8 = 7;
+/// Last format version without a recorded hit window.
+const LEGACY_V8_VERSION: u8 = 8;
+/// Last format version without a combo break rule.
+const LEGACY_V9_VERSION: u8 = 9;
+/// Last format version without the incomplete flag.
+const LEGACY_V10_VERSION: u8 = 10;
+
+/// Replay layout of format version 5 and earlier (no scoring model).
+#[derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)]
+struct ReplayDataV5 {
+    version: u8,
+    inputs: Vec<ReplayInput>,
+    rate: f64,
+    is_practice_mode: bool,
+    checkpoints: Vec<i64>,
+}
+
+impl From<ReplayDataV5> for ReplayData {
+    fn from(legacy: ReplayDataV5) -> Self {
+        Self {
+            version: REPLAY_FORMAT_VERSION,
+            inputs: legacy.inputs,
+            rate: legacy.rate,
+            is_practice_mode: legacy.is_practice_mode,
+            checkpoints: legacy.checkpoints,
+            // Scores of older replays were always raw
+            scoring_model: ScoringModel::Raw,
+            key_count: 0,
+            mods: Vec::new(),
+            judged_notes: 0,
+            hit_window_us: None,
+            combo_break: ComboBreak::Miss,
+            incomplete: false,
+        }
+    }
+}
+
+/// Replay layout of format version 6 (no key count or mods).
+#[derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)]
+struct ReplayDataV6 {
+    version: u8,
+    inputs: Vec<ReplayInput>,
+    rate: f64,
+    is_practice_mode: bool,
+    checkpoints: Vec<i64>,
+    scoring_model: ScoringModel,
+}
+
+impl From<ReplayDataV6> for ReplayData {
+    fn from(legacy: ReplayDataV6) -> Self {
+        Self {
+            version: REPLAY_FORMAT_VERSION,
+            inputs: legacy.inputs,
+            rate: legacy.rate,
+            is_practice_mode: legacy.is_practice_mode,
+            checkpoints: legacy.checkpoints,
+            scoring_model: legacy.scoring_model,
+            key_count: 0,
+            mods: Vec::new(),
+            judged_notes: 0,
+            hit_window_us: None,
+            combo_break: ComboBreak::Miss,
+            incomplete: false,
+        }
+    }
+}
+
+/// Replay layout of format version 7 (no judged note count).
+#[derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)]
+struct ReplayDataV7 {
+    version: u8,
+    inputs: Vec<ReplayInput>,
+    rate: f64,
+    is_practice_mode: bool,
+    checkpoints: Vec<i64>,
+    scoring_model: ScoringModel,
+    key_count: usize,
+    mods: Vec<GameMod>,
+}
+
+impl From<ReplayDataV7> for ReplayData {
+    fn from(legacy: ReplayDataV7) -> Self {
+        Self {
+            version: REPLAY_FORMAT_VERSION,
+            inputs: legacy.inputs,
+            rate: legacy.rate,
+            is_practice_mode: legacy.is_practice_mode,
+            checkpoints: legacy.checkpoints,
+            scoring_model: legacy.scoring_model,
+            key_count: legacy.key_count,
+            mods: legacy.mods,
+            judged_notes: 0,
+            hit_window_us: None,
+            combo_break: ComboBreak::Miss,
+            incomplete: false,
+        }
+    }
+}
+
+/// Replay layout of format version 8 (no recorded hit window).
+#[derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)]
+struct ReplayDataV8 {
+    version: u8,
+    inputs: Vec<ReplayInput>,
+    rate: f64,
+    is_practice_mode: bool,
+    checkpoints: Vec<i64>,
+    scoring_model: ScoringModel,
+    key_count: usize,
+    mods: Vec<GameMod>,
+    judged_notes: usize,
+}
+
+impl From<ReplayDataV8> for ReplayData {
+    fn from(legacy: ReplayDataV8) -> Self {
+        Self {
+            version: REPLAY_FORMAT_VERSION,
+            inputs: legacy.inputs,
+            rate: legacy.rate,
+            is_practice_mode: legacy.is_practice_mode,
+            checkpoints: legacy.checkpoints,
+            scoring_model: legacy.scoring_model,
+            key_count: legacy.key_count,
+            mods: legacy.mods,
+            judged_notes: legacy.judged_notes,
+            hit_window_us: None,
+            combo_break: ComboBreak::Miss,
+            incomplete: false,
+        }
+    }
+}
+
+/// Replay layout of format version 9 (no combo break rule).
+#[derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)]
+struct ReplayDataV9 {
+    version: u8,
+    inputs: Vec<ReplayInput>,
+    rate: f64,
+    is_practice_mode: bool,
+    checkpoints: Vec<i64>,
+    scoring_model: ScoringModel,
+    key_count: usize,
+    mods: Vec<GameMod>,
+    judged_notes: usize,
+    hit_window_us: Option<[i64; 6]>,
+}
+
+impl From<ReplayDataV9> for ReplayData {
+    fn from(legacy: ReplayDataV9) -> Self {
+        Self {
+            version: REPLAY_FORMAT_VERSION,
+            inputs: legacy.inputs,
+            rate: legacy.rate,
+            is_practice_mode: legacy.is_practice_mode,
+            checkpoints: legacy.checkpoints,
+            scoring_model: legacy.scoring_model,
+            key_count: legacy.key_count,
+            mods: legacy.mods,
+            judged_notes: legacy.judged_notes,
+            hit_window_us: legacy.hit_window_us,
+            // Only misses broke combo before the rule was configurable
+            combo_break: ComboBreak::Miss,
+            incomplete: false,
+        }
+    }
+}
+
+/// Replay layout of format version 10 (no incomplete flag).
+#[derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)]
+struct ReplayDataV10 {
+    version: u8,
+    inputs: Vec<ReplayInput>,
+    rate: f64,
+    is_practice_mode: bool,
+    checkpoints: Vec<i64>,
+    scoring_model: ScoringModel,
+    key_count: usize,
+    mods: Vec<GameMod>,
+    judged_notes: usize,
+    hit_window_us: Option<[i64; 6]>,
+    combo_break: ComboBreak,
+}
+
+impl From<ReplayDataV10> for ReplayData {
+    fn from(legacy: ReplayDataV10) -> Self {
+        Self {
+            version: REPLAY_FORMAT_VERSION,
+            inputs: legacy.inputs,
+            rate: legacy.rate,
+            is_practice_mode: legacy.is_practice_mode,
+            checkpoints: legacy.checkpoints,
+            scoring_model: legacy.scoring_model,
+            key_count: legacy.key_count,
+            mods: legacy.mods,
+            judged_notes: legacy.judged_notes,
+            hit_window_us: legacy.hit_window_us,
+            combo_break: legacy.combo_break,
+            // Only finished plays were saved before
+            incomplete: false,
+        }
+    }
+}
+
+fn log_migration(from_version: u8) {
+    log::info!(
+        "REPLAY: Migrated replay from format v{} to v{}",
+        from_version,
+        REPLAY_FORMAT_VERSION
+    );
+}
+
 /// Decompress replay data from bytes.
 ///
 /// Takes compressed bytes and returns the original ReplayData.
+/// Older format versions are upgraded to the current shape, and replays
+/// from a newer, unknown version are rejected.
 pub fn decompress(compressed: &[u8]) -> std::io::Result<ReplayData> {
     let binary_data = decode_all(compressed)?;
 
-    rkyv::from_bytes::<ReplayData, Error>(&binary_data).map_err(|e| {
-        std::io::Error::new(
-            std::io::ErrorKind::InvalidData,
-            format!("Deserialization error: {}", e),
-        )
-    })
+    let current_error = match rkyv::from_bytes::<ReplayData, Error>(&binary_data) {
+        Ok(data) if data.version == REPLAY_FORMAT_VERSION => return Ok(data),
+        Ok(data) if data.version > REPLAY_FORMAT_VERSION => {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!(
+                    "Unsupported replay format version {} (newest supported is {})",
+                    data.version, REPLAY_FORMAT_VERSION
+                ),
+            ));
+        }
+        // Older layouts can parse as the current one, so check the legacy shape
+        Ok(data) => format!("unexpected version {} for current layout", data.version),
+        Err(e) => e.to_string(),
+    };
+
+    if let Ok(legacy) = rkyv::from_bytes::<ReplayDataV10, Error>(&binary_data)
+        && legacy.version == LEGACY_V10_VERSION
+    {
+        log_migration(legacy.version);
+        return Ok(legacy.into());
+    }
+
+    if let Ok(legacy) = rkyv::from_bytes::<ReplayDataV9, Error>(&binary_data)
+        && legacy.version == LEGACY_V9_VERSION
+    {
+        log_migration(legacy.version);
+        return Ok(legacy.into());
+    }
+
+    if let Ok(legacy) = rkyv::from_bytes::<ReplayDataV8, Error>(&binary_data)
+        && legacy.version == LEGACY_V8_VERSION
+    {
+        log_migration(legacy.version);
+        return Ok(legacy.into());
+    }
+
+    if let Ok(legacy) = rkyv::from_bytes::<ReplayDataV7, Error>(&binary_data)
+        && legacy.version == LEGACY_V7_VERSION
+    {
+        log_migration(legacy.version);
+        return Ok(legacy.into());
+    }
+
+    if let Ok(legacy) = rkyv::from_bytes::<ReplayDataV6, Error>(&binary_data)
+        && legacy.version == LEGACY_V6_VERSION
+    {
+        log_migration(legacy.version);
+        return Ok(legacy.into());
+    }
+
+    if let Ok(legacy) = rkyv::from_bytes::<ReplayDataV5, Error>(&binary_data)
+        && legacy.version <= LEGACY_V5_VERSION
+    {
+        log_migration(legacy.version);
+        return Ok(legacy.into());
+    }
+
+    Err(std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        format!("Deserialization error: {current_error}"),
+    ))
 }
 
 #[cfg(test)]
@@ -64,4 +341,136 @@ mod tests {
         assert_eq!(decompressed, data);
         assert_eq!(decompressed.input_count(), 3);
     }
+
+    #[test]
+    fn test_decompress_upgrades_v5_replay() {
+        let legacy = ReplayDataV5 {
+            version: LEGACY_V5_VERSION,
+            inputs: vec![
+                ReplayInput::new(1000, 2, true),
+                ReplayInput::new(1500, 2, false),
+            ],
+            rate: 1.25,
+            is_practice_mode: true,
+            checkpoints: vec![20_000_000],
+        };
+        let binary_data = rkyv::to_bytes::<Error>(&legacy).unwrap();
+        let compressed = encode_all(&binary_data[..], COMPRESSION_LEVEL).unwrap();
+
+        let upgraded = decompress(&compressed).unwrap();
+        assert_eq!(upgraded.version, REPLAY_FORMAT_VERSION);
+        assert_eq!(upgraded.input_count(), 2);
+        assert_eq!(upgraded.inputs[0].unpack(), (2, true));
+        assert_eq!(upgraded.rate, 1.25);
+        assert!(upgraded.is_practice_mode);
+        assert_eq!(upgraded.checkpoints, vec![20_000_000]);
+        assert_eq!(upgraded.scoring_model, ScoringModel::Raw);
+    }
+
+    #[test]
+    fn test_decompress_upgrades_v6_replay() {
+        let legacy = ReplayDataV6 {
+            version: LEGACY_V6_VERSION,
+            inputs: vec![ReplayInput::new(1000, 1, true)],
+            rate: 1.0,
+            is_practice_mode: false,
+            checkpoints: Vec::new(),
+            scoring_model: ScoringModel::Normalized,
+        };
+        let binary_data = rkyv::to_bytes::<Error>(&legacy).unwrap();
+        let compressed = encode_all(&binary_data[..], COMPRESSION_LEVEL).unwrap();
+
+        let upgraded = decompress(&compressed).unwrap();
+        assert_eq!(upgraded.version, REPLAY_FORMAT_VERSION);
+        assert_eq!(upgraded.scoring_model, ScoringModel::Normalized);
+        assert_eq!(upgraded.key_count, 0);
+        assert!(!upgraded.is_modded());
+    }
+
+    #[test]
+    fn test_decompress_upgrades_v7_replay() {
+        let legacy = ReplayDataV7 {
+            version: LEGACY_V7_VERSION,
+            inputs: vec![ReplayInput::new(1000, 3, true)],
+            rate: 1.1,
+            is_practice_mode: false,
+            checkpoints: Vec::new(),
+            scoring_model: ScoringModel::Normalized,
+            key_count: 4,
+            mods: vec![GameMod::Hidden],
+        };
+        let binary_data = rkyv::to_bytes::<Error>(&legacy).unwrap();
+        let compressed = encode_all(&binary_data[..], COMPRESSION_LEVEL).unwrap();
+
+        let upgraded = decompress(&compressed).unwrap();
+        assert_eq!(upgraded.version, REPLAY_FORMAT_VERSION);
+        assert_eq!(upgraded.key_count, 4);
+        assert_eq!(upgraded.mods, vec![GameMod::Hidden]);
+        assert_eq!(upgraded.judged_notes, 0);
+        assert!(!upgraded.is_stale_for(1234));
+    }
+
+    #[test]
+    fn test_decompress_upgrades_v10_replay() {
+        let legacy = ReplayDataV10 {
+            version: LEGACY_V10_VERSION,
+            inputs: vec![ReplayInput::new(1000, 0, true)],
+            rate: 1.0,
+            is_practice_mode: false,
+            checkpoints: Vec::new(),
+            scoring_model: ScoringModel::Normalized,
+            key_count: 4,
+            mods: Vec::new(),
+            judged_notes: 10,
+            hit_window_us: None,
+            combo_break: ComboBreak::Good,
+        };
+        let binary_data = rkyv::to_bytes::<Error>(&legacy).unwrap();
+        let compressed = encode_all(&binary_data[..], COMPRESSION_LEVEL).unwrap();
+
+        let upgraded = decompress(&compressed).unwrap();
+        assert_eq!(upgraded.version, REPLAY_FORMAT_VERSION);
+        assert_eq!(upgraded.combo_break, ComboBreak::Good);
+        assert!(!upgraded.incomplete);
+    }
+
+    #[test]
+    fn test_compress_keeps_hit_window() {
+        let mut data = ReplayData::new(1.0);
+        data.set_hit_window(&engine::HitWindow::from_etterna_judge(4));
+
+        let decompressed = decompress(&compress(&data).unwrap()).unwrap();
+        assert_eq!(decompressed, data);
+        assert_eq!(
+            decompressed.recorded_hit_window().map(|hw| hw.marv_us),
+            Some(22_500)
+        );
+    }
+
+    #[test]
+    fn test_compress_with_mods() {
+        let mut data = ReplayData::new(1.0);
+        data.key_count = 7;
+        data.mods = vec![GameMod::NoSpecial, GameMod::Hidden];
+        data.add_press(1000, 6);
+
+        let decompressed = decompress(&compress(&data).unwrap()).unwrap();
+        assert_eq!(decompressed, data);
+        assert_eq!(decompressed.key_count, 7);
+        assert_eq!(decompressed.mods, vec![GameMod::NoSpecial, GameMod::Hidden]);
+    }
+
+    #[test]
+    fn test_decompress_rejects_future_version() {
+        let mut data = ReplayData::new(1.0);
+        data.version = REPLAY_FORMAT_VERSION + 1;
+        let compressed = compress(&data).unwrap();
+
+        let err = decompress(&compressed).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        assert!(
+            err.to_string()
+                .contains("Unsupported replay format version")
+        );
+    }
 }
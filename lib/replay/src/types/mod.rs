@@ -5,7 +5,9 @@
 mod input;
 mod replay;
 mod result;
+mod summary;
 
 pub use input::ReplayInput;
 pub use replay::{CHECKPOINT_MIN_INTERVAL_US, REPLAY_FORMAT_VERSION, ReplayData};
 pub use result::{GhostTap, HitTiming, ReplayResult};
+pub use summary::{ColumnSummary, JudgementCounts, ReplaySummary, SUMMARY_FORMAT_VERSION};
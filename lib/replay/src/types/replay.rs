@@ -1,19 +1,21 @@
 //! Replay data structure - the main replay container.
 
 use super::input::ReplayInput;
+use engine::{ComboBreak, GameMod, HitWindow, ScoringModel};
 use serde::{Deserialize, Serialize};
 
 /// Current replay format version for compatibility.
-pub const REPLAY_FORMAT_VERSION: u8 = 5;
+pub const REPLAY_FORMAT_VERSION: u8 = 11;
 
 /// Minimum interval between checkpoints (in µs).
 pub const CHECKPOINT_MIN_INTERVAL_US: i64 = 15_000_000; // 15 seconds
 
 /// Minimal replay data containing only raw inputs.
 ///
-/// Hit windows are NOT stored - they are applied server-side during
-/// score calculation, allowing replays to be re-judged with different
-/// timing parameters.
+/// Judgements are not stored: replays are re-judged from their inputs.
+/// The hit window of the play is recorded (`hit_window_us`) so the
+/// original score can be reproduced, but a replay can still be re-judged
+/// with different timing parameters.
 #[derive(
     Debug,
     Clone,
@@ -38,6 +40,29 @@ pub struct ReplayData {
     /// Checkpoints placed by the user (timestamps in µs).
     #[serde(default)]
     pub checkpoints: Vec<i64>,
+    /// Scoring model used to compute the score of this play.
+    #[serde(default)]
+    pub scoring_model: ScoringModel,
+    /// Number of columns of the played chart (0 = unknown).
+    #[serde(default)]
+    pub key_count: usize,
+    /// Mods active during the play.
+    #[serde(default)]
+    pub mods: Vec<GameMod>,
+    /// Judgeable notes of the played chart before mods (0 = unknown).
+    #[serde(default)]
+    pub judged_notes: usize,
+    /// Resolved hit window of the play in µs
+    /// (`[marv, perfect, great, good, bad, miss]`), if recorded.
+    #[serde(default)]
+    pub hit_window_us: Option<[i64; 6]>,
+    /// Combo-break rule configured for the play (which judgements reset combo).
+    #[serde(default)]
+    pub combo_break: ComboBreak,
+    /// The player left before the end of the chart. Incomplete replays are
+    /// kept for history but never count as scores.
+    #[serde(default)]
+    pub incomplete: bool,
 }
 
 impl ReplayData {
@@ -49,6 +74,13 @@ impl ReplayData {
             rate,
             is_practice_mode: false,
             checkpoints: Vec::new(),
+            scoring_model: ScoringModel::default(),
+            key_count: 0,
+            mods: Vec::new(),
+            judged_notes: 0,
+            hit_window_us: None,
+            combo_break: ComboBreak::default(),
+            incomplete: false,
         }
     }
 
@@ -103,10 +135,43 @@ impl ReplayData {
         self.inputs.len()
     }
 
+    /// Whether any mod was active during the play.
+    pub fn is_modded(&self) -> bool {
+        !self.mods.is_empty()
+    }
+
     /// Check if replay is empty.
     pub fn is_empty(&self) -> bool {
         self.inputs.is_empty()
     }
+
+    /// Records the hit window the replay is played with.
+    pub fn set_hit_window(&mut self, hit_window: &HitWindow) {
+        self.hit_window_us = Some([
+            hit_window.marv_us,
+            hit_window.perfect_us,
+            hit_window.great_us,
+            hit_window.good_us,
+            hit_window.bad_us,
+            hit_window.miss_us,
+        ]);
+    }
+
+    /// Hit window the replay was played with, if recorded.
+    pub fn recorded_hit_window(&self) -> Option<HitWindow> {
+        self.hit_window_us
+            .map(|[marv, perf, great, good, bad, miss]| {
+                HitWindow::from_custom_us(marv, perf, great, good, bad, miss)
+            })
+    }
+
+    /// Whether this replay was recorded on a chart with a different number
+    /// of judgeable notes, e.g. before the chart file was updated.
+    ///
+    /// Replays that did not record their note count are never stale.
+    pub fn is_stale_for(&self, judgeable_notes: usize) -> bool {
+        self.judged_notes != 0 && self.judged_notes != judgeable_notes
+    }
 }
 
 impl Default for ReplayData {
@@ -117,6 +182,13 @@ impl Default for ReplayData {
             rate: 1.0,
             is_practice_mode: false,
             checkpoints: Vec::new(),
+            scoring_model: ScoringModel::default(),
+            key_count: 0,
+            mods: Vec::new(),
+            judged_notes: 0,
+            hit_window_us: None,
+            combo_break: ComboBreak::default(),
+            incomplete: false,
         }
     }
 }
@@ -14,6 +14,9 @@ pub struct HitTiming {
     pub judgement: Judgement,
     /// Timestamp of the note in the map (µs).
     pub note_time_us: i64,
+    /// Column of the hit note.
+    #[serde(default)]
+    pub column: usize,
 }
 
 impl HitTiming {
@@ -0,0 +1,218 @@
+//! Play session summary for external analysis tools.
+//!
+//! Unlike the compressed replay format, the summary is plain JSON with
+//! stable snake_case keys, meant for sharing or importing into spreadsheets.
+//! Bump [`SUMMARY_FORMAT_VERSION`] whenever a field is renamed or removed.
+
+use super::replay::ReplayData;
+use super::result::{HitTiming, ReplayResult};
+use crate::simulation::simulate;
+use engine::{HitStats, HitWindow, Judgement, NoteData};
+use serde::{Deserialize, Serialize};
+
+/// Current summary format version.
+pub const SUMMARY_FORMAT_VERSION: u32 = 1;
+
+/// Judgement counts of a play.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JudgementCounts {
+    pub marv: u32,
+    pub perfect: u32,
+    pub great: u32,
+    pub good: u32,
+    pub bad: u32,
+    pub miss: u32,
+    pub ghost_tap: u32,
+    pub mine_hits: u32,
+}
+
+impl From<&HitStats> for JudgementCounts {
+    fn from(stats: &HitStats) -> Self {
+        Self {
+            marv: stats.marv,
+            perfect: stats.perfect,
+            great: stats.great,
+            good: stats.good,
+            bad: stats.bad,
+            miss: stats.miss,
+            ghost_tap: stats.ghost_tap,
+            mine_hits: stats.mine_hits,
+        }
+    }
+}
+
+/// Timing statistics of a single column.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ColumnSummary {
+    pub column: usize,
+    /// Notes hit (any judgement but miss).
+    pub hits: u32,
+    pub misses: u32,
+    pub mean_error_ms: f64,
+    pub unstable_rate: f64,
+}
+
+/// Versioned summary of a play session.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplaySummary {
+    pub version: u32,
+    /// Playback rate, when known.
+    pub rate: Option<f64>,
+    /// Accuracy (0-100).
+    pub accuracy: f64,
+    pub score: u32,
+    pub max_combo: u32,
+    pub judgements: JudgementCounts,
+    /// Standard deviation of hit errors ×10 (osu! convention).
+    pub unstable_rate: f64,
+    /// Mean hit error in ms (negative = early).
+    pub mean_error_ms: f64,
+    pub columns: Vec<ColumnSummary>,
+}
+
+impl ReplaySummary {
+    /// Builds the summary of a simulated play.
+    pub fn from_result(result: &ReplayResult) -> Self {
+        let (mean_error_ms, unstable_rate) = timing_stats(result.hit_timings.iter());
+
+        let column_count = result
+            .hit_timings
+            .iter()
+            .map(|t| t.column + 1)
+            .max()
+            .unwrap_or(0);
+        let columns = (0..column_count)
+            .map(|column| {
+                let timings = || {
+                    result
+                        .hit_timings
+                        .iter()
+                        .filter(move |t| t.column == column)
+                };
+                let misses = timings().filter(|t| t.judgement == Judgement::Miss).count() as u32;
+                let hits = timings().count() as u32 - misses;
+                let (mean_error_ms, unstable_rate) = timing_stats(timings());
+                ColumnSummary {
+                    column,
+                    hits,
+                    misses,
+                    mean_error_ms,
+                    unstable_rate,
+                }
+            })
+            .collect();
+
+        Self {
+            version: SUMMARY_FORMAT_VERSION,
+            rate: None,
+            accuracy: result.accuracy,
+            score: result.score,
+            max_combo: result.max_combo,
+            judgements: JudgementCounts::from(&result.hit_stats),
+            unstable_rate,
+            mean_error_ms,
+            columns,
+        }
+    }
+
+    /// Serializes the summary to pretty-printed JSON.
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+/// Mean error and unstable rate (in ms) of the non-miss timings.
+fn timing_stats<'a>(timings: impl Iterator<Item = &'a HitTiming>) -> (f64, f64) {
+    let offsets: Vec<f64> = timings
+        .filter(|t| !matches!(t.judgement, Judgement::Miss | Judgement::GhostTap))
+        .map(HitTiming::timing_ms)
+        .collect();
+    if offsets.is_empty() {
+        return (0.0, 0.0);
+    }
+
+    let count = offsets.len() as f64;
+    let mean = offsets.iter().sum::<f64>() / count;
+    let variance = offsets.iter().map(|o| (o - mean).powi(2)).sum::<f64>() / count;
+    (mean, variance.sqrt() * 10.0)
+}
+
+impl ReplayResult {
+    /// Exports this result as a versioned JSON summary.
+    pub fn to_summary_json(&self) -> serde_json::Result<String> {
+        ReplaySummary::from_result(self).to_json()
+    }
+}
+
+impl ReplayData {
+    /// Simulates this replay on `chart` and exports the JSON summary.
+    pub fn to_summary_json(
+        &self,
+        chart: &[NoteData],
+        hit_window: &HitWindow,
+    ) -> serde_json::Result<String> {
+        let result = simulate(self, chart, hit_window);
+        let mut summary = ReplaySummary::from_result(&result);
+        summary.rate = Some(self.rate);
+        summary.to_json()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary_json_keys_and_roundtrip() {
+        let mut replay = ReplayData::new(1.2);
+        let mut chart = Vec::new();
+        for i in 0..6 {
+            let time_us = 1_000_000 + i * 250_000;
+            let column = (i % 3) as u8;
+            chart.push(NoteData::tap(time_us, column));
+            // Leave the last note unhit so one column records a miss
+            if i != 5 {
+                let press = time_us + (i - 2) * 4_000;
+                replay.add_press(press, column as usize);
+                replay.add_release(press + 40_000, column as usize);
+            }
+        }
+        let hit_window = HitWindow::new();
+
+        let json = replay.to_summary_json(&chart, &hit_window).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        for key in [
+            "version",
+            "rate",
+            "accuracy",
+            "score",
+            "max_combo",
+            "judgements",
+            "unstable_rate",
+            "mean_error_ms",
+            "columns",
+        ] {
+            assert!(value.get(key).is_some(), "missing key {key}");
+        }
+        assert_eq!(value["version"], SUMMARY_FORMAT_VERSION);
+        assert_eq!(value["judgements"]["miss"], 1);
+        assert_eq!(value["columns"].as_array().unwrap().len(), 3);
+        assert_eq!(value["columns"][2]["misses"], 1);
+
+        let parsed: ReplaySummary = serde_json::from_str(&json).unwrap();
+        let expected = ReplaySummary::from_result(&simulate(&replay, &chart, &hit_window));
+        let close = |a: f64, b: f64| (a - b).abs() < 1e-9;
+        assert_eq!(parsed.rate, Some(1.2));
+        assert_eq!(parsed.judgements, expected.judgements);
+        assert_eq!(parsed.max_combo, expected.max_combo);
+        assert_eq!(parsed.score, expected.score);
+        assert!(close(parsed.accuracy, expected.accuracy));
+        assert!(close(parsed.unstable_rate, expected.unstable_rate));
+        assert!(close(parsed.mean_error_ms, 0.0));
+        assert!(parsed.unstable_rate > 0.0);
+        for (a, b) in parsed.columns.iter().zip(&expected.columns) {
+            assert_eq!((a.column, a.hits, a.misses), (b.column, b.hits, b.misses));
+            assert!(close(a.unstable_rate, b.unstable_rate));
+        }
+    }
+}
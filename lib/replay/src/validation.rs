@@ -0,0 +1,131 @@
+//! Sanity checks run on a replay before it is stored.
+//!
+//! A replay that desyncs from its chart (truncated inputs, impossible
+//! stats) would pollute the leaderboard, so it is rejected instead.
+
+use crate::simulation::simulate;
+use crate::types::{ReplayData, ReplayResult};
+use engine::{HitWindow, NoteData};
+
+/// Minimum share of the chart that must be reached by the replay inputs.
+pub const MIN_JUDGED_RATIO: f64 = 0.5;
+
+/// Error type for replays that don't match their chart.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReplayError {
+    /// The playback rate is not a positive finite number.
+    InvalidRate(f64),
+    /// The inputs stop long before the end of the chart.
+    NotEnoughJudgements { judged: usize, total: usize },
+    /// The judgement counts don't add up to the number of notes.
+    JudgementCountMismatch { judged: usize, total: usize },
+    /// The accuracy is outside 0-100 or not a number.
+    ImpossibleAccuracy(f64),
+}
+
+impl std::fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReplayError::InvalidRate(rate) => write!(f, "Invalid rate: {}", rate),
+            ReplayError::NotEnoughJudgements { judged, total } => {
+                write!(f, "Only {} of {} notes reached by inputs", judged, total)
+            }
+            ReplayError::JudgementCountMismatch { judged, total } => {
+                write!(f, "{} judgements for {} notes", judged, total)
+            }
+            ReplayError::ImpossibleAccuracy(accuracy) => {
+                write!(f, "Impossible accuracy: {}", accuracy)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+/// Simulates the replay on the chart and checks the result is plausible.
+pub fn validate_against_chart(
+    replay: &ReplayData,
+    chart: &[NoteData],
+    hit_window: &HitWindow,
+) -> Result<(), ReplayError> {
+    let result = simulate(replay, chart, hit_window);
+    validate_result(&result, replay, chart, hit_window)
+}
+
+/// Same as [`validate_against_chart`] for an already simulated result.
+pub fn validate_result(
+    result: &ReplayResult,
+    replay: &ReplayData,
+    chart: &[NoteData],
+    hit_window: &HitWindow,
+) -> Result<(), ReplayError> {
+    if !replay.rate.is_finite() || replay.rate <= 0.0 {
+        return Err(ReplayError::InvalidRate(replay.rate));
+    }
+
+    let total = chart.iter().filter(|n| !n.is_mine()).count();
+    let stats = &result.hit_stats;
+    let judged =
+        (stats.marv + stats.perfect + stats.great + stats.good + stats.bad + stats.miss) as usize;
+    if judged != total {
+        return Err(ReplayError::JudgementCountMismatch { judged, total });
+    }
+
+    // Notes past the last input (plus the miss window) were never played
+    let last_input_us = replay.inputs.last().map_or(i64::MIN, |i| i.time_us);
+    let reach_us = last_input_us.saturating_add(hit_window.miss_us);
+    let reached = chart
+        .iter()
+        .filter(|n| !n.is_mine() && n.time_us() <= reach_us)
+        .count();
+    if total > 0 && (reached as f64) < total as f64 * MIN_JUDGED_RATIO {
+        return Err(ReplayError::NotEnoughJudgements {
+            judged: reached,
+            total,
+        });
+    }
+
+    if !(0.0..=100.0).contains(&result.accuracy) {
+        return Err(ReplayError::ImpossibleAccuracy(result.accuracy));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chart_and_replay(played_notes: i64) -> (Vec<NoteData>, ReplayData) {
+        let mut replay = ReplayData::new(1.0);
+        let mut chart = Vec::new();
+        for i in 0..10 {
+            let time_us = 1_000_000 + i * 300_000;
+            let column = (i % 4) as u8;
+            chart.push(NoteData::tap(time_us, column));
+            if i < played_notes {
+                replay.add_press(time_us, column as usize);
+                replay.add_release(time_us + 50_000, column as usize);
+            }
+        }
+        (chart, replay)
+    }
+
+    #[test]
+    fn test_valid_replay_passes() {
+        let (chart, replay) = chart_and_replay(10);
+        assert_eq!(
+            validate_against_chart(&replay, &chart, &HitWindow::new()),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_truncated_replay_fails() {
+        let (chart, replay) = chart_and_replay(3);
+        assert!(matches!(
+            validate_against_chart(&replay, &chart, &HitWindow::new()),
+            Err(ReplayError::NotEnoughJudgements { total: 10, .. })
+        ));
+    }
+}
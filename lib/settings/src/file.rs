@@ -0,0 +1,87 @@
+//! Crash-safe writes of configuration files.
+//!
+//! A file is never rewritten in place: the new content goes to a temporary
+//! file next to it, which then replaces it with a rename. A crash mid-write
+//! leaves the previous file untouched.
+
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Extension appended to the temporary file of a write.
+const TEMP_EXTENSION: &str = "tmp";
+
+/// Extension appended to a file that failed to parse.
+const BACKUP_EXTENSION: &str = "bak";
+
+/// `path` with `extension` appended (`settings.toml` -> `settings.toml.tmp`).
+fn with_appended_extension(path: &Path, extension: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".");
+    name.push(extension);
+    PathBuf::from(name)
+}
+
+/// Writes `content` to the temporary file of `path` and flushes it to disk.
+fn write_temp(path: &Path, content: &[u8]) -> io::Result<PathBuf> {
+    let temp_path = with_appended_extension(path, TEMP_EXTENSION);
+    let mut file = fs::File::create(&temp_path)?;
+    file.write_all(content)?;
+    file.sync_all()?;
+    Ok(temp_path)
+}
+
+/// Replaces the content of `path` atomically, creating its parent directory
+/// if needed.
+pub fn write_atomic<P: AsRef<Path>, C: AsRef<[u8]>>(path: P, content: C) -> io::Result<()> {
+    let path = path.as_ref();
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        fs::create_dir_all(parent)?;
+    }
+    let temp_path = write_temp(path, content.as_ref())?;
+    fs::rename(&temp_path, path).inspect_err(|_| {
+        let _ = fs::remove_file(&temp_path);
+    })
+}
+
+/// Moves a file that failed to parse aside (`<file>.bak`, replacing an older
+/// backup) so the defaults written next do not lose it.
+pub fn backup_invalid_file<P: AsRef<Path>>(path: P) -> io::Result<PathBuf> {
+    let path = path.as_ref();
+    let backup_path = with_appended_extension(path, BACKUP_EXTENSION);
+    fs::rename(path, &backup_path)?;
+    Ok(backup_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interrupted_write_keeps_previous_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.toml");
+
+        write_atomic(&path, "scroll_speed = 500.0\n").unwrap();
+
+        // Crash after the temporary file is written, before the rename
+        let temp_path = write_temp(&path, b"scroll_speed = 6").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "scroll_speed = 500.0\n");
+        assert_eq!(temp_path, dir.path().join("settings.toml.tmp"));
+
+        // The next save replaces the leftover temporary file
+        write_atomic(&path, "scroll_speed = 600.0\n").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "scroll_speed = 600.0\n");
+        assert!(!temp_path.exists());
+
+        let backup_path = backup_invalid_file(&path).unwrap();
+        assert!(!path.exists());
+        assert_eq!(backup_path, dir.path().join("settings.toml.bak"));
+        assert_eq!(
+            fs::read_to_string(&backup_path).unwrap(),
+            "scroll_speed = 600.0\n"
+        );
+    }
+}
@@ -2,6 +2,9 @@
 
 use serde::{Deserialize, Serialize};
 
+/// Default custom windows in ms (`[marv, perfect, great, good, bad, miss]`).
+pub const DEFAULT_CUSTOM_HIT_WINDOW_MS: [f64; 6] = [16.0, 50.0, 65.0, 100.0, 150.0, 200.0];
+
 /// Hit window calculation mode.
 #[derive(
     Debug,
@@ -21,6 +24,8 @@ pub enum HitWindowMode {
     OsuOD,
     /// Etterna/Quaver judge level based timing.
     EtternaJudge,
+    /// Windows given directly in milliseconds.
+    Custom,
 }
 
 impl Default for HitWindowMode {
@@ -34,6 +39,7 @@ impl std::fmt::Display for HitWindowMode {
         match self {
             Self::OsuOD => write!(f, "osu! OD"),
             Self::EtternaJudge => write!(f, "Etterna Judge"),
+            Self::Custom => write!(f, "Custom"),
         }
     }
 }
@@ -3,6 +3,17 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+/// Separates the keys of a column bound to several keys (`"KeyD|ArrowLeft"`).
+pub const COLUMN_KEY_SEPARATOR: char = '|';
+
+/// Splits a column entry into its key labels.
+pub fn column_keys(entry: &str) -> impl Iterator<Item = &str> {
+    entry
+        .split(COLUMN_KEY_SEPARATOR)
+        .map(str::trim)
+        .filter(|key| !key.is_empty())
+}
+
 /// Default keybinds for 4K, 5K, 6K, and 7K.
 pub fn default_keybinds() -> HashMap<String, Vec<String>> {
     let mut map = HashMap::new();
@@ -51,6 +62,135 @@ pub fn default_keybinds() -> HashMap<String, Vec<String>> {
     map
 }
 
+/// Non-gameplay action that can be bound to keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BindableAction {
+    /// Leave the current screen.
+    Back,
+    /// Confirm the current selection.
+    Confirm,
+    /// Rescan the songs folder.
+    Rescan,
+    /// Restart the current map.
+    Restart,
+    /// Increase the playback rate.
+    RateUp,
+    /// Decrease the playback rate.
+    RateDown,
+    /// Shift the offset of the map being played later.
+    BeatmapOffsetUp,
+    /// Shift the offset of the map being played earlier.
+    BeatmapOffsetDown,
+    /// Show or hide the recap of the plays since launch.
+    SessionRecap,
+    /// Grow the notes (the column width is unchanged).
+    NoteSizeUp,
+    /// Shrink the notes (the column width is unchanged).
+    NoteSizeDown,
+    /// Widen the columns (the note size is unchanged).
+    ColumnWidthUp,
+    /// Narrow the columns (the note size is unchanged).
+    ColumnWidthDown,
+}
+
+impl BindableAction {
+    /// Returns all bindable actions, in display order.
+    pub fn all() -> &'static [BindableAction] {
+        &[
+            BindableAction::Back,
+            BindableAction::Confirm,
+            BindableAction::Rescan,
+            BindableAction::Restart,
+            BindableAction::RateUp,
+            BindableAction::RateDown,
+            BindableAction::BeatmapOffsetUp,
+            BindableAction::BeatmapOffsetDown,
+            BindableAction::SessionRecap,
+            BindableAction::NoteSizeUp,
+            BindableAction::NoteSizeDown,
+            BindableAction::ColumnWidthUp,
+            BindableAction::ColumnWidthDown,
+        ]
+    }
+
+    /// Key of the action in the settings file.
+    pub fn id(&self) -> &'static str {
+        match self {
+            BindableAction::Back => "back",
+            BindableAction::Confirm => "confirm",
+            BindableAction::Rescan => "rescan",
+            BindableAction::Restart => "restart",
+            BindableAction::RateUp => "rate_up",
+            BindableAction::RateDown => "rate_down",
+            BindableAction::BeatmapOffsetUp => "beatmap_offset_up",
+            BindableAction::BeatmapOffsetDown => "beatmap_offset_down",
+            BindableAction::SessionRecap => "session_recap",
+            BindableAction::NoteSizeUp => "note_size_up",
+            BindableAction::NoteSizeDown => "note_size_down",
+            BindableAction::ColumnWidthUp => "column_width_up",
+            BindableAction::ColumnWidthDown => "column_width_down",
+        }
+    }
+
+    /// Parses a settings file key.
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::all().iter().copied().find(|action| action.id() == id)
+    }
+
+    /// Returns a user-friendly display name.
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            BindableAction::Back => "Back",
+            BindableAction::Confirm => "Confirm",
+            BindableAction::Rescan => "Rescan songs",
+            BindableAction::Restart => "Restart",
+            BindableAction::RateUp => "Rate up",
+            BindableAction::RateDown => "Rate down",
+            BindableAction::BeatmapOffsetUp => "Map offset +",
+            BindableAction::BeatmapOffsetDown => "Map offset -",
+            BindableAction::SessionRecap => "Session recap",
+            BindableAction::NoteSizeUp => "Note size +",
+            BindableAction::NoteSizeDown => "Note size -",
+            BindableAction::ColumnWidthUp => "Column width +",
+            BindableAction::ColumnWidthDown => "Column width -",
+        }
+    }
+
+    /// Keys bound to the action by default.
+    pub fn default_keys(&self) -> &'static [&'static str] {
+        match self {
+            BindableAction::Back => &["Escape"],
+            BindableAction::Confirm => &["Enter"],
+            BindableAction::Rescan => &["F8"],
+            BindableAction::Restart => &["F5"],
+            BindableAction::RateUp => &["PageDown"],
+            BindableAction::RateDown => &["PageUp"],
+            BindableAction::BeatmapOffsetUp => &["Equal"],
+            BindableAction::BeatmapOffsetDown => &["Minus"],
+            BindableAction::SessionRecap => &["F11"],
+            BindableAction::NoteSizeUp => &["Period"],
+            BindableAction::NoteSizeDown => &["Comma"],
+            BindableAction::ColumnWidthUp => &["Quote"],
+            BindableAction::ColumnWidthDown => &["Semicolon"],
+        }
+    }
+}
+
+/// Default keybinds for non-gameplay actions (key = [`BindableAction::id`]).
+pub fn default_action_keybinds() -> HashMap<String, Vec<String>> {
+    BindableAction::all()
+        .iter()
+        .map(|action| {
+            let keys = action
+                .default_keys()
+                .iter()
+                .map(|k| k.to_string())
+                .collect();
+            (action.id().to_string(), keys)
+        })
+        .collect()
+}
+
 /// Keybind configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Keybinds {
@@ -70,6 +210,23 @@ impl Keybinds {
         self.bindings.get(&key_count.to_string())
     }
 
+    /// Column hit by `key` in a key count, if the key is bound.
+    pub fn key_to_column(&self, key_count: usize, key: &str) -> Option<usize> {
+        self.get(key_count)?
+            .iter()
+            .position(|entry| column_keys(entry).any(|k| k == key))
+    }
+
+    /// Binds an additional key to a column.
+    pub fn add_column_key(&mut self, key_count: usize, column: usize, key: &str) {
+        add_column_key(&mut self.bindings, key_count, column, key);
+    }
+
+    /// Unbinds one key of a column, keeping its other keys.
+    pub fn remove_column_key(&mut self, key_count: usize, column: usize, key: &str) {
+        remove_column_key(&mut self.bindings, key_count, column, key);
+    }
+
     /// Set keybinds for a specific key count.
     pub fn set(&mut self, key_count: usize, keys: Vec<String>) {
         self.bindings.insert(key_count.to_string(), keys);
@@ -81,8 +238,75 @@ impl Keybinds {
     }
 }
 
+/// Adds `key` to a column of a keybind map, unbinding it from the other
+/// columns of that key count.
+pub fn add_column_key(
+    bindings: &mut HashMap<String, Vec<String>>,
+    key_count: usize,
+    column: usize,
+    key: &str,
+) {
+    if column >= key_count {
+        return;
+    }
+    let entries = bindings.entry(key_count.to_string()).or_default();
+    if entries.len() < key_count {
+        entries.resize(key_count, String::new());
+    }
+    for entry in entries.iter_mut() {
+        *entry = join_column_keys(column_keys(entry).filter(|k| *k != key));
+    }
+    let entry = &mut entries[column];
+    *entry = join_column_keys(column_keys(entry).chain(std::iter::once(key)));
+}
+
+/// Removes `key` from a column of a keybind map.
+pub fn remove_column_key(
+    bindings: &mut HashMap<String, Vec<String>>,
+    key_count: usize,
+    column: usize,
+    key: &str,
+) {
+    if let Some(entry) = bindings
+        .get_mut(&key_count.to_string())
+        .and_then(|entries| entries.get_mut(column))
+    {
+        *entry = join_column_keys(column_keys(entry).filter(|k| *k != key));
+    }
+}
+
+fn join_column_keys<'a>(keys: impl Iterator<Item = &'a str>) -> String {
+    keys.collect::<Vec<_>>()
+        .join(&COLUMN_KEY_SEPARATOR.to_string())
+}
+
 impl Default for Keybinds {
     fn default() -> Self {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_two_keys_resolve_to_one_column() {
+        let mut keybinds = Keybinds::new();
+        keybinds.add_column_key(4, 0, "ArrowLeft");
+
+        assert_eq!(keybinds.get(4).unwrap()[0], "KeyD|ArrowLeft");
+        assert_eq!(keybinds.key_to_column(4, "KeyD"), Some(0));
+        assert_eq!(keybinds.key_to_column(4, "ArrowLeft"), Some(0));
+        assert_eq!(keybinds.key_to_column(4, "KeyF"), Some(1));
+
+        // Moving a key to another column unbinds it from the first one
+        keybinds.add_column_key(4, 1, "ArrowLeft");
+        assert_eq!(keybinds.key_to_column(4, "ArrowLeft"), Some(1));
+        assert_eq!(keybinds.get(4).unwrap()[0], "KeyD");
+
+        keybinds.remove_column_key(4, 1, "KeyF");
+        assert_eq!(keybinds.key_to_column(4, "KeyF"), None);
+        assert_eq!(keybinds.key_to_column(4, "ArrowLeft"), Some(1));
+    }
+}
@@ -6,13 +6,28 @@
 //! - [`hit_window_mode`] - Hit window calculation modes
 //! - [`aspect_ratio`] - Aspect ratio options
 //! - [`keybinds`] - Keybind configuration
+//! - [`rate_range`] - Rates rated by the difficulty calculators
+//! - [`profile`] - Per-player settings and database locations
+//! - [`file`] - Crash-safe writes of configuration files
 
 mod aspect_ratio;
+mod file;
 mod hit_window_mode;
 mod keybinds;
+mod profile;
+mod rate_range;
 mod settings;
 
 pub use aspect_ratio::AspectRatioMode;
-pub use hit_window_mode::HitWindowMode;
-pub use keybinds::{default_keybinds, Keybinds};
-pub use settings::{GameSettings, SETTINGS_FILE};
+pub use file::{backup_invalid_file, write_atomic};
+pub use hit_window_mode::{DEFAULT_CUSTOM_HIT_WINDOW_MS, HitWindowMode};
+pub use keybinds::{
+    BindableAction, COLUMN_KEY_SEPARATOR, Keybinds, add_column_key, column_keys,
+    default_action_keybinds, default_keybinds, remove_column_key,
+};
+pub use profile::{DATABASE_FILE, DEFAULT_PROFILE, PROFILES_DIR, Profile, REPLAYS_DIR};
+pub use rate_range::RateRange;
+pub use settings::{
+    DEFAULT_TICK_RATE, DEFAULT_UI_SCALE, GameSettings, SETTINGS_FILE, SETTINGS_FILE_ENV,
+    TICK_RATE_RANGE, UI_SCALE_RANGE,
+};
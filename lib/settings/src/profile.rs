@@ -0,0 +1,220 @@
+//! Player profiles - separate settings and database per player.
+//!
+//! The default profile keeps `settings.toml`, `main.db` and the replays in
+//! the working directory; named profiles live under `profiles/<name>/`. The
+//! database and replays can be moved elsewhere with
+//! [`crate::GameSettings::data_dir`]. The profile is
+//! chosen once at launch (`--profile <name>`) and read through
+//! [`Profile::active`] afterwards.
+//!
+//! The settings file can be moved anywhere with the `PRISM_CONFIG`
+//! environment variable (see [`Profile::settings_path`]).
+
+use crate::{SETTINGS_FILE, SETTINGS_FILE_ENV};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Name of the profile used when none is selected.
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Directory holding the named profiles.
+pub const PROFILES_DIR: &str = "profiles";
+
+/// Database file name.
+pub const DATABASE_FILE: &str = "main.db";
+
+/// Directory of the replay files, next to the database.
+pub const REPLAYS_DIR: &str = "data/r";
+
+static ACTIVE_PROFILE: OnceLock<Profile> = OnceLock::new();
+
+/// A named set of settings and scores.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Profile {
+    name: String,
+}
+
+impl Profile {
+    /// Creates a profile, or `None` if the name is not usable as a
+    /// directory name (letters, digits, `-` and `_` only).
+    pub fn new(name: &str) -> Option<Self> {
+        let valid = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        valid.then(|| Self {
+            name: name.to_string(),
+        })
+    }
+
+    /// Reads the profile from the launch arguments (`--profile <name>`).
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Option<Self> {
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let name = match arg.strip_prefix("--profile=") {
+                Some(name) => name.to_string(),
+                None if arg == "--profile" => args.next()?,
+                None => continue,
+            };
+            return Self::new(&name);
+        }
+        None
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn is_default(&self) -> bool {
+        self.name == DEFAULT_PROFILE
+    }
+
+    /// Directory holding the profile files.
+    pub fn dir(&self) -> PathBuf {
+        if self.is_default() {
+            PathBuf::new()
+        } else {
+            Path::new(PROFILES_DIR).join(&self.name)
+        }
+    }
+
+    /// Settings file of the profile, or the path in [`SETTINGS_FILE_ENV`]
+    /// if it is set.
+    pub fn settings_path(&self) -> PathBuf {
+        self.settings_path_from(std::env::var_os(SETTINGS_FILE_ENV))
+    }
+
+    /// Settings file given the value of [`SETTINGS_FILE_ENV`] (ignored if
+    /// empty).
+    fn settings_path_from(&self, env_path: Option<OsString>) -> PathBuf {
+        match env_path {
+            Some(path) if !path.is_empty() => PathBuf::from(path),
+            _ => self.dir().join(SETTINGS_FILE),
+        }
+    }
+
+    /// Database file of the profile, in `data_dir` if one is configured.
+    pub fn db_path(&self, data_dir: Option<&Path>) -> PathBuf {
+        self.data_dir(data_dir).join(DATABASE_FILE)
+    }
+
+    /// Replay directory of the profile, in `data_dir` if one is configured.
+    pub fn replay_dir(&self, data_dir: Option<&Path>) -> PathBuf {
+        self.data_dir(data_dir).join(REPLAYS_DIR)
+    }
+
+    fn data_dir(&self, data_dir: Option<&Path>) -> PathBuf {
+        data_dir
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| self.dir())
+    }
+
+    /// Names of the profiles created so far, default first.
+    pub fn list() -> Vec<String> {
+        let mut names: Vec<String> = std::fs::read_dir(PROFILES_DIR)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter(|entry| entry.path().is_dir())
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .filter(|name| Self::new(name).is_some() && name != DEFAULT_PROFILE)
+            .collect();
+        names.sort();
+        names.insert(0, DEFAULT_PROFILE.to_string());
+        names
+    }
+
+    /// Selects the profile of this run. Only the first call has an effect.
+    pub fn set_active(profile: Profile) {
+        if let Err(profile) = ACTIVE_PROFILE.set(profile) {
+            eprintln!("Profile already selected, ignoring '{}'", profile.name);
+        }
+    }
+
+    /// Profile of this run (the default one if none was selected).
+    pub fn active() -> &'static Profile {
+        ACTIVE_PROFILE.get_or_init(Profile::default)
+    }
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Self {
+            name: DEFAULT_PROFILE.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profiles_have_distinct_db_paths() {
+        let alice = Profile::new("alice").unwrap();
+        let bob = Profile::new("bob").unwrap();
+        assert_ne!(alice.db_path(None), bob.db_path(None));
+        assert_ne!(alice.settings_path_from(None), bob.settings_path_from(None));
+        assert_eq!(
+            alice.db_path(None),
+            Path::new("profiles/alice").join(DATABASE_FILE)
+        );
+
+        assert_ne!(alice.replay_dir(None), bob.replay_dir(None));
+
+        // The default profile keeps the historical locations
+        assert_eq!(Profile::default().db_path(None), Path::new(DATABASE_FILE));
+        assert_eq!(
+            Profile::default().settings_path_from(None),
+            Path::new(SETTINGS_FILE)
+        );
+        assert_eq!(Profile::default().replay_dir(None), Path::new("data/r"));
+
+        // A configured data directory holds the database and the replays
+        let data_dir = Path::new("/mnt/scores");
+        assert_eq!(bob.db_path(Some(data_dir)), data_dir.join(DATABASE_FILE));
+        assert_eq!(bob.replay_dir(Some(data_dir)), data_dir.join(REPLAYS_DIR));
+    }
+
+    #[test]
+    fn test_profile_from_args() {
+        let args = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            Profile::from_args(args(&["prism", "--profile", "sis"])),
+            Profile::new("sis")
+        );
+        assert_eq!(
+            Profile::from_args(args(&["prism", "--profile=bro"])),
+            Profile::new("bro")
+        );
+        assert_eq!(Profile::from_args(args(&["prism"])), None);
+        // Names must stay inside the profiles directory
+        assert_eq!(Profile::from_args(args(&["--profile", "../x"])), None);
+    }
+
+    #[test]
+    fn test_settings_path_env_override() {
+        // The variable is passed in: setting it would race the other tests
+        let alice = Profile::new("alice").unwrap();
+        let custom = Some(OsString::from("/tmp/prism/test.toml"));
+        assert_eq!(
+            alice.settings_path_from(custom.clone()),
+            Path::new("/tmp/prism/test.toml")
+        );
+        assert_eq!(
+            Profile::default().settings_path_from(custom),
+            Path::new("/tmp/prism/test.toml")
+        );
+
+        // Unset or empty: the profile file
+        assert_eq!(
+            alice.settings_path_from(None),
+            alice.dir().join(SETTINGS_FILE)
+        );
+        assert_eq!(
+            alice.settings_path_from(Some(OsString::new())),
+            alice.dir().join(SETTINGS_FILE)
+        );
+    }
+}
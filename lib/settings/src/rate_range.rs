@@ -0,0 +1,65 @@
+//! Rates the difficulty calculators rate.
+
+use serde::{Deserialize, Serialize};
+
+/// Range of playback rates rated in song select, e.g. 0.7x to 2.0x by 0.05.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RateRange {
+    /// Lowest rate (inclusive).
+    pub min: f64,
+    /// Highest rate (inclusive).
+    pub max: f64,
+    /// Spacing between rates, counted from `min` (0 = any rate in range).
+    pub step: f64,
+}
+
+impl RateRange {
+    /// Tolerance for rates coming from string keys ("1.05").
+    const EPSILON: f64 = 1e-6;
+
+    /// Whether `rate` is one of the rates of this range.
+    pub fn contains(&self, rate: f64) -> bool {
+        if rate < self.min - Self::EPSILON || rate > self.max + Self::EPSILON {
+            return false;
+        }
+        if self.step <= 0.0 {
+            return true;
+        }
+        let steps = (rate - self.min) / self.step;
+        (steps - steps.round()).abs() < Self::EPSILON / self.step
+    }
+}
+
+impl Default for RateRange {
+    fn default() -> Self {
+        Self {
+            min: 0.7,
+            max: 2.0,
+            step: 0.05,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_range_contains() {
+        let range = RateRange {
+            min: 1.0,
+            max: 1.5,
+            step: 0.1,
+        };
+        assert!(range.contains(1.0));
+        assert!(range.contains(1.3));
+        assert!(range.contains(1.5));
+        assert!(!range.contains(0.9));
+        assert!(!range.contains(1.6));
+        // Off-step rates are left out
+        assert!(!range.contains(1.25));
+
+        let any = RateRange { step: 0.0, ..range };
+        assert!(any.contains(1.25));
+    }
+}
@@ -1,14 +1,32 @@
 //! Main settings structure.
 
-use crate::{AspectRatioMode, HitWindowMode, default_keybinds};
+use crate::{
+    AspectRatioMode, DEFAULT_CUSTOM_HIT_WINDOW_MS, HitWindowMode, Profile, RateRange,
+    backup_invalid_file, default_action_keybinds, default_keybinds, write_atomic,
+};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /// Settings file name.
 pub const SETTINGS_FILE: &str = "settings.toml";
 
+/// Environment variable replacing the settings file path of every profile.
+pub const SETTINGS_FILE_ENV: &str = "PRISM_CONFIG";
+
+/// Logic ticks per second used when none is configured.
+pub const DEFAULT_TICK_RATE: u32 = 200;
+
+/// Accepted logic tick rates (ticks per second).
+pub const TICK_RATE_RANGE: std::ops::RangeInclusive<u32> = 100..=1000;
+
+/// UI scale used when none is configured.
+pub const DEFAULT_UI_SCALE: f32 = 1.0;
+
+/// Accepted UI scales (multiplier of the window scale factor).
+pub const UI_SCALE_RANGE: std::ops::RangeInclusive<f32> = 0.5..=2.0;
+
 /// Persistent user settings.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GameSettings {
@@ -24,12 +42,42 @@ pub struct GameSettings {
     pub hit_window_mode: HitWindowMode,
     /// Hit window value (OD or judge level).
     pub hit_window_value: f64,
+    /// Windows of the custom mode in ms (`[marv, perfect, great, good, bad, miss]`).
+    #[serde(default = "default_custom_hit_window_ms")]
+    pub custom_hit_window_ms: [f64; 6],
     /// Aspect ratio mode.
     pub aspect_ratio_mode: AspectRatioMode,
     /// Current skin name.
     pub current_skin: String,
     /// Keybinds per key count.
     pub keybinds: HashMap<String, Vec<String>>,
+    /// Keybinds of non-gameplay actions (menu navigation, restart, rate).
+    #[serde(default = "default_action_keybinds")]
+    pub action_keybinds: HashMap<String, Vec<String>>,
+    /// Rates the difficulty calculators rate in song select.
+    #[serde(default)]
+    pub rate_range: RateRange,
+    /// Directory of the score database (`None` = the profile directory).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data_dir: Option<PathBuf>,
+    /// Logic ticks per second, within [`TICK_RATE_RANGE`].
+    #[serde(default = "default_tick_rate")]
+    pub tick_rate: u32,
+    /// Size of menus and panels, within [`UI_SCALE_RANGE`].
+    #[serde(default = "default_ui_scale")]
+    pub ui_scale: f32,
+}
+
+fn default_custom_hit_window_ms() -> [f64; 6] {
+    DEFAULT_CUSTOM_HIT_WINDOW_MS
+}
+
+fn default_tick_rate() -> u32 {
+    DEFAULT_TICK_RATE
+}
+
+fn default_ui_scale() -> f32 {
+    DEFAULT_UI_SCALE
 }
 
 impl GameSettings {
@@ -41,38 +89,58 @@ impl GameSettings {
             global_audio_offset_ms: 0.0,
             hit_window_mode: HitWindowMode::OsuOD,
             hit_window_value: 5.0,
+            custom_hit_window_ms: DEFAULT_CUSTOM_HIT_WINDOW_MS,
             aspect_ratio_mode: AspectRatioMode::Auto,
             current_skin: "default".to_string(),
             keybinds: default_keybinds(),
+            action_keybinds: default_action_keybinds(),
+            rate_range: RateRange::default(),
+            data_dir: None,
+            tick_rate: DEFAULT_TICK_RATE,
+            ui_scale: DEFAULT_UI_SCALE,
         }
     }
 
     /// Loads settings from a file, or returns defaults if not found.
+    ///
+    /// A file that fails to parse is backed up (see [`backup_invalid_file`])
+    /// and the defaults are used.
     pub fn load_from<P: AsRef<Path>>(path: P) -> Self {
         if let Ok(content) = fs::read_to_string(path.as_ref()) {
             if let Ok(settings) = toml::from_str::<GameSettings>(&content) {
                 return settings;
             }
-            eprintln!("Failed to parse settings file, using defaults.");
+            match backup_invalid_file(path.as_ref()) {
+                Ok(backup) => eprintln!(
+                    "Failed to parse settings file, using defaults (backed up to {}).",
+                    backup.display()
+                ),
+                Err(e) => eprintln!("Failed to parse settings file, using defaults ({e})."),
+            }
         }
         Self::new()
     }
 
-    /// Loads settings from the default file.
+    /// Loads settings from the file of the active profile.
     pub fn load() -> Self {
-        Self::load_from(SETTINGS_FILE)
+        Self::load_from(Profile::active().settings_path())
     }
 
-    /// Saves settings to a file.
+    /// Saves settings to a file, atomically (see [`write_atomic`]).
     pub fn save_to<P: AsRef<Path>>(&self, path: P) -> Result<(), std::io::Error> {
         let content = toml::to_string_pretty(self)
             .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
-        fs::write(path, content)
+        write_atomic(path, content)
     }
 
-    /// Saves settings to the default file.
+    /// Saves settings to the file of the active profile.
     pub fn save(&self) -> Result<(), std::io::Error> {
-        self.save_to(SETTINGS_FILE)
+        self.save_to(Profile::active().settings_path())
+    }
+
+    /// Database file of the active profile.
+    pub fn db_path(&self) -> PathBuf {
+        Profile::active().db_path(self.data_dir.as_deref())
     }
 
     /// Gets keybinds for a specific key count.
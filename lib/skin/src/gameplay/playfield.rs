@@ -33,6 +33,14 @@ pub struct PlayfieldConfig {
     #[serde(default = "default_receptor_spacing")]
     pub receptor_spacing: f32,
 
+    /// Per-column widths; missing entries fall back to `column_width`
+    #[serde(default)]
+    pub column_widths: Vec<f32>,
+
+    /// Gap after each column; missing entries fall back to `receptor_spacing`
+    #[serde(default)]
+    pub column_gaps: Vec<f32>,
+
     #[serde(default = "default_note_size")]
     pub note_size: Vec2Conf,
 
@@ -53,6 +61,8 @@ impl Default for PlayfieldConfig {
             position: default_position(),
             column_width: default_column_width(),
             receptor_spacing: default_receptor_spacing(),
+            column_widths: Vec::new(),
+            column_gaps: Vec::new(),
             note_size: default_note_size(),
             receptor_size: default_receptor_size(),
             hit_position_y: default_hit_position_y(),
@@ -60,3 +70,37 @@ impl Default for PlayfieldConfig {
         }
     }
 }
+
+impl PlayfieldConfig {
+    /// Column layout and sizes of the playfield, in skin pixels.
+    ///
+    /// Offsets are left to the caller: they depend on the screen.
+    pub fn layout(&self) -> engine::PlayfieldConfig {
+        let mut layout = engine::PlayfieldConfig::new();
+        layout.column_width_pixels = self.column_width;
+        layout.column_widths = self.column_widths.clone();
+        layout.column_gaps = self.column_gaps.clone();
+        layout.receptor_spacing_pixels = self.receptor_spacing;
+        layout.note_width_pixels = self.note_size.x;
+        layout.note_height_pixels = self.note_size.y;
+        layout.receptor_width_pixels = self.receptor_size.x;
+        layout.receptor_height_pixels = self.receptor_size.y;
+        layout
+    }
+
+    /// Sets the width of a column, filling earlier columns with the uniform width.
+    pub fn set_column_width(&mut self, col: usize, width: f32) {
+        if self.column_widths.len() <= col {
+            self.column_widths.resize(col + 1, self.column_width);
+        }
+        self.column_widths[col] = width;
+    }
+
+    /// Sets the gap after a column, filling earlier gaps with the uniform spacing.
+    pub fn set_column_gap(&mut self, col: usize, gap: f32) {
+        if self.column_gaps.len() <= col {
+            self.column_gaps.resize(col + 1, self.receptor_spacing);
+        }
+        self.column_gaps[col] = gap;
+    }
+}
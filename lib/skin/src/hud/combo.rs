@@ -15,6 +15,9 @@ fn default_color() -> Color {
 fn default_scale() -> f32 {
     48.0
 }
+fn default_break_shake() -> f32 {
+    8.0
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ComboConfig {
@@ -40,6 +43,10 @@ pub struct ComboConfig {
 
     #[serde(default = "default_true")]
     pub visible: bool,
+
+    /// Receptor shake amplitude in pixels when the combo breaks (0 disables it)
+    #[serde(default = "default_break_shake")]
+    pub break_shake: f32,
 }
 
 fn default_format() -> String {
@@ -59,6 +66,7 @@ impl Default for ComboConfig {
             image: None,
             format: default_format(),
             visible: true,
+            break_shake: default_break_shake(),
         }
     }
 }
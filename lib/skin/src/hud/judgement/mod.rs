@@ -21,6 +21,7 @@ pub use miss::JudgementFlashMiss;
 pub use panel::JudgementPanelConfig;
 pub use perfect::JudgementFlashPerfect;
 
+use crate::common::Vec2Conf;
 use serde::{Deserialize, Serialize};
 
 /// Labels for judgement text display
@@ -49,6 +50,42 @@ impl Default for JudgementLabels {
     }
 }
 
+/// Screen position of each judgement flash
+#[derive(Debug, Clone, Copy)]
+pub struct JudgementPositions {
+    pub marv: Vec2Conf,
+    pub perfect: Vec2Conf,
+    pub great: Vec2Conf,
+    pub good: Vec2Conf,
+    pub bad: Vec2Conf,
+    pub miss: Vec2Conf,
+    pub ghost_tap: Vec2Conf,
+}
+
+impl JudgementPositions {
+    /// Same position for every judgement
+    pub fn splat(position: Vec2Conf) -> Self {
+        Self {
+            marv: position,
+            perfect: position,
+            great: position,
+            good: position,
+            bad: position,
+            miss: position,
+            ghost_tap: position,
+        }
+    }
+}
+
+/// Uses `default` when a skin leaves a label blank
+fn label_or_default(label: &str, default: String) -> String {
+    if label.trim().is_empty() {
+        default
+    } else {
+        label.to_string()
+    }
+}
+
 /// Complete set of all judgement flash configurations (the centered flash when hitting notes)
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct JudgementFlashSet {
@@ -77,19 +114,38 @@ pub struct JudgementFlashSet {
     /// - = early hit, + = late hit
     #[serde(default)]
     pub show_timing: bool,
+
+    /// Show the flash above the column that was hit (each judgement keeps
+    /// its own Y) instead of at the judgement's position
+    #[serde(default)]
+    pub per_column: bool,
 }
 
 impl JudgementFlashSet {
-    /// Get labels from the flash set
+    /// Get labels from the flash set (blank labels fall back to the defaults)
     pub fn labels(&self) -> JudgementLabels {
+        let defaults = JudgementLabels::default();
         JudgementLabels {
-            marv: self.marv.label.clone(),
-            perfect: self.perfect.label.clone(),
-            great: self.great.label.clone(),
-            good: self.good.label.clone(),
-            bad: self.bad.label.clone(),
-            miss: self.miss.label.clone(),
-            ghost_tap: self.ghost_tap.label.clone(),
+            marv: label_or_default(&self.marv.label, defaults.marv),
+            perfect: label_or_default(&self.perfect.label, defaults.perfect),
+            great: label_or_default(&self.great.label, defaults.great),
+            good: label_or_default(&self.good.label, defaults.good),
+            bad: label_or_default(&self.bad.label, defaults.bad),
+            miss: label_or_default(&self.miss.label, defaults.miss),
+            ghost_tap: label_or_default(&self.ghost_tap.label, defaults.ghost_tap),
+        }
+    }
+
+    /// Get the flash position of each judgement
+    pub fn positions(&self) -> JudgementPositions {
+        JudgementPositions {
+            marv: self.marv.position,
+            perfect: self.perfect.position,
+            great: self.great.position,
+            good: self.good.position,
+            bad: self.bad.position,
+            miss: self.miss.position,
+            ghost_tap: self.ghost_tap.position,
         }
     }
 }
@@ -13,7 +13,7 @@ pub mod time_left;
 pub use accuracy::AccuracyConfig;
 pub use combo::ComboConfig;
 pub use hit_bar::HitBarConfig;
-pub use judgement::{JudgementFlashSet, JudgementLabels, JudgementPanelConfig};
+pub use judgement::{JudgementFlashSet, JudgementLabels, JudgementPanelConfig, JudgementPositions};
 pub use notes_remaining::NotesRemainingConfig;
 pub use nps::NpsConfig;
 pub use score::ScoreConfig;
@@ -40,6 +40,10 @@ pub struct NpsConfig {
 
     #[serde(default = "default_true")]
     pub visible: bool,
+
+    /// Show the exponentially smoothed NPS instead of the raw 1-second count
+    #[serde(default)]
+    pub smoothed: bool,
 }
 
 fn default_format() -> String {
@@ -59,6 +63,7 @@ impl Default for NpsConfig {
             image: None,
             format: default_format(),
             visible: true,
+            smoothed: false,
         }
     }
 }
@@ -18,9 +18,10 @@ pub use common::{
 pub use editor::EditorConfig;
 pub use gameplay::{/*BurstConfig,*/ GameplayDefaults, /*HoldConfig,*/ KeyModeConfig};
 pub use general::SkinGeneral;
-pub use hud::{HudConfig, JudgementLabels};
+pub use hud::{HudConfig, JudgementLabels, JudgementPositions};
 pub use menus::MenusConfig;
 
+use settings::write_atomic;
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -101,7 +102,7 @@ impl Skin {
         })
     }
 
-    /// Save the current configuration
+    /// Save the current configuration (each file is replaced atomically)
     pub fn save(&self) -> Result<(), String> {
         let conf_path = self.base_path.join("conf");
 
@@ -112,7 +113,7 @@ impl Skin {
 
         let hud_path = conf_path.join("hud.toml");
         let hud_content = toml::to_string_pretty(&self.hud).map_err(|e| e.to_string())?;
-        fs::write(hud_path, hud_content).map_err(|e| e.to_string())?;
+        write_atomic(hud_path, hud_content).map_err(|e| e.to_string())?;
 
         let gameplay_path = conf_path.join("gameplay.toml");
         let gameplay_content = toml::to_string_pretty(&self.gameplay).map_err(|e| e.to_string())?;
@@ -121,11 +122,11 @@ impl Skin {
             "Gameplay content check (note image): {:?}",
             self.gameplay.notes.note.image
         );
-        fs::write(gameplay_path, gameplay_content).map_err(|e| e.to_string())?;
+        write_atomic(gameplay_path, gameplay_content).map_err(|e| e.to_string())?;
 
         let menus_path = conf_path.join("menus.toml");
         let menus_content = toml::to_string_pretty(&self.menus).map_err(|e| e.to_string())?;
-        fs::write(menus_path, menus_content).map_err(|e| e.to_string())?;
+        write_atomic(menus_path, menus_content).map_err(|e| e.to_string())?;
 
         // Save key modes (4k.toml, 7k.toml, etc.)
         for (key, config) in &self.key_modes {
@@ -133,7 +134,7 @@ impl Skin {
             let path = conf_path.join(&filename);
             let content = toml::to_string_pretty(config).map_err(|e| e.to_string())?;
             log::info!("Saving keymode config: {}", path.display());
-            fs::write(path, content).map_err(|e| e.to_string())?;
+            write_atomic(path, content).map_err(|e| e.to_string())?;
         }
 
         Ok(())
@@ -199,6 +200,23 @@ impl Skin {
             .or_else(|| check_file(&self.base_path, "receptor_pressed.png"))
     }
 
+    /// Get the receptor image to show for a column given its key state.
+    ///
+    /// Uses the pressed image while the key is held, falling back to the
+    /// normal receptor when the skin has no pressed image.
+    pub fn get_receptor_image_for_state(
+        &self,
+        key_count: usize,
+        col: usize,
+        held: bool,
+    ) -> Option<PathBuf> {
+        select_receptor_image(
+            self.get_receptor_image(key_count, col),
+            self.get_receptor_pressed_image(key_count, col),
+            held,
+        )
+    }
+
     // ===== Note helpers =====
 
     /// Get note image for a specific column
@@ -330,6 +348,11 @@ impl Skin {
         self.hud.judgement.labels()
     }
 
+    /// Get judgement flash positions from skin
+    pub fn get_judgement_positions(&self) -> JudgementPositions {
+        self.hud.judgement.positions()
+    }
+
     // ===== Menu image helpers =====
 
     pub fn get_song_button_image(&self) -> Option<PathBuf> {
@@ -413,6 +436,15 @@ impl Skin {
     }
 }
 
+/// Picks the pressed receptor image only when the key is held and one exists.
+pub fn select_receptor_image(
+    normal: Option<PathBuf>,
+    pressed: Option<PathBuf>,
+    held: bool,
+) -> Option<PathBuf> {
+    if held { pressed.or(normal) } else { normal }
+}
+
 /// Initialize the default skin structure
 pub fn init_skin_structure() -> Result<(), String> {
     let skins_dir = Path::new("skins");
@@ -459,3 +491,45 @@ pub fn init_skin_structure() -> Result<(), String> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_select_receptor_image() {
+        let normal = Some(PathBuf::from("receptor.png"));
+        let pressed = Some(PathBuf::from("receptor_pressed.png"));
+
+        assert_eq!(
+            select_receptor_image(normal.clone(), pressed.clone(), true),
+            pressed
+        );
+        assert_eq!(
+            select_receptor_image(normal.clone(), pressed.clone(), false),
+            normal
+        );
+        // No pressed image: held keys keep the normal receptor
+        assert_eq!(select_receptor_image(normal.clone(), None, true), normal);
+    }
+
+    #[test]
+    fn test_receptor_image_for_state_uses_skin_config() {
+        let mut skin = Skin {
+            base_path: PathBuf::from("missing-skin-dir"),
+            ..Default::default()
+        };
+        skin.gameplay.receptors.image = Some("receptor.png".to_string());
+
+        let normal = skin.base_path.join("receptor.png");
+        assert_eq!(
+            skin.get_receptor_image_for_state(4, 0, true),
+            Some(normal.clone())
+        );
+
+        skin.gameplay.receptors.pressed_image = Some("receptor_down.png".to_string());
+        let pressed = skin.base_path.join("receptor_down.png");
+        assert_eq!(skin.get_receptor_image_for_state(4, 0, true), Some(pressed));
+        assert_eq!(skin.get_receptor_image_for_state(4, 0, false), Some(normal));
+    }
+}
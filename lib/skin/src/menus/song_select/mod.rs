@@ -11,7 +11,7 @@ mod song_button;
 pub use beatmap_info::BeatmapInfoConfig;
 pub use difficulty_button::DifficultyButtonConfig;
 pub use leaderboard::LeaderboardConfig;
-pub use rating_colors::RatingColorsConfig;
+pub use rating_colors::{DEFAULT_RATING_THRESHOLDS, RatingColorsConfig, rating_tier};
 pub use search_bar::SearchBarConfig;
 pub use search_panel::SearchPanelConfig;
 pub use song_button::SongButtonConfig;
@@ -3,6 +3,13 @@
 use crate::common::Color;
 use serde::{Deserialize, Serialize};
 
+/// Default upper bounds of the first four rating tiers (Etterna MSD scale).
+pub const DEFAULT_RATING_THRESHOLDS: [f64; 4] = [15.0, 22.0, 28.0, 34.0];
+
+fn default_thresholds() -> [f64; 4] {
+    DEFAULT_RATING_THRESHOLDS
+}
+
 fn default_stream() -> Color {
     [0.30, 0.85, 0.50, 1.0]
 } // Green
@@ -47,6 +54,11 @@ pub struct RatingColorsConfig {
 
     #[serde(default = "default_technical")]
     pub technical: Color,
+
+    /// Ratings below `thresholds[i]` use the i-th color of the ramp
+    /// (stream, jumpstream, handstream, stamina), anything above uses jackspeed.
+    #[serde(default = "default_thresholds")]
+    pub thresholds: [f64; 4],
 }
 
 impl Default for RatingColorsConfig {
@@ -59,6 +71,51 @@ impl Default for RatingColorsConfig {
             jackspeed: default_jackspeed(),
             chordjack: default_chordjack(),
             technical: default_technical(),
+            thresholds: default_thresholds(),
         }
     }
 }
+
+impl RatingColorsConfig {
+    /// Returns the ramp color for an overall rating.
+    pub fn color_for_rating(&self, rating: f64) -> Color {
+        let ramp = [
+            self.stream,
+            self.jumpstream,
+            self.handstream,
+            self.stamina,
+            self.jackspeed,
+        ];
+        ramp[rating_tier(&self.thresholds, rating)]
+    }
+}
+
+/// Index (0-4) of the tier a rating falls into.
+pub fn rating_tier(thresholds: &[f64; 4], rating: f64) -> usize {
+    thresholds
+        .iter()
+        .position(|&threshold| rating < threshold)
+        .unwrap_or(thresholds.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_custom_ramp_maps_rating() {
+        // osu! star scale
+        let colors = RatingColorsConfig {
+            thresholds: [2.0, 3.5, 5.0, 6.5],
+            ..Default::default()
+        };
+        assert_eq!(colors.color_for_rating(1.0), colors.stream);
+        assert_eq!(colors.color_for_rating(4.2), colors.handstream);
+        assert_eq!(colors.color_for_rating(8.0), colors.jackspeed);
+
+        // Same rating with the default MSD ramp
+        let defaults = RatingColorsConfig::default();
+        assert_eq!(defaults.color_for_rating(4.2), defaults.stream);
+        assert_eq!(defaults.color_for_rating(30.0), defaults.stamina);
+    }
+}